@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+#[derive(Debug)]
+pub struct ColumnHierarchyCmdInput {
+    pub columns: Vec<String>,
+}
+
+impl FromStr for ColumnHierarchyCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let columns: Vec<String> = s.split(DELIMITER).map(|c| c.to_owned()).collect();
+
+        if columns.len() >= 2 && columns.iter().all(|c| !c.is_empty()) {
+            Ok(ColumnHierarchyCmdInput { columns })
+        } else {
+            Err(
+                "wrong format, expected: <coarsest column>,<next column>,...,<finest column>"
+                    .to_owned(),
+            )
+        }
+    }
+}
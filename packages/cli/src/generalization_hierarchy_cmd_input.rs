@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+#[derive(Debug)]
+pub struct GeneralizationHierarchyCmdInput {
+    pub column_name: String,
+    pub hierarchy_path: String,
+}
+
+impl FromStr for GeneralizationHierarchyCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((column_name, hierarchy_path)) = s.split_once(DELIMITER) {
+            if !column_name.is_empty() && !hierarchy_path.is_empty() {
+                return Ok(GeneralizationHierarchyCmdInput {
+                    column_name: column_name.to_owned(),
+                    hierarchy_path: hierarchy_path.to_owned(),
+                });
+            }
+        }
+        Err("wrong format, expected: <column name>,<hierarchy path>".to_owned())
+    }
+}
+
+#[derive(Debug)]
+pub struct GeneralizationIntervalsCmdInput {
+    pub column_name: String,
+    pub widths: Vec<f64>,
+}
+
+impl FromStr for GeneralizationIntervalsCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((column_name, widths)) = s.split_once(DELIMITER) {
+            if let Ok(widths) = widths
+                .split(DELIMITER)
+                .map(|w| w.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+            {
+                if !column_name.is_empty() {
+                    return Ok(GeneralizationIntervalsCmdInput {
+                        column_name: column_name.to_owned(),
+                        widths,
+                    });
+                }
+            }
+        }
+        Err("wrong format, expected: <column name>,<width>[,<width>...]".to_owned())
+    }
+}
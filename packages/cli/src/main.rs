@@ -1,18 +1,43 @@
+mod column_hierarchy_cmd_input;
+mod generalization_hierarchy_cmd_input;
 mod multi_value_column_cmd_input;
+mod target_proportion_cmd_input;
 
-use log::{error, log_enabled, trace, Level::Debug};
+use column_hierarchy_cmd_input::ColumnHierarchyCmdInput;
+use generalization_hierarchy_cmd_input::{
+    GeneralizationHierarchyCmdInput, GeneralizationIntervalsCmdInput,
+};
+use log::{error, info, log_enabled, trace, warn, Level::Debug};
 use multi_value_column_cmd_input::MultiValueColumnCmdInput;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlockCreator},
-    dp::{DpParameters, NoisyCountThreshold},
+    data_block::{
+        CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreator, RecordSampling,
+    },
+    dp::{
+        audit::{DpAuditParameters, DpAuditor, NeighboringDataBlocks},
+        DpAdvisorInputs, DpColumnHierarchy, DpMarginalParameters, DpParameters,
+        DpParametersAdvisor, DpWorkload, InputValueByLen, NoisyCountThreshold,
+    },
     processing::{
         aggregator::{AggregatedData, Aggregator},
-        generator::{Generator, OversamplingParameters},
+        evaluator::{
+            AttributeInferenceAttack, AttributeInferenceParameters, BootstrapParameters,
+            ClosestRecordDistanceResult, Evaluator, LDiversityMeasure, MembershipAttackScore,
+            MembershipInferenceAttack, MembershipInferenceParameters, MlUtilityClassifier,
+            MlUtilityEvaluator, MlUtilityParameters, PrivacyModelAssessor, PrivacyModelParameters,
+            UtilityBreakdownMetric,
+        },
+        generalizer::{GeneralizationHierarchiesByColumn, GeneralizationHierarchy, Generalizer},
+        generator::{
+            CsvSyntheticDataSink, Generator, OversamplingParameters, SynthesisConstraints,
+            TargetDistribution,
+        },
     },
     utils::{reporting::LoggerProgressReporter, threading::set_number_of_threads},
 };
 use std::{process, sync::Arc};
 use structopt::StructOpt;
+use target_proportion_cmd_input::TargetProportionCmdInput;
 
 #[derive(StructOpt, Debug)]
 enum Command {
@@ -49,7 +74,7 @@ enum Command {
         #[structopt(
             long = "mode",
             help = "synthesis mode",
-            possible_values = &["row_seeded", "unseeded", "value_seeded", "aggregate_seeded"],
+            possible_values = &["row_seeded", "unseeded", "value_seeded", "aggregate_seeded", "dp_marginal"],
             case_insensitive = true,
             default_value = "row_seeded"
         )]
@@ -61,47 +86,113 @@ enum Command {
         )]
         aggregates_json: Option<String>,
 
+        #[structopt(
+            long = "aggregates-tsv",
+            help = "aggregate count table (such as the one generated on the aggregate step) used instead of the aggregates json, this allows synthesizing from published aggregates without the sensitive microdata on \"aggregate_seeded\" and \"value_seeded\" modes",
+            conflicts_with = "aggregates-json"
+        )]
+        aggregates_tsv: Option<String>,
+
+        #[structopt(
+            long = "aggregates-tsv-delimiter",
+            help = "csv delimiter for the aggregate count table",
+            default_value = "\t"
+        )]
+        aggregates_tsv_delimiter: String,
+
         #[structopt(
             long = "oversampling-ratio",
-            help = "allowed oversampling ratio used on \"value_seeded\" mode (0.1 means 10%)",
-            requires = "aggregates-json"
+            help = "allowed oversampling ratio used on \"value_seeded\" mode (0.1 means 10%)"
         )]
         oversampling_ratio: Option<f64>,
 
         #[structopt(
             long = "oversampling-tries",
-            help = "how many times try to resample in case the currently sampled value causes oversampling (\"value_seeded\" mode)",
-            requires = "aggregates-json"
+            help = "how many times try to resample in case the currently sampled value causes oversampling (\"value_seeded\" mode)"
         )]
         oversampling_tries: Option<usize>,
 
         #[structopt(
             long = "use-synthetic-counts",
-            help = "use synthetic aggregates to balance attribute sampling on \"aggregate_seeded\" mode",
-            requires = "aggregates-json"
+            help = "use synthetic aggregates to balance attribute sampling on \"aggregate_seeded\" mode"
         )]
         use_synthetic_counts: bool,
 
         #[structopt(
             long = "weight-selection-percentile",
-            help = "percentile used for the weight selection (\"aggregate_seeded\" mode)",
-            requires = "aggregates-json"
+            help = "percentile used for the weight selection (\"aggregate_seeded\" mode)"
         )]
         weight_selection_percentile: Option<usize>,
 
         #[structopt(
             long = "aggregate-counts-scale-factor",
-            help = "multiplier for aggregate counts before synthesis (\"aggregate_seeded\" mode), if not provided, use raw counts",
-            requires = "aggregates-json"
+            help = "multiplier for aggregate counts before synthesis (\"aggregate_seeded\" mode), if not provided, use raw counts"
         )]
         aggregate_counts_scale_factor: Option<f64>,
 
         #[structopt(
             long = "target-number-of-records",
-            help = "total number of records do be synthesized (\"aggregate_seeded\" mode), if not provided, sample from all available counts",
-            requires = "aggregates-json"
+            help = "total number of records do be synthesized (\"aggregate_seeded\" and \"dp_marginal\" modes), if not provided, sample from all available counts"
         )]
         target_number_of_records: Option<usize>,
+
+        #[structopt(
+            long = "marginal-epsilon",
+            help = "privacy budget used to select and measure the marginals on \"dp_marginal\" mode"
+        )]
+        marginal_epsilon: Option<f64>,
+
+        #[structopt(
+            long = "marginal-delta",
+            help = "delta used on \"dp_marginal\" mode [default: 1/(ln(number of records) * number of records)]",
+            requires = "marginal-epsilon"
+        )]
+        marginal_delta: Option<f64>,
+
+        #[structopt(
+            long = "constraints-json",
+            help = "json file with logical constraints (deny combinations, implications and allowed values) the synthetic records should respect"
+        )]
+        constraints_json: Option<String>,
+
+        #[structopt(
+            long = "constraints-report-path",
+            help = "write how many sampling choices were pruned by each constraint to this path",
+            requires = "constraints-json"
+        )]
+        constraints_report_path: Option<String>,
+
+        #[structopt(
+            long = "reject-unique-copies",
+            help = "reject synthetic records that exactly reproduce a sensitive record occurring only once (\"row_seeded\", \"unseeded\" and \"value_seeded\" modes)"
+        )]
+        reject_unique_copies: bool,
+
+        #[structopt(
+            long = "parallel-aggregate-seeded",
+            help = "split the record budget across parallel workers (\"aggregate_seeded\" mode), this is faster but preserves fewer rare combinations"
+        )]
+        parallel_aggregate_seeded: bool,
+
+        #[structopt(
+            long = "target-distribution-column",
+            help = "column to be rebalanced to match the target proportions (\"unseeded\" and \"aggregate_seeded\" modes)",
+            requires = "target-proportions"
+        )]
+        target_distribution_column: Option<String>,
+
+        #[structopt(
+            long = "target-proportions",
+            help = "<value>,<proportion> target proportion for a value of the rebalanced column (can be set multiple times)",
+            requires = "target-distribution-column"
+        )]
+        target_proportions: Vec<TargetProportionCmdInput>,
+
+        #[structopt(
+            long = "stream-batch-size",
+            help = "write the synthetic records to the output file in batches of this size as they are generated (records are not sorted and suppression is done on the fly)"
+        )]
+        stream_batch_size: Option<usize>,
     },
     Aggregate {
         #[structopt(long = "aggregates-path", help = "generated aggregates file path")]
@@ -167,8 +258,8 @@ enum Command {
 
         #[structopt(
             long = "noise-threshold-type",
-            help = "threshold type, could be fixed or adaptive",
-            possible_values = &["fixed", "adaptive"],
+            help = "threshold type, could be fixed, adaptive or top_k (keep only the k highest noisy counts)",
+            possible_values = &["fixed", "adaptive", "top_k"],
             case_insensitive = true,
             default_value = "fixed",
         )]
@@ -195,292 +286,2294 @@ enum Command {
         )]
         number_of_records_epsilon_proportion: Option<f64>,
 
+        #[structopt(
+            long = "workload-json",
+            help = "json file with column groups and importance weights used to split the privacy budget within each combination length",
+            requires = "dp"
+        )]
+        workload_json: Option<String>,
+
+        #[structopt(
+            long = "column-hierarchy",
+            help = "<coarsest column>,...,<finest column>, noisy counts across the hierarchy levels are made consistent (children summing to their parent) and kept consistent during synthesis (can be set multiple times)",
+            requires = "dp"
+        )]
+        column_hierarchy: Vec<ColumnHierarchyCmdInput>,
+
+        #[structopt(
+            long = "budget-allocation-path",
+            help = "write how the privacy budget was split across combination lengths and workload weights to this path",
+            requires = "dp"
+        )]
+        budget_allocation_path: Option<String>,
+
         #[structopt(
             long = "aggregates-json",
             help = "serialize aggregated data to json file (sensitive)"
         )]
         aggregates_json: Option<String>,
     },
-}
+    AdviseDp {
+        #[structopt(
+            long = "number-of-records",
+            help = "number of records in the sensitive dataset (or a DP estimate of it)"
+        )]
+        number_of_records: usize,
 
-#[derive(StructOpt, Debug)]
-#[structopt(name = "sds-cli")]
-struct Cli {
-    #[structopt(subcommand)]
-    cmd: Command,
+        #[structopt(
+            long = "attribute-cardinalities",
+            help = "number of distinct values of each column"
+        )]
+        attribute_cardinalities: Vec<usize>,
 
-    /* common parameters */
-    #[structopt(long = "sensitive-path", help = "sensitive microdata path")]
-    sensitive_path: String,
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length to compute attribute combinations"
+        )]
+        reporting_length: usize,
 
-    #[structopt(
-        long = "sensitive-delimiter",
-        help = "csv delimiter for the sensitive microdata file",
-        default_value = "\t"
-    )]
-    sensitive_delimiter: String,
+        #[structopt(
+            long = "target-relative-errors",
+            help = "target relative error of the combination counts for each combination length"
+        )]
+        target_relative_errors: Vec<f64>,
 
-    #[structopt(
-        long = "resolution",
-        help = "minimum threshold to build/evaluate synthetic microdata"
-    )]
-    resolution: usize,
+        #[structopt(
+            long = "noise-delta",
+            help = "delta used to generate noise [default: 1/(ln(number of records) * number of records)]"
+        )]
+        noise_delta: Option<f64>,
 
-    #[structopt(
-        long = "record-limit",
-        help = "take only these first records (0 means all)",
-        default_value = "0"
-    )]
-    record_limit: usize,
+        #[structopt(
+            long = "max-epsilon",
+            help = "maximum acceptable privacy budget, targets requiring more are reported as unreachable"
+        )]
+        max_epsilon: Option<f64>,
 
-    #[structopt(long = "subject-id", help = "column indicating the subject id")]
-    subject_id: Option<String>,
+        #[structopt(
+            long = "recommendation-json",
+            help = "write the recommended parameters to this json file (if not provided, print them)"
+        )]
+        recommendation_json: Option<String>,
+    },
+    RecordsRisk {
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the combinations used to score the records (0 means all)",
+            default_value = "3"
+        )]
+        reporting_length: usize,
 
-    #[structopt(
-        long = "use-columns",
-        help = "use this column (can be set multiple times)"
-    )]
-    use_columns: Vec<String>,
+        #[structopt(
+            long = "top-combinations",
+            help = "number of combinations contributing the most to the risk listed for each record",
+            default_value = "5"
+        )]
+        top_combinations: usize,
 
-    #[structopt(long = "multi-value-columns", help = "<column name>,<delimiter>")]
-    multi_value_columns: Vec<MultiValueColumnCmdInput>,
+        #[structopt(
+            long = "records-risk-path",
+            help = "write the risk of every record containing unique or rare combinations to this tsv file"
+        )]
+        records_risk_path: Option<String>,
 
-    #[structopt(
-        long = "sensitive-zeros",
-        help = "columns where zeros should not be ignored (can be set multiple times)"
-    )]
-    sensitive_zeros: Vec<String>,
+        #[structopt(
+            long = "result-json",
+            help = "write the risk report to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    AttackMembership {
+        #[structopt(
+            long = "holdout-path",
+            help = "microdata from the same population as the sensitive microdata, but not used for synthesis"
+        )]
+        holdout_path: String,
 
-    #[structopt(
-        long = "n-threads",
-        help = "number of threads used to process the data in parallel (default is the number of cores)"
-    )]
-    n_threads: Option<usize>,
-}
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
 
-fn main() {
-    env_logger::init();
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the holdout and synthetic microdata files",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
 
-    let cli = Cli::from_args();
-    let mut progress_reporter = if log_enabled!(Debug) {
-        Some(LoggerProgressReporter::new(Debug))
-    } else {
-        None
-    };
+        #[structopt(
+            long = "score",
+            help = "how the attacker scores the target records: distance (columns matching the closest synthetic record) or density (proportion of synthetic records within \"density-radius\")",
+            possible_values = &["distance", "density"],
+            case_insensitive = true,
+            default_value = "distance"
+        )]
+        score: String,
 
-    trace!("execution parameters: {:#?}", cli);
+        #[structopt(
+            long = "density-radius",
+            help = "maximum hamming distance for a synthetic record to count towards the density score",
+            default_value = "0"
+        )]
+        density_radius: usize,
 
-    if let Some(n_threads) = cli.n_threads {
-        set_number_of_threads(n_threads);
-    }
+        #[structopt(
+            long = "confidence-level",
+            help = "confidence level for the attack metrics intervals (default is 0.95)"
+        )]
+        confidence_level: Option<f64>,
 
-    match CsvDataBlockCreator::create(
-        csv::ReaderBuilder::new()
-            .delimiter(cli.sensitive_delimiter.chars().next().unwrap() as u8)
-            .from_path(cli.sensitive_path),
-        cli.subject_id,
-        &cli.use_columns,
-        &cli.multi_value_columns
-            .iter()
-            .map(|mvc| (mvc.column_name.clone(), mvc.attr_delimiter.clone()))
-            .collect(),
-        &cli.sensitive_zeros,
-        cli.record_limit,
-    ) {
-        Ok(data_block) => match cli.cmd {
-            Command::Generate {
-                synthetic_path,
-                synthetic_delimiter,
-                join_multi_value_columns,
-                long_form,
-                cache_max_size,
-                mode,
-                aggregates_json,
-                oversampling_ratio,
-                oversampling_tries,
-                use_synthetic_counts,
-                weight_selection_percentile,
-                aggregate_counts_scale_factor,
-                target_number_of_records,
-            } => {
-                let aggregated_data = aggregates_json.map(|json_path| {
-                    match AggregatedData::read_from_json(&json_path) {
-                        Ok(data) => Arc::new(data),
-                        Err(err) => {
-                            error!("error reading aggregates json file: {}", err);
-                            process::exit(1);
-                        }
-                    }
-                });
+        #[structopt(
+            long = "result-json",
+            help = "write the attack results to this json file (if not provided, print them)"
+        )]
+        result_json: Option<String>,
+    },
+    AttackAttribute {
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
 
-                if (oversampling_ratio.is_some()
-                    || oversampling_tries.is_some()
-                    || mode == "aggregate_seeded")
-                    && aggregated_data.is_none()
-                {
-                    error!("aggregates json file should be provided");
-                    process::exit(1);
-                }
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
 
-                let oversampling_parameters =
-                    if oversampling_ratio.is_some() || oversampling_tries.is_some() {
-                        Some(OversamplingParameters::new(
-                            aggregated_data.clone().unwrap(),
-                            oversampling_ratio,
-                            oversampling_tries,
-                        ))
-                    } else {
-                        None
-                    };
-                let generator = Generator::default();
-                let generated_data = match mode.as_str() {
-                    "unseeded" => generator.generate_unseeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        &mut progress_reporter,
-                    ),
-                    "row_seeded" => generator.generate_row_seeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        &mut progress_reporter,
-                    ),
-                    "value_seeded" => generator.generate_value_seeded(
-                        &data_block,
-                        cli.resolution,
-                        cache_max_size,
-                        "",
-                        oversampling_parameters,
-                        &mut progress_reporter,
-                    ),
-                    "aggregate_seeded" => generator.generate_aggregate_seeded(
-                        "",
-                        aggregated_data.unwrap(),
-                        use_synthetic_counts,
-                        weight_selection_percentile,
-                        aggregate_counts_scale_factor,
-                        target_number_of_records,
-                        &mut progress_reporter,
-                    ),
-                    _ => {
-                        error!("invalid mode");
-                        process::exit(1);
-                    }
-                };
+        #[structopt(
+            long = "quasi-identifiers",
+            help = "columns known by the attacker (can be set multiple times)",
+            required = true
+        )]
+        quasi_identifiers: Vec<String>,
 
-                if let Err(err) = generated_data.map(|gd| {
-                    gd.write_synthetic_data(
-                        &synthetic_path,
-                        synthetic_delimiter.chars().next().unwrap(),
-                        "",
-                        join_multi_value_columns,
-                        long_form,
-                    )
-                }) {
-                    error!("error writing output file: {}", err);
-                    process::exit(1);
-                }
-            }
-            Command::Aggregate {
-                aggregates_path,
-                aggregates_delimiter,
-                reporting_length,
-                not_protect,
-                records_sensitivity_path,
-                sensitivities_percentile,
-                sensitivities_epsilon_proportion,
-                dp,
-                noise_delta,
-                noise_epsilon,
-                noise_threshold_type,
-                noise_threshold_values,
-                sigma_proportions,
-                number_of_records_epsilon_proportion,
-                aggregates_json,
-            } => {
-                let mut aggregator = Aggregator::new(data_block.clone());
-                let aggregated_data = if dp {
-                    let thresholds_map = noise_threshold_values
-                        .unwrap()
-                        .iter()
-                        .enumerate()
-                        .map(|(i, t)| (i + 2, *t))
-                        .collect();
-
-                    let threshold = match noise_threshold_type.as_str() {
-                        "fixed" => NoisyCountThreshold::Fixed(thresholds_map),
-                        "adaptive" => NoisyCountThreshold::Adaptive(thresholds_map),
-                        _ => {
-                            error!("invalid noise threshold type");
-                            process::exit(1);
-                        }
-                    };
+        #[structopt(long = "target", help = "column the attacker tries to infer")]
+        target: String,
 
-                    match aggregator.aggregate_with_dp(
-                        reporting_length,
-                        &DpParameters::new(
-                            noise_epsilon.unwrap(),
-                            sensitivities_percentile.unwrap(),
-                            sensitivities_epsilon_proportion.unwrap(),
-                            noise_delta,
-                            sigma_proportions,
-                            number_of_records_epsilon_proportion,
-                        ),
-                        threshold,
-                        &mut progress_reporter,
-                    ) {
-                        Err(err) => {
-                            error!("error making aggregates noisy: {}", err);
-                            process::exit(1);
-                        }
-                        Ok(ad) => ad,
-                    }
-                } else {
-                    match aggregator.aggregate(reporting_length, &mut progress_reporter) {
-                        Ok(mut aggregated_data) => {
-                            if !not_protect {
-                                aggregated_data.protect_with_k_anonymity(cli.resolution);
-                            }
-                            aggregated_data
-                        }
-                        Err(err) => {
-                            error!("data aggregation error: {}", err);
-                            process::exit(1);
-                        }
-                    }
-                };
+        #[structopt(
+            long = "inferences-path",
+            help = "write the inference made for each sensitive record to this tsv file"
+        )]
+        inferences_path: Option<String>,
 
-                if let Err(err) = aggregated_data.write_aggregates_count(
-                    &aggregates_path,
-                    aggregates_delimiter.chars().next().unwrap(),
-                    ";",
-                    None,
-                ) {
-                    error!("error writing output file: {}", err);
-                    process::exit(1);
-                }
+        #[structopt(
+            long = "result-json",
+            help = "write the attack results to this json file (if not provided, print the summaries)"
+        )]
+        result_json: Option<String>,
+    },
+    PrivacyModels {
+        #[structopt(
+            long = "quasi-identifiers",
+            help = "columns forming the equivalence classes (can be set multiple times)",
+            required = true
+        )]
+        quasi_identifiers: Vec<String>,
 
-                if let Some(json_path) = aggregates_json {
-                    if let Err(err) = aggregated_data.write_to_json(&json_path) {
-                        error!("error writing aggregates json file: {}", err);
-                        process::exit(1);
-                    }
+        #[structopt(
+            long = "sensitive-columns",
+            help = "columns assessed for l-diversity and t-closeness (can be set multiple times)"
+        )]
+        sensitive_columns: Vec<String>,
+
+        #[structopt(
+            long = "k",
+            help = "equivalence classes smaller than this violate k-anonymity (if not provided, the resolution is used)"
+        )]
+        k: Option<usize>,
+
+        #[structopt(
+            long = "l",
+            help = "equivalence classes less diverse than this violate l-diversity",
+            default_value = "2"
+        )]
+        l: f64,
+
+        #[structopt(
+            long = "l-diversity-measure",
+            help = "how the diversity of the sensitive values is measured",
+            possible_values = &["distinct", "entropy"],
+            case_insensitive = true,
+            default_value = "distinct"
+        )]
+        l_diversity_measure: String,
+
+        #[structopt(
+            long = "t",
+            help = "equivalence classes farther than this from the overall distribution violate t-closeness",
+            default_value = "0.2"
+        )]
+        t: f64,
+
+        #[structopt(
+            long = "synthetic-path",
+            help = "also assess synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: Option<String>,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "offending-classes-path",
+            help = "write the equivalence classes of the sensitive microdata violating any model to this tsv file"
+        )]
+        offending_classes_path: Option<String>,
+
+        #[structopt(
+            long = "synthetic-offending-classes-path",
+            help = "write the equivalence classes of the synthetic microdata violating any model to this tsv file"
+        )]
+        synthetic_offending_classes_path: Option<String>,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the assessment to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    MlUtility {
+        #[structopt(
+            long = "test-path",
+            help = "microdata from the same population as the sensitive microdata, but not used for synthesis"
+        )]
+        test_path: String,
+
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the test and synthetic microdata files",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(long = "target", help = "column the classifiers predict")]
+        target: String,
+
+        #[structopt(
+            long = "features",
+            help = "columns used as features (can be set multiple times, if not provided, all the other columns are used)"
+        )]
+        features: Vec<String>,
+
+        #[structopt(
+            long = "classifiers",
+            help = "classifiers to evaluate (can be set multiple times, if not provided, all of them are used)",
+            possible_values = &["naive_bayes", "logistic_regression", "decision_tree"],
+            case_insensitive = true
+        )]
+        classifiers: Vec<String>,
+
+        #[structopt(
+            long = "max-tree-depth",
+            help = "maximum depth of the decision tree",
+            default_value = "5"
+        )]
+        max_tree_depth: usize,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the evaluation results to this json file (if not provided, print them)"
+        )]
+        result_json: Option<String>,
+    },
+    ClosestRecords {
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "distances-path",
+            help = "write the distance of each synthetic record to the closest sensitive records to this tsv file"
+        )]
+        distances_path: Option<String>,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the distances summary to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    Evaluate {
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the marginals compared between the sensitive and synthetic microdata (0 means all)",
+            default_value = "3"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "significance-level",
+            help = "significance level of the chi-square tests used to report the marginals that differ",
+            default_value = "0.05"
+        )]
+        significance_level: f64,
+
+        #[structopt(
+            long = "marginal-distances-path",
+            help = "write the distances of every marginal to this tsv file"
+        )]
+        marginal_distances_path: Option<String>,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the evaluation report to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    Compare {
+        #[structopt(
+            long = "synthetic-paths",
+            help = "synthetic microdata files generated from the sensitive microdata (can be set multiple times)",
+            required = true
+        )]
+        synthetic_paths: Vec<String>,
+
+        #[structopt(
+            long = "synthetic-names",
+            help = "names identifying each synthetic microdata file, in the same order as --synthetic-paths (if not provided, the paths are used)"
+        )]
+        synthetic_names: Vec<String>,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata files",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the combinations compared between the sensitive and synthetic microdata (0 means all)",
+            default_value = "3"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the comparison to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    UtilityBreakdown {
+        #[structopt(
+            long = "synthetic-path",
+            help = "synthetic microdata generated from the sensitive microdata"
+        )]
+        synthetic_path: String,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata file",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the combinations compared between the sensitive and synthetic microdata (0 means all)",
+            default_value = "3"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "sort-by",
+            help = "metric used to sort the columns and attributes from the worst to the best preserved (if not provided, they are sorted by column and value)",
+            possible_values = &[
+                "mean_preservation",
+                "mean_proportional_error",
+                "suppressed",
+                "fabricated",
+                "mean_abs_error"
+            ],
+            case_insensitive = true
+        )]
+        sort_by: Option<String>,
+
+        #[structopt(
+            long = "columns-path",
+            help = "write the breakdown by column to this tsv file"
+        )]
+        columns_path: Option<String>,
+
+        #[structopt(
+            long = "attributes-path",
+            help = "write the breakdown by attribute to this tsv file"
+        )]
+        attributes_path: Option<String>,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the breakdown to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    EvaluateUncertainty {
+        #[structopt(
+            long = "synthetic-paths",
+            help = "synthetic microdata generated from the sensitive microdata, set it multiple times to summarize generation replicates or once to bootstrap the records",
+            required = true
+        )]
+        synthetic_paths: Vec<String>,
+
+        #[structopt(
+            long = "synthetic-delimiter",
+            help = "csv delimiter for the synthetic microdata files",
+            default_value = "\t"
+        )]
+        synthetic_delimiter: String,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the combinations compared between the sensitive and synthetic microdata (0 means all)",
+            default_value = "3"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "bootstrap-samples",
+            help = "number of times the sensitive and synthetic records are resampled (only used with a single synthetic path)",
+            default_value = "100"
+        )]
+        bootstrap_samples: usize,
+
+        #[structopt(
+            long = "seed",
+            help = "seed used to resample the records",
+            default_value = "0"
+        )]
+        seed: u64,
+
+        #[structopt(
+            long = "confidence-level",
+            help = "confidence level of the metrics intervals",
+            default_value = "0.95"
+        )]
+        confidence_level: f64,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the metrics summary to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+    AuditDp {
+        #[structopt(
+            long = "mechanism",
+            help = "mechanism to be audited: noise_aggregator (noisy aggregates) or percentile (sensitivity percentile selection)",
+            default_value = "noise_aggregator"
+        )]
+        mechanism: String,
+
+        #[structopt(
+            long = "record-index",
+            help = "index of the sensitive record removed to build the neighboring data block",
+            default_value = "0"
+        )]
+        record_index: usize,
+
+        #[structopt(
+            long = "number-of-trials",
+            help = "number of times the mechanism runs on each neighboring data block",
+            default_value = "1000"
+        )]
+        number_of_trials: usize,
+
+        #[structopt(
+            long = "seed",
+            help = "seed used to derive the seeds of every run",
+            default_value = "0"
+        )]
+        seed: u64,
+
+        #[structopt(
+            long = "confidence-level",
+            help = "confidence level for the empirical epsilon lower bound [default: 0.95]"
+        )]
+        confidence_level: Option<f64>,
+
+        #[structopt(long = "epsilon", help = "epsilon claimed by the audited mechanism")]
+        epsilon: f64,
+
+        #[structopt(
+            long = "noise-delta",
+            help = "delta claimed by the noise aggregator (required for \"noise_aggregator\")"
+        )]
+        noise_delta: Option<f64>,
+
+        #[structopt(
+            long = "reporting-length",
+            help = "maximum length of the noisy aggregates (\"noise_aggregator\")",
+            default_value = "1"
+        )]
+        reporting_length: usize,
+
+        #[structopt(
+            long = "sensitivities-percentile",
+            help = "percentile used as record sensitivity filter",
+            default_value = "99"
+        )]
+        sensitivities_percentile: usize,
+
+        #[structopt(
+            long = "sensitivities-epsilon-proportion",
+            help = "proportion of epsilon used to generate noise during sensitivity filter selection (\"noise_aggregator\")",
+            default_value = "0.1"
+        )]
+        sensitivities_epsilon_proportion: f64,
+
+        #[structopt(
+            long = "result-json",
+            help = "write the audit result to this json file (if not provided, print it)"
+        )]
+        result_json: Option<String>,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sds-cli")]
+struct Cli {
+    #[structopt(subcommand)]
+    cmd: Command,
+
+    /* common parameters */
+    #[structopt(
+        long = "sensitive-path",
+        help = "sensitive microdata path (optional when generating on \"aggregate_seeded\" mode from an aggregate count table)"
+    )]
+    sensitive_path: Option<String>,
+
+    #[structopt(
+        long = "sensitive-delimiter",
+        help = "csv delimiter for the sensitive microdata file",
+        default_value = "\t"
+    )]
+    sensitive_delimiter: String,
+
+    #[structopt(
+        long = "resolution",
+        help = "minimum threshold to build/evaluate synthetic microdata"
+    )]
+    resolution: usize,
+
+    #[structopt(
+        long = "record-limit",
+        help = "take only these records (0 means all), how they are selected depends on \"record-sampling\"",
+        default_value = "0"
+    )]
+    record_limit: usize,
+
+    #[structopt(
+        long = "record-sampling",
+        help = "how records are selected: first (take the first \"record-limit\" records), uniform (randomly sample \"record-limit\" subjects) or poisson (keep each subject with probability \"sampling-rate\"), poisson sampling amplifies the DP guarantees",
+        possible_values = &["first", "uniform", "poisson"],
+        case_insensitive = true,
+        default_value = "first"
+    )]
+    record_sampling: String,
+
+    #[structopt(
+        long = "sampling-rate",
+        help = "probability of keeping each subject when \"record-sampling\" is poisson",
+        required_if("record-sampling", "poisson")
+    )]
+    sampling_rate: Option<f64>,
+
+    #[structopt(long = "subject-id", help = "column indicating the subject id")]
+    subject_id: Option<String>,
+
+    #[structopt(
+        long = "max-records-per-subject",
+        help = "keep one record per row instead of joining rows by subject id, sampling at most this many rows per subject (user-level DP)",
+        requires = "subject-id"
+    )]
+    max_records_per_subject: Option<usize>,
+
+    #[structopt(
+        long = "use-columns",
+        help = "use this column (can be set multiple times)"
+    )]
+    use_columns: Vec<String>,
+
+    #[structopt(long = "multi-value-columns", help = "<column name>,<delimiter>")]
+    multi_value_columns: Vec<MultiValueColumnCmdInput>,
+
+    #[structopt(
+        long = "sensitive-zeros",
+        help = "columns where zeros should not be ignored (can be set multiple times)"
+    )]
+    sensitive_zeros: Vec<String>,
+
+    #[structopt(
+        long = "generalization-hierarchy",
+        help = "<column name>,<hierarchy path>, where each line of the hierarchy file (same delimiter as the sensitive file, no headers) contains an original value followed by its generalization at each level (can be set multiple times)"
+    )]
+    generalization_hierarchy: Vec<GeneralizationHierarchyCmdInput>,
+
+    #[structopt(
+        long = "generalization-intervals",
+        help = "<column name>,<width>[,<width>...], generalizes a numeric column into intervals of increasing width at each level (can be set multiple times)"
+    )]
+    generalization_intervals: Vec<GeneralizationIntervalsCmdInput>,
+
+    #[structopt(
+        long = "generalization-target",
+        help = "target percentage of records with rare combinations, the least generalized levels meeting it are applied to the sensitive data before processing"
+    )]
+    generalization_target: Option<f64>,
+
+    #[structopt(
+        long = "generalization-reporting-length",
+        help = "maximum combination length used to find rare combinations during the generalization search",
+        default_value = "3"
+    )]
+    generalization_reporting_length: usize,
+
+    #[structopt(
+        long = "generalization-result-json",
+        help = "write the chosen generalization levels to this json file"
+    )]
+    generalization_result_json: Option<String>,
+
+    #[structopt(
+        long = "n-threads",
+        help = "number of threads used to process the data in parallel (default is the number of cores)"
+    )]
+    n_threads: Option<usize>,
+}
+
+fn main() {
+    env_logger::init();
+
+    let cli = Cli::from_args();
+    let mut progress_reporter = if log_enabled!(Debug) {
+        Some(LoggerProgressReporter::new(Debug))
+    } else {
+        None
+    };
+
+    trace!("execution parameters: {:#?}", cli);
+
+    if let Some(n_threads) = cli.n_threads {
+        set_number_of_threads(n_threads);
+    }
+
+    let data_block = match &cli.sensitive_path {
+        Some(sensitive_path) => match read_sensitive_data_block(&cli, sensitive_path) {
+            Ok(data_block) => Some(generalize_sensitive_data_block(
+                &cli,
+                data_block,
+                &mut progress_reporter,
+            )),
+            Err(err) => {
+                error!("error generating data block from path: {}", err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    match cli.cmd {
+        Command::Generate {
+            synthetic_path,
+            synthetic_delimiter,
+            join_multi_value_columns,
+            long_form,
+            cache_max_size,
+            mode,
+            aggregates_json,
+            aggregates_tsv,
+            aggregates_tsv_delimiter,
+            oversampling_ratio,
+            oversampling_tries,
+            use_synthetic_counts,
+            weight_selection_percentile,
+            aggregate_counts_scale_factor,
+            target_number_of_records,
+            marginal_epsilon,
+            marginal_delta,
+            constraints_json,
+            constraints_report_path,
+            reject_unique_copies,
+            parallel_aggregate_seeded,
+            target_distribution_column,
+            target_proportions,
+            stream_batch_size,
+        } => {
+            let aggregated_data = aggregates_json
+                .map(
+                    |json_path| match AggregatedData::read_from_json(&json_path) {
+                        Ok(data) => Arc::new(data),
+                        Err(err) => {
+                            error!("error reading aggregates json file: {}", err);
+                            process::exit(1);
+                        }
+                    },
+                )
+                .or_else(|| {
+                    aggregates_tsv.map(|tsv_path| {
+                        match AggregatedData::read_aggregates_count(
+                            &tsv_path,
+                            aggregates_tsv_delimiter.chars().next().unwrap(),
+                            ";",
+                        ) {
+                            Ok(data) => Arc::new(data),
+                            Err(err) => {
+                                error!("error reading aggregates tsv file: {}", err);
+                                process::exit(1);
+                            }
+                        }
+                    })
+                });
+
+            if (oversampling_ratio.is_some()
+                || oversampling_tries.is_some()
+                || use_synthetic_counts
+                || weight_selection_percentile.is_some()
+                || aggregate_counts_scale_factor.is_some()
+                || (target_number_of_records.is_some() && mode != "dp_marginal")
+                || mode == "aggregate_seeded")
+                && aggregated_data.is_none()
+            {
+                error!("aggregates json or tsv file should be provided");
+                process::exit(1);
+            }
+
+            // aggregate seeded and value seeded synthesis can run
+            // from the aggregates without the sensitive microdata
+            if mode != "aggregate_seeded" && (mode != "value_seeded" || aggregated_data.is_none()) {
+                expect_data_block(&data_block);
+            }
+
+            let oversampling_parameters =
+                if oversampling_ratio.is_some() || oversampling_tries.is_some() {
+                    Some(OversamplingParameters::new(
+                        aggregated_data.clone().unwrap(),
+                        oversampling_ratio,
+                        oversampling_tries,
+                    ))
+                } else {
+                    None
+                };
+            let synthesis_constraints = constraints_json.map(|json_path| {
+                match SynthesisConstraints::read_from_json(&json_path).and_then(|c| {
+                    c.validate(match (&data_block, &aggregated_data) {
+                        (Some(data_block), _) => &data_block.headers,
+                        (None, Some(aggregated_data)) => &aggregated_data.headers,
+                        _ => unreachable!(),
+                    })?;
+                    Ok(c)
+                }) {
+                    Ok(constraints) => constraints,
+                    Err(err) => {
+                        error!("error reading constraints json file: {}", err);
+                        process::exit(1);
+                    }
+                }
+            });
+            let target_distribution = target_distribution_column.map(|column| {
+                TargetDistribution::new(
+                    column,
+                    target_proportions
+                        .iter()
+                        .map(|tp| (tp.value.clone(), tp.proportion))
+                        .collect(),
+                )
+            });
+
+            if target_distribution.is_some() && mode != "unseeded" && mode != "aggregate_seeded" {
+                error!("target distribution is only supported on \"unseeded\" and \"aggregate_seeded\" modes");
+                process::exit(1);
+            }
+
+            if mode == "dp_marginal" {
+                if marginal_epsilon.is_none() {
+                    error!("marginal epsilon should be provided on \"dp_marginal\" mode");
+                    process::exit(1);
+                }
+                if synthesis_constraints.is_some() || stream_batch_size.is_some() {
+                    error!("constraints and streaming are not supported on \"dp_marginal\" mode");
+                    process::exit(1);
+                }
+                if let Err(err) =
+                    DpMarginalParameters::new(marginal_epsilon.unwrap(), marginal_delta, None, None)
+                        .validate()
+                {
+                    error!("invalid marginal parameters: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if reject_unique_copies && (mode == "aggregate_seeded" || mode == "dp_marginal") {
+                error!("rejecting unique copies is only supported on \"row_seeded\", \"unseeded\" and \"value_seeded\" modes");
+                process::exit(1);
+            }
+
+            if reject_unique_copies && data_block.is_none() {
+                error!("rejecting unique copies requires the sensitive data");
+                process::exit(1);
+            }
+
+            if parallel_aggregate_seeded && mode != "aggregate_seeded" {
+                error!("parallel workers are only supported on \"aggregate_seeded\" mode");
+                process::exit(1);
+            }
+
+            if parallel_aggregate_seeded && stream_batch_size.is_some() {
+                error!("parallel workers are not supported when streaming the synthetic data");
+                process::exit(1);
+            }
+
+            let generator = Generator::new(
+                synthesis_constraints,
+                reject_unique_copies,
+                parallel_aggregate_seeded,
+            );
+            let constraints_report = if let Some(batch_size) = stream_batch_size {
+                let file = match std::fs::File::create(&synthetic_path) {
+                    Ok(file) => file,
+                    Err(err) => {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                };
+                let mut sink = CsvSyntheticDataSink::new(
+                    std::io::BufWriter::new(file),
+                    synthetic_delimiter.chars().next().unwrap(),
+                    "",
+                    match (&data_block, &aggregated_data) {
+                        (Some(data_block), _) => data_block.multi_value_column_metadata_map.clone(),
+                        (None, Some(aggregated_data)) => {
+                            aggregated_data.multi_value_column_metadata_map.clone()
+                        }
+                        _ => unreachable!(),
+                    },
+                    join_multi_value_columns,
+                    long_form,
+                );
+                let summary = match mode.as_str() {
+                    "unseeded" => generator.stream_unseeded(
+                        expect_data_block(&data_block),
+                        cli.resolution,
+                        cache_max_size,
+                        "",
+                        target_distribution,
+                        batch_size,
+                        &mut sink,
+                        &mut progress_reporter,
+                    ),
+                    "row_seeded" => generator.stream_row_seeded(
+                        expect_data_block(&data_block),
+                        cli.resolution,
+                        cache_max_size,
+                        "",
+                        batch_size,
+                        &mut sink,
+                        &mut progress_reporter,
+                    ),
+                    "value_seeded" => match &data_block {
+                        Some(data_block) => generator.stream_value_seeded(
+                            data_block,
+                            cli.resolution,
+                            cache_max_size,
+                            "",
+                            oversampling_parameters,
+                            batch_size,
+                            &mut sink,
+                            &mut progress_reporter,
+                        ),
+                        None => generator.stream_value_seeded_from_aggregates(
+                            aggregated_data.unwrap(),
+                            cli.resolution,
+                            "",
+                            oversampling_parameters,
+                            batch_size,
+                            &mut sink,
+                            &mut progress_reporter,
+                        ),
+                    },
+                    "aggregate_seeded" => generator.stream_aggregate_seeded(
+                        "",
+                        aggregated_data.unwrap(),
+                        use_synthetic_counts,
+                        weight_selection_percentile,
+                        aggregate_counts_scale_factor,
+                        target_number_of_records,
+                        target_distribution,
+                        batch_size,
+                        &mut sink,
+                        &mut progress_reporter,
+                    ),
+                    _ => {
+                        error!("invalid mode");
+                        process::exit(1);
+                    }
+                };
+
+                match summary {
+                    Ok(summary) => summary.constraints_report,
+                    Err(err) => {
+                        error!("error generating synthetic data: {}", err);
+                        process::exit(1);
+                    }
+                }
+            } else {
+                let generated_data = match mode.as_str() {
+                    "unseeded" => generator.generate_unseeded(
+                        expect_data_block(&data_block),
+                        cli.resolution,
+                        cache_max_size,
+                        "",
+                        target_distribution,
+                        &mut progress_reporter,
+                    ),
+                    "row_seeded" => generator.generate_row_seeded(
+                        expect_data_block(&data_block),
+                        cli.resolution,
+                        cache_max_size,
+                        "",
+                        &mut progress_reporter,
+                    ),
+                    "value_seeded" => match &data_block {
+                        Some(data_block) => generator.generate_value_seeded(
+                            data_block,
+                            cli.resolution,
+                            cache_max_size,
+                            "",
+                            oversampling_parameters,
+                            &mut progress_reporter,
+                        ),
+                        None => generator.generate_value_seeded_from_aggregates(
+                            aggregated_data.unwrap(),
+                            cli.resolution,
+                            "",
+                            oversampling_parameters,
+                            &mut progress_reporter,
+                        ),
+                    },
+                    "aggregate_seeded" => generator.generate_aggregate_seeded(
+                        "",
+                        aggregated_data.unwrap(),
+                        use_synthetic_counts,
+                        weight_selection_percentile,
+                        aggregate_counts_scale_factor,
+                        target_number_of_records,
+                        target_distribution,
+                        &mut progress_reporter,
+                    ),
+                    "dp_marginal" => match generator.fit_dp_marginal(
+                        expect_data_block(&data_block),
+                        DpMarginalParameters::new(
+                            marginal_epsilon.unwrap(),
+                            marginal_delta,
+                            None,
+                            None,
+                        ),
+                        &mut progress_reporter,
+                    ) {
+                        Ok(model) => generator.generate_dp_marginal(
+                            &model,
+                            "",
+                            target_number_of_records,
+                            &mut progress_reporter,
+                        ),
+                        Err(err) => {
+                            error!("error fitting the marginal model: {}", err);
+                            process::exit(1);
+                        }
+                    },
+                    _ => {
+                        error!("invalid mode");
+                        process::exit(1);
+                    }
+                };
+
+                let generated_data = match generated_data {
+                    Ok(gd) => gd,
+                    Err(err) => {
+                        error!("error generating synthetic data: {}", err);
+                        process::exit(1);
+                    }
+                };
+
+                if let Err(err) = generated_data.write_synthetic_data(
+                    &synthetic_path,
+                    synthetic_delimiter.chars().next().unwrap(),
+                    "",
+                    join_multi_value_columns,
+                    long_form,
+                ) {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+
+                generated_data.constraints_report
+            };
+
+            if let (Some(path), Some(report)) = (constraints_report_path, &constraints_report) {
+                if let Err(err) = report.write_report(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Aggregate {
+            aggregates_path,
+            aggregates_delimiter,
+            reporting_length,
+            not_protect,
+            records_sensitivity_path,
+            sensitivities_percentile,
+            sensitivities_epsilon_proportion,
+            dp,
+            noise_delta,
+            noise_epsilon,
+            noise_threshold_type,
+            noise_threshold_values,
+            sigma_proportions,
+            number_of_records_epsilon_proportion,
+            workload_json,
+            column_hierarchy,
+            budget_allocation_path,
+            aggregates_json,
+        } => {
+            let data_block = expect_data_block(&data_block);
+            let mut aggregator = Aggregator::new(data_block.clone());
+            let aggregated_data = if dp {
+                let workload = workload_json.map(|json_path| {
+                    match DpWorkload::read_from_json(&json_path).and_then(|w| {
+                        w.validate(
+                            &data_block.headers,
+                            &data_block.multi_value_column_metadata_map,
+                        )?;
+                        Ok(w)
+                    }) {
+                        Ok(workload) => workload,
+                        Err(err) => {
+                            error!("error reading workload json file: {}", err);
+                            process::exit(1);
+                        }
+                    }
+                });
+                let column_hierarchies: Option<Vec<DpColumnHierarchy>> =
+                    if column_hierarchy.is_empty() {
+                        None
+                    } else {
+                        let hierarchies: Vec<DpColumnHierarchy> = column_hierarchy
+                            .into_iter()
+                            .map(|h| DpColumnHierarchy::new(h.columns))
+                            .collect();
+
+                        if let Err(err) = DpColumnHierarchy::validate_all(
+                            &hierarchies,
+                            &data_block.headers,
+                            &data_block.multi_value_column_metadata_map,
+                        ) {
+                            error!("invalid column hierarchy: {}", err);
+                            process::exit(1);
+                        }
+                        Some(hierarchies)
+                    };
+                let thresholds_map: InputValueByLen<f64> = noise_threshold_values
+                    .unwrap()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (i + 2, *t))
+                    .collect();
+
+                let threshold = match noise_threshold_type.as_str() {
+                    "fixed" => NoisyCountThreshold::Fixed(thresholds_map),
+                    "adaptive" => NoisyCountThreshold::Adaptive(thresholds_map),
+                    "top_k" => NoisyCountThreshold::TopK(
+                        thresholds_map
+                            .iter()
+                            .map(|(l, k)| (*l, *k as usize))
+                            .collect(),
+                    ),
+                    _ => {
+                        error!("invalid noise threshold type");
+                        process::exit(1);
+                    }
+                };
+
+                match aggregator.aggregate_with_dp(
+                    reporting_length,
+                    &DpParameters::new(
+                        noise_epsilon.unwrap(),
+                        sensitivities_percentile.unwrap(),
+                        sensitivities_epsilon_proportion.unwrap(),
+                        noise_delta,
+                        sigma_proportions,
+                        number_of_records_epsilon_proportion,
+                        workload,
+                        column_hierarchies,
+                    ),
+                    threshold,
+                    &mut progress_reporter,
+                ) {
+                    Err(err) => {
+                        error!("error making aggregates noisy: {}", err);
+                        process::exit(1);
+                    }
+                    Ok(ad) => ad,
+                }
+            } else {
+                match aggregator.aggregate(reporting_length, &mut progress_reporter) {
+                    Ok(mut aggregated_data) => {
+                        if !not_protect {
+                            aggregated_data.protect_with_k_anonymity(cli.resolution);
+                        }
+                        aggregated_data
+                    }
+                    Err(err) => {
+                        error!("data aggregation error: {}", err);
+                        process::exit(1);
+                    }
+                }
+            };
+
+            if let Err(err) = aggregated_data.write_aggregates_count(
+                &aggregates_path,
+                aggregates_delimiter.chars().next().unwrap(),
+                ";",
+                None,
+            ) {
+                error!("error writing output file: {}", err);
+                process::exit(1);
+            }
+
+            if let Some(json_path) = aggregates_json {
+                if let Err(err) = aggregated_data.write_to_json(&json_path) {
+                    error!("error writing aggregates json file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(path) = records_sensitivity_path {
+                if let Err(err) = aggregated_data.write_records_sensitivity(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let (Some(path), Some(allocation)) = (
+                budget_allocation_path,
+                &aggregated_data.dp_budget_allocation,
+            ) {
+                if let Err(err) = allocation.write_report(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::AdviseDp {
+            number_of_records,
+            attribute_cardinalities,
+            reporting_length,
+            target_relative_errors,
+            noise_delta,
+            max_epsilon,
+            recommendation_json,
+        } => {
+            let recommendation = match DpParametersAdvisor::new(DpAdvisorInputs::new(
+                number_of_records,
+                attribute_cardinalities,
+                reporting_length,
+                target_relative_errors,
+                noise_delta,
+                max_epsilon,
+            ))
+            .recommend()
+            {
+                Ok(recommendation) => recommendation,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+            let dp_parameters = &recommendation.dp_parameters;
+
+            info!(
+                "recommended aggregate parameters: --dp --noise-epsilon {} --noise-delta {} --sensitivities-percentile {} --sensitivities-epsilon-proportion {} --number-of-records-epsilon-proportion {} --sigma-proportions {} --noise-threshold-type adaptive --noise-threshold-values {}",
+                dp_parameters.epsilon,
+                dp_parameters.delta.unwrap_or_default(),
+                dp_parameters.percentile_percentage,
+                dp_parameters.percentile_epsilon_proportion,
+                dp_parameters.number_of_records_epsilon_proportion.unwrap_or_default(),
+                dp_parameters
+                    .sigma_proportions
+                    .clone()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                match &recommendation.threshold {
+                    NoisyCountThreshold::Adaptive(values) | NoisyCountThreshold::Fixed(values) =>
+                        (2..=reporting_length)
+                            .map(|l| values.get(&l).cloned().unwrap_or(1.0).to_string())
+                            .collect::<Vec<String>>()
+                            .join(" "),
+                    NoisyCountThreshold::TopK(values) => (2..=reporting_length)
+                        .map(|l| values.get(&l).cloned().unwrap_or_default().to_string())
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                }
+            );
+
+            let json = serde_json::to_string_pretty(&recommendation).unwrap();
+
+            match recommendation_json {
+                Some(path) => {
+                    if let Err(err) = std::fs::write(&path, json) {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{json}"),
+            }
+        }
+        Command::RecordsRisk {
+            reporting_length,
+            top_combinations,
+            records_risk_path,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let aggregated_data = match Aggregator::new(sensitive_data_block.clone())
+                .aggregate(reporting_length, &mut progress_reporter)
+            {
+                Ok(aggregated_data) => aggregated_data,
+                Err(err) => {
+                    error!("data aggregation error: {}", err);
+                    process::exit(1);
+                }
+            };
+            let records_risk =
+                aggregated_data.calc_records_risk(cli.resolution, top_combinations, ";");
+
+            info!(
+                "{:.2} % of the records contain unique or rare combinations",
+                records_risk.percentage_of_risky_records
+            );
+
+            if let Some(path) = records_risk_path {
+                if let Err(err) = records_risk.write_records_risk(&path, '\t', "|") {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&records_risk).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&records_risk).unwrap()),
+            }
+        }
+        Command::AttackMembership {
+            holdout_path,
+            synthetic_path,
+            synthetic_delimiter,
+            score,
+            density_radius,
+            confidence_level,
+            result_json,
+        } => {
+            let training_data_block = expect_data_block(&data_block);
+            let delimiter = synthetic_delimiter.chars().next().unwrap();
+            let holdout_data_block = match read_evaluation_data_block(
+                &holdout_path,
+                delimiter,
+                cli.subject_id.clone(),
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from holdout path: {}", err);
+                    process::exit(1);
+                }
+            };
+            // synthetic records are never joined by subject
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                delimiter,
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
                 }
+            };
+            let result = match MembershipInferenceAttack::new(MembershipInferenceParameters::new(
+                match score.to_lowercase().as_str() {
+                    "density" => MembershipAttackScore::Density,
+                    _ => MembershipAttackScore::Distance,
+                },
+                density_radius,
+                confidence_level,
+            ))
+            .run(
+                training_data_block,
+                &holdout_data_block,
+                &synthetic_data_block,
+            ) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+            let json = serde_json::to_string_pretty(&result).unwrap();
 
-                if let Some(path) = records_sensitivity_path {
-                    if let Err(err) = aggregated_data.write_records_sensitivity(&path, '\t') {
+            match result_json {
+                Some(path) => {
+                    if let Err(err) = std::fs::write(&path, json) {
                         error!("error writing output file: {}", err);
                         process::exit(1);
                     }
                 }
+                None => println!("{json}"),
             }
-        },
+        }
+        Command::AttackAttribute {
+            synthetic_path,
+            synthetic_delimiter,
+            quasi_identifiers,
+            target,
+            inferences_path,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                synthetic_delimiter.chars().next().unwrap(),
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
+                }
+            };
+            let result = match AttributeInferenceAttack::new(AttributeInferenceParameters::new(
+                quasi_identifiers,
+                target,
+                cli.resolution,
+            ))
+            .run(sensitive_data_block, &synthetic_data_block)
+            {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+
+            if let Some(path) = inferences_path {
+                if let Err(err) = result.write_inferences(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&result).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "overall": result.overall,
+                        "rare": result.rare,
+                        "common": result.common
+                    }))
+                    .unwrap()
+                ),
+            }
+        }
+        Command::PrivacyModels {
+            quasi_identifiers,
+            sensitive_columns,
+            k,
+            l,
+            l_diversity_measure,
+            t,
+            synthetic_path,
+            synthetic_delimiter,
+            offending_classes_path,
+            synthetic_offending_classes_path,
+            result_json,
+        } => {
+            let assessor = PrivacyModelAssessor::new(PrivacyModelParameters::new(
+                quasi_identifiers,
+                sensitive_columns,
+                k.unwrap_or(cli.resolution),
+                l,
+                match l_diversity_measure.to_lowercase().as_str() {
+                    "entropy" => LDiversityMeasure::Entropy,
+                    _ => LDiversityMeasure::Distinct,
+                },
+                t,
+            ));
+            let mut data_blocks = vec![(
+                "sensitive",
+                expect_data_block(&data_block).clone(),
+                offending_classes_path,
+            )];
+
+            if let Some(synthetic_path) = synthetic_path {
+                match read_evaluation_data_block(
+                    &synthetic_path,
+                    synthetic_delimiter.chars().next().unwrap(),
+                    None,
+                    &cli.multi_value_columns,
+                    &cli.sensitive_zeros,
+                ) {
+                    Ok(data_block) => data_blocks.push((
+                        "synthetic",
+                        data_block,
+                        synthetic_offending_classes_path,
+                    )),
+                    Err(err) => {
+                        error!("error generating data block from synthetic path: {}", err);
+                        process::exit(1);
+                    }
+                }
+            }
+
+            let mut report = serde_json::Map::default();
+
+            for (name, data_block, path) in data_blocks {
+                let assessment = match assessor.assess(&data_block) {
+                    Ok(assessment) => assessment,
+                    Err(err) => {
+                        error!("{} microdata: {}", name, err);
+                        process::exit(1);
+                    }
+                };
+
+                if let Some(path) = path {
+                    if let Err(err) = assessment.write_offending_classes(&path, '\t') {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                report.insert(name.to_owned(), serde_json::to_value(&assessment).unwrap());
+            }
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&report).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            }
+        }
+        Command::MlUtility {
+            test_path,
+            synthetic_path,
+            synthetic_delimiter,
+            target,
+            features,
+            classifiers,
+            max_tree_depth,
+            result_json,
+        } => {
+            let training_data_block = expect_data_block(&data_block);
+            let delimiter = synthetic_delimiter.chars().next().unwrap();
+            let test_data_block = match read_evaluation_data_block(
+                &test_path,
+                delimiter,
+                cli.subject_id.clone(),
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from test path: {}", err);
+                    process::exit(1);
+                }
+            };
+            // synthetic records are never joined by subject
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                delimiter,
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
+                }
+            };
+            let mut parameters = MlUtilityParameters::new(target, features);
+
+            if !classifiers.is_empty() {
+                parameters.classifiers = classifiers
+                    .iter()
+                    .map(|c| match c.to_lowercase().as_str() {
+                        "naive_bayes" => MlUtilityClassifier::NaiveBayes,
+                        "logistic_regression" => MlUtilityClassifier::LogisticRegression,
+                        _ => MlUtilityClassifier::DecisionTree,
+                    })
+                    .collect();
+            }
+            parameters.max_tree_depth = max_tree_depth;
+
+            let result = match MlUtilityEvaluator::new(parameters).run(
+                training_data_block,
+                &test_data_block,
+                &synthetic_data_block,
+            ) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&result).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&result).unwrap()),
+            }
+        }
+        Command::ClosestRecords {
+            synthetic_path,
+            synthetic_delimiter,
+            distances_path,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                synthetic_delimiter.chars().next().unwrap(),
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
+                }
+            };
+            let mut result =
+                match ClosestRecordDistanceResult::from_sensitive_and_synthetic_data_blocks(
+                    sensitive_data_block,
+                    &synthetic_data_block,
+                ) {
+                    Ok(result) => result,
+                    Err(err) => {
+                        error!("{}", err);
+                        process::exit(1);
+                    }
+                };
+
+            if let Some(path) = distances_path {
+                if let Err(err) = result.write_distances(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&result).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => {
+                    // per record distances are only printed through the distances path
+                    result.distances.clear();
+                    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                }
+            }
+        }
+        Command::Evaluate {
+            synthetic_path,
+            synthetic_delimiter,
+            reporting_length,
+            significance_level,
+            marginal_distances_path,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                synthetic_delimiter.chars().next().unwrap(),
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
+                }
+            };
+
+            if sensitive_data_block.headers != synthetic_data_block.headers {
+                error!("sensitive and synthetic microdata must have the same columns in the same order");
+                process::exit(1);
+            }
+
+            let mut aggregate = |data_block: &Arc<DataBlock>| -> AggregatedData {
+                match Aggregator::new(data_block.clone())
+                    .aggregate(reporting_length, &mut progress_reporter)
+                {
+                    Ok(aggregated_data) => aggregated_data,
+                    Err(err) => {
+                        error!("data aggregation error: {}", err);
+                        process::exit(1);
+                    }
+                }
+            };
+            let sensitive_aggregated_data = aggregate(sensitive_data_block);
+            let synthetic_aggregated_data = aggregate(&synthetic_data_block);
+            let marginal_distances = Evaluator::default()
+                .calc_marginal_distances(&sensitive_aggregated_data, &synthetic_aggregated_data);
+
+            if let Some(path) = marginal_distances_path {
+                if let Err(err) = marginal_distances.write_marginal_distances(&path, '\t', ";") {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            let report = serde_json::json!({
+                "reportingLength": sensitive_aggregated_data.reporting_length,
+                "marginalDistances": {
+                    "meanTotalVariationDistanceByLen":
+                        marginal_distances.calc_mean_total_variation_distance_by_len(),
+                    "meanJensenShannonDivergenceByLen":
+                        marginal_distances.calc_mean_jensen_shannon_divergence_by_len(),
+                    "meanChiSquareByLen": marginal_distances.calc_mean_chi_square_by_len(),
+                    "percentageOfDifferentMarginalsByLen": marginal_distances
+                        .calc_percentage_of_different_marginals_by_len(significance_level),
+                    "marginals": marginal_distances.marginals,
+                },
+            });
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&report).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            }
+        }
+        Command::Compare {
+            synthetic_paths,
+            synthetic_names,
+            synthetic_delimiter,
+            reporting_length,
+            result_json,
+        } => {
+            let names = if synthetic_names.is_empty() {
+                synthetic_paths.clone()
+            } else if synthetic_names.len() == synthetic_paths.len() {
+                synthetic_names
+            } else {
+                error!("--synthetic-names must be set once for each --synthetic-paths");
+                process::exit(1);
+            };
+            let sensitive_data_block = expect_data_block(&data_block);
+            let mut aggregate = |data_block: &Arc<DataBlock>| -> AggregatedData {
+                match Aggregator::new(data_block.clone())
+                    .aggregate(reporting_length, &mut progress_reporter)
+                {
+                    Ok(aggregated_data) => aggregated_data,
+                    Err(err) => {
+                        error!("data aggregation error: {}", err);
+                        process::exit(1);
+                    }
+                }
+            };
+            let sensitive_aggregated_data = aggregate(sensitive_data_block);
+            let mut synthetic_aggregated_data = Vec::with_capacity(synthetic_paths.len());
+
+            for synthetic_path in synthetic_paths.iter() {
+                let synthetic_data_block = match read_evaluation_data_block(
+                    synthetic_path,
+                    synthetic_delimiter.chars().next().unwrap(),
+                    None,
+                    &cli.multi_value_columns,
+                    &cli.sensitive_zeros,
+                ) {
+                    Ok(data_block) => data_block,
+                    Err(err) => {
+                        error!(
+                            "error generating data block from synthetic path {}: {}",
+                            synthetic_path, err
+                        );
+                        process::exit(1);
+                    }
+                };
+
+                if sensitive_data_block.headers != synthetic_data_block.headers {
+                    error!(
+                        "sensitive and synthetic microdata ({}) must have the same columns in the same order",
+                        synthetic_path
+                    );
+                    process::exit(1);
+                }
+                synthetic_aggregated_data.push(aggregate(&synthetic_data_block));
+            }
+
+            let comparison = Evaluator::default().compare_synthetic_data(
+                &sensitive_aggregated_data,
+                &names
+                    .iter()
+                    .map(|name| name.as_str())
+                    .zip(synthetic_aggregated_data.iter())
+                    .collect::<Vec<_>>(),
+                cli.resolution,
+            );
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&comparison).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&comparison).unwrap()),
+            }
+        }
+        Command::UtilityBreakdown {
+            synthetic_path,
+            synthetic_delimiter,
+            reporting_length,
+            sort_by,
+            columns_path,
+            attributes_path,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let synthetic_data_block = match read_evaluation_data_block(
+                &synthetic_path,
+                synthetic_delimiter.chars().next().unwrap(),
+                None,
+                &cli.multi_value_columns,
+                &cli.sensitive_zeros,
+            ) {
+                Ok(data_block) => data_block,
+                Err(err) => {
+                    error!("error generating data block from synthetic path: {}", err);
+                    process::exit(1);
+                }
+            };
+
+            if sensitive_data_block.headers != synthetic_data_block.headers {
+                error!("sensitive and synthetic microdata must have the same columns in the same order");
+                process::exit(1);
+            }
+
+            let mut aggregate = |data_block: &Arc<DataBlock>| -> AggregatedData {
+                match Aggregator::new(data_block.clone())
+                    .aggregate(reporting_length, &mut progress_reporter)
+                {
+                    Ok(aggregated_data) => aggregated_data,
+                    Err(err) => {
+                        error!("data aggregation error: {}", err);
+                        process::exit(1);
+                    }
+                }
+            };
+            let sensitive_aggregated_data = aggregate(sensitive_data_block);
+            let synthetic_aggregated_data = aggregate(&synthetic_data_block);
+            let mut breakdown = Evaluator::default().calc_utility_breakdown(
+                &sensitive_aggregated_data,
+                &synthetic_aggregated_data,
+                cli.resolution,
+            );
+
+            if let Some(sort_by) = sort_by {
+                breakdown.sort_by_metric(match sort_by.to_lowercase().as_str() {
+                    "mean_preservation" => UtilityBreakdownMetric::MeanPreservation,
+                    "mean_proportional_error" => UtilityBreakdownMetric::MeanProportionalError,
+                    "suppressed" => UtilityBreakdownMetric::PercentageOfSuppressedCombinations,
+                    "fabricated" => UtilityBreakdownMetric::PercentageOfFabricatedCombinations,
+                    _ => UtilityBreakdownMetric::CombinationsCountMeanAbsError,
+                });
+            }
+
+            if let Some(path) = columns_path {
+                if let Err(err) = breakdown.write_columns_breakdown(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            if let Some(path) = attributes_path {
+                if let Err(err) = breakdown.write_attributes_breakdown(&path, '\t') {
+                    error!("error writing output file: {}", err);
+                    process::exit(1);
+                }
+            }
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) =
+                        std::fs::write(&path, serde_json::to_string_pretty(&breakdown).unwrap())
+                    {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{}", serde_json::to_string_pretty(&breakdown).unwrap()),
+            }
+        }
+        Command::EvaluateUncertainty {
+            synthetic_paths,
+            synthetic_delimiter,
+            reporting_length,
+            bootstrap_samples,
+            seed,
+            confidence_level,
+            result_json,
+        } => {
+            let sensitive_data_block = expect_data_block(&data_block);
+            let mut synthetic_data_blocks = Vec::with_capacity(synthetic_paths.len());
+
+            for synthetic_path in synthetic_paths.iter() {
+                let synthetic_data_block = match read_evaluation_data_block(
+                    synthetic_path,
+                    synthetic_delimiter.chars().next().unwrap(),
+                    None,
+                    &cli.multi_value_columns,
+                    &cli.sensitive_zeros,
+                ) {
+                    Ok(data_block) => data_block,
+                    Err(err) => {
+                        error!(
+                            "error generating data block from synthetic path {}: {}",
+                            synthetic_path, err
+                        );
+                        process::exit(1);
+                    }
+                };
+
+                if sensitive_data_block.headers != synthetic_data_block.headers {
+                    error!(
+                        "sensitive and synthetic microdata ({}) must have the same columns in the same order",
+                        synthetic_path
+                    );
+                    process::exit(1);
+                }
+                synthetic_data_blocks.push(synthetic_data_block);
+            }
+
+            let evaluator = Evaluator::default();
+            let result = if synthetic_data_blocks.len() == 1 {
+                evaluator.calc_uncertainty_with_bootstrap(
+                    sensitive_data_block,
+                    &synthetic_data_blocks[0],
+                    reporting_length,
+                    cli.resolution,
+                    &BootstrapParameters::new(bootstrap_samples, seed, Some(confidence_level)),
+                    &mut progress_reporter,
+                )
+            } else {
+                let mut aggregate = |data_block: &Arc<DataBlock>| -> AggregatedData {
+                    match Aggregator::new(data_block.clone())
+                        .aggregate(reporting_length, &mut progress_reporter)
+                    {
+                        Ok(aggregated_data) => aggregated_data,
+                        Err(err) => {
+                            error!("data aggregation error: {}", err);
+                            process::exit(1);
+                        }
+                    }
+                };
+                let sensitive_aggregated_data = aggregate(sensitive_data_block);
+                let synthetic_aggregated_data: Vec<AggregatedData> =
+                    synthetic_data_blocks.iter().map(&mut aggregate).collect();
+
+                evaluator.calc_uncertainty_from_replicates(
+                    &sensitive_aggregated_data,
+                    &synthetic_aggregated_data.iter().collect::<Vec<_>>(),
+                    cli.resolution,
+                    confidence_level,
+                )
+            };
+
+            match result {
+                Ok(uncertainty) => match result_json {
+                    Some(path) => {
+                        if let Err(err) = std::fs::write(
+                            &path,
+                            serde_json::to_string_pretty(&uncertainty).unwrap(),
+                        ) {
+                            error!("error writing output file: {}", err);
+                            process::exit(1);
+                        }
+                    }
+                    None => println!("{}", serde_json::to_string_pretty(&uncertainty).unwrap()),
+                },
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::AuditDp {
+            mechanism,
+            record_index,
+            number_of_trials,
+            seed,
+            confidence_level,
+            epsilon,
+            noise_delta,
+            reporting_length,
+            sensitivities_percentile,
+            sensitivities_epsilon_proportion,
+            result_json,
+        } => {
+            let neighbors = match NeighboringDataBlocks::by_removing_record(
+                expect_data_block(&data_block).clone(),
+                record_index,
+            ) {
+                Ok(neighbors) => neighbors,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+            let auditor = DpAuditor::new(DpAuditParameters::new(
+                number_of_trials,
+                seed,
+                confidence_level,
+            ));
+            let result = match mechanism.as_str() {
+                "noise_aggregator" => auditor.audit_noise_aggregator(
+                    &neighbors,
+                    reporting_length,
+                    &DpParameters::new(
+                        epsilon,
+                        sensitivities_percentile,
+                        sensitivities_epsilon_proportion,
+                        noise_delta,
+                        None,
+                        None,
+                        None,
+                        None,
+                    ),
+                    NoisyCountThreshold::Fixed(InputValueByLen::default()),
+                    &mut progress_reporter,
+                ),
+                "percentile" => auditor.audit_percentile(
+                    &neighbors,
+                    sensitivities_percentile,
+                    epsilon,
+                    &mut progress_reporter,
+                ),
+                _ => {
+                    error!("invalid mechanism");
+                    process::exit(1);
+                }
+            };
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            };
+            let json = serde_json::to_string_pretty(&result).unwrap();
+
+            match result_json {
+                Some(path) => {
+                    if let Err(err) = std::fs::write(&path, json) {
+                        error!("error writing output file: {}", err);
+                        process::exit(1);
+                    }
+                }
+                None => println!("{json}"),
+            }
+
+            if !result.passed {
+                error!(
+                    "empirical epsilon lower bound {:.4} exceeds the claimed epsilon {}",
+                    result.empirical_epsilon.lower_bound, result.claimed_epsilon
+                );
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn read_sensitive_data_block(
+    cli: &Cli,
+    sensitive_path: &str,
+) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+    let reader = csv::ReaderBuilder::new()
+        .delimiter(cli.sensitive_delimiter.chars().next().unwrap() as u8)
+        .from_path(sensitive_path);
+    let multi_value_columns = cli
+        .multi_value_columns
+        .iter()
+        .map(|mvc| (mvc.column_name.clone(), mvc.attr_delimiter.clone()))
+        .collect();
+
+    let record_sampling = match cli.record_sampling.to_lowercase().as_str() {
+        "uniform" => RecordSampling::Uniform,
+        "poisson" => RecordSampling::Poisson(cli.sampling_rate.unwrap_or_default()),
+        _ => RecordSampling::First,
+    };
+
+    match (&cli.subject_id, cli.max_records_per_subject) {
+        (Some(subject_id), Some(max_records_per_subject)) => {
+            CsvDataBlockCreator::create_with_max_records_per_subject(
+                reader,
+                subject_id.clone(),
+                max_records_per_subject,
+                &cli.use_columns,
+                &multi_value_columns,
+                &cli.sensitive_zeros,
+                cli.record_limit,
+                record_sampling,
+            )
+        }
+        (subject_id, _) => CsvDataBlockCreator::create(
+            reader,
+            subject_id.clone(),
+            &cli.use_columns,
+            &multi_value_columns,
+            &cli.sensitive_zeros,
+            cli.record_limit,
+            record_sampling,
+        ),
+    }
+}
+
+/// Applies the least generalized levels meeting the target percentage of
+/// records with rare combinations, when generalization hierarchies are provided
+fn generalize_sensitive_data_block(
+    cli: &Cli,
+    data_block: Arc<DataBlock>,
+    progress_reporter: &mut Option<LoggerProgressReporter>,
+) -> Arc<DataBlock> {
+    if cli.generalization_hierarchy.is_empty() && cli.generalization_intervals.is_empty() {
+        return data_block;
+    }
+
+    // the search looks at the sensitive data without spending any privacy budget
+    if matches!(&cli.cmd, Command::Aggregate { dp: true, .. })
+        || matches!(&cli.cmd, Command::Generate { mode, .. } if mode == "dp_marginal")
+    {
+        error!("generalization hierarchies are not supported with differential privacy, the generalization search is not covered by the privacy budget");
+        process::exit(1);
+    }
+
+    let target = match cli.generalization_target {
+        Some(target) => target,
+        None => {
+            error!("generalization target should be provided along with the hierarchies");
+            process::exit(1);
+        }
+    };
+    let mut hierarchies = GeneralizationHierarchiesByColumn::default();
+
+    for h in cli.generalization_hierarchy.iter() {
+        match GeneralizationHierarchy::from_csv_reader(
+            match csv::ReaderBuilder::new()
+                .delimiter(cli.sensitive_delimiter.chars().next().unwrap() as u8)
+                .has_headers(false)
+                .flexible(true)
+                .from_path(&h.hierarchy_path)
+            {
+                Ok(reader) => reader,
+                Err(err) => {
+                    error!("error reading hierarchy file: {}", err);
+                    process::exit(1);
+                }
+            },
+        ) {
+            Ok(hierarchy) => {
+                hierarchies.insert(h.column_name.clone(), hierarchy);
+            }
+            Err(err) => {
+                error!("error reading hierarchy file: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+    for i in cli.generalization_intervals.iter() {
+        match GeneralizationHierarchy::from_interval_widths(i.widths.clone()) {
+            Ok(hierarchy) => {
+                hierarchies.insert(i.column_name.clone(), hierarchy);
+            }
+            Err(err) => {
+                error!("generalization error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+
+    let generalizer = match Generalizer::new(data_block, &hierarchies) {
+        Ok(generalizer) => generalizer,
+        Err(err) => {
+            error!("generalization error: {}", err);
+            process::exit(1);
+        }
+    };
+    let result = match generalizer.search(
+        cli.generalization_reporting_length,
+        cli.resolution,
+        target,
+        progress_reporter,
+    ) {
+        Ok(result) => result,
         Err(err) => {
-            error!("error generating data block from path: {}", err);
+            error!("generalization error: {}", err);
             process::exit(1);
         }
     };
+
+    if result.target_reached {
+        info!(
+            "generalization reduced the records with rare combinations from {:.2} % to {:.2} %",
+            result.original_percentage_of_records_with_rare_combinations,
+            result.percentage_of_records_with_rare_combinations
+        );
+    } else {
+        warn!(
+            "generalization target not reached, using the levels resulting in {:.2} % of records with rare combinations",
+            result.percentage_of_records_with_rare_combinations
+        );
+    }
+    for c in result.columns.iter() {
+        info!(
+            "column \"{}\" generalized to level {} of {}",
+            c.column, c.level, c.number_of_levels
+        );
+    }
+
+    if let Some(path) = &cli.generalization_result_json {
+        if let Err(err) = std::fs::write(path, serde_json::to_string_pretty(&result).unwrap()) {
+            error!("error writing output file: {}", err);
+            process::exit(1);
+        }
+    }
+
+    match generalizer.generalize(&result.columns) {
+        Ok(data_block) => data_block,
+        Err(err) => {
+            error!("generalization error: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Reads microdata used to evaluate the synthesis (e.g. holdout or synthetic),
+/// all the records and columns are kept, so they can be aligned to the sensitive data
+fn read_evaluation_data_block(
+    path: &str,
+    delimiter: char,
+    subject_id: Option<String>,
+    multi_value_columns: &[MultiValueColumnCmdInput],
+    sensitive_zeros: &[String],
+) -> Result<Arc<DataBlock>, CsvDataBlockCreatorError> {
+    let multi_value_columns = multi_value_columns
+        .iter()
+        .map(|mvc| (mvc.column_name.clone(), mvc.attr_delimiter.clone()))
+        .collect();
+
+    CsvDataBlockCreator::create(
+        csv::ReaderBuilder::new()
+            .delimiter(delimiter as u8)
+            .from_path(path),
+        subject_id,
+        &[],
+        &multi_value_columns,
+        sensitive_zeros,
+        0,
+        RecordSampling::First,
+    )
+}
+
+fn expect_data_block(data_block: &Option<Arc<DataBlock>>) -> &Arc<DataBlock> {
+    match data_block {
+        Some(data_block) => data_block,
+        None => {
+            error!("sensitive microdata path should be provided");
+            process::exit(1);
+        }
+    }
 }
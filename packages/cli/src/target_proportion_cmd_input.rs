@@ -0,0 +1,27 @@
+use std::str::FromStr;
+
+const DELIMITER: char = ',';
+
+#[derive(Debug)]
+pub struct TargetProportionCmdInput {
+    pub value: String,
+    pub proportion: f64,
+}
+
+impl FromStr for TargetProportionCmdInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((value, proportion)) = s.rsplit_once(DELIMITER) {
+            if let Ok(proportion) = proportion.parse::<f64>() {
+                if !value.is_empty() && proportion >= 0.0 {
+                    return Ok(TargetProportionCmdInput {
+                        value: value.to_owned(),
+                        proportion,
+                    });
+                }
+            }
+        }
+        Err("wrong format, expected: <value>,<proportion>".to_owned())
+    }
+}
@@ -23,7 +23,7 @@ use pyo3::prelude::*;
 /// The goal of this is to allow data processing to handle with memory references
 /// to the data block instead of copying data around
 #[cfg_attr(feature = "pyo3", pyclass)]
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct DataBlock {
     /// Vector of strings representing the data headers
     pub headers: DataBlockHeaders,
@@ -31,6 +31,19 @@ pub struct DataBlock {
     pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
     /// Vector of data records, where each record represents a row (headers not included)
     pub records: DataBlockRecords,
+    /// Maximum number of records a single subject contributes with
+    /// (`1` when each record represents a different subject)
+    #[serde(default = "default_max_records_per_subject")]
+    pub max_records_per_subject: usize,
+    /// Probability of a subject being part of the data block, when
+    /// the records were Poisson subsampled (`None` otherwise)
+    #[serde(default)]
+    pub sampling_rate: Option<f64>,
+}
+
+#[inline]
+fn default_max_records_per_subject() -> usize {
+    1
 }
 
 impl Default for DataBlock {
@@ -41,6 +54,8 @@ impl Default for DataBlock {
             headers: DataBlockHeaders::default(),
             multi_value_column_metadata_map: MultiValueColumnMetadataMap::default(),
             records: DataBlockRecords::default(),
+            max_records_per_subject: default_max_records_per_subject(),
+            sampling_rate: None,
         }
     }
 }
@@ -62,6 +77,8 @@ impl DataBlock {
             headers,
             multi_value_column_metadata_map,
             records,
+            max_records_per_subject: default_max_records_per_subject(),
+            sampling_rate: None,
         }
     }
 
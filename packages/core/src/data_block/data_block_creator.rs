@@ -4,6 +4,8 @@ use super::{
     headers_metadata::DataBlockHeadersMetadata,
     input_value::DataBlockInputValue,
     record::DataBlockRecord,
+    record_sampling::RecordSampling,
+    subject_contribution_bounder::SubjectContributionBounder,
     subject_id_joiner::SubjectIdJoiner,
     typedefs::{CsvRecord, DataBlockRecords},
     value::DataBlockValue,
@@ -80,7 +82,62 @@ where
         multi_value_columns: &HashMap<String, String>,
         sensitive_zeros: &[String],
         record_limit: usize,
+        record_sampling: RecordSampling,
     ) -> Result<Arc<DataBlock>, DataBlockCreatorError<Self::ErrorType>> {
+        Self::create_data_block(
+            input_res,
+            subject_id,
+            None,
+            use_columns,
+            multi_value_columns,
+            sensitive_zeros,
+            record_limit,
+            record_sampling,
+        )
+    }
+
+    /// Creates a data block keeping one record per row, instead of joining
+    /// the rows by subject id. At most `max_records_per_subject` rows
+    /// are randomly sampled for each subject, so the contribution
+    /// of a single subject is bounded (for user-level DP)
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn create_with_max_records_per_subject(
+        input_res: Result<Self::InputType, Self::ErrorType>,
+        subject_id: String,
+        max_records_per_subject: usize,
+        use_columns: &[String],
+        multi_value_columns: &HashMap<String, String>,
+        sensitive_zeros: &[String],
+        record_limit: usize,
+        record_sampling: RecordSampling,
+    ) -> Result<Arc<DataBlock>, DataBlockCreatorError<Self::ErrorType>> {
+        Self::create_data_block(
+            input_res,
+            Some(subject_id),
+            Some(max_records_per_subject),
+            use_columns,
+            multi_value_columns,
+            sensitive_zeros,
+            record_limit,
+            record_sampling,
+        )
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn create_data_block(
+        input_res: Result<Self::InputType, Self::ErrorType>,
+        subject_id: Option<String>,
+        max_records_per_subject: Option<usize>,
+        use_columns: &[String],
+        multi_value_columns: &HashMap<String, String>,
+        sensitive_zeros: &[String],
+        record_limit: usize,
+        record_sampling: RecordSampling,
+    ) -> Result<Arc<DataBlock>, DataBlockCreatorError<Self::ErrorType>> {
+        record_sampling.validate()?;
+
         let mut input = input_res.map_err(DataBlockCreatorError::ParsingError)?;
         let headers_metadata = DataBlockHeadersMetadata::new(
             Self::get_headers(&mut input).map_err(DataBlockCreatorError::ParsingError)?,
@@ -89,23 +146,36 @@ where
             multi_value_columns,
             sensitive_zeros,
         );
-        let records_inputs = SubjectIdJoiner::join_records_by_subject_id(
-            CsvRecordInputValues::create_records_input_values(
-                Self::get_records(&mut input).map_err(DataBlockCreatorError::ParsingError)?,
-                &headers_metadata,
-                record_limit,
-            ),
+        let records_inputs = CsvRecordInputValues::create_records_input_values(
+            Self::get_records(&mut input).map_err(DataBlockCreatorError::ParsingError)?,
             &headers_metadata,
-        )?;
+            // random sampling happens after the records are grouped by subject
+            if record_sampling == RecordSampling::First {
+                record_limit
+            } else {
+                0
+            },
+        );
+        let records_inputs = match max_records_per_subject {
+            Some(max_records_per_subject) => {
+                SubjectContributionBounder::bound_records_by_subject_id(
+                    records_inputs,
+                    max_records_per_subject,
+                )?
+            }
+            None => SubjectIdJoiner::join_records_by_subject_id(records_inputs, &headers_metadata)?,
+        };
+        let (records_inputs, sampling_rate) =
+            record_sampling.sample_records_inputs(records_inputs, record_limit);
         let (headers, multi_value_column_metadata_map) =
             headers_metadata.create_headers_and_multi_value_columns_metadata(&records_inputs);
         let records = Self::create_records(&headers, &headers_metadata, records_inputs);
+        let mut data_block = DataBlock::new(headers, multi_value_column_metadata_map, records);
+
+        data_block.max_records_per_subject = max_records_per_subject.unwrap_or(1);
+        data_block.sampling_rate = sampling_rate;
 
-        Ok(Arc::new(DataBlock::new(
-            headers,
-            multi_value_column_metadata_map,
-            records,
-        )))
+        Ok(Arc::new(data_block))
     }
 
     /// Should be implemented to return the CsvRecords representing the headers
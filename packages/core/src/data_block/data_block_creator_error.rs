@@ -18,6 +18,8 @@ where
     /// This is generated while trying to join records using the
     /// Subject ID
     JoinRecordsByIdError(String),
+    /// This is generated when the record sampling parameters are not valid
+    InvalidRecordSampling(String),
 }
 
 impl<T> Display for DataBlockCreatorError<T>
@@ -31,6 +33,7 @@ where
             match self {
                 DataBlockCreatorError::ParsingError(err) => format!("{err}"),
                 DataBlockCreatorError::JoinRecordsByIdError(err) => err.clone(),
+                DataBlockCreatorError::InvalidRecordSampling(err) => err.clone(),
             }
         )
     }
@@ -45,6 +48,7 @@ where
         PyIOError::new_err(match err {
             DataBlockCreatorError::ParsingError(err) => format!("{err}"),
             DataBlockCreatorError::JoinRecordsByIdError(err) => err,
+            DataBlockCreatorError::InvalidRecordSampling(err) => err,
         })
     }
 }
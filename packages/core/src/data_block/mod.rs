@@ -9,6 +9,8 @@ mod input_value;
 mod multi_value_column_metadata;
 mod raw_data;
 mod record;
+mod record_sampling;
+mod subject_contribution_bounder;
 mod subject_id_joiner;
 mod typedefs;
 mod value;
@@ -21,5 +23,6 @@ pub use data_block_creator_error::*;
 pub use multi_value_column_metadata::*;
 pub use raw_data::*;
 pub use record::*;
+pub use record_sampling::*;
 pub use typedefs::*;
 pub use value::*;
@@ -0,0 +1,101 @@
+use super::{csv_record_input_values::CsvRecordInputValues, DataBlockCreatorError};
+use itertools::Itertools;
+use rand::{seq::IteratorRandom, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Strategy used to select the records that will be part of the data block
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", content = "rate")]
+pub enum RecordSampling {
+    /// Take the first `record_limit` records (0 means all).
+    /// This gives no privacy amplification
+    #[default]
+    First,
+    /// Uniformly sample `record_limit` subjects without replacement (0 means all).
+    /// If a subject id is set, all the records of a sampled subject are kept.
+    /// This gives no reported privacy amplification, since the sampling rate depends
+    /// on the number of subjects and the amplification bound only holds for `Poisson`
+    Uniform,
+    /// Keep each subject independently with the given probability (`record_limit` is ignored).
+    /// If a subject id is set, all the records of a sampled subject are kept
+    Poisson(f64),
+}
+
+impl RecordSampling {
+    /// Validates the sampling parameters
+    pub fn validate<ErrorType>(&self) -> Result<(), DataBlockCreatorError<ErrorType>>
+    where
+        ErrorType: Display,
+    {
+        match self {
+            RecordSampling::Poisson(rate) if !(*rate > 0.0 && *rate <= 1.0) => {
+                Err(DataBlockCreatorError::InvalidRecordSampling(
+                    "poisson sampling rate must be > 0 and <= 1".to_owned(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Samples the subjects from `records_inputs` (records with the same non-empty id
+    /// belong to the same subject, otherwise each record is its own subject).
+    ///
+    /// Returns the sampled records and the probability of a subject being sampled,
+    /// which is only set for `Poisson` sampling (the rate is provided by the user,
+    /// so releasing it reveals nothing about the data)
+    pub(crate) fn sample_records_inputs(
+        &self,
+        mut records_inputs: Vec<CsvRecordInputValues>,
+        record_limit: usize,
+    ) -> (Vec<CsvRecordInputValues>, Option<f64>) {
+        if *self == RecordSampling::First {
+            // the record limit has already been applied when reading the records
+            return (records_inputs, None);
+        }
+
+        // sort by key, group by only works on consecutive elements
+        records_inputs.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut subjects: Vec<Vec<CsvRecordInputValues>> = Vec::default();
+
+        for (id, group) in records_inputs
+            .drain(..)
+            .group_by(|record_input| record_input.id.clone())
+            .into_iter()
+        {
+            if id.is_empty() {
+                subjects.extend(group.map(|record_input| vec![record_input]));
+            } else {
+                subjects.push(group.collect());
+            }
+        }
+
+        let number_of_subjects = subjects.len();
+
+        match self {
+            RecordSampling::Uniform if record_limit > 0 && record_limit < number_of_subjects => (
+                subjects
+                    .into_iter()
+                    .choose_multiple(&mut thread_rng(), record_limit)
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                None,
+            ),
+            RecordSampling::Poisson(rate) => {
+                let mut rng = thread_rng();
+
+                (
+                    subjects
+                        .into_iter()
+                        .filter(|_| rng.gen_bool(*rate))
+                        .flatten()
+                        .collect(),
+                    Some(*rate),
+                )
+            }
+            _ => (subjects.into_iter().flatten().collect(), None),
+        }
+    }
+}
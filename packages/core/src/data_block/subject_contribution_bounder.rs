@@ -0,0 +1,53 @@
+use super::{csv_record_input_values::CsvRecordInputValues, DataBlockCreatorError};
+use itertools::Itertools;
+use rand::{seq::IteratorRandom, thread_rng};
+use std::{fmt::Display, marker::PhantomData};
+
+/// Takes the given Subject ID and keeps at most `max_records_per_subject`
+/// records for each subject, without joining them
+pub struct SubjectContributionBounder<ErrorType>
+where
+    ErrorType: Display,
+{
+    phantom: PhantomData<ErrorType>,
+}
+
+impl<ErrorType> SubjectContributionBounder<ErrorType>
+where
+    ErrorType: Display,
+{
+    /// Takes the `records_input` vector and randomly samples at most
+    /// `max_records_per_subject` records for each subject id. Every record
+    /// must have a subject id
+    #[inline]
+    pub fn bound_records_by_subject_id(
+        mut records_inputs: Vec<CsvRecordInputValues>,
+        max_records_per_subject: usize,
+    ) -> Result<Vec<CsvRecordInputValues>, DataBlockCreatorError<ErrorType>> {
+        if max_records_per_subject == 0 {
+            return Err(DataBlockCreatorError::JoinRecordsByIdError(
+                "max records per subject must be > 0".to_owned(),
+            ));
+        }
+        if records_inputs
+            .iter()
+            .any(|record_input| record_input.id.is_empty())
+        {
+            return Err(DataBlockCreatorError::JoinRecordsByIdError(
+                "records with missing/empty Subject ID".to_owned(),
+            ));
+        }
+
+        // sort by key, group by only works on consecutive elements
+        records_inputs.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(records_inputs
+            .drain(..)
+            .group_by(|record_input| record_input.id.clone())
+            .into_iter()
+            .flat_map(|(_id, group)| {
+                group.choose_multiple(&mut thread_rng(), max_records_per_subject)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::utils::reporting::ProcessingStoppedError;
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when auditing a differential privacy mechanism
+#[derive(Debug)]
+pub enum DpAuditError {
+    /// Generated when the audit inputs are not valid
+    InvalidInput(String),
+    /// Generated when the audit is stopped through the progress reporter
+    Stopped(ProcessingStoppedError),
+}
+
+impl Display for DpAuditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DpAuditError::InvalidInput(err) => {
+                write!(f, "invalid dp audit input: {err}")
+            }
+            DpAuditError::Stopped(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<ProcessingStoppedError> for DpAuditError {
+    fn from(err: ProcessingStoppedError) -> DpAuditError {
+        DpAuditError::Stopped(err)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<DpAuditError> for PyErr {
+    fn from(err: DpAuditError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,252 @@
+use super::{DpAuditError, EmpiricalEpsilon, EmpiricalEpsilonEstimator, NeighboringDataBlocks};
+use crate::{
+    data_block::DataBlock,
+    dp::{DpParameters, NoiseAggregator, NoisyCountThreshold},
+    processing::aggregator::{AggregatedData, ValueCombination},
+    utils::{
+        math::calc_percentage,
+        reporting::{ReportProgress, StoppableResult},
+    },
+};
+use log::info;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Default confidence level used for the empirical epsilon bounds
+pub const DEFAULT_DP_AUDIT_CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Mechanism being audited
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DpAuditMechanism {
+    /// Noisy aggregates generated by the `NoiseAggregator`, the audited output
+    /// is the sum of the noisy single attribute counts of the differing record
+    NoiseAggregator,
+    /// Sensitivity percentile selected by the `DpPercentile`, the audited output
+    /// is the selected value (data is the number of attributes of each record)
+    Percentile,
+}
+
+/// Parameters used to audit a mechanism
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DpAuditParameters {
+    /// Number of times the mechanism runs on each neighboring data block
+    pub number_of_trials: usize,
+    /// Seed used to derive the seeds of every run
+    pub seed: u64,
+    /// Confidence level for the empirical epsilon bound (e.g. `0.95`)
+    pub confidence_level: f64,
+}
+
+impl DpAuditParameters {
+    /// Returns a new DpAuditParameters
+    /// # Arguments
+    /// * `number_of_trials` - Number of times the mechanism runs on each neighboring data block
+    /// * `seed` - Seed used to derive the seeds of every run
+    /// * `confidence_level` - Confidence level for the empirical epsilon bound,
+    /// if None will be set to `DEFAULT_DP_AUDIT_CONFIDENCE_LEVEL`
+    #[inline]
+    pub fn new(number_of_trials: usize, seed: u64, confidence_level: Option<f64>) -> Self {
+        DpAuditParameters {
+            number_of_trials,
+            seed,
+            confidence_level: confidence_level.unwrap_or(DEFAULT_DP_AUDIT_CONFIDENCE_LEVEL),
+        }
+    }
+}
+
+/// Result of auditing a mechanism
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DpAuditResult {
+    /// Audited mechanism
+    pub mechanism: DpAuditMechanism,
+    /// Epsilon the mechanism claims to satisfy
+    pub claimed_epsilon: f64,
+    /// Delta the mechanism claims to satisfy
+    pub claimed_delta: f64,
+    /// Number of times the mechanism ran on each neighboring data block
+    pub number_of_trials: usize,
+    /// Confidence level of the empirical bound
+    pub confidence_level: f64,
+    /// Empirical lower bound on epsilon
+    pub empirical_epsilon: EmpiricalEpsilon,
+    /// Whether the empirical lower bound does not exceed the claimed epsilon
+    /// (`false` means the mechanism does not satisfy its guarantee)
+    pub passed: bool,
+}
+
+/// Runs a mechanism many times on neighboring data blocks and
+/// checks whether its outputs are consistent with the claimed epsilon
+pub struct DpAuditor {
+    parameters: DpAuditParameters,
+}
+
+impl DpAuditor {
+    /// Returns a new DpAuditor
+    /// # Arguments
+    /// * `parameters` - Parameters used to audit the mechanisms
+    #[inline]
+    pub fn new(parameters: DpAuditParameters) -> DpAuditor {
+        DpAuditor { parameters }
+    }
+
+    /// Audits the `NoiseAggregator`
+    /// # Arguments
+    /// * `neighbors` - Data blocks differing on a single record
+    /// * `reporting_length` - Maximum combination length to process
+    /// * `dp_parameters` - Differential privacy parameters (`delta` needs to be set,
+    /// so both data blocks use the same value)
+    /// * `threshold` - Threshold used to filter noisy counts
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn audit_noise_aggregator<T>(
+        &self,
+        neighbors: &NeighboringDataBlocks,
+        reporting_length: usize,
+        dp_parameters: &DpParameters,
+        threshold: NoisyCountThreshold,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<DpAuditResult, DpAuditError>
+    where
+        T: ReportProgress,
+    {
+        let delta = dp_parameters.delta.ok_or_else(|| {
+            DpAuditError::InvalidInput("delta must be set to audit the noise aggregator".to_owned())
+        })?;
+        let record_combinations: Vec<ValueCombination> = neighbors
+            .record
+            .values
+            .iter()
+            .map(|v| ValueCombination::new(vec![v.clone()]))
+            .collect();
+        let run = |data_block: &Arc<DataBlock>, seed: u64| -> StoppableResult<f64> {
+            let aggregated_data = NoiseAggregator::new(
+                data_block.clone(),
+                reporting_length,
+                dp_parameters,
+                threshold.clone(),
+            )
+            .with_seed(seed)
+            .generate_noisy_aggregates(&mut None::<T>)?;
+
+            Ok(Self::sum_counts(&aggregated_data, &record_combinations))
+        };
+
+        self.audit(
+            DpAuditMechanism::NoiseAggregator,
+            dp_parameters.epsilon,
+            delta,
+            neighbors,
+            run,
+            progress_reporter,
+        )
+    }
+
+    /// Audits the `DpPercentile` selection.
+    /// The selectable values range from `0` to the number of columns, so the range
+    /// does not change when the record holding the maximum is removed
+    /// # Arguments
+    /// * `neighbors` - Data blocks differing on a single record
+    /// * `percentile_percentage` - Percentage used to calculate the percentile
+    /// * `epsilon` - Epsilon used to select the percentile
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn audit_percentile<T>(
+        &self,
+        neighbors: &NeighboringDataBlocks,
+        percentile_percentage: usize,
+        epsilon: f64,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<DpAuditResult, DpAuditError>
+    where
+        T: ReportProgress,
+    {
+        let run = |data_block: &Arc<DataBlock>, seed: u64| -> StoppableResult<f64> {
+            // same selection the `NoiseAggregator` uses for the single attribute counts
+            Ok(NoiseAggregator::select_allowed_sensitivity(
+                data_block.records.iter().map(|r| r.values.len()).collect(),
+                data_block.headers.len(),
+                1,
+                percentile_percentage,
+                epsilon,
+                &mut StdRng::seed_from_u64(seed),
+            ) as f64)
+        };
+
+        self.audit(
+            DpAuditMechanism::Percentile,
+            epsilon,
+            0.0,
+            neighbors,
+            run,
+            progress_reporter,
+        )
+    }
+
+    fn audit<F, T>(
+        &self,
+        mechanism: DpAuditMechanism,
+        claimed_epsilon: f64,
+        claimed_delta: f64,
+        neighbors: &NeighboringDataBlocks,
+        run: F,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<DpAuditResult, DpAuditError>
+    where
+        F: Fn(&Arc<DataBlock>, u64) -> StoppableResult<f64>,
+        T: ReportProgress,
+    {
+        let estimator =
+            EmpiricalEpsilonEstimator::new(claimed_delta, self.parameters.confidence_level)?;
+        let mut seeds = StdRng::seed_from_u64(self.parameters.seed);
+        let mut outputs_with_record = Vec::with_capacity(self.parameters.number_of_trials);
+        let mut outputs_without_record = Vec::with_capacity(self.parameters.number_of_trials);
+
+        info!(
+            "auditing {:?} with {} trial(s) per neighboring data block",
+            mechanism, self.parameters.number_of_trials
+        );
+
+        for trial in 0..self.parameters.number_of_trials {
+            outputs_with_record.push(run(&neighbors.with_record, seeds.gen())?);
+            outputs_without_record.push(run(&neighbors.without_record, seeds.gen())?);
+
+            if let Some(r) = progress_reporter.as_mut() {
+                r.report(calc_percentage(
+                    (trial + 1) as f64,
+                    self.parameters.number_of_trials as f64,
+                ))?;
+            }
+        }
+
+        let empirical_epsilon =
+            estimator.estimate(&outputs_with_record, &outputs_without_record)?;
+        let result = DpAuditResult {
+            mechanism,
+            claimed_epsilon,
+            claimed_delta,
+            number_of_trials: self.parameters.number_of_trials,
+            confidence_level: self.parameters.confidence_level,
+            passed: empirical_epsilon.lower_bound <= claimed_epsilon,
+            empirical_epsilon,
+        };
+
+        info!(
+            "{:?} audit: claimed epsilon = {}, empirical epsilon lower bound = {:.4}",
+            mechanism, result.claimed_epsilon, result.empirical_epsilon.lower_bound
+        );
+
+        Ok(result)
+    }
+
+    #[inline]
+    fn sum_counts(aggregated_data: &AggregatedData, combinations: &[ValueCombination]) -> f64 {
+        combinations
+            .iter()
+            .filter_map(|comb| aggregated_data.aggregates_count.get(comb))
+            .map(|count| count.count as f64)
+            .sum()
+    }
+}
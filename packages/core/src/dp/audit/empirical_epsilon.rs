@@ -0,0 +1,213 @@
+use super::DpAuditError;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{Beta, ContinuousCDF};
+use std::cmp::Ordering;
+
+/// Output event used to distinguish the neighboring data blocks
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DistinguishingEvent {
+    /// Outputs are compared against this threshold
+    pub threshold: f64,
+    /// Whether the event is `output >= threshold` (otherwise `output < threshold`)
+    pub greater_or_equal: bool,
+    /// Whether the event is more likely on the data block with the differing record
+    pub more_likely_with_record: bool,
+}
+
+impl DistinguishingEvent {
+    #[inline]
+    fn contains(&self, output: f64) -> bool {
+        (output >= self.threshold) == self.greater_or_equal
+    }
+
+    #[inline]
+    fn count(&self, outputs: &[f64]) -> usize {
+        outputs.iter().filter(|o| self.contains(**o)).count()
+    }
+}
+
+/// Empirical lower bound on epsilon and the event reaching it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmpiricalEpsilon {
+    /// Epsilon lower bound holding with the estimator confidence level
+    pub lower_bound: f64,
+    /// Event used to compute the bound (`None` if no event gave a positive bound)
+    pub event: Option<DistinguishingEvent>,
+}
+
+/// Estimates lower bounds on epsilon from the outputs of a mechanism run
+/// on two neighboring data blocks, using the hypothesis testing interpretation
+/// of differential privacy: for any output event `S`,
+/// `Pr[M(D) in S] <= e^epsilon * Pr[M(D') in S] + delta`.
+///
+/// Half of the outputs is used to choose the event and the other half to bound
+/// both probabilities with Clopper-Pearson intervals, so the bound holds with
+/// the requested confidence level
+pub struct EmpiricalEpsilonEstimator {
+    delta: f64,
+    confidence_level: f64,
+}
+
+impl EmpiricalEpsilonEstimator {
+    /// Returns a new EmpiricalEpsilonEstimator
+    /// # Arguments
+    /// * `delta` - Delta claimed by the mechanism
+    /// * `confidence_level` - Probability of the bound holding (e.g. `0.95`)
+    pub fn new(delta: f64, confidence_level: f64) -> Result<Self, DpAuditError> {
+        if !(0.0..1.0).contains(&delta) {
+            return Err(DpAuditError::InvalidInput(
+                "delta must be >= 0 and < 1".to_owned(),
+            ));
+        }
+        if confidence_level <= 0.0 || confidence_level >= 1.0 {
+            return Err(DpAuditError::InvalidInput(
+                "confidence level must be > 0 and < 1".to_owned(),
+            ));
+        }
+        Ok(EmpiricalEpsilonEstimator {
+            delta,
+            confidence_level,
+        })
+    }
+
+    /// Estimates the epsilon lower bound
+    /// # Arguments
+    /// * `outputs_with_record` - Scalar outputs of the mechanism on the data block
+    /// containing the differing record
+    /// * `outputs_without_record` - Scalar outputs of the mechanism on the data block
+    /// without the differing record
+    pub fn estimate(
+        &self,
+        outputs_with_record: &[f64],
+        outputs_without_record: &[f64],
+    ) -> Result<EmpiricalEpsilon, DpAuditError> {
+        if outputs_with_record.len() < 2 || outputs_without_record.len() < 2 {
+            return Err(DpAuditError::InvalidInput(
+                "at least 2 outputs are needed for each data block".to_owned(),
+            ));
+        }
+
+        let (selection_with, bound_with) =
+            outputs_with_record.split_at(outputs_with_record.len() / 2);
+        let (selection_without, bound_without) =
+            outputs_without_record.split_at(outputs_without_record.len() / 2);
+        let event = self.select_event(selection_with, selection_without);
+
+        Ok(match event {
+            Some(event) => {
+                let lower_bound = self.calc_lower_bound(&event, bound_with, bound_without);
+
+                EmpiricalEpsilon {
+                    lower_bound,
+                    event: if lower_bound > 0.0 { Some(event) } else { None },
+                }
+            }
+            None => EmpiricalEpsilon {
+                lower_bound: 0.0,
+                event: None,
+            },
+        })
+    }
+
+    /// Picks the event with the highest bound on the selection outputs
+    fn select_event(
+        &self,
+        outputs_with_record: &[f64],
+        outputs_without_record: &[f64],
+    ) -> Option<DistinguishingEvent> {
+        outputs_with_record
+            .iter()
+            .chain(outputs_without_record.iter())
+            .cloned()
+            .sorted_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .dedup()
+            .flat_map(|threshold| {
+                [(true, true), (true, false), (false, true), (false, false)].map(
+                    |(greater_or_equal, more_likely_with_record)| DistinguishingEvent {
+                        threshold,
+                        greater_or_equal,
+                        more_likely_with_record,
+                    },
+                )
+            })
+            .map(|event| {
+                (
+                    self.calc_lower_bound(&event, outputs_with_record, outputs_without_record),
+                    event,
+                )
+            })
+            .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(_, event)| event)
+    }
+
+    fn calc_lower_bound(
+        &self,
+        event: &DistinguishingEvent,
+        outputs_with_record: &[f64],
+        outputs_without_record: &[f64],
+    ) -> f64 {
+        let (more_likely, less_likely) = if event.more_likely_with_record {
+            (outputs_with_record, outputs_without_record)
+        } else {
+            (outputs_without_record, outputs_with_record)
+        };
+        // each probability is bounded with half of the allowed error (union bound)
+        let alpha = (1.0 - self.confidence_level) / 2.0;
+        let p_more_likely =
+            Self::clopper_pearson_lower(event.count(more_likely), more_likely.len(), alpha);
+        let p_less_likely =
+            Self::clopper_pearson_upper(event.count(less_likely), less_likely.len(), alpha);
+
+        if p_more_likely - self.delta <= 0.0 {
+            0.0
+        } else {
+            ((p_more_likely - self.delta) / p_less_likely).ln().max(0.0)
+        }
+    }
+
+    /// One-sided lower bound for a binomial proportion
+    #[inline]
+    fn clopper_pearson_lower(successes: usize, trials: usize, alpha: f64) -> f64 {
+        if successes == 0 {
+            0.0
+        } else {
+            Self::beta_quantile(successes as f64, (trials - successes + 1) as f64, alpha)
+        }
+    }
+
+    /// One-sided upper bound for a binomial proportion
+    #[inline]
+    fn clopper_pearson_upper(successes: usize, trials: usize, alpha: f64) -> f64 {
+        if successes == trials {
+            1.0
+        } else {
+            Self::beta_quantile(
+                (successes + 1) as f64,
+                (trials - successes) as f64,
+                1.0 - alpha,
+            )
+        }
+    }
+
+    /// Beta quantile by bisection (the default statrs inverse cdf
+    /// is not precise enough for small probabilities)
+    fn beta_quantile(a: f64, b: f64, p: f64) -> f64 {
+        let beta = Beta::new(a, b).expect("error creating Beta for inverse CDF");
+        let mut low = 0.0;
+        let mut high = 1.0;
+
+        for _ in 0..64 {
+            let mid = (low + high) / 2.0;
+
+            if beta.cdf(mid) >= p {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+}
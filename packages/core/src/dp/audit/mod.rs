@@ -0,0 +1,9 @@
+mod dp_audit_error;
+mod dp_auditor;
+mod empirical_epsilon;
+mod neighboring_data_blocks;
+
+pub use dp_audit_error::*;
+pub use dp_auditor::*;
+pub use empirical_epsilon::*;
+pub use neighboring_data_blocks::*;
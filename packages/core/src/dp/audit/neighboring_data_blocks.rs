@@ -0,0 +1,81 @@
+use super::DpAuditError;
+use crate::data_block::{DataBlock, DataBlockRecord};
+use std::sync::Arc;
+
+/// Pair of data blocks differing on a single record
+pub struct NeighboringDataBlocks {
+    /// Data block containing the differing record
+    pub with_record: Arc<DataBlock>,
+    /// Data block without the differing record
+    pub without_record: Arc<DataBlock>,
+    /// Record present only on `with_record`
+    pub record: Arc<DataBlockRecord>,
+}
+
+impl NeighboringDataBlocks {
+    /// Builds the neighbor of `data_block` by removing one of its records
+    /// # Arguments
+    /// * `data_block` - Data block containing the record
+    /// * `record_index` - Index of the record to be removed
+    pub fn by_removing_record(
+        data_block: Arc<DataBlock>,
+        record_index: usize,
+    ) -> Result<NeighboringDataBlocks, DpAuditError> {
+        if record_index >= data_block.number_of_records() {
+            return Err(DpAuditError::InvalidInput(format!(
+                "record index {} is out of bounds for {} record(s)",
+                record_index,
+                data_block.number_of_records()
+            )));
+        }
+
+        let mut records = data_block.records.clone();
+        let record = records.remove(record_index);
+
+        Ok(NeighboringDataBlocks {
+            without_record: Self::with_records(&data_block, records),
+            with_record: data_block,
+            record,
+        })
+    }
+
+    /// Builds the neighbor of `data_block` by adding a new record to it
+    /// # Arguments
+    /// * `data_block` - Data block without the record
+    /// * `record` - Record to be added (values should refer to the `data_block` columns)
+    pub fn by_adding_record(
+        data_block: Arc<DataBlock>,
+        record: Arc<DataBlockRecord>,
+    ) -> Result<NeighboringDataBlocks, DpAuditError> {
+        if record
+            .values
+            .iter()
+            .any(|v| v.column_index >= data_block.headers.len())
+        {
+            return Err(DpAuditError::InvalidInput(
+                "added record refers to columns not present on the data block".to_owned(),
+            ));
+        }
+
+        let mut records = data_block.records.clone();
+
+        records.push(record.clone());
+
+        Ok(NeighboringDataBlocks {
+            with_record: Self::with_records(&data_block, records),
+            without_record: data_block,
+            record,
+        })
+    }
+
+    #[inline]
+    fn with_records(data_block: &DataBlock, records: Vec<Arc<DataBlockRecord>>) -> Arc<DataBlock> {
+        Arc::new(DataBlock {
+            headers: data_block.headers.clone(),
+            multi_value_column_metadata_map: data_block.multi_value_column_metadata_map.clone(),
+            records,
+            max_records_per_subject: data_block.max_records_per_subject,
+            sampling_rate: data_block.sampling_rate,
+        })
+    }
+}
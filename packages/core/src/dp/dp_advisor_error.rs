@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when recommending DP parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DpAdvisorError {
+    /// Generated when the advisor inputs are not valid
+    InvalidInput(String),
+    /// Generated when the target errors can not be reached,
+    /// explaining why
+    Unreachable(String),
+}
+
+impl Display for DpAdvisorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DpAdvisorError::InvalidInput(err) => {
+                write!(f, "invalid dp advisor input: {err}")
+            }
+            DpAdvisorError::Unreachable(err) => {
+                write!(f, "target errors are unreachable: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<DpAdvisorError> for PyErr {
+    fn from(err: DpAdvisorError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
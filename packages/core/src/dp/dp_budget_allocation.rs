@@ -0,0 +1,99 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, Write};
+
+use crate::utils::time::ElapsedDurationLogger;
+
+/// Privacy budget spent to report the combinations
+/// of a given length and workload weight
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DpBudgetShare {
+    /// Length of the reported combinations
+    pub combination_length: usize,
+    /// Workload weight of the reported combinations
+    pub weight: f64,
+    /// Epsilon used to select the allowed sensitivity with the percentile filter
+    pub percentile_epsilon: f64,
+    /// Sigma used to add gaussian noise (before scaling by the allowed sensitivity)
+    pub sigma: f64,
+    /// Allowed sensitivity selected by the percentile filter
+    pub allowed_sensitivity: usize,
+    /// zCDP rho spent by the percentile filter and the gaussian noise
+    pub rho: f64,
+    /// Proportion of the marginals budget spent by this share
+    pub budget_proportion: f64,
+    /// Number of combinations kept after adding noise and thresholding
+    pub number_of_combinations: usize,
+}
+
+/// Report on how the privacy budget was split when generating
+/// the noisy aggregates
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DpBudgetAllocation {
+    /// Overall privacy budget
+    pub epsilon: f64,
+    /// Delta used for noisy generation
+    pub delta: f64,
+    /// Epsilon spent to protect the number of records
+    pub number_of_records_epsilon: f64,
+    /// Epsilon spent to generate the noisy combination counts
+    pub marginals_epsilon: f64,
+    /// zCDP rho equivalent to `marginals_epsilon`
+    pub marginals_rho: f64,
+    /// Budget spent by combination length and workload weight
+    pub shares: Vec<DpBudgetShare>,
+    /// Probability of a subject being part of the aggregated data,
+    /// when the records were Poisson subsampled
+    #[serde(default)]
+    pub sampling_rate: Option<f64>,
+    /// Epsilon guaranteed for the whole dataset, after amplification by subsampling
+    /// (same as `epsilon` if there was no subsampling)
+    #[serde(default)]
+    pub amplified_epsilon: f64,
+    /// Delta guaranteed for the whole dataset, after amplification by subsampling
+    /// (same as `delta` if there was no subsampling)
+    #[serde(default)]
+    pub amplified_delta: f64,
+}
+
+impl DpBudgetAllocation {
+    /// Writes the budget shares to the file system in a csv/tsv like format
+    /// # Arguments:
+    /// * `report_path` - File path to be written
+    /// * `report_delimiter` - Delimiter to use when writing to `report_path`
+    pub fn write_report(&self, report_path: &str, report_delimiter: char) -> Result<(), Error> {
+        info!("writing file: {}", report_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write dp budget allocation");
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(report_path)?);
+        let d = report_delimiter;
+
+        file.write_all(
+            format!(
+                "combination_length{d}weight{d}percentile_epsilon{d}sigma{d}allowed_sensitivity{d}rho{d}budget_proportion{d}number_of_combinations\n"
+            )
+            .as_bytes(),
+        )?;
+
+        for s in self.shares.iter() {
+            file.write_all(
+                format!(
+                    "{}{d}{}{d}{}{d}{}{d}{}{d}{}{d}{}{d}{}\n",
+                    s.combination_length,
+                    s.weight,
+                    s.percentile_epsilon,
+                    s.sigma,
+                    s.allowed_sensitivity,
+                    s.rho,
+                    s.budget_proportion,
+                    s.number_of_combinations
+                )
+                .as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+use crate::data_block::{DataBlockHeadersSlice, MultiValueColumnMetadataMap};
+
+/// Columns forming a hierarchy, such as country/state/county
+/// or ICD chapter/block/code. Noisy counts of the combinations
+/// made of the hierarchy levels are post-processed to be consistent
+/// (children never adding up to more than their parent)
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DpColumnHierarchy {
+    /// Hierarchy columns, from the coarsest to the finest level
+    pub columns: Vec<String>,
+}
+
+impl DpColumnHierarchy {
+    /// Returns the column indexes of the hierarchy levels, from the
+    /// coarsest to the finest, or `None` if any column is not part of `headers`
+    /// # Arguments
+    /// * `headers` - Headers of the data
+    pub fn column_indexes(&self, headers: &DataBlockHeadersSlice) -> Option<Vec<usize>> {
+        self.columns
+            .iter()
+            .map(|column| headers.iter().position(|h| **h == *column))
+            .collect()
+    }
+
+    /// Checks whether the hierarchies are valid for the data `headers`
+    /// (every hierarchy should have at least two existing columns,
+    /// which can not be multi-value columns nor be part of another hierarchy)
+    /// # Arguments
+    /// * `hierarchies` - Hierarchies to be validated
+    /// * `headers` - Headers of the data being aggregated
+    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name
+    /// to its corresponding metadata
+    pub fn validate_all(
+        hierarchies: &[DpColumnHierarchy],
+        headers: &DataBlockHeadersSlice,
+        multi_value_column_metadata_map: &MultiValueColumnMetadataMap,
+    ) -> Result<(), Error> {
+        let mut used_columns: Vec<&String> = Vec::default();
+
+        for hierarchy in hierarchies.iter() {
+            if hierarchy.columns.len() < 2 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "column hierarchies should have at least two columns",
+                ));
+            }
+            for column in hierarchy.columns.iter() {
+                if multi_value_column_metadata_map
+                    .values()
+                    .any(|m| *m.src_header_name == *column)
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("multi-value column \"{column}\" can not be part of a hierarchy"),
+                    ));
+                }
+                if !headers.iter().any(|h| **h == *column) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("hierarchy column \"{column}\" not found in the data headers"),
+                    ));
+                }
+                if used_columns.contains(&column) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("column \"{column}\" is used more than once in the hierarchies"),
+                    ));
+                }
+                used_columns.push(column);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl DpColumnHierarchy {
+    #[inline]
+    #[cfg(feature = "pyo3")]
+    #[new]
+    /// Creates a new DpColumnHierarchy structure
+    /// # Arguments
+    /// * `columns` - Hierarchy columns, from the coarsest to the finest level
+    pub fn new(columns: Vec<String>) -> Self {
+        DpColumnHierarchy { columns }
+    }
+
+    #[inline]
+    #[cfg(not(feature = "pyo3"))]
+    /// Creates a new DpColumnHierarchy structure
+    /// # Arguments
+    /// * `columns` - Hierarchy columns, from the coarsest to the finest level
+    pub fn new(columns: Vec<String>) -> Self {
+        DpColumnHierarchy { columns }
+    }
+}
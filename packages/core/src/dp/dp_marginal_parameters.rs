@@ -0,0 +1,121 @@
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+/// Default proportion of the privacy budget used to measure
+/// the single attribute counts
+pub const DEFAULT_SINGLE_ATTRIBUTES_BUDGET_PROPORTION: f64 = 1.0 / 3.0;
+
+/// Default proportion of the privacy budget used to select
+/// which pairs of columns should be measured
+pub const DEFAULT_SELECTION_BUDGET_PROPORTION: f64 = 1.0 / 3.0;
+
+/// Parameters for the marginal based synthesis with differential privacy
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DpMarginalParameters {
+    /// Overall privacy budget used to measure the single attribute counts,
+    /// select the column pairs and measure them
+    pub epsilon: f64,
+    /// Delta value used to convert the budget to zCDP, if None will be set
+    /// in runtime to `1 / (ln(number_of_records) * number_of_records)`
+    pub delta: Option<f64>,
+    /// Proportion of the privacy budget used to measure the single attribute counts
+    pub single_attributes_budget_proportion: f64,
+    /// Proportion of the privacy budget used to select which pairs of columns
+    /// are measured (the remaining budget is used to measure the selected pairs)
+    pub selection_budget_proportion: f64,
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl DpMarginalParameters {
+    #[inline]
+    #[cfg(feature = "pyo3")]
+    #[new]
+    /// Creates a new DpMarginalParameters structure
+    /// # Arguments
+    /// * `epsilon` - Overall privacy budget used to measure the single attribute counts,
+    /// select the column pairs and measure them
+    /// * `delta` - Delta value used to convert the budget to zCDP, if None will be set
+    /// in runtime to `1 / (ln(number_of_records) * number_of_records)`
+    /// * `single_attributes_budget_proportion` - Proportion of the privacy budget used to measure
+    /// the single attribute counts (default is 1/3)
+    /// * `selection_budget_proportion` - Proportion of the privacy budget used to select which
+    /// pairs of columns are measured (default is 1/3)
+    pub fn new(
+        epsilon: f64,
+        delta: Option<f64>,
+        single_attributes_budget_proportion: Option<f64>,
+        selection_budget_proportion: Option<f64>,
+    ) -> Self {
+        DpMarginalParameters {
+            epsilon,
+            delta,
+            single_attributes_budget_proportion: single_attributes_budget_proportion
+                .unwrap_or(DEFAULT_SINGLE_ATTRIBUTES_BUDGET_PROPORTION),
+            selection_budget_proportion: selection_budget_proportion
+                .unwrap_or(DEFAULT_SELECTION_BUDGET_PROPORTION),
+        }
+    }
+
+    #[inline]
+    #[cfg(not(feature = "pyo3"))]
+    /// Creates a new DpMarginalParameters structure
+    /// # Arguments
+    /// * `epsilon` - Overall privacy budget used to measure the single attribute counts,
+    /// select the column pairs and measure them
+    /// * `delta` - Delta value used to convert the budget to zCDP, if None will be set
+    /// in runtime to `1 / (ln(number_of_records) * number_of_records)`
+    /// * `single_attributes_budget_proportion` - Proportion of the privacy budget used to measure
+    /// the single attribute counts (default is 1/3)
+    /// * `selection_budget_proportion` - Proportion of the privacy budget used to select which
+    /// pairs of columns are measured (default is 1/3)
+    pub fn new(
+        epsilon: f64,
+        delta: Option<f64>,
+        single_attributes_budget_proportion: Option<f64>,
+        selection_budget_proportion: Option<f64>,
+    ) -> Self {
+        DpMarginalParameters {
+            epsilon,
+            delta,
+            single_attributes_budget_proportion: single_attributes_budget_proportion
+                .unwrap_or(DEFAULT_SINGLE_ATTRIBUTES_BUDGET_PROPORTION),
+            selection_budget_proportion: selection_budget_proportion
+                .unwrap_or(DEFAULT_SELECTION_BUDGET_PROPORTION),
+        }
+    }
+}
+
+impl DpMarginalParameters {
+    /// Validates the privacy budget and how it is split,
+    /// returning an error if they can not be used for the synthesis
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.epsilon <= 0.0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "marginal epsilon must be > 0",
+            ));
+        }
+        if let Some(delta) = self.delta {
+            if delta <= 0.0 || delta >= 1.0 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "marginal delta must be > 0 and < 1",
+                ));
+            }
+        }
+        if self.single_attributes_budget_proportion <= 0.0
+            || self.selection_budget_proportion <= 0.0
+            || self.single_attributes_budget_proportion + self.selection_budget_proportion >= 1.0
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "single attributes and selection budget proportions must be > 0 and sum up to < 1",
+            ));
+        }
+        Ok(())
+    }
+}
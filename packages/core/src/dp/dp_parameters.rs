@@ -2,6 +2,8 @@
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::{DpColumnHierarchy, DpWorkload};
+
 /// Parameters for aggregate generation with differential privacy
 #[cfg_attr(feature = "pyo3", pyclass)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -27,6 +29,14 @@ pub struct DpParameters {
     /// Proportion of epsilon used to add noise to the protected number of records in
     /// the aggregated data (if None, no noise is added)
     pub number_of_records_epsilon_proportion: Option<f64>,
+    /// Column groups and importance weights used to further split the budget
+    /// within each combination length (if None, all the combinations
+    /// of the same length will have the same sigma)
+    pub workload: Option<DpWorkload>,
+    /// Columns forming hierarchies, the noisy counts of the combinations
+    /// made of their levels are post-processed to be consistent (if None,
+    /// no consistency is enforced)
+    pub column_hierarchies: Option<Vec<DpColumnHierarchy>>,
 }
 
 #[cfg_attr(feature = "pyo3", pymethods)]
@@ -50,6 +60,13 @@ impl DpParameters {
     ///     - If `None` all the sigma values will be the same
     /// * `number_of_records_epsilon_proportion` - Proportion of epsilon used to add noise to the protected number of records
     /// in the aggregated data (if None, no noise is added)
+    /// * `workload` - Column groups and importance weights used to further split the budget
+    /// within each combination length (if None, all the combinations
+    /// of the same length will have the same sigma)
+    /// * `column_hierarchies` - Columns forming hierarchies, the noisy counts of the combinations
+    /// made of their levels are post-processed to be consistent (if None,
+    /// no consistency is enforced)
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         epsilon: f64,
         percentile_percentage: usize,
@@ -57,6 +74,8 @@ impl DpParameters {
         delta: Option<f64>,
         sigma_proportions: Option<Vec<f64>>,
         number_of_records_epsilon_proportion: Option<f64>,
+        workload: Option<DpWorkload>,
+        column_hierarchies: Option<Vec<DpColumnHierarchy>>,
     ) -> Self {
         DpParameters {
             epsilon,
@@ -65,6 +84,8 @@ impl DpParameters {
             delta,
             sigma_proportions,
             number_of_records_epsilon_proportion,
+            workload,
+            column_hierarchies,
         }
     }
 
@@ -86,6 +107,13 @@ impl DpParameters {
     ///     - If `None` all the sigma values will be the same
     /// * `number_of_records_epsilon_proportion` - Proportion of epsilon used to add noise to the protected number of records
     /// in the aggregated data (if None, no noise is added)
+    /// * `workload` - Column groups and importance weights used to further split the budget
+    /// within each combination length (if None, all the combinations
+    /// of the same length will have the same sigma)
+    /// * `column_hierarchies` - Columns forming hierarchies, the noisy counts of the combinations
+    /// made of their levels are post-processed to be consistent (if None,
+    /// no consistency is enforced)
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         epsilon: f64,
         percentile_percentage: usize,
@@ -93,6 +121,8 @@ impl DpParameters {
         delta: Option<f64>,
         sigma_proportions: Option<Vec<f64>>,
         number_of_records_epsilon_proportion: Option<f64>,
+        workload: Option<DpWorkload>,
+        column_hierarchies: Option<Vec<DpColumnHierarchy>>,
     ) -> Self {
         DpParameters {
             epsilon,
@@ -101,6 +131,8 @@ impl DpParameters {
             delta,
             sigma_proportions,
             number_of_records_epsilon_proportion,
+            workload,
+            column_hierarchies,
         }
     }
 }
@@ -0,0 +1,310 @@
+use super::{DpAdvisorError, DpParameters, NoiseParameters, NoisyCountThreshold};
+use log::info;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Default percentage used to calculate the percentile that filters sensitivity
+pub const DEFAULT_ADVISOR_PERCENTILE_PERCENTAGE: usize = 99;
+
+/// Default proportion of the privacy budget used by the sensitivity filter
+pub const DEFAULT_ADVISOR_PERCENTILE_EPSILON_PROPORTION: f64 = 0.01;
+
+/// Default proportion of the privacy budget used to protect the number of records
+pub const DEFAULT_ADVISOR_NUMBER_OF_RECORDS_EPSILON_PROPORTION: f64 = 0.005;
+
+/// Below this expected count, most combinations of a given length
+/// would be suppressed regardless of the privacy budget
+const MIN_EXPECTED_COUNT: f64 = 1.0;
+
+/// Adaptive thresholds must be in (0, 1]
+const MIN_ADAPTIVE_THRESHOLD: f64 = 1e-9;
+
+/// Data independent inputs used to recommend the DP parameters
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DpAdvisorInputs {
+    /// Number of records in the dataset (or a DP estimate of it)
+    pub number_of_records: usize,
+    /// Number of distinct values of each column (the number of
+    /// columns is the length of this vector)
+    pub attribute_cardinalities: Vec<usize>,
+    /// Maximum combination length to be reported
+    pub reporting_length: usize,
+    /// Target relative error of the combination counts by length
+    /// (index `0` is for the 1-counts, and so on)
+    pub target_relative_errors: Vec<f64>,
+    /// Delta value, if None will be set
+    /// to `1 / (ln(number_of_records) * number_of_records)`
+    pub delta: Option<f64>,
+    /// Maximum acceptable privacy budget, targets requiring more than
+    /// this are reported as unreachable
+    pub max_epsilon: Option<f64>,
+}
+
+impl DpAdvisorInputs {
+    /// Returns a new DpAdvisorInputs
+    /// # Arguments
+    /// * `number_of_records` - Number of records in the dataset (or a DP estimate of it)
+    /// * `attribute_cardinalities` - Number of distinct values of each column
+    /// * `reporting_length` - Maximum combination length to be reported
+    /// * `target_relative_errors` - Target relative error of the combination counts by length
+    /// * `delta` - Delta value, if None will be set
+    /// to `1 / (ln(number_of_records) * number_of_records)`
+    /// * `max_epsilon` - Maximum acceptable privacy budget
+    #[inline]
+    pub fn new(
+        number_of_records: usize,
+        attribute_cardinalities: Vec<usize>,
+        reporting_length: usize,
+        target_relative_errors: Vec<f64>,
+        delta: Option<f64>,
+        max_epsilon: Option<f64>,
+    ) -> Self {
+        DpAdvisorInputs {
+            number_of_records,
+            attribute_cardinalities,
+            reporting_length,
+            target_relative_errors,
+            delta,
+            max_epsilon,
+        }
+    }
+}
+
+/// DP parameters recommended to reach the target errors, alongside the
+/// estimates they were derived from (indexed by combination length - 1)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DpRecommendation {
+    /// Recommended DP parameters
+    pub dp_parameters: DpParameters,
+    /// Recommended threshold used to filter noisy counts
+    pub threshold: NoisyCountThreshold,
+    /// Expected mean count of a combination
+    pub expected_mean_counts: Vec<f64>,
+    /// Maximum number of combinations a single record contributes to
+    pub sensitivities: Vec<usize>,
+    /// Sigma used to add gaussian noise (before scaling by the sensitivity)
+    pub sigmas: Vec<f64>,
+    /// Expected relative error of the reported counts
+    pub expected_relative_errors: Vec<f64>,
+    /// Count below which combinations are expected to be suppressed
+    pub count_thresholds: Vec<f64>,
+}
+
+/// Recommends DP parameters that meet target relative errors
+/// without accessing the sensitive data.
+///
+/// The noise added to each combination count is compared to the mean count
+/// expected for its length, assuming every record has a value for every column:
+/// `number_of_records * C(columns, length) / (number of possible combinations of that length)`
+pub struct DpParametersAdvisor {
+    inputs: DpAdvisorInputs,
+}
+
+impl DpParametersAdvisor {
+    /// Returns a new DpParametersAdvisor
+    /// # Arguments
+    /// * `inputs` - Data independent inputs used to recommend the parameters
+    #[inline]
+    pub fn new(inputs: DpAdvisorInputs) -> DpParametersAdvisor {
+        DpParametersAdvisor { inputs }
+    }
+
+    #[inline]
+    fn n_choose_k(n: usize, k: usize) -> f64 {
+        if k > n {
+            0.0
+        } else {
+            (0..k).fold(1.0, |acc, i| acc * ((n - i) as f64) / ((i + 1) as f64))
+        }
+    }
+
+    /// Number of possible combinations by length, this is the elementary
+    /// symmetric polynomial of the attribute cardinalities
+    #[inline]
+    fn calc_number_of_possible_combinations(&self) -> Vec<f64> {
+        let mut e = vec![0.0; self.inputs.reporting_length + 1];
+
+        e[0] = 1.0;
+        for c in self.inputs.attribute_cardinalities.iter() {
+            for l in (1..=self.inputs.reporting_length).rev() {
+                e[l] += e[l - 1] * (*c as f64);
+            }
+        }
+        e.drain(1..).collect()
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), DpAdvisorError> {
+        let inputs = &self.inputs;
+
+        if inputs.number_of_records <= 1 {
+            return Err(DpAdvisorError::InvalidInput(
+                "number of records must be > 1".to_owned(),
+            ));
+        }
+        if inputs.attribute_cardinalities.is_empty() || inputs.attribute_cardinalities.contains(&0)
+        {
+            return Err(DpAdvisorError::InvalidInput(
+                "attribute cardinalities must be provided for every column and be > 0".to_owned(),
+            ));
+        }
+        if inputs.reporting_length == 0
+            || inputs.reporting_length > inputs.attribute_cardinalities.len()
+        {
+            return Err(DpAdvisorError::InvalidInput(
+                "reporting length must be > 0 and <= number of columns".to_owned(),
+            ));
+        }
+        if inputs.target_relative_errors.len() != inputs.reporting_length {
+            return Err(DpAdvisorError::InvalidInput(
+                "a target relative error should be provided for every combination length"
+                    .to_owned(),
+            ));
+        }
+        if inputs.target_relative_errors.iter().any(|e| *e <= 0.0) {
+            return Err(DpAdvisorError::InvalidInput(
+                "target relative errors must be > 0".to_owned(),
+            ));
+        }
+        if let Some(delta) = inputs.delta {
+            if delta <= 0.0 || delta >= 1.0 {
+                return Err(DpAdvisorError::InvalidInput(
+                    "delta must be > 0 and < 1".to_owned(),
+                ));
+            }
+        }
+        if let Some(max_epsilon) = inputs.max_epsilon {
+            if max_epsilon <= 0.0 {
+                return Err(DpAdvisorError::InvalidInput(
+                    "max epsilon must be > 0".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Recommends the DP parameters, or explains why the targets are unreachable
+    pub fn recommend(&self) -> Result<DpRecommendation, DpAdvisorError> {
+        self.validate()?;
+
+        let inputs = &self.inputs;
+        let number_of_columns = inputs.attribute_cardinalities.len();
+        let delta =
+            NoiseParameters::delta_value_or_default(&inputs.delta, inputs.number_of_records);
+        let normal = Normal::new(0.0, 1.0).expect("error creating Normal for inverse CDF");
+        let sensitivities: Vec<usize> = (1..=inputs.reporting_length)
+            .map(|l| DpParametersAdvisor::n_choose_k(number_of_columns, l).round() as usize)
+            .collect();
+        let expected_mean_counts: Vec<f64> = self
+            .calc_number_of_possible_combinations()
+            .iter()
+            .zip(sensitivities.iter())
+            .map(|(n_comb, s)| (inputs.number_of_records as f64) * (*s as f64) / n_comb)
+            .collect();
+
+        for (l, mean_count) in expected_mean_counts.iter().enumerate() {
+            if *mean_count < MIN_EXPECTED_COUNT {
+                return Err(DpAdvisorError::Unreachable(format!(
+                    "the expected mean count of {}-combinations is {:.4}, so most of them would be suppressed regardless of the privacy budget - reduce the reporting length or the attribute cardinalities (e.g. by binning)",
+                    l + 1,
+                    mean_count
+                )));
+            }
+        }
+
+        // noise standard deviation is sigma * sqrt(sensitivity) and
+        // should be at most target_error * expected_mean_count
+        let mut sigmas: Vec<f64> = inputs
+            .target_relative_errors
+            .iter()
+            .zip(expected_mean_counts.iter())
+            .zip(sensitivities.iter())
+            .map(|((e, m), s)| e * m / (*s as f64).sqrt())
+            .collect();
+
+        // 1-counts are filtered by a threshold that guarantees DP, make sure
+        // it stays below half of the expected mean count
+        let single_attributes_z =
+            normal.inverse_cdf((1.0 - (delta / 2.0)).powf(1.0 / (sensitivities[0] as f64)));
+        let max_single_attributes_threshold = expected_mean_counts[0] / 2.0;
+
+        if max_single_attributes_threshold <= 1.0 {
+            return Err(DpAdvisorError::Unreachable(format!(
+                "the expected mean count of single attributes is {:.4}, which is too close to the minimum threshold of 1 required to report them with differential privacy - increase the number of records or reduce the attribute cardinalities",
+                expected_mean_counts[0]
+            )));
+        }
+        sigmas[0] = sigmas[0].min(
+            (max_single_attributes_threshold - 1.0)
+                / ((sensitivities[0] as f64).sqrt() * single_attributes_z),
+        );
+
+        let marginals_rho = sigmas.iter().map(|s| 1.0 / (2.0 * s * s)).sum::<f64>()
+            / (1.0 - DEFAULT_ADVISOR_PERCENTILE_EPSILON_PROPORTION);
+        let marginals_epsilon = NoiseParameters::calc_epsilon(marginals_rho, delta);
+        let epsilon =
+            marginals_epsilon / (1.0 - DEFAULT_ADVISOR_NUMBER_OF_RECORDS_EPSILON_PROPORTION);
+
+        info!("dp advisor: delta = {delta}, sigmas = {sigmas:?}, epsilon = {epsilon}");
+
+        if let Some(max_epsilon) = inputs.max_epsilon {
+            if epsilon > max_epsilon {
+                return Err(DpAdvisorError::Unreachable(format!(
+                    "reaching the target errors requires epsilon = {epsilon:.4}, which is above the maximum of {max_epsilon} - loosen the target errors, reduce the reporting length or the attribute cardinalities",
+                )));
+            }
+        }
+
+        let noise_standard_deviations: Vec<f64> = sigmas
+            .iter()
+            .zip(sensitivities.iter())
+            .map(|(sigma, s)| sigma * (*s as f64).sqrt())
+            .collect();
+        // for longer combinations, threshold at half of the expected mean count
+        // so typical combinations are kept and fabricated ones are mostly filtered
+        let adaptive_thresholds = (2..=inputs.reporting_length)
+            .map(|l| {
+                let z = expected_mean_counts[l - 1] / (2.0 * noise_standard_deviations[l - 1]);
+
+                (
+                    l,
+                    (2.0 * (1.0 - normal.cdf(z))).clamp(MIN_ADAPTIVE_THRESHOLD, 1.0),
+                )
+            })
+            .collect();
+        let count_thresholds = (1..=inputs.reporting_length)
+            .map(|l| {
+                if l == 1 {
+                    1.0 + noise_standard_deviations[0] * single_attributes_z
+                } else {
+                    expected_mean_counts[l - 1] / 2.0
+                }
+            })
+            .collect();
+
+        Ok(DpRecommendation {
+            dp_parameters: DpParameters::new(
+                epsilon,
+                DEFAULT_ADVISOR_PERCENTILE_PERCENTAGE,
+                DEFAULT_ADVISOR_PERCENTILE_EPSILON_PROPORTION,
+                Some(delta),
+                Some(sigmas.iter().map(|s| s / sigmas[0]).collect()),
+                Some(DEFAULT_ADVISOR_NUMBER_OF_RECORDS_EPSILON_PROPORTION),
+                None,
+                None,
+            ),
+            threshold: NoisyCountThreshold::Adaptive(adaptive_thresholds),
+            expected_relative_errors: noise_standard_deviations
+                .iter()
+                .zip(expected_mean_counts.iter())
+                .map(|(sd, m)| sd / m)
+                .collect(),
+            expected_mean_counts,
+            sensitivities,
+            sigmas,
+            count_thresholds,
+        })
+    }
+}
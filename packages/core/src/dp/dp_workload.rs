@@ -0,0 +1,202 @@
+use log::info;
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Error, ErrorKind};
+
+use crate::{
+    data_block::{DataBlockHeadersSlice, MultiValueColumnMetadataMap},
+    utils::time::ElapsedDurationLogger,
+};
+
+/// Default weight given to the combinations not covered by any workload query
+pub const DEFAULT_UNCOVERED_WEIGHT: f64 = 1.0;
+
+/// Group of columns that are usually analyzed together
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DpWorkloadQuery {
+    /// Columns analyzed together, combinations made of any subset
+    /// of these columns are covered by the query
+    pub columns: Vec<String>,
+    /// Importance of the query, the sigma used to add noise to the
+    /// covered combinations is divided by this weight (must be > 0)
+    pub weight: f64,
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl DpWorkloadQuery {
+    #[inline]
+    #[cfg(feature = "pyo3")]
+    #[new]
+    /// Creates a new DpWorkloadQuery structure
+    /// # Arguments
+    /// * `columns` - Columns analyzed together, combinations made of any subset
+    /// of these columns are covered by the query
+    /// * `weight` - Importance of the query, the sigma used to add noise to the
+    /// covered combinations is divided by this weight (must be > 0)
+    pub fn new(columns: Vec<String>, weight: f64) -> Self {
+        DpWorkloadQuery { columns, weight }
+    }
+
+    #[inline]
+    #[cfg(not(feature = "pyo3"))]
+    /// Creates a new DpWorkloadQuery structure
+    /// # Arguments
+    /// * `columns` - Columns analyzed together, combinations made of any subset
+    /// of these columns are covered by the query
+    /// * `weight` - Importance of the query, the sigma used to add noise to the
+    /// covered combinations is divided by this weight (must be > 0)
+    pub fn new(columns: Vec<String>, weight: f64) -> Self {
+        DpWorkloadQuery { columns, weight }
+    }
+}
+
+/// Expected analysis workload, used to split the privacy budget
+/// between the attribute combinations
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DpWorkload {
+    /// Column groups that matter for the analysis
+    pub queries: Vec<DpWorkloadQuery>,
+    /// Weight of the combinations not covered by any query,
+    /// if `0.0` these combinations are not reported at all
+    #[serde(default = "default_uncovered_weight")]
+    pub uncovered_weight: f64,
+}
+
+#[inline]
+fn default_uncovered_weight() -> f64 {
+    DEFAULT_UNCOVERED_WEIGHT
+}
+
+impl DpWorkload {
+    /// Parses the workload from a json string, such as:
+    /// ```json
+    /// {
+    ///   "queries": [
+    ///     { "columns": ["age", "region", "diagnosis"], "weight": 4.0 },
+    ///     { "columns": ["sex", "age"], "weight": 2.0 }
+    ///   ],
+    ///   "uncoveredWeight": 0.5
+    /// }
+    /// ```
+    /// # Arguments
+    /// * `json` - Json string to parse
+    pub fn from_json_str(json: &str) -> Result<DpWorkload, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Reads the workload from a json file
+    /// (check `from_json_str` for the expected format)
+    /// # Arguments
+    /// * `file_path` - File path to read from
+    pub fn read_from_json(file_path: &str) -> Result<DpWorkload, Error> {
+        info!("reading file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("read dp workload");
+
+        Ok(serde_json::from_reader(BufReader::new(
+            std::fs::File::open(file_path)?,
+        ))?)
+    }
+
+    /// Checks whether the workload weights are valid and at least one
+    /// combination is reported, without checking the referenced columns
+    pub fn validate_weights(&self) -> Result<(), Error> {
+        if self.uncovered_weight < 0.0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "workload uncovered weight must be >= 0",
+            ));
+        }
+
+        if self.queries.is_empty() && self.uncovered_weight == 0.0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "workload without queries must have an uncovered weight > 0",
+            ));
+        }
+
+        for query in self.queries.iter() {
+            if query.weight <= 0.0 {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "workload query weights must be > 0",
+                ));
+            }
+            if query.columns.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "workload queries should reference at least one column",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether the workload is valid for the data `headers`
+    /// (every referenced column should exist and weights should be valid)
+    /// # Arguments
+    /// * `headers` - Headers of the data being aggregated
+    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name
+    /// to its corresponding metadata (columns can also be referenced by the multi-value column name)
+    pub fn validate(
+        &self,
+        headers: &DataBlockHeadersSlice,
+        multi_value_column_metadata_map: &MultiValueColumnMetadataMap,
+    ) -> Result<(), Error> {
+        self.validate_weights()?;
+
+        for query in self.queries.iter() {
+            for column in query.columns.iter() {
+                let exists = headers.iter().any(|h| **h == *column)
+                    || multi_value_column_metadata_map
+                        .values()
+                        .any(|m| *m.src_header_name == *column);
+
+                if !exists {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("workload column \"{column}\" not found in the data headers"),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl DpWorkload {
+    #[inline]
+    #[cfg(feature = "pyo3")]
+    #[new]
+    /// Creates a new DpWorkload structure
+    /// # Arguments
+    /// * `queries` - Column groups that matter for the analysis
+    /// * `uncovered_weight` - Weight of the combinations not covered by any query,
+    /// if `0.0` these combinations are not reported at all (default is 1.0)
+    pub fn new(queries: Vec<DpWorkloadQuery>, uncovered_weight: Option<f64>) -> Self {
+        DpWorkload {
+            queries,
+            uncovered_weight: uncovered_weight.unwrap_or(DEFAULT_UNCOVERED_WEIGHT),
+        }
+    }
+
+    #[inline]
+    #[cfg(not(feature = "pyo3"))]
+    /// Creates a new DpWorkload structure
+    /// # Arguments
+    /// * `queries` - Column groups that matter for the analysis
+    /// * `uncovered_weight` - Weight of the combinations not covered by any query,
+    /// if `0.0` these combinations are not reported at all (default is 1.0)
+    pub fn new(queries: Vec<DpWorkloadQuery>, uncovered_weight: Option<f64>) -> Self {
+        DpWorkload {
+            queries,
+            uncovered_weight: uncovered_weight.unwrap_or(DEFAULT_UNCOVERED_WEIGHT),
+        }
+    }
+}
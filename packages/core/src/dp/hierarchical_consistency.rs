@@ -0,0 +1,358 @@
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use std::sync::Arc;
+
+use crate::{dp::typedefs::CombinationsCountMapByLen, processing::aggregator::ValueCombination};
+
+/// Node of a hierarchy tree, represented by a combination with
+/// a value for each of the first hierarchy levels (e.g. `country:US;state:WA`)
+struct HierarchyNode {
+    comb: Arc<ValueCombination>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    noisy_count: f64,
+    variance: f64,
+    /// Estimate combining the node noisy count with the ones from its subtree
+    subtree_estimate: f64,
+    subtree_variance: f64,
+    /// Estimate consistent with the parent and children estimates
+    consistent_count: f64,
+    /// Non-negative integer count, still consistent
+    count: f64,
+}
+
+/// Post-processes the noisy counts of the combinations made of
+/// the levels of a column hierarchy, so children never add up to more than
+/// their parent. Children might have been suppressed by the noise threshold,
+/// so each parent keeps a non-negative residual accounting for them instead
+/// of being forced to the sum of the released children.
+///
+/// Only the released noisy counts and their noise variances are used,
+/// so this does not affect the differential privacy guarantees
+pub(crate) struct HierarchicalConsistency {
+    /// Maps a column index to its level on the hierarchy (`0` is the coarsest)
+    level_by_column: FnvHashMap<usize, usize>,
+    /// Number of hierarchy levels covered by the noisy counts
+    depth: usize,
+}
+
+impl HierarchicalConsistency {
+    /// Returns a new HierarchicalConsistency
+    /// # Arguments
+    /// * `column_indexes` - Column indexes of the hierarchy levels, from the coarsest to the finest
+    /// * `reporting_length` - Maximum length of the noisy combinations
+    #[inline]
+    pub fn new(column_indexes: &[usize], reporting_length: usize) -> HierarchicalConsistency {
+        HierarchicalConsistency {
+            level_by_column: column_indexes
+                .iter()
+                .enumerate()
+                .map(|(level, column_index)| (*column_index, level))
+                .collect(),
+            depth: column_indexes.len().min(reporting_length),
+        }
+    }
+
+    /// Makes the noisy counts of the hierarchy tree consistent. Nodes are the combinations
+    /// of the first hierarchy levels (up to the reporting length), estimated over the tree
+    /// so the children sum is at most their parent, and then rounded down to integers
+    /// keeping that order. Nodes whose parent was suppressed are removed, since the parent
+    /// count bounds theirs. Combinations of other hierarchy levels
+    /// (such as `state:WA;county:King` or `county:King`) are replaced by the sum of the
+    /// nodes containing them, and combinations containing a hierarchy combination
+    /// with a zero count are removed
+    /// # Arguments
+    /// * `noisy_aggregates_by_len` - Noisy counts to be updated
+    /// * `variance_of` - Returns the variance of the noise added to a combination
+    pub fn enforce<F>(
+        &self,
+        noisy_aggregates_by_len: &mut CombinationsCountMapByLen,
+        variance_of: F,
+    ) where
+        F: Fn(&ValueCombination) -> f64,
+    {
+        let mut nodes = self.build_nodes(noisy_aggregates_by_len, variance_of);
+
+        Self::estimate_subtrees(&mut nodes);
+        Self::distribute_differences(&mut nodes);
+        Self::round_counts(&mut nodes);
+        self.update_counts(noisy_aggregates_by_len, &nodes);
+    }
+
+    #[inline]
+    fn level_of(&self, column_index: usize) -> Option<usize> {
+        self.level_by_column.get(&column_index).cloned()
+    }
+
+    /// Whether all the values of `comb` belong to the hierarchy
+    /// and are on the levels covered by the tree
+    #[inline]
+    fn is_covered(&self, comb: &ValueCombination) -> bool {
+        comb.iter().all(|v| {
+            self.level_of(v.column_index)
+                .is_some_and(|l| l < self.depth)
+        })
+    }
+
+    /// Whether `comb` has a value for each of the first `comb.len()` levels
+    #[inline]
+    fn is_node(&self, comb: &ValueCombination) -> bool {
+        comb.len() <= self.depth
+            && comb.iter().all(|v| {
+                self.level_of(v.column_index)
+                    .is_some_and(|l| l < comb.len())
+            })
+    }
+
+    #[inline]
+    fn project(&self, comb: &ValueCombination) -> ValueCombination {
+        ValueCombination::new(
+            comb.iter()
+                .filter(|v| self.level_by_column.contains_key(&v.column_index))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    fn build_nodes<F>(
+        &self,
+        noisy_aggregates_by_len: &CombinationsCountMapByLen,
+        variance_of: F,
+    ) -> Vec<HierarchyNode>
+    where
+        F: Fn(&ValueCombination) -> f64,
+    {
+        let mut nodes: Vec<HierarchyNode> = Vec::default();
+        let mut index_by_comb: FnvHashMap<Arc<ValueCombination>, usize> = FnvHashMap::default();
+
+        // nodes are sorted by depth, so parents always come before their children
+        for depth in 1..=self.depth {
+            if let Some(aggregates) = noisy_aggregates_by_len.get(&depth) {
+                for (comb, noisy_count) in aggregates
+                    .iter()
+                    .filter(|(comb, _)| self.is_node(comb))
+                    .sorted_by(|(a, _), (b, _)| a.iter().cmp(b.iter()))
+                {
+                    let parent = if depth > 1 {
+                        index_by_comb
+                            .get(&ValueCombination::new(
+                                comb.iter()
+                                    .filter(|v| self.level_of(v.column_index) != Some(depth - 1))
+                                    .cloned()
+                                    .collect(),
+                            ))
+                            .cloned()
+                    } else {
+                        None
+                    };
+                    let index = nodes.len();
+
+                    match parent {
+                        Some(parent) => nodes[parent].children.push(index),
+                        // the parent was suppressed, so is its subtree
+                        None if depth > 1 => continue,
+                        None => {}
+                    }
+                    index_by_comb.insert(comb.clone(), index);
+                    nodes.push(HierarchyNode {
+                        comb: comb.clone(),
+                        parent,
+                        children: Vec::default(),
+                        noisy_count: *noisy_count,
+                        variance: variance_of(comb).max(f64::EPSILON),
+                        subtree_estimate: 0.0,
+                        subtree_variance: 0.0,
+                        consistent_count: 0.0,
+                        count: 0.0,
+                    });
+                }
+            }
+        }
+        nodes
+    }
+
+    /// Bottom-up pass: keeps the noisy count of each node when it is not below the sum
+    /// of its children estimates (the residual accounts for the suppressed children),
+    /// otherwise combines both, weighting them by the inverse of their variances
+    fn estimate_subtrees(nodes: &mut [HierarchyNode]) {
+        for i in (0..nodes.len()).rev() {
+            if nodes[i].children.is_empty() {
+                nodes[i].subtree_estimate = nodes[i].noisy_count;
+                nodes[i].subtree_variance = nodes[i].variance;
+            } else {
+                let children_sum: f64 = nodes[i]
+                    .children
+                    .iter()
+                    .map(|c| nodes[*c].subtree_estimate)
+                    .sum();
+                if nodes[i].noisy_count >= children_sum {
+                    nodes[i].subtree_estimate = nodes[i].noisy_count;
+                    nodes[i].subtree_variance = nodes[i].variance;
+                    continue;
+                }
+
+                let children_variance: f64 = nodes[i]
+                    .children
+                    .iter()
+                    .map(|c| nodes[*c].subtree_variance)
+                    .sum();
+                let precision = 1.0 / nodes[i].variance + 1.0 / children_variance;
+
+                nodes[i].subtree_estimate = (nodes[i].noisy_count / nodes[i].variance
+                    + children_sum / children_variance)
+                    / precision;
+                nodes[i].subtree_variance = 1.0 / precision;
+            }
+        }
+    }
+
+    /// Top-down pass: a node estimate above the sum of its children estimates
+    /// is left to the residual, while a node estimate below it is split between
+    /// the children proportionally to their variances
+    fn distribute_differences(nodes: &mut [HierarchyNode]) {
+        for i in 0..nodes.len() {
+            if nodes[i].parent.is_none() {
+                nodes[i].consistent_count = nodes[i].subtree_estimate;
+            }
+            if !nodes[i].children.is_empty() {
+                let children = nodes[i].children.clone();
+                let children_sum: f64 = children.iter().map(|c| nodes[*c].subtree_estimate).sum();
+                let children_variance: f64 =
+                    children.iter().map(|c| nodes[*c].subtree_variance).sum();
+                let difference = (nodes[i].consistent_count - children_sum).min(0.0);
+
+                for c in children {
+                    nodes[c].consistent_count = nodes[c].subtree_estimate
+                        + nodes[c].subtree_variance / children_variance * difference;
+                }
+            }
+        }
+    }
+
+    /// Rounds the roots and splits each node integer count between
+    /// its children and its residual (largest remainder), so the children
+    /// still do not exceed their parent
+    fn round_counts(nodes: &mut [HierarchyNode]) {
+        for i in 0..nodes.len() {
+            if nodes[i].parent.is_none() {
+                nodes[i].count = nodes[i].consistent_count.round().max(0.0);
+            }
+            if nodes[i].children.is_empty() {
+                continue;
+            }
+
+            let children = nodes[i].children.clone();
+            let count = nodes[i].count;
+            let mut weights: Vec<f64> = children
+                .iter()
+                .map(|c| nodes[*c].consistent_count.max(0.0))
+                .collect();
+            let children_weight: f64 = weights.iter().sum();
+
+            // the residual is the last share
+            weights.push((nodes[i].consistent_count - children_weight).max(0.0));
+
+            let mut total_weight: f64 = weights.iter().sum();
+
+            if total_weight <= 0.0 {
+                weights = vec![1.0; children.len()];
+                weights.push(0.0);
+                total_weight = children.len() as f64;
+            }
+
+            let shares: Vec<f64> = weights.iter().map(|w| count * w / total_weight).collect();
+            let remainder =
+                (count - shares.iter().map(|s| s.floor()).sum::<f64>()).round() as usize;
+            let mut counts: Vec<f64> = shares.iter().map(|s| s.floor()).collect();
+
+            for (index, _) in shares
+                .iter()
+                .enumerate()
+                .sorted_by(|(_, a), (_, b)| b.fract().partial_cmp(&a.fract()).unwrap())
+                .take(remainder)
+            {
+                counts[index] += 1.0;
+            }
+            for (c, count) in children.iter().zip(counts) {
+                nodes[*c].count = count;
+            }
+        }
+    }
+
+    fn update_counts(
+        &self,
+        noisy_aggregates_by_len: &mut CombinationsCountMapByLen,
+        nodes: &[HierarchyNode],
+    ) {
+        let mut sums: FnvHashMap<ValueCombination, f64> = FnvHashMap::default();
+
+        for node in nodes.iter() {
+            let depth = node.comb.len();
+            let upper_positions = node
+                .comb
+                .iter()
+                .positions(|v| self.level_of(v.column_index) != Some(depth - 1))
+                .collect_vec();
+
+            // every combination of upper levels along with the deepest
+            // level, except the node itself
+            for mask in 0..((1usize << upper_positions.len()) - 1) {
+                let sub_comb = ValueCombination::new(
+                    node.comb
+                        .iter()
+                        .enumerate()
+                        .filter(|(pos, _)| {
+                            upper_positions
+                                .iter()
+                                .position(|p| p == pos)
+                                .is_none_or(|bit| mask & (1 << bit) != 0)
+                        })
+                        .map(|(_, v)| v.clone())
+                        .collect(),
+                );
+
+                *sums.entry(sub_comb).or_insert(0.0) += node.count;
+            }
+            if let Some(count) = noisy_aggregates_by_len
+                .get_mut(&depth)
+                .and_then(|aggregates| aggregates.get_mut(&node.comb))
+            {
+                *count = node.count;
+            }
+        }
+
+        let tree_combs: FnvHashSet<&Arc<ValueCombination>> =
+            nodes.iter().map(|node| &node.comb).collect();
+        let mut zero_combs: FnvHashSet<ValueCombination> = FnvHashSet::default();
+
+        for aggregates in noisy_aggregates_by_len.values_mut() {
+            for (comb, count) in aggregates.iter_mut() {
+                if self.is_covered(comb) {
+                    if !self.is_node(comb) {
+                        *count = sums.get(&**comb).cloned().unwrap_or(0.0);
+                    } else if !tree_combs.contains(comb) {
+                        // nodes whose parent was suppressed
+                        *count = 0.0;
+                    }
+                    if *count <= 0.0 {
+                        zero_combs.insert((**comb).clone());
+                    }
+                }
+            }
+        }
+
+        for aggregates in noisy_aggregates_by_len.values_mut() {
+            aggregates.retain(|comb, count| {
+                if self.is_covered(comb) {
+                    *count > 0.0
+                } else {
+                    let projection = self.project(comb);
+
+                    projection.is_empty()
+                        || !self.is_covered(&projection)
+                        || !zero_combs.contains(&projection)
+                }
+            });
+        }
+    }
+}
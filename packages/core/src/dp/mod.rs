@@ -1,20 +1,39 @@
+/// Module to empirically audit the differential privacy mechanisms
+pub mod audit;
+
+mod dp_advisor_error;
+mod dp_budget_allocation;
+mod dp_column_hierarchy;
+mod dp_marginal_parameters;
 mod dp_parameters;
+mod dp_parameters_advisor;
+mod dp_workload;
+mod hierarchical_consistency;
 mod noise_aggregator;
 mod noise_parameters;
 mod noisy_count_threshold;
 mod percentile;
 mod stats_error;
 mod typedefs;
+mod workload_weights;
 
 #[cfg(feature = "pyo3")]
 mod register_pyo3;
 
+pub use dp_advisor_error::*;
+pub use dp_budget_allocation::*;
+pub use dp_column_hierarchy::*;
+pub use dp_marginal_parameters::*;
 pub use dp_parameters::*;
+pub use dp_parameters_advisor::*;
+pub use dp_workload::*;
 pub use noise_aggregator::*;
 pub use noisy_count_threshold::*;
 pub use percentile::*;
 pub use stats_error::*;
 pub use typedefs::*;
 
+pub(crate) use noise_parameters::NoiseParameters;
+
 #[cfg(feature = "pyo3")]
 pub use register_pyo3::*;
@@ -1,10 +1,15 @@
-use super::{CombinationsByRecord, DpParameters, DpPercentile, NoisyCountThreshold};
+use super::{
+    hierarchical_consistency::HierarchicalConsistency, workload_weights::WorkloadWeights,
+    CombinationsByRecord, DpBudgetAllocation, DpBudgetShare, DpColumnHierarchy, DpParameters,
+    DpPercentile, NoisyCountThreshold,
+};
 use fnv::FnvHashSet;
 use itertools::Itertools;
 use log::{debug, info, warn};
 use rand::{
     prelude::{Distribution as rand_dist, IteratorRandom},
-    thread_rng,
+    rngs::StdRng,
+    Rng, SeedableRng,
 };
 use statrs::distribution::{ContinuousCDF, Normal};
 use std::sync::Arc;
@@ -37,6 +42,11 @@ pub struct NoiseAggregator {
     sigmas: Vec<f64>,
     threshold: NoisyCountThreshold,
     protected_number_of_records: Option<usize>,
+    workload_weights: WorkloadWeights,
+    weights_by_len: Vec<Vec<f64>>,
+    budget_allocation: DpBudgetAllocation,
+    column_hierarchies: Vec<DpColumnHierarchy>,
+    rng: StdRng,
 }
 
 impl NoiseAggregator {
@@ -157,25 +167,67 @@ impl NoiseAggregator {
 
     #[inline]
     fn get_max_and_allowed_sensitivities(
-        &self,
+        &mut self,
         combinations_by_record: &CombinationsByRecord,
+        comb_len: usize,
     ) -> (usize, usize) {
-        let sensitivities: Vec<usize> = combinations_by_record
-            .iter()
-            .map(|combinations| combinations.len())
-            .collect();
-        let max_sensitivity = sensitivities.iter().max().cloned().unwrap_or(0);
-        let percentile_selector = DpPercentile::new(sensitivities);
-        let allowed_sensitivity = percentile_selector
-            .kth_percentile_quality_scores_iter(self.percentile_percentage)
-            .get_noisy_max(self.percentile_epsilon)
-            .unwrap_or(0);
-
-        (max_sensitivity, allowed_sensitivity)
+        let number_of_columns = self.data_block.headers.len();
+        let allowed_sensitivity = NoiseAggregator::select_allowed_sensitivity(
+            combinations_by_record
+                .iter()
+                .map(|combinations| combinations.len())
+                .collect(),
+            number_of_columns,
+            comb_len,
+            self.percentile_percentage,
+            // a single subject changes the sensitivities of up to `max_records_per_subject` records
+            self.percentile_epsilon / self.max_records_per_subject(),
+            &mut self.rng,
+        );
+
+        (
+            NoiseAggregator::calc_max_sensitivity(number_of_columns, comb_len),
+            allowed_sensitivity,
+        )
+    }
+
+    /// Maximum number of `comb_len` combinations a single record can contribute to:
+    /// `C(number_of_columns, comb_len)`, which does not depend on the data
+    #[inline]
+    pub(crate) fn calc_max_sensitivity(number_of_columns: usize, comb_len: usize) -> usize {
+        if comb_len > number_of_columns {
+            return 0;
+        }
+        (1..=comb_len).fold(1, |acc: usize, i| {
+            acc.saturating_mul(number_of_columns + 1 - i) / i
+        })
+    }
+
+    /// Selects the allowed sensitivity for the `comb_len` counts as the DP
+    /// `percentile_percentage` percentile of `sensitivities` (number of combinations
+    /// of each record). The selectable values range up to `calc_max_sensitivity`,
+    /// so the range itself does not reveal the largest sensitivity on the data
+    #[inline]
+    pub(crate) fn select_allowed_sensitivity<R: Rng>(
+        sensitivities: Vec<usize>,
+        number_of_columns: usize,
+        comb_len: usize,
+        percentile_percentage: usize,
+        epsilon: f64,
+        rng: &mut R,
+    ) -> usize {
+        DpPercentile::new_with_max_value(
+            sensitivities,
+            NoiseAggregator::calc_max_sensitivity(number_of_columns, comb_len),
+        )
+        .kth_percentile_quality_scores_iter(percentile_percentage)
+        .get_noisy_max_with_rng(epsilon, rng)
+        .unwrap_or(0)
     }
 
     #[inline]
-    fn increment_count_based_on_sensitivity(
+    fn increment_count_based_on_sensitivity<R: Rng>(
+        rng: &mut R,
         all_current_aggregates: &mut CombinationsCountMap,
         combinations_by_record: &CombinationsByRecord,
         l1_sensitivity: usize,
@@ -184,7 +236,7 @@ impl NoiseAggregator {
             if combinations.len() > l1_sensitivity {
                 for comb in combinations
                     .iter()
-                    .choose_multiple(&mut thread_rng(), l1_sensitivity)
+                    .choose_multiple(rng, l1_sensitivity)
                     .drain(..)
                 {
                     (*all_current_aggregates
@@ -202,32 +254,53 @@ impl NoiseAggregator {
     }
 
     #[inline]
-    fn add_gaussian_noise(all_current_aggregates: &mut CombinationsCountMap, current_sigma: f64) {
+    fn add_gaussian_noise<R: Rng>(
+        rng: &mut R,
+        all_current_aggregates: &mut CombinationsCountMap,
+        current_sigma: f64,
+    ) {
         let noise = Normal::new(0.0, 1.0).expect("error generating Normal noise");
 
         for count in all_current_aggregates.values_mut() {
-            (*count) += current_sigma * noise.sample(&mut thread_rng());
+            (*count) += current_sigma * noise.sample(rng);
         }
     }
 
     #[inline]
-    fn calc_threshold(&self, l1_sensitivity: f64, comb_len: usize) -> f64 {
+    fn max_records_per_subject(&self) -> f64 {
+        self.data_block.max_records_per_subject as f64
+    }
+
+    #[inline]
+    fn calc_threshold(&self, l1_sensitivity: f64, comb_len: usize, sigma: f64) -> f64 {
         if comb_len == 1 {
-            1.0 + (self.sigmas[0]
+            // delta is split between the single attribute
+            // counts reported with different weights
+            let delta = self.delta / (self.weights_by_len[0].len() as f64);
+            // a single subject contributes to up to `k * l1_sensitivity` single
+            // attributes, increasing each of their counts by up to `k`
+            let k = self.max_records_per_subject();
+
+            k + (sigma
                 * l1_sensitivity.sqrt()
+                * k
                 * Normal::new(0.0, 1.0)
                     .expect("error creating Normal for inverse CDF")
-                    .inverse_cdf((1.0 - (self.delta / 2.0)).powf(1.0 / l1_sensitivity)))
+                    .inverse_cdf((1.0 - (delta / 2.0)).powf(1.0 / (k * l1_sensitivity))))
         } else {
             // thresholds should start at index 2 (1-counts needs to be fixed to guarantee DP)
             match self.threshold.clone() {
                 NoisyCountThreshold::Fixed(thresholds) => {
                     thresholds.get(&comb_len).cloned().unwrap_or(0.0)
                 }
+                // the top-k selection happens after all
+                // the weights have been processed for the length
+                NoisyCountThreshold::TopK(_) => 0.0,
                 NoisyCountThreshold::Adaptive(thresholds) => {
                     // PPF at 0.5 should give threshold = 0
-                    self.sigmas[comb_len - 1]
+                    sigma
                         * l1_sensitivity.sqrt()
+                        * self.max_records_per_subject()
                         // threshold values should be between 0 and 0.5
                         // we are dividing by 2 here to normalize it between 0 and 1.0
                         * Normal::new(0.0, 1.0).expect("error creating Normal for inverse CDF").inverse_cdf(
@@ -246,8 +319,9 @@ impl NoiseAggregator {
         all_current_aggregates: &mut CombinationsCountMap,
         l1_sensitivity: f64,
         comb_len: usize,
+        sigma: f64,
     ) {
-        let threshold = self.calc_threshold(l1_sensitivity, comb_len);
+        let threshold = self.calc_threshold(l1_sensitivity, comb_len, sigma);
 
         debug!("used threshold = {}", threshold);
 
@@ -258,30 +332,42 @@ impl NoiseAggregator {
 
     #[inline]
     fn add_gaussian_noise_and_retain_based_on_threshold(
-        &self,
+        &mut self,
         all_current_aggregates: &mut CombinationsCountMap,
         combinations_by_record: &CombinationsByRecord,
         comb_len: usize,
         l1_sensitivity: usize,
+        sigma: f64,
     ) {
         info!(
-            "applying gaussian noise to aggregates with length = {}, sigma = {}, l1_sensitivity = {}",
-            comb_len, self.sigmas[comb_len - 1], l1_sensitivity
+            "applying gaussian noise to aggregates with length = {}, sigma = {}, l1_sensitivity = {}, max_records_per_subject = {}",
+            comb_len, sigma, l1_sensitivity, self.data_block.max_records_per_subject
         );
 
         if l1_sensitivity > 0 {
             let l1_sensitivity_f64 = l1_sensitivity as f64;
-            let current_sigma = self.sigmas[comb_len - 1] * l1_sensitivity_f64.sqrt();
+            // l2 sensitivity of a subject contributing with up to `max_records_per_subject` records
+            let current_sigma = sigma * l1_sensitivity_f64.sqrt() * self.max_records_per_subject();
 
             NoiseAggregator::increment_count_based_on_sensitivity(
+                &mut self.rng,
                 all_current_aggregates,
                 combinations_by_record,
                 l1_sensitivity,
             );
 
-            NoiseAggregator::add_gaussian_noise(all_current_aggregates, current_sigma);
+            NoiseAggregator::add_gaussian_noise(
+                &mut self.rng,
+                all_current_aggregates,
+                current_sigma,
+            );
 
-            self.retain_based_on_threshold(all_current_aggregates, l1_sensitivity_f64, comb_len);
+            self.retain_based_on_threshold(
+                all_current_aggregates,
+                l1_sensitivity_f64,
+                comb_len,
+                sigma,
+            );
 
             debug!("noise added to {}-counts", comb_len);
         } else {
@@ -325,10 +411,145 @@ impl NoiseAggregator {
         aggregated_data.remove_zero_counts();
         aggregated_data.add_missing_parent_combinations();
         aggregated_data.normalize_noisy_combinations();
+        aggregated_data.dp_budget_allocation = Some(self.budget_allocation.clone());
+        aggregated_data.column_hierarchies = self.column_hierarchies.clone();
 
         aggregated_data
     }
 
+    #[inline]
+    fn split_aggregates_by_weight(
+        &self,
+        mut all_current_aggregates: CombinationsCountMap,
+        comb_len: usize,
+    ) -> Vec<CombinationsCountMap> {
+        let weights = &self.weights_by_len[comb_len - 1];
+        let mut aggregates_by_weight: Vec<CombinationsCountMap> = weights
+            .iter()
+            .map(|_| CombinationsCountMap::default())
+            .collect();
+
+        for (comb, count) in all_current_aggregates.drain() {
+            let weight = self.workload_weights.weight_of(&comb);
+
+            // combinations without a matching weight are not
+            // covered by the workload and should not be reported
+            if let Some(i) = weights.iter().position(|w| *w == weight) {
+                aggregates_by_weight[i].insert(comb, count);
+            }
+        }
+        aggregates_by_weight
+    }
+
+    #[inline]
+    fn retain_top_k(
+        &mut self,
+        current_noisy_aggregates: &mut CombinationsCountMap,
+        comb_len: usize,
+    ) {
+        let k = match &self.threshold {
+            NoisyCountThreshold::TopK(top_k) => top_k.get(&comb_len).cloned(),
+            _ => None,
+        };
+
+        if let Some(k) = k {
+            if current_noisy_aggregates.len() > k {
+                // sorting the noisy counts only post-processes the gaussian noise
+                let mut sorted_aggregates = current_noisy_aggregates.drain().collect_vec();
+
+                sorted_aggregates.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+                sorted_aggregates.truncate(k);
+                current_noisy_aggregates.extend(sorted_aggregates);
+
+                debug!("kept the top {} noisy {}-counts", k, comb_len);
+
+                // update the reported number of combinations for the shares of this length
+                for share in self
+                    .budget_allocation
+                    .shares
+                    .iter_mut()
+                    .filter(|share| share.combination_length == comb_len)
+                {
+                    share.number_of_combinations = current_noisy_aggregates
+                        .keys()
+                        .filter(|comb| self.workload_weights.weight_of(comb) == share.weight)
+                        .count();
+                }
+            }
+        }
+    }
+
+    /// Variance of the noise added to `comb`, based on the
+    /// budget share used to report it
+    #[inline]
+    fn noise_variance_of(&self, comb: &ValueCombination) -> f64 {
+        let weight = self.workload_weights.weight_of(comb);
+
+        self.budget_allocation
+            .shares
+            .iter()
+            .find(|share| share.combination_length == comb.len() && share.weight == weight)
+            .map_or(1.0, |share| {
+                let std = share.sigma
+                    * (share.allowed_sensitivity as f64).sqrt()
+                    * self.max_records_per_subject();
+                std * std
+            })
+    }
+
+    fn enforce_hierarchical_consistency(
+        &self,
+        noisy_aggregates_by_len: &mut CombinationsCountMapByLen,
+    ) {
+        for hierarchy in self.column_hierarchies.iter() {
+            match hierarchy.column_indexes(&self.data_block.headers) {
+                Some(column_indexes) => {
+                    info!(
+                        "enforcing consistency for column hierarchy {}",
+                        hierarchy.columns.join(" > ")
+                    );
+                    HierarchicalConsistency::new(&column_indexes, self.reporting_length)
+                        .enforce(noisy_aggregates_by_len, |comb| self.noise_variance_of(comb));
+                }
+                None => warn!(
+                    "column hierarchy {:?} not found in the data headers, skipping it",
+                    hierarchy.columns
+                ),
+            }
+        }
+    }
+
+    #[inline]
+    fn log_budget_allocation(&self) {
+        info!(
+            "dp budget allocation: epsilon = {}, delta = {}, number of records epsilon = {}, marginals epsilon = {}, marginals rho = {}",
+            self.budget_allocation.epsilon,
+            self.budget_allocation.delta,
+            self.budget_allocation.number_of_records_epsilon,
+            self.budget_allocation.marginals_epsilon,
+            self.budget_allocation.marginals_rho
+        );
+        if let Some(sampling_rate) = self.budget_allocation.sampling_rate {
+            info!(
+                "amplified by subsampling with rate = {}: epsilon = {}, delta = {}",
+                sampling_rate,
+                self.budget_allocation.amplified_epsilon,
+                self.budget_allocation.amplified_delta
+            );
+        }
+        for share in self.budget_allocation.shares.iter() {
+            info!(
+                "dp budget share: length = {}, weight = {}, sigma = {}, allowed sensitivity = {}, budget proportion = {:.4}, combinations = {}",
+                share.combination_length,
+                share.weight,
+                share.sigma,
+                share.allowed_sensitivity,
+                share.budget_proportion,
+                share.number_of_combinations
+            );
+        }
+    }
+
     #[inline]
     fn update_progress<T>(
         progress_reporter: &mut Option<T>,
@@ -359,6 +580,12 @@ impl NoiseAggregator {
         dp_parameters: &DpParameters,
         threshold: NoisyCountThreshold,
     ) -> NoiseAggregator {
+        let workload_weights = WorkloadWeights::new(
+            &dp_parameters.workload,
+            &data_block.headers,
+            &data_block.multi_value_column_metadata_map,
+        );
+        let weights_by_len = workload_weights.weights_by_len(reporting_length);
         let noise_parameters = NoiseParameters::new(
             reporting_length,
             dp_parameters.epsilon,
@@ -366,11 +593,23 @@ impl NoiseAggregator {
             dp_parameters.percentile_epsilon_proportion,
             &dp_parameters.number_of_records_epsilon_proportion,
             &dp_parameters.sigma_proportions,
+            &weights_by_len,
             data_block.number_of_records(),
+            data_block.max_records_per_subject,
         );
 
         info!("resulting noise parameters = {noise_parameters:?}");
 
+        let sampling_rate = data_block.sampling_rate;
+        let (amplified_epsilon, amplified_delta) = match sampling_rate {
+            Some(sampling_rate) => NoiseParameters::calc_amplified_epsilon_and_delta(
+                dp_parameters.epsilon,
+                noise_parameters.delta,
+                sampling_rate,
+            ),
+            None => (dp_parameters.epsilon, noise_parameters.delta),
+        };
+
         NoiseAggregator {
             data_block,
             reporting_length,
@@ -380,9 +619,35 @@ impl NoiseAggregator {
             sigmas: noise_parameters.sigmas,
             threshold,
             protected_number_of_records: noise_parameters.protected_number_of_records,
+            workload_weights,
+            weights_by_len,
+            budget_allocation: DpBudgetAllocation {
+                epsilon: dp_parameters.epsilon,
+                delta: noise_parameters.delta,
+                number_of_records_epsilon: noise_parameters.number_of_records_epsilon,
+                marginals_epsilon: noise_parameters.marginals_epsilon,
+                marginals_rho: noise_parameters.marginals_rho,
+                shares: Vec::default(),
+                sampling_rate,
+                amplified_epsilon,
+                amplified_delta,
+            },
+            column_hierarchies: dp_parameters.column_hierarchies.clone().unwrap_or_default(),
+            rng: StdRng::from_entropy(),
         }
     }
 
+    /// Seeds the random generator used to add noise to the aggregates,
+    /// so the same noisy aggregates are generated on every run
+    /// (the noise protecting the number of records is not affected)
+    /// # Arguments
+    /// * `seed` - Seed for the random generator
+    #[inline]
+    pub fn with_seed(mut self, seed: u64) -> NoiseAggregator {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
     /// Generates the `current_comb_len`-counts. These will be the cross
     /// product `noisy_aggregates_by_len[current_comb_len - 1] x distinct_attributes(noisy_aggregates_by_len[current_comb_len - 1])`:
     /// - All the combinations will be created as a starting 0 count
@@ -414,36 +679,70 @@ impl NoiseAggregator {
         let mut noisy_aggregates_by_len = CombinationsCountMapByLen::default();
         let sorted_records = self.gen_sorted_records();
 
+        self.budget_allocation.shares.clear();
+
         for l in 1..=self.reporting_length {
-            let mut all_current_aggregates =
+            let all_current_aggregates =
                 self.gen_all_current_aggregates(&noisy_aggregates_by_len, l);
-            let combinations_by_record = NoiseAggregator::gen_valid_combinations_by_record(
-                &sorted_records,
-                l,
-                &all_current_aggregates,
-            );
-            let (max_sensitivity, allowed_sensitivity) =
-                self.get_max_and_allowed_sensitivities(&combinations_by_record);
+            let mut current_noisy_aggregates = CombinationsCountMap::default();
+
+            // each workload weight is reported with its own sensitivity and sigma
+            for (mut aggregates, weight) in self
+                .split_aggregates_by_weight(all_current_aggregates, l)
+                .into_iter()
+                .zip(self.weights_by_len[l - 1].clone())
+            {
+                let sigma = self.sigmas[l - 1] / weight;
+                let combinations_by_record = NoiseAggregator::gen_valid_combinations_by_record(
+                    &sorted_records,
+                    l,
+                    &aggregates,
+                );
+                let (max_sensitivity, allowed_sensitivity) =
+                    self.get_max_and_allowed_sensitivities(&combinations_by_record, l);
 
-            debug!(
-                "allowed sensitivity for {}-counts is {} out of {}",
-                l, allowed_sensitivity, max_sensitivity
-            );
+                debug!(
+                    "allowed sensitivity for {}-counts with weight {} is {} out of at most {}",
+                    l, weight, allowed_sensitivity, max_sensitivity
+                );
 
-            self.add_gaussian_noise_and_retain_based_on_threshold(
-                &mut all_current_aggregates,
-                &combinations_by_record,
-                l,
-                allowed_sensitivity,
-            );
+                self.add_gaussian_noise_and_retain_based_on_threshold(
+                    &mut aggregates,
+                    &combinations_by_record,
+                    l,
+                    allowed_sensitivity,
+                    sigma,
+                );
+
+                let rho = (self.percentile_epsilon * self.percentile_epsilon / 2.0)
+                    + (1.0 / (2.0 * sigma * sigma));
+
+                self.budget_allocation.shares.push(DpBudgetShare {
+                    combination_length: l,
+                    weight,
+                    percentile_epsilon: self.percentile_epsilon,
+                    sigma,
+                    allowed_sensitivity,
+                    rho,
+                    budget_proportion: rho / self.budget_allocation.marginals_rho,
+                    number_of_combinations: aggregates.len(),
+                });
+
+                current_noisy_aggregates.extend(aggregates);
+            }
+
+            self.retain_top_k(&mut current_noisy_aggregates, l);
 
             debug!("generated noisy {}-counts", l);
 
-            noisy_aggregates_by_len.insert(l, all_current_aggregates);
+            noisy_aggregates_by_len.insert(l, current_noisy_aggregates);
 
             NoiseAggregator::update_progress(progress_reporter, l, self.reporting_length)?;
         }
 
+        self.log_budget_allocation();
+        self.enforce_hierarchical_consistency(&mut noisy_aggregates_by_len);
+
         Ok(self.build_aggregated_data(noisy_aggregates_by_len))
     }
 }
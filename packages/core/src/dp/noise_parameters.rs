@@ -11,6 +11,9 @@ pub(crate) struct NoiseParameters {
     pub(crate) sigmas: Vec<f64>,
     pub(crate) delta: f64,
     pub(crate) protected_number_of_records: Option<usize>,
+    pub(crate) number_of_records_epsilon: f64,
+    pub(crate) marginals_epsilon: f64,
+    pub(crate) marginals_rho: f64,
 }
 
 impl NoiseParameters {
@@ -33,7 +36,7 @@ impl NoiseParameters {
     }
 
     #[inline]
-    fn delta_value_or_default(delta_opt: &Option<f64>, number_of_records: usize) -> f64 {
+    pub(crate) fn delta_value_or_default(delta_opt: &Option<f64>, number_of_records: usize) -> f64 {
         assert!(
             number_of_records > 0,
             "number_of_records must be greater than 0"
@@ -101,6 +104,38 @@ impl NoiseParameters {
         protected_number_of_records as usize
     }
 
+    /// Amplifies an (epsilon, delta) budget spent on a Poisson subsample,
+    /// where each subject is independently sampled with probability `sampling_rate`
+    /// (`epsilon' = ln(1 + rate * (e^epsilon - 1))` and `delta' = rate * delta`).
+    /// This bound holds for add/remove neighboring datasets, so it should not be
+    /// used for fixed size (uniform) subsamples
+    #[inline]
+    pub(crate) fn calc_amplified_epsilon_and_delta(
+        epsilon: f64,
+        delta: f64,
+        sampling_rate: f64,
+    ) -> (f64, f64) {
+        (
+            (sampling_rate * epsilon.exp_m1()).ln_1p(),
+            sampling_rate * delta,
+        )
+    }
+
+    /// Converts an (epsilon, delta) budget to the equivalent
+    /// zero concentrated differential privacy (zCDP) rho
+    #[inline]
+    pub(crate) fn calc_rho(epsilon: f64, delta: f64) -> f64 {
+        let rho_sqrt = (epsilon + (2.0 / delta).ln()).sqrt() - (2.0 / delta).ln().sqrt();
+        rho_sqrt * rho_sqrt
+    }
+
+    /// Converts a zero concentrated differential privacy (zCDP) rho
+    /// back to the equivalent epsilon (inverse of `calc_rho`)
+    #[inline]
+    pub(crate) fn calc_epsilon(rho: f64, delta: f64) -> f64 {
+        rho + 2.0 * (rho * (2.0 / delta).ln()).sqrt()
+    }
+
     #[inline]
     fn get_tolerance_from_delta(delta: f64) -> f64 {
         assert!(delta < 1.0 && delta > 0.0, "delta must be between 0 and 1");
@@ -134,26 +169,43 @@ impl NoiseParameters {
 
     #[inline]
     fn calc_percentile_epsilon_and_sigmas(
-        reporting_length: usize,
         marginals_epsilon: f64,
         delta: f64,
         sigma_proportions: &[f64],
+        weights_by_len: &[Vec<f64>],
         percentile_epsilon_proportion: f64,
-    ) -> (f64, Vec<f64>) {
+    ) -> (f64, Vec<f64>, f64) {
         assert!(
             percentile_epsilon_proportion < 1.0 && percentile_epsilon_proportion > 0.0,
             "percentile_epsilon_proportion must be > 0 and < 1"
         );
+        assert!(
+            sigma_proportions.len() == weights_by_len.len(),
+            "workload weights should be provided for every combination length",
+        );
 
-        let t = reporting_length as f64;
-        let rho_sqrt = (marginals_epsilon + (2.0 / delta).ln()).sqrt() - (2.0 / delta).ln().sqrt();
-        let rho = rho_sqrt * rho_sqrt;
-        let k: f64 = sigma_proportions.iter().map(|p| 1.0 / (p * p)).sum();
+        // each (combination length, weight) pair is reported with its own
+        // percentile filter and sigma = sigma_proportion * base_sigma / weight
+        let t = weights_by_len.iter().map(|w| w.len()).sum::<usize>() as f64;
+
+        assert!(t > 0.0, "there are no combinations to be reported");
+
+        let rho = NoiseParameters::calc_rho(marginals_epsilon, delta);
+        let k: f64 = sigma_proportions
+            .iter()
+            .zip(weights_by_len.iter())
+            .map(|(p, weights)| weights.iter().map(|w| (w * w) / (p * p)).sum::<f64>())
+            .sum();
         let percentile_epsilon = (2.0 * rho * percentile_epsilon_proportion / t).sqrt();
         let base_sigma = (k / (2.0 * rho * (1.0 - percentile_epsilon_proportion))).sqrt();
         let sigmas: Vec<f64> = sigma_proportions.iter().map(|p| p * base_sigma).collect();
         let lhs = ((t * percentile_epsilon * percentile_epsilon) / 2.0)
-            + (sigmas.iter().map(|s| 1.0 / (s * s)).sum::<f64>() / 2.0);
+            + (sigmas
+                .iter()
+                .zip(weights_by_len.iter())
+                .map(|(s, weights)| weights.iter().map(|w| (w * w) / (s * s)).sum::<f64>())
+                .sum::<f64>()
+                / 2.0);
 
         info!("percentile epsilon = {percentile_epsilon}, calculated sigmas = {sigmas:?}");
 
@@ -162,7 +214,7 @@ impl NoiseParameters {
             NoiseParameters::get_tolerance_from_delta(delta).min(DEFAULT_TOLERANCE),
         );
 
-        (percentile_epsilon, sigmas)
+        (percentile_epsilon, sigmas, rho)
     }
 
     #[inline]
@@ -173,6 +225,7 @@ impl NoiseParameters {
         number_of_records_epsilon_proportion_opt: &Option<f64>,
         sigma_proportions_opt: &Option<Vec<f64>>,
         number_of_records: usize,
+        max_records_per_subject: usize,
     ) -> (Vec<f64>, f64, f64, Option<usize>, f64) {
         let sigma_proportions = NoiseParameters::unwrap_sigma_proportions_or_default(
             sigma_proportions_opt,
@@ -192,9 +245,10 @@ impl NoiseParameters {
                     *number_of_records_epsilon_proportion,
                 );
 
-            // consume budget to protect number of records
+            // consume budget to protect number of records - a single subject
+            // can change the number of records by up to `max_records_per_subject`
             protected_number_of_records = Some(NoiseParameters::protect_number_of_records(
-                number_of_records_epsilon,
+                number_of_records_epsilon / (max_records_per_subject as f64),
                 number_of_records,
             ));
 
@@ -222,6 +276,7 @@ impl NoiseParameters {
     }
 
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         reporting_length: usize,
         total_epsilon: f64,
@@ -229,7 +284,9 @@ impl NoiseParameters {
         percentile_epsilon_proportion: f64,
         number_of_records_epsilon_proportion_opt: &Option<f64>,
         sigma_proportions_opt: &Option<Vec<f64>>,
+        weights_by_len: &[Vec<f64>],
         number_of_records: usize,
+        max_records_per_subject: usize,
     ) -> NoiseParameters {
         let (
             sigma_proportions,
@@ -244,6 +301,7 @@ impl NoiseParameters {
             number_of_records_epsilon_proportion_opt,
             sigma_proportions_opt,
             number_of_records,
+            max_records_per_subject,
         );
 
         info!(
@@ -254,22 +312,27 @@ impl NoiseParameters {
                 delta = {delta},
                 percentile_epsilon_proportion = {percentile_epsilon_proportion},
                 number_of_records_epsilon_proportion = {number_of_records_epsilon_proportion_opt:?},
-                sigma_proportions = {sigma_proportions:?}"
+                sigma_proportions = {sigma_proportions:?},
+                weights_by_len = {weights_by_len:?}"
         );
 
-        let (percentile_epsilon, sigmas) = NoiseParameters::calc_percentile_epsilon_and_sigmas(
-            reporting_length,
-            marginals_epsilon,
-            delta,
-            &sigma_proportions,
-            percentile_epsilon_proportion,
-        );
+        let (percentile_epsilon, sigmas, marginals_rho) =
+            NoiseParameters::calc_percentile_epsilon_and_sigmas(
+                marginals_epsilon,
+                delta,
+                &sigma_proportions,
+                weights_by_len,
+                percentile_epsilon_proportion,
+            );
 
         NoiseParameters {
             percentile_epsilon,
             sigmas,
             delta,
             protected_number_of_records,
+            number_of_records_epsilon,
+            marginals_epsilon,
+            marginals_rho,
         }
     }
 }
@@ -13,4 +13,9 @@ pub enum NoisyCountThreshold {
     /// the fabricated counts distribution
     /// (this should be a value between 0 and 1.0)
     Adaptive(InputValueByLen<f64>),
+    /// Keep only the `k` combinations with the highest noisy counts
+    /// per combination length (lengths not set keep every positive noisy count).
+    /// This is post-processing of the gaussian noise already added to the counts,
+    /// so no additional privacy budget is spent
+    TopK(InputValueByLen<usize>),
 }
@@ -1,6 +1,6 @@
 use fnv::FnvHashMap;
 use itertools::Itertools;
-use rand::prelude::Distribution;
+use rand::{prelude::Distribution, Rng};
 use statrs::{
     distribution::Exp,
     statistics::{Data, OrderStatistics},
@@ -17,6 +17,7 @@ use std::{cmp::Ordering, ops::RangeInclusive};
 /// vector that need to change their values for the current element to became the k-th percentile }`.
 pub struct DpPercentile {
     data: Vec<usize>,
+    max_value: Option<usize>,
 }
 
 impl DpPercentile {
@@ -24,7 +25,23 @@ impl DpPercentile {
     /// Creates a new `DpPercentile` for the vector `data` given as parameter
     pub fn new(mut data: Vec<usize>) -> DpPercentile {
         data.sort_unstable();
-        DpPercentile { data }
+        DpPercentile {
+            data,
+            max_value: None,
+        }
+    }
+
+    #[inline]
+    /// Creates a new `DpPercentile` for the vector `data` given as parameter,
+    /// using `M = max_value` instead of the maximum on `data`. Since `max_value`
+    /// does not depend on the data, the range of selectable values is the same
+    /// for neighboring inputs (values greater than `max_value` are clamped to it)
+    pub fn new_with_max_value(data: Vec<usize>, max_value: usize) -> DpPercentile {
+        let mut percentile =
+            DpPercentile::new(data.into_iter().map(|d| d.min(max_value)).collect());
+
+        percentile.max_value = Some(max_value);
+        percentile
     }
 
     /// Calculates the raw quality scores for the k-th percentile and returns an iterator
@@ -69,7 +86,9 @@ impl DpPercentile {
         percentile_value: usize,
         percentile_index: isize,
     ) -> QualityScoreIter {
-        let last_index = *self.data.iter().max().unwrap();
+        let last_index = self
+            .max_value
+            .unwrap_or_else(|| *self.data.iter().max().unwrap());
         let data_len_float = self.data.len() as f64;
         let max_indexes = self.build_max_index_map();
 
@@ -122,12 +141,17 @@ impl QualityScoreIter {
     /// Consumes the quality iterator, applying exponential noise with scale of
     /// `epsilon / 2` and selecting the `relative_value` with highest `score + noise`.
     pub fn get_noisy_max(&mut self, epsilon: f64) -> Option<usize> {
+        self.get_noisy_max_with_rng(epsilon, &mut rand::thread_rng())
+    }
+
+    /// Same as `get_noisy_max`, but sampling the noise from `rng`
+    /// (so the selection can be reproduced with a seeded generator)
+    pub fn get_noisy_max_with_rng<R: Rng>(&mut self, epsilon: f64, rng: &mut R) -> Option<usize> {
         let noise = Exp::new(epsilon / 2.0).ok()?;
-        let mut r = rand::thread_rng();
         let mut max_score: Option<(usize, f64)> = None;
 
         for (i, score) in self.enumerate() {
-            let noisy_score = (score as f64) + noise.sample(&mut r);
+            let noisy_score = (score as f64) + noise.sample(rng);
 
             max_score = Some(if let Some((related_value, score)) = max_score {
                 if noisy_score > score {
@@ -1,7 +1,11 @@
-use super::DpParameters;
+use super::{DpColumnHierarchy, DpMarginalParameters, DpParameters, DpWorkload, DpWorkloadQuery};
 use pyo3::{types::PyModule, PyResult, Python};
 
 pub fn register_pyo3(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<DpParameters>()?;
+    m.add_class::<DpMarginalParameters>()?;
+    m.add_class::<DpWorkloadQuery>()?;
+    m.add_class::<DpWorkload>()?;
+    m.add_class::<DpColumnHierarchy>()?;
     Ok(())
 }
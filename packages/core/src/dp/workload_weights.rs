@@ -0,0 +1,143 @@
+use super::DpWorkload;
+use fnv::FnvHashSet;
+use itertools::Itertools;
+
+use crate::{
+    data_block::{DataBlockHeadersSlice, MultiValueColumnMetadataMap},
+    processing::aggregator::ValueCombination,
+};
+
+/// Resolves the workload weight of each attribute combination,
+/// based on the columns the combination is made of
+pub(crate) struct WorkloadWeights {
+    /// Sorted column indexes covered by each query with its weight
+    queries: Vec<(Vec<usize>, f64)>,
+    uncovered_weight: f64,
+    number_of_columns: usize,
+}
+
+impl WorkloadWeights {
+    /// Returns a new WorkloadWeights
+    /// # Arguments
+    /// * `workload` - Workload to resolve the weights from (already checked
+    /// with `DpWorkload::validate`), if `None` every combination will have a weight of `1.0`
+    /// * `headers` - Headers of the data being aggregated
+    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name
+    /// to its corresponding metadata
+    pub fn new(
+        workload: &Option<DpWorkload>,
+        headers: &DataBlockHeadersSlice,
+        multi_value_column_metadata_map: &MultiValueColumnMetadataMap,
+    ) -> WorkloadWeights {
+        match workload {
+            Some(workload) => WorkloadWeights {
+                queries: workload
+                    .queries
+                    .iter()
+                    .map(|query| {
+                        let columns = headers
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(column_index, header)| {
+                                let src_header = multi_value_column_metadata_map
+                                    .get(header)
+                                    .map(|m| &m.src_header_name);
+
+                                if query.columns.iter().any(|c| {
+                                    **header == *c || src_header.is_some_and(|s| **s == *c)
+                                }) {
+                                    Some(column_index)
+                                } else {
+                                    None
+                                }
+                            })
+                            .collect();
+                        (columns, query.weight)
+                    })
+                    .collect(),
+                uncovered_weight: workload.uncovered_weight,
+                number_of_columns: headers.len(),
+            },
+            None => WorkloadWeights {
+                queries: Vec::default(),
+                uncovered_weight: 1.0,
+                number_of_columns: headers.len(),
+            },
+        }
+    }
+
+    #[inline]
+    fn weight_of_columns(&self, columns: &[usize]) -> f64 {
+        self.queries
+            .iter()
+            .filter_map(|(query_columns, weight)| {
+                if columns
+                    .iter()
+                    .all(|c| query_columns.binary_search(c).is_ok())
+                {
+                    Some(*weight)
+                } else {
+                    None
+                }
+            })
+            .reduce(f64::max)
+            .unwrap_or(self.uncovered_weight)
+    }
+
+    /// Weight of the combination, this is the highest weight among the queries
+    /// covering all the combination columns (or the uncovered weight, if there is none)
+    #[inline]
+    pub fn weight_of(&self, comb: &ValueCombination) -> f64 {
+        self.weight_of_columns(&comb.iter().map(|v| v.column_index).collect_vec())
+    }
+
+    #[inline]
+    fn n_choose_k(n: usize, k: usize) -> f64 {
+        if k > n {
+            0.0
+        } else {
+            (0..k).fold(1.0, |acc, i| acc * ((n - i) as f64) / ((i + 1) as f64))
+        }
+    }
+
+    /// Lists the distinct weights (sorted desc) that combinations of each length
+    /// can have - this only depends on the workload and headers, not on the data.
+    /// Index `0` of the result holds the weights for length `1`, and so on
+    /// # Arguments
+    /// * `reporting_length` - Maximum combination length
+    pub fn weights_by_len(&self, reporting_length: usize) -> Vec<Vec<f64>> {
+        (1..=reporting_length)
+            .map(|l| {
+                if self.queries.is_empty() {
+                    // no workload, every combination shares the same weight
+                    return if self.uncovered_weight > 0.0 {
+                        vec![self.uncovered_weight]
+                    } else {
+                        Vec::default()
+                    };
+                }
+
+                let mut covered_columns: FnvHashSet<Vec<usize>> = FnvHashSet::default();
+                let mut weights: Vec<f64> = Vec::default();
+
+                for (query_columns, _) in self.queries.iter() {
+                    for columns in query_columns.iter().cloned().combinations(l) {
+                        weights.push(self.weight_of_columns(&columns));
+                        covered_columns.insert(columns);
+                    }
+                }
+
+                if self.uncovered_weight > 0.0
+                    && WorkloadWeights::n_choose_k(self.number_of_columns, l)
+                        > covered_columns.len() as f64
+                {
+                    weights.push(self.uncovered_weight);
+                }
+
+                weights.sort_by(|a, b| b.partial_cmp(a).unwrap());
+                weights.dedup();
+                weights
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
     records_analysis_data::RecordsAnalysisData,
+    records_risk::RecordsRisk,
     typedefs::{
         AggregatedCountByLenMap, AggregatedMetricByLenMap, AggregatesCountMap,
         AggregatesCountStringMap, RecordsByLenMap, RecordsSensitivityByLen,
@@ -26,8 +27,12 @@ use crate::{
     data_block::{
         DataBlockHeaders, DataBlockValue, MultiValueColumnMetadataMap, COLUMN_VALUE_DELIMITER,
     },
+    dp::{DpBudgetAllocation, DpColumnHierarchy},
     processing::{
-        aggregator::{typedefs::RecordsSet, value_combination::ValueCombination, AggregatedCount},
+        aggregator::{
+            aggregates_count_reader::AggregatesCountReader, typedefs::RecordsSet,
+            value_combination::ValueCombination, AggregatedCount,
+        },
         generator::AttributeCountMap,
     },
     utils::{math::uround_down, time::ElapsedDurationLogger},
@@ -52,6 +57,14 @@ pub struct AggregatedData {
     pub records_sensitivity_by_len: RecordsSensitivityByLen,
     /// Maximum length used to compute attribute combinations
     pub reporting_length: usize,
+    /// How the privacy budget was split (only present when the
+    /// aggregates are generated with differential privacy)
+    #[serde(default)]
+    pub dp_budget_allocation: Option<DpBudgetAllocation>,
+    /// Column hierarchies whose noisy counts were made consistent
+    /// (only present when the aggregates are generated with differential privacy)
+    #[serde(default)]
+    pub column_hierarchies: Vec<DpColumnHierarchy>,
 }
 
 impl Default for AggregatedData {
@@ -66,6 +79,8 @@ impl Default for AggregatedData {
             aggregates_count: AggregatesCountMap::default(),
             records_sensitivity_by_len: RecordsSensitivityByLen::default(),
             reporting_length: 0,
+            dp_budget_allocation: None,
+            column_hierarchies: Vec::default(),
         }
     }
 }
@@ -99,6 +114,8 @@ impl AggregatedData {
             aggregates_count,
             records_sensitivity_by_len,
             reporting_length,
+            dp_budget_allocation: None,
+            column_hierarchies: Vec::default(),
         }
     }
 
@@ -198,6 +215,20 @@ impl AggregatedData {
         records_by_len.retain(|_, records| !records.is_empty());
     }
 
+    /// Same as `read_aggregates_count`, but reading the table from a string
+    /// # Arguments:
+    /// * `aggregates_count` - Aggregate count table content
+    /// * `aggregates_delimiter` - Delimiter between the combination and its count
+    /// * `combination_delimiter` - Delimiter used to join the attributes of a combination
+    pub fn from_aggregates_count_str(
+        aggregates_count: &str,
+        aggregates_delimiter: char,
+        combination_delimiter: &str,
+    ) -> Result<AggregatedData, Error> {
+        AggregatesCountReader::new(aggregates_delimiter, combination_delimiter)
+            .read(aggregates_count.as_bytes())
+    }
+
     #[inline]
     fn _read_from_json(file_path: &str) -> Result<AggregatedData, Error> {
         info!("reading file: {}", file_path);
@@ -209,6 +240,20 @@ impl AggregatedData {
         ))?)
     }
 
+    #[inline]
+    fn _read_aggregates_count(
+        file_path: &str,
+        aggregates_delimiter: char,
+        combination_delimiter: &str,
+    ) -> Result<AggregatedData, Error> {
+        info!("reading file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("read aggregates count");
+
+        AggregatesCountReader::new(aggregates_delimiter, combination_delimiter)
+            .read(BufReader::new(std::fs::File::open(file_path)?))
+    }
+
     #[inline]
     fn _write_aggregates_count<T: Write>(
         &self,
@@ -317,7 +362,7 @@ impl AggregatedData {
     }
 
     #[inline]
-    fn get_original_header_name(&self, index: usize) -> String {
+    pub(super) fn get_original_header_name(&self, index: usize) -> String {
         let output_header_name = &self.headers[index];
         if let Some(metadata) = self.multi_value_column_metadata_map.get(output_header_name) {
             (*metadata.src_header_name).clone()
@@ -818,6 +863,27 @@ impl AggregatedData {
         )
     }
 
+    /// Scores the re-identification risk of every record containing unique or
+    /// rare combinations, listing the combinations contributing the most to it
+    /// # Arguments:
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `number_of_top_combinations` - How many combinations should be listed per record
+    /// * `combination_delimiter` - Delimiter used to join combinations and format them
+    /// as strings
+    pub fn calc_records_risk(
+        &self,
+        resolution: usize,
+        number_of_top_combinations: usize,
+        combination_delimiter: &str,
+    ) -> RecordsRisk {
+        RecordsRisk::from_aggregated_data(
+            self,
+            resolution,
+            number_of_top_combinations,
+            combination_delimiter,
+        )
+    }
+
     /// Calculates the number of distinct combinations grouped by combination length
     pub fn calc_total_number_of_combinations_by_len(&self) -> AggregatedCountByLenMap {
         let mut result: AggregatedCountByLenMap = AggregatedCountByLenMap::default();
@@ -982,4 +1048,47 @@ impl AggregatedData {
     pub fn read_from_json(file_path: &str) -> Result<AggregatedData, Error> {
         AggregatedData::_read_from_json(file_path)
     }
+
+    #[cfg(feature = "pyo3")]
+    #[staticmethod]
+    /// Reads the aggregated data from an aggregate count table
+    /// (e.g. produced by `write_aggregates_count`), without requiring the microdata.
+    /// Headers, single attribute counts and reporting length are reconstructed
+    /// from the table, which is validated for consistency between lengths
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    /// * `aggregates_delimiter` - Delimiter between the combination and its count
+    /// * `combination_delimiter` - Delimiter used to join the attributes of a combination
+    pub fn read_aggregates_count(
+        file_path: &str,
+        aggregates_delimiter: char,
+        combination_delimiter: &str,
+    ) -> Result<AggregatedData, Error> {
+        AggregatedData::_read_aggregates_count(
+            file_path,
+            aggregates_delimiter,
+            combination_delimiter,
+        )
+    }
+
+    #[cfg(not(feature = "pyo3"))]
+    /// Reads the aggregated data from an aggregate count table
+    /// (e.g. produced by `write_aggregates_count`), without requiring the microdata.
+    /// Headers, single attribute counts and reporting length are reconstructed
+    /// from the table, which is validated for consistency between lengths
+    /// # Arguments:
+    /// * `file_path` - File path to read from
+    /// * `aggregates_delimiter` - Delimiter between the combination and its count
+    /// * `combination_delimiter` - Delimiter used to join the attributes of a combination
+    pub fn read_aggregates_count(
+        file_path: &str,
+        aggregates_delimiter: char,
+        combination_delimiter: &str,
+    ) -> Result<AggregatedData, Error> {
+        AggregatedData::_read_aggregates_count(
+            file_path,
+            aggregates_delimiter,
+            combination_delimiter,
+        )
+    }
 }
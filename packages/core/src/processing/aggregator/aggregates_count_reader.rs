@@ -0,0 +1,255 @@
+use super::{AggregatedCount, AggregatedData, AggregatesCountMap, ValueCombination};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use std::{
+    io::{BufRead, Error, ErrorKind},
+    sync::Arc,
+};
+
+use crate::data_block::{
+    DataBlockHeaders, DataBlockValue, MultiValueColumnMetadataMap, COLUMN_VALUE_DELIMITER,
+};
+
+const SELECTIONS_LABEL: &str = "selections";
+
+const RECORD_COUNT_LABEL: &str = "record_count";
+
+const PROTECTED_COUNT_LABEL: &str = "protected_count";
+
+#[inline]
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Reads aggregate count tables in the same format written by
+/// `AggregatedData::write_aggregates_count`, reconstructing the aggregated data
+pub(crate) struct AggregatesCountReader<'combination_delimiter> {
+    aggregates_delimiter: char,
+    combination_delimiter: &'combination_delimiter str,
+    headers: DataBlockHeaders,
+    column_index_by_header: FnvHashMap<String, usize>,
+}
+
+impl<'combination_delimiter> AggregatesCountReader<'combination_delimiter> {
+    /// Returns a new AggregatesCountReader
+    /// # Arguments
+    /// * `aggregates_delimiter` - Delimiter between the combination and its count
+    /// * `combination_delimiter` - Delimiter used to join the attributes of a combination
+    #[inline]
+    pub fn new(
+        aggregates_delimiter: char,
+        combination_delimiter: &'combination_delimiter str,
+    ) -> Self {
+        AggregatesCountReader {
+            aggregates_delimiter,
+            combination_delimiter,
+            headers: DataBlockHeaders::default(),
+            column_index_by_header: FnvHashMap::default(),
+        }
+    }
+
+    /// Reads the aggregates table from `reader`.
+    /// Headers are reconstructed in the order they first appear, the reporting
+    /// length is the longest combination found and the counts of each combination
+    /// are validated against its sub-combinations
+    /// # Arguments
+    /// * `reader` - Where the aggregates table is read from
+    pub fn read<R: BufRead>(mut self, reader: R) -> Result<AggregatedData, Error> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()));
+        let is_protected = match lines.next() {
+            Some((line_index, line)) => self.read_labels(line_index, &line?)?,
+            None => return Err(invalid_data("aggregates table is empty".to_owned())),
+        };
+        let number_of_records = match lines.next() {
+            Some((line_index, line)) => self.read_record_count(line_index, &line?)?,
+            None => {
+                return Err(invalid_data(format!(
+                    "aggregates table is missing the \"{}\" line",
+                    RECORD_COUNT_LABEL
+                )))
+            }
+        };
+        let mut aggregates_count = AggregatesCountMap::default();
+
+        for (line_index, line) in lines {
+            let (combination, count) = self.read_aggregate(line_index, &line?)?;
+
+            if aggregates_count
+                .insert(
+                    Arc::new(combination),
+                    AggregatedCount {
+                        count,
+                        ..Default::default()
+                    },
+                )
+                .is_some()
+            {
+                return Err(invalid_data(format!(
+                    "line {}: duplicated combination",
+                    line_index + 1
+                )));
+            }
+        }
+
+        let reporting_length = aggregates_count.keys().map(|c| c.len()).max().unwrap_or(0);
+
+        self.validate_consistency(&aggregates_count)?;
+
+        Ok(AggregatedData::new(
+            self.headers,
+            MultiValueColumnMetadataMap::default(),
+            number_of_records,
+            if is_protected {
+                Some(number_of_records)
+            } else {
+                None
+            },
+            aggregates_count,
+            // records are not available, so neither are their sensitivities
+            Default::default(),
+            reporting_length,
+        ))
+    }
+
+    #[inline]
+    fn split_line<'line>(
+        &self,
+        line_index: usize,
+        line: &'line str,
+    ) -> Result<(&'line str, &'line str), Error> {
+        line.trim_end_matches(['\r', '\n'])
+            .rsplit_once(self.aggregates_delimiter)
+            .ok_or_else(|| {
+                invalid_data(format!(
+                    "line {}: expected \"<combination>{}<count>\"",
+                    line_index + 1,
+                    self.aggregates_delimiter
+                ))
+            })
+    }
+
+    #[inline]
+    fn parse_count(&self, line_index: usize, count: &str) -> Result<usize, Error> {
+        count.trim().parse::<usize>().map_err(|err| {
+            invalid_data(format!(
+                "line {}: invalid count \"{}\": {}",
+                line_index + 1,
+                count,
+                err
+            ))
+        })
+    }
+
+    #[inline]
+    fn read_labels(&self, line_index: usize, line: &str) -> Result<bool, Error> {
+        let (selections, count_label) = self.split_line(line_index, line)?;
+
+        if selections != SELECTIONS_LABEL {
+            return Err(invalid_data(format!(
+                "line {}: expected the \"{}\" header",
+                line_index + 1,
+                SELECTIONS_LABEL
+            )));
+        }
+        Ok(count_label == PROTECTED_COUNT_LABEL)
+    }
+
+    #[inline]
+    fn read_record_count(&self, line_index: usize, line: &str) -> Result<usize, Error> {
+        let (label, count) = self.split_line(line_index, line)?;
+
+        if label != RECORD_COUNT_LABEL {
+            return Err(invalid_data(format!(
+                "line {}: expected the \"{}\" line",
+                line_index + 1,
+                RECORD_COUNT_LABEL
+            )));
+        }
+        self.parse_count(line_index, count)
+    }
+
+    #[inline]
+    fn get_or_insert_column_index(&mut self, header: &str) -> usize {
+        if let Some(column_index) = self.column_index_by_header.get(header) {
+            return *column_index;
+        }
+
+        let column_index = self.headers.len();
+
+        self.headers.push(Arc::new(header.to_owned()));
+        self.column_index_by_header
+            .insert(header.to_owned(), column_index);
+        column_index
+    }
+
+    #[inline]
+    fn read_aggregate(
+        &mut self,
+        line_index: usize,
+        line: &str,
+    ) -> Result<(ValueCombination, usize), Error> {
+        let (combination_str, count) = self.split_line(line_index, line)?;
+        let count = self.parse_count(line_index, count)?;
+        let mut values = Vec::default();
+
+        for attr in combination_str.split(self.combination_delimiter) {
+            let (header, value) = attr.split_once(COLUMN_VALUE_DELIMITER).ok_or_else(|| {
+                invalid_data(format!(
+                    "line {}: expected \"<column>{}<value>\", but got \"{}\"",
+                    line_index + 1,
+                    COLUMN_VALUE_DELIMITER,
+                    attr
+                ))
+            })?;
+
+            values.push(Arc::new(DataBlockValue::new(
+                self.get_or_insert_column_index(header),
+                Arc::new(value.to_owned()),
+            )));
+        }
+        // keep the same order used by the aggregator
+        values.sort_by_key(|k| k.as_str_using_headers(&self.headers));
+
+        if !values.iter().map(|v| v.column_index).all_unique() {
+            return Err(invalid_data(format!(
+                "line {}: combination has more than one value for the same column",
+                line_index + 1
+            )));
+        }
+        Ok((ValueCombination::new(values), count))
+    }
+
+    #[inline]
+    fn validate_consistency(&self, aggregates_count: &AggregatesCountMap) -> Result<(), Error> {
+        for (combination, count) in aggregates_count.iter() {
+            if combination.len() < 2 {
+                continue;
+            }
+            // every sub-combination (one attribute shorter) should
+            // be reported with at least the same count
+            for sub_combination in combination
+                .iter()
+                .cloned()
+                .combinations(combination.len() - 1)
+            {
+                let sub_combination = ValueCombination::new(sub_combination);
+                let is_consistent = aggregates_count
+                    .get(&sub_combination)
+                    .is_some_and(|sub_count| sub_count.count >= count.count);
+
+                if !is_consistent {
+                    return Err(invalid_data(format!(
+                        "inconsistent aggregates: \"{}\" ({}) should be reported with a count of at least {}",
+                        sub_combination.as_str_using_headers(&self.headers, self.combination_delimiter),
+                        aggregates_count.get(&sub_combination).map_or(0, |c| c.count),
+                        count.count
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 mod aggregated_count;
 mod aggregated_data;
+mod aggregates_count_reader;
 mod data_aggregator;
 mod records_analysis_data;
+mod records_risk;
 mod rows_aggregator;
 mod typedefs;
 mod value_combination;
@@ -13,6 +15,7 @@ pub use aggregated_count::*;
 pub use aggregated_data::*;
 pub use data_aggregator::*;
 pub use records_analysis_data::*;
+pub use records_risk::*;
 pub use typedefs::*;
 pub use value_combination::*;
 
@@ -0,0 +1,364 @@
+use super::{
+    aggregated_data::AggregatedData, typedefs::RecordsCountByStringKey,
+    value_combination::ValueCombination,
+};
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    io::{Error, Write},
+    sync::Arc,
+};
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+use crate::utils::math::calc_percentage;
+
+/// Rare combination (count below the reporting resolution)
+/// contributing to the risk of a record
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordRiskCombination {
+    /// Combination formatted as `{header_name}:{value};{header_name}:{value}...`
+    pub combination: String,
+    /// Number of attributes in the combination
+    pub combination_length: usize,
+    /// How many sensitive records contain the combination
+    pub count: usize,
+    /// Contribution to the record risk (`1 / (count * combination_length)`),
+    /// shorter and less frequent combinations are easier to link
+    pub contribution: f64,
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl RecordRiskCombination {
+    #[getter]
+    /// Combination formatted as `{header_name}:{value};{header_name}:{value}...`
+    fn combination(&self) -> String {
+        self.combination.clone()
+    }
+
+    #[getter]
+    /// Number of attributes in the combination
+    fn combination_length(&self) -> usize {
+        self.combination_length
+    }
+
+    #[getter]
+    /// How many sensitive records contain the combination
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    #[getter]
+    /// Contribution to the record risk (`1 / (count * combination_length)`)
+    fn contribution(&self) -> f64 {
+        self.contribution
+    }
+}
+
+/// Re-identification risk of a single sensitive record
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordRisk {
+    /// Index of the record on the sensitive data block
+    pub record_index: usize,
+    /// Risk score from `0` to `1` (`1 - product(1 - contribution)` over the
+    /// record rare combinations), `1` if a single attribute is unique
+    pub risk_score: f64,
+    /// Smallest count among all the combinations the record contains
+    pub smallest_combination_count: usize,
+    /// Number of combinations the record contains that appear only once
+    pub number_of_unique_combinations: usize,
+    /// Number of combinations the record contains that are rare
+    /// (unique are not taken into account)
+    pub number_of_rare_combinations: usize,
+    /// Columns involved in the record unique/rare combinations
+    pub risky_columns: Vec<String>,
+    /// Combinations contributing the most to the risk score
+    pub top_combinations: Vec<RecordRiskCombination>,
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl RecordRisk {
+    #[getter]
+    /// Index of the record on the sensitive data block
+    fn record_index(&self) -> usize {
+        self.record_index
+    }
+
+    #[getter]
+    /// Risk score from `0` to `1`
+    fn risk_score(&self) -> f64 {
+        self.risk_score
+    }
+
+    #[getter]
+    /// Smallest count among all the combinations the record contains
+    fn smallest_combination_count(&self) -> usize {
+        self.smallest_combination_count
+    }
+
+    #[getter]
+    /// Number of combinations the record contains that appear only once
+    fn number_of_unique_combinations(&self) -> usize {
+        self.number_of_unique_combinations
+    }
+
+    #[getter]
+    /// Number of combinations the record contains that are rare
+    /// (unique are not taken into account)
+    fn number_of_rare_combinations(&self) -> usize {
+        self.number_of_rare_combinations
+    }
+
+    #[getter]
+    /// Columns involved in the record unique/rare combinations
+    fn risky_columns(&self) -> Vec<String> {
+        self.risky_columns.clone()
+    }
+
+    #[getter]
+    /// Combinations contributing the most to the risk score
+    fn top_combinations(&self) -> Vec<RecordRiskCombination> {
+        self.top_combinations.clone()
+    }
+}
+
+/// Re-identification risk report for the sensitive records
+/// containing unique or rare combinations
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordsRisk {
+    /// Reporting resolution used to define rare combinations
+    pub resolution: usize,
+    /// Total number of sensitive records
+    pub number_of_records: usize,
+    /// Number of records containing unique or rare combinations
+    pub number_of_risky_records: usize,
+    /// Percentage of records containing unique or rare combinations
+    pub percentage_of_risky_records: f64,
+    /// Number of risky records with each column involved in
+    /// their unique/rare combinations
+    pub number_of_risky_records_by_column: RecordsCountByStringKey,
+    /// Risk of every risky record, from the highest to the lowest score
+    pub records: Vec<RecordRisk>,
+}
+
+impl RecordsRisk {
+    /// Scores every record containing combinations with counts below `resolution`.
+    /// Each rare combination contributes `1 / (count * combination_length)` and
+    /// the contributions are combined as `1 - product(1 - contribution)`
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data for the sensitive records
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `number_of_top_combinations` - How many combinations should be listed per record
+    /// * `combination_delimiter` - Delimiter used to join combinations and format them
+    /// as strings
+    pub fn from_aggregated_data(
+        aggregated_data: &AggregatedData,
+        resolution: usize,
+        number_of_top_combinations: usize,
+        combination_delimiter: &str,
+    ) -> RecordsRisk {
+        let mut smallest_count = vec![usize::MAX; aggregated_data.number_of_records];
+        let mut rare_combinations: Vec<Vec<(&Arc<ValueCombination>, usize)>> =
+            vec![Vec::default(); aggregated_data.number_of_records];
+
+        for (comb, count) in aggregated_data.aggregates_count.iter() {
+            for record_index in count.contained_in_records.iter() {
+                smallest_count[*record_index] = smallest_count[*record_index].min(count.count);
+
+                if count.count < resolution {
+                    rare_combinations[*record_index].push((comb, count.count));
+                }
+            }
+        }
+
+        let mut number_of_risky_records_by_column = RecordsCountByStringKey::default();
+        let records: Vec<RecordRisk> = rare_combinations
+            .into_iter()
+            .enumerate()
+            .filter(|(_, combs)| !combs.is_empty())
+            .map(|(record_index, combs)| {
+                let risky_columns: Vec<String> = combs
+                    .iter()
+                    .flat_map(|(comb, _)| comb.iter().map(|v| v.column_index))
+                    .sorted()
+                    .dedup()
+                    // multi-value attribute columns share the same original header
+                    .map(|column_index| aggregated_data.get_original_header_name(column_index))
+                    .unique()
+                    .collect();
+
+                for column in risky_columns.iter() {
+                    *number_of_risky_records_by_column
+                        .entry(column.clone())
+                        .or_insert(0) += 1;
+                }
+
+                Self::calc_record_risk(
+                    aggregated_data,
+                    record_index,
+                    smallest_count[record_index],
+                    &combs,
+                    risky_columns,
+                    number_of_top_combinations,
+                    combination_delimiter,
+                )
+            })
+            .sorted_by(|a, b| {
+                b.risk_score
+                    .partial_cmp(&a.risk_score)
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(|| {
+                        a.smallest_combination_count
+                            .cmp(&b.smallest_combination_count)
+                    })
+                    .then_with(|| a.record_index.cmp(&b.record_index))
+            })
+            .collect();
+
+        info!(
+            "{} out of {} records contain unique or rare combinations",
+            records.len(),
+            aggregated_data.number_of_records
+        );
+
+        RecordsRisk {
+            resolution,
+            number_of_records: aggregated_data.number_of_records,
+            number_of_risky_records: records.len(),
+            percentage_of_risky_records: if aggregated_data.number_of_records > 0 {
+                calc_percentage(
+                    records.len() as f64,
+                    aggregated_data.number_of_records as f64,
+                )
+            } else {
+                0.0
+            },
+            number_of_risky_records_by_column,
+            records,
+        }
+    }
+
+    #[inline]
+    fn calc_record_risk(
+        aggregated_data: &AggregatedData,
+        record_index: usize,
+        smallest_combination_count: usize,
+        combs: &[(&Arc<ValueCombination>, usize)],
+        risky_columns: Vec<String>,
+        number_of_top_combinations: usize,
+        combination_delimiter: &str,
+    ) -> RecordRisk {
+        let contribution =
+            |comb: &ValueCombination, count: usize| 1.0 / ((count * comb.len()) as f64);
+        let risk_score = 1.0
+            - combs
+                .iter()
+                .map(|(comb, count)| 1.0 - contribution(comb, *count))
+                .product::<f64>();
+
+        RecordRisk {
+            record_index,
+            risk_score,
+            smallest_combination_count,
+            number_of_unique_combinations: combs.iter().filter(|(_, count)| *count == 1).count(),
+            number_of_rare_combinations: combs.iter().filter(|(_, count)| *count > 1).count(),
+            risky_columns,
+            top_combinations: combs
+                .iter()
+                .map(|(comb, count)| RecordRiskCombination {
+                    combination: comb
+                        .as_str_using_headers(&aggregated_data.headers, combination_delimiter),
+                    combination_length: comb.len(),
+                    count: *count,
+                    contribution: contribution(comb, *count),
+                })
+                .sorted_by(|a, b| {
+                    b.contribution
+                        .partial_cmp(&a.contribution)
+                        .unwrap_or(Ordering::Equal)
+                        .then_with(|| a.combination.cmp(&b.combination))
+                })
+                .take(number_of_top_combinations)
+                .collect(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl RecordsRisk {
+    /// Returns the risk of every risky record, from the highest to the lowest score.
+    /// This method will clone the data, so its recommended to have its result stored
+    /// in a local variable to avoid it being called multiple times
+    pub fn get_records(&self) -> Vec<RecordRisk> {
+        self.records.clone()
+    }
+
+    /// Returns the number of risky records with each column involved
+    /// in their unique/rare combinations
+    pub fn get_number_of_risky_records_by_column(&self) -> RecordsCountByStringKey {
+        self.number_of_risky_records_by_column.clone()
+    }
+
+    /// Writes the risk of every risky record to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `records_risk_path` - File path to be written
+    /// * `records_risk_delimiter` - Delimiter to use when writing to `records_risk_path`
+    /// * `list_delimiter` - Delimiter used to join the risky columns and top combinations
+    pub fn write_records_risk(
+        &self,
+        records_risk_path: &str,
+        records_risk_delimiter: char,
+        list_delimiter: &str,
+    ) -> Result<(), Error> {
+        info!("writing file: {}", records_risk_path);
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(records_risk_path)?);
+
+        file.write_all(
+            [
+                "record_index",
+                "risk_score",
+                "smallest_combination_count",
+                "number_of_unique_combinations",
+                "number_of_rare_combinations",
+                "risky_columns",
+                "top_combinations",
+            ]
+            .join(&records_risk_delimiter.to_string())
+            .as_bytes(),
+        )?;
+        file.write_all("\n".as_bytes())?;
+        for r in self.records.iter() {
+            file.write_all(
+                [
+                    r.record_index.to_string(),
+                    format!("{:.6}", r.risk_score),
+                    r.smallest_combination_count.to_string(),
+                    r.number_of_unique_combinations.to_string(),
+                    r.number_of_rare_combinations.to_string(),
+                    r.risky_columns.join(list_delimiter),
+                    r.top_combinations
+                        .iter()
+                        .map(|c| format!("{} ({})", c.combination, c.count))
+                        .join(list_delimiter),
+                ]
+                .join(&records_risk_delimiter.to_string())
+                .as_bytes(),
+            )?;
+            file.write_all("\n".as_bytes())?;
+        }
+        Ok(())
+    }
+}
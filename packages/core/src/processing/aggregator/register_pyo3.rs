@@ -1,7 +1,10 @@
-use super::AggregatedData;
+use super::{AggregatedData, RecordRisk, RecordRiskCombination, RecordsRisk};
 use pyo3::{types::PyModule, PyResult, Python};
 
 pub fn register_pyo3(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<AggregatedData>()?;
+    m.add_class::<RecordRisk>()?;
+    m.add_class::<RecordRiskCombination>()?;
+    m.add_class::<RecordsRisk>()?;
     Ok(())
 }
@@ -0,0 +1,134 @@
+use crate::data_block::DataBlock;
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+/// Record values indexed by the position of the column in
+/// `AlignedRecords::columns` (`None` means the value is empty)
+pub type AlignedRecord = Vec<Option<Arc<String>>>;
+
+/// Records of a data block aligned to a list of column names, so
+/// records from different data blocks can be compared column by column
+#[derive(Debug, Clone)]
+pub struct AlignedRecords {
+    /// Column names the records are aligned to
+    pub columns: Vec<Arc<String>>,
+    /// Aligned records, in the same order as in the data block
+    pub records: Vec<AlignedRecord>,
+}
+
+impl AlignedRecords {
+    /// Returns the columns of the first data block that are
+    /// present on all the others (keeping the first data block order)
+    /// # Arguments
+    /// * `data_blocks` - Data blocks to look for common columns
+    pub fn common_columns(data_blocks: &[&DataBlock]) -> Vec<Arc<String>> {
+        match data_blocks.split_first() {
+            Some((first, others)) => first
+                .headers
+                .iter()
+                .filter(|h| others.iter().all(|db| db.headers.contains(h)))
+                .cloned()
+                .collect(),
+            None => Vec::default(),
+        }
+    }
+
+    /// Aligns the records of `data_block` to `columns`,
+    /// columns not present on the data block are considered empty
+    /// # Arguments
+    /// * `data_block` - Data block to read the records from
+    /// * `columns` - Column names to align the records to
+    pub fn new(data_block: &DataBlock, columns: &[Arc<String>]) -> AlignedRecords {
+        let position_by_name: FnvHashMap<&Arc<String>, usize> =
+            columns.iter().enumerate().map(|(i, c)| (c, i)).collect();
+        let position_by_column_index: Vec<Option<usize>> = data_block
+            .headers
+            .iter()
+            .map(|h| position_by_name.get(h).cloned())
+            .collect();
+
+        AlignedRecords {
+            columns: columns.to_vec(),
+            records: data_block
+                .records
+                .iter()
+                .map(|r| {
+                    let mut aligned: AlignedRecord = vec![None; columns.len()];
+
+                    for v in r.values.iter() {
+                        if let Some(pos) = position_by_column_index[v.column_index] {
+                            aligned[pos] = Some(v.value.clone());
+                        }
+                    }
+                    aligned
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the number of aligned records
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns whether there are no aligned records
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Hamming distance between two aligned records
+    /// (number of columns where the values differ)
+    #[inline]
+    pub fn hamming_distance(a: &AlignedRecord, b: &AlignedRecord) -> usize {
+        a.iter().zip(b.iter()).filter(|(va, vb)| va != vb).count()
+    }
+
+    /// For each record in `self`, calculates the hamming distance
+    /// to the closest record in `other`
+    /// (`None` if `other` is empty)
+    /// # Arguments
+    /// * `other` - Records to look for the closest one
+    pub fn calc_closest_distances(&self, other: &AlignedRecords) -> Vec<Option<usize>> {
+        self.records
+            .iter()
+            .map(|r| {
+                other
+                    .records
+                    .iter()
+                    .map(|o| Self::hamming_distance(r, o))
+                    .min()
+            })
+            .collect()
+    }
+
+    /// For each record in `self`, calculates the hamming distances
+    /// to the closest and to the second closest records in `other`
+    /// (`None` if `other` does not have enough records)
+    /// # Arguments
+    /// * `other` - Records to look for the closest ones
+    pub fn calc_two_closest_distances(
+        &self,
+        other: &AlignedRecords,
+    ) -> Vec<(Option<usize>, Option<usize>)> {
+        self.records
+            .iter()
+            .map(|r| {
+                other
+                    .records
+                    .iter()
+                    .fold((None, None), |(first, second), o| {
+                        let d = Self::hamming_distance(r, o);
+
+                        match first {
+                            Some(f) if d >= f => {
+                                (first, Some(second.map_or(d, |s: usize| s.min(d))))
+                            }
+                            _ => (Some(d), first),
+                        }
+                    })
+            })
+            .collect()
+    }
+}
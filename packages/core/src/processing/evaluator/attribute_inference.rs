@@ -0,0 +1,395 @@
+use super::{AlignedRecord, AlignedRecords, PrivacyAttackError};
+use crate::{
+    data_block::{DataBlock, COLUMN_VALUE_DELIMITER},
+    processing::aggregator::COMBINATIONS_DELIMITER,
+};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Error, Write},
+    sync::Arc,
+};
+
+/// Target values and how many times they occur
+type TargetValueCounts = FnvHashMap<Option<Arc<String>>, usize>;
+
+/// Parameters used to run the attribute inference attack
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeInferenceParameters {
+    /// Columns known by the attacker
+    pub quasi_identifiers: Vec<String>,
+    /// Column the attacker tries to infer
+    pub target: String,
+    /// Quasi-identifier combinations occurring less than
+    /// this on the sensitive data are considered rare
+    pub resolution: usize,
+}
+
+impl AttributeInferenceParameters {
+    /// Returns a new AttributeInferenceParameters
+    /// # Arguments
+    /// * `quasi_identifiers` - Columns known by the attacker
+    /// * `target` - Column the attacker tries to infer
+    /// * `resolution` - Quasi-identifier combinations occurring less than
+    /// this on the sensitive data are considered rare
+    #[inline]
+    pub fn new(
+        quasi_identifiers: Vec<String>,
+        target: String,
+        resolution: usize,
+    ) -> AttributeInferenceParameters {
+        AttributeInferenceParameters {
+            quasi_identifiers,
+            target,
+            resolution,
+        }
+    }
+}
+
+/// Inference made by the attacker for a single sensitive record
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeInferenceComparison {
+    /// Quasi-identifier values of the record formatted as string
+    pub quasi_identifiers: String,
+    /// Index of the sensitive record
+    pub record_index: usize,
+    /// Number of synthetic records matching the quasi-identifiers
+    pub synthetic_count: usize,
+    /// Number of sensitive records matching the quasi-identifiers
+    pub sensitive_count: usize,
+    /// Target value inferred by the attacker
+    pub inferred_value: String,
+    /// Actual target value on the sensitive record
+    pub actual_value: String,
+}
+
+impl AttributeInferenceComparison {
+    /// Whether the attacker inferred the actual value
+    #[inline]
+    pub fn is_correct(&self) -> bool {
+        self.inferred_value == self.actual_value
+    }
+}
+
+/// Attack accuracy over a group of sensitive records
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeInferenceSummary {
+    /// Number of sensitive records in the group
+    pub number_of_records: usize,
+    /// Number of sensitive records with at least one synthetic
+    /// record matching their quasi-identifiers
+    pub number_of_matched_records: usize,
+    /// Proportion of records where the attacker inferred the actual value
+    /// (unmatched records are inferred from the target marginal)
+    pub accuracy: f64,
+    /// Accuracy only over the matched records
+    pub matched_accuracy: f64,
+    /// Accuracy of always inferring the most common target
+    /// value on the synthetic data (marginals only baseline)
+    pub baseline_accuracy: f64,
+}
+
+impl AttributeInferenceSummary {
+    #[inline]
+    fn from_comparisons<'a, I>(comparisons: I, baseline_value: &str) -> AttributeInferenceSummary
+    where
+        I: Iterator<Item = &'a AttributeInferenceComparison>,
+    {
+        let (mut total, mut matched, mut correct, mut matched_correct, mut baseline_correct) =
+            (0, 0, 0, 0, 0);
+
+        for c in comparisons {
+            total += 1;
+            if c.is_correct() {
+                correct += 1;
+            }
+            if c.synthetic_count > 0 {
+                matched += 1;
+                if c.is_correct() {
+                    matched_correct += 1;
+                }
+            }
+            if c.actual_value == baseline_value {
+                baseline_correct += 1;
+            }
+        }
+
+        AttributeInferenceSummary {
+            number_of_records: total,
+            number_of_matched_records: matched,
+            accuracy: calc_proportion(correct, total),
+            matched_accuracy: calc_proportion(matched_correct, matched),
+            baseline_accuracy: calc_proportion(baseline_correct, total),
+        }
+    }
+}
+
+#[inline]
+fn calc_proportion(count: usize, total: usize) -> f64 {
+    if total > 0 {
+        (count as f64) / (total as f64)
+    } else {
+        0.0
+    }
+}
+
+/// Results of the attribute inference attack
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeInferenceResult {
+    /// Columns known by the attacker
+    pub quasi_identifiers: Vec<String>,
+    /// Column the attacker tries to infer
+    pub target: String,
+    /// Summary over all the sensitive records
+    pub overall: AttributeInferenceSummary,
+    /// Summary over records with rare quasi-identifier combinations
+    /// (`sensitive_count < resolution`)
+    pub rare: AttributeInferenceSummary,
+    /// Summary over records with common quasi-identifier combinations
+    pub common: AttributeInferenceSummary,
+    /// Inference made for each sensitive record
+    pub inferences: Vec<AttributeInferenceComparison>,
+}
+
+impl AttributeInferenceResult {
+    /// Writes the inference made for each record to the file system in a csv/tsv like format
+    /// # Arguments:
+    /// * `inferences_path` - File path to be written
+    /// * `inferences_delimiter` - Delimiter to use when writing to `inferences_path`
+    pub fn write_inferences(
+        &self,
+        inferences_path: &str,
+        inferences_delimiter: char,
+    ) -> Result<(), Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(inferences_path)?);
+
+        file.write_all(
+            format!(
+                "quasi_identifiers{}record_id{}syn_count{}sen_count{}inferred_value{}actual_value\n",
+                inferences_delimiter,
+                inferences_delimiter,
+                inferences_delimiter,
+                inferences_delimiter,
+                inferences_delimiter,
+            )
+            .as_bytes(),
+        )?;
+        for inference in self.inferences.iter() {
+            file.write_all(
+                format!(
+                    "{}{}{}{}{}{}{}{}{}{}{}\n",
+                    inference.quasi_identifiers,
+                    inferences_delimiter,
+                    inference.record_index,
+                    inferences_delimiter,
+                    inference.synthetic_count,
+                    inferences_delimiter,
+                    inference.sensitive_count,
+                    inferences_delimiter,
+                    inference.inferred_value,
+                    inferences_delimiter,
+                    inference.actual_value,
+                )
+                .as_bytes(),
+            )?
+        }
+        Ok(())
+    }
+}
+
+/// Attacker that knows the quasi-identifiers of the sensitive records and
+/// infers the target column from the synthetic records matching them
+/// (the most common target value among the matches)
+pub struct AttributeInferenceAttack {
+    parameters: AttributeInferenceParameters,
+}
+
+impl AttributeInferenceAttack {
+    /// Returns a new AttributeInferenceAttack
+    /// # Arguments
+    /// * `parameters` - Parameters used to run the attack
+    #[inline]
+    pub fn new(parameters: AttributeInferenceParameters) -> AttributeInferenceAttack {
+        AttributeInferenceAttack { parameters }
+    }
+
+    /// Runs the attack against every sensitive record
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data (attack targets)
+    /// * `synthetic_data_block` - Synthesized data available to the attacker
+    pub fn run(
+        &self,
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<AttributeInferenceResult, PrivacyAttackError> {
+        let columns = self.validate_and_get_columns(sensitive_data_block, synthetic_data_block)?;
+        let n_quasi_identifiers = self.parameters.quasi_identifiers.len();
+        let sensitive = AlignedRecords::new(sensitive_data_block, &columns);
+        let synthetic = AlignedRecords::new(synthetic_data_block, &columns);
+        let sensitive_counts = Self::calc_target_counts_by_quasi_identifiers(&sensitive);
+        let synthetic_counts = Self::calc_target_counts_by_quasi_identifiers(&synthetic);
+        let baseline_value = Self::most_common_value(
+            &synthetic
+                .records
+                .iter()
+                .map(|r| r[n_quasi_identifiers].clone())
+                .counts()
+                .into_iter()
+                .collect(),
+        );
+        let baseline_value_str = Self::format_value(&baseline_value);
+        let inferences: Vec<AttributeInferenceComparison> = sensitive
+            .records
+            .iter()
+            .enumerate()
+            .map(|(record_index, record)| {
+                let quasi_identifiers = &record[..n_quasi_identifiers];
+                let matches = synthetic_counts.get(quasi_identifiers);
+
+                AttributeInferenceComparison {
+                    quasi_identifiers: Self::format_quasi_identifiers(&columns, quasi_identifiers),
+                    record_index,
+                    synthetic_count: matches.map_or(0, |m| m.values().sum()),
+                    sensitive_count: sensitive_counts
+                        .get(quasi_identifiers)
+                        .map_or(0, |m| m.values().sum()),
+                    inferred_value: matches
+                        .map(|m| Self::format_value(&Self::most_common_value(m)))
+                        .unwrap_or_else(|| baseline_value_str.clone()),
+                    actual_value: Self::format_value(&record[n_quasi_identifiers]),
+                }
+            })
+            .collect();
+        let resolution = self.parameters.resolution;
+        let result = AttributeInferenceResult {
+            quasi_identifiers: self.parameters.quasi_identifiers.clone(),
+            target: self.parameters.target.clone(),
+            overall: AttributeInferenceSummary::from_comparisons(
+                inferences.iter(),
+                &baseline_value_str,
+            ),
+            rare: AttributeInferenceSummary::from_comparisons(
+                inferences.iter().filter(|c| c.sensitive_count < resolution),
+                &baseline_value_str,
+            ),
+            common: AttributeInferenceSummary::from_comparisons(
+                inferences
+                    .iter()
+                    .filter(|c| c.sensitive_count >= resolution),
+                &baseline_value_str,
+            ),
+            inferences,
+        };
+
+        info!(
+            "attribute inference: accuracy = {:.4} (rare = {:.4}, common = {:.4}), baseline accuracy = {:.4}",
+            result.overall.accuracy,
+            result.rare.accuracy,
+            result.common.accuracy,
+            result.overall.baseline_accuracy
+        );
+
+        Ok(result)
+    }
+
+    /// Returns the quasi-identifier columns followed by the target column
+    fn validate_and_get_columns(
+        &self,
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<Vec<Arc<String>>, PrivacyAttackError> {
+        if self.parameters.quasi_identifiers.is_empty() {
+            return Err(PrivacyAttackError::InvalidInput(
+                "at least one quasi-identifier must be provided".to_owned(),
+            ));
+        }
+        if self
+            .parameters
+            .quasi_identifiers
+            .contains(&self.parameters.target)
+        {
+            return Err(PrivacyAttackError::InvalidInput(format!(
+                "target column \"{}\" can not be a quasi-identifier",
+                self.parameters.target
+            )));
+        }
+
+        let common_columns =
+            AlignedRecords::common_columns(&[sensitive_data_block, synthetic_data_block]);
+
+        self.parameters
+            .quasi_identifiers
+            .iter()
+            .chain(std::iter::once(&self.parameters.target))
+            .map(|name| {
+                common_columns
+                    .iter()
+                    .find(|c| ***c == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        PrivacyAttackError::InvalidInput(format!(
+                            "column \"{name}\" not found on the sensitive and synthetic data"
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn calc_target_counts_by_quasi_identifiers(
+        records: &AlignedRecords,
+    ) -> FnvHashMap<&[Option<Arc<String>>], TargetValueCounts> {
+        let mut counts: FnvHashMap<&[Option<Arc<String>>], TargetValueCounts> =
+            FnvHashMap::default();
+
+        for record in records.records.iter() {
+            let (target, quasi_identifiers) = Self::split_target(record);
+
+            *counts
+                .entry(quasi_identifiers)
+                .or_insert_with(TargetValueCounts::default)
+                .entry(target.clone())
+                .or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[inline]
+    fn split_target(record: &AlignedRecord) -> (&Option<Arc<String>>, &[Option<Arc<String>>]) {
+        // the target is always the last aligned column
+        record.split_last().unwrap()
+    }
+
+    /// Most common value, ties are broken by the value itself
+    /// so the inference is deterministic
+    #[inline]
+    fn most_common_value(counts: &TargetValueCounts) -> Option<Arc<String>> {
+        counts
+            .iter()
+            .max_by(|(va, ca), (vb, cb)| ca.cmp(cb).then_with(|| vb.cmp(va)))
+            .and_then(|(v, _)| v.clone())
+    }
+
+    #[inline]
+    fn format_value(value: &Option<Arc<String>>) -> String {
+        value.as_ref().map(|v| (**v).clone()).unwrap_or_default()
+    }
+
+    #[inline]
+    fn format_quasi_identifiers(
+        columns: &[Arc<String>],
+        quasi_identifiers: &[Option<Arc<String>>],
+    ) -> String {
+        columns
+            .iter()
+            .zip(quasi_identifiers.iter())
+            .map(|(c, v)| format!("{}{}{}", c, COLUMN_VALUE_DELIMITER, Self::format_value(v)))
+            .join(&COMBINATIONS_DELIMITER.to_string())
+    }
+}
@@ -0,0 +1,224 @@
+use super::{AlignedRecord, AlignedRecords, PrivacyAttackError};
+use crate::data_block::DataBlock;
+use fnv::{FnvHashMap, FnvHashSet};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, Write};
+
+/// Distance from a synthetic record to the closest sensitive records
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosestRecordDistance {
+    /// Index of the synthetic record
+    pub record_index: usize,
+    /// Hamming distance to the closest sensitive record
+    pub distance: usize,
+    /// Hamming distance to the second closest sensitive record
+    /// (`None` if there is a single sensitive record)
+    pub second_distance: Option<usize>,
+    /// Nearest neighbor distance ratio (`distance / second_distance`),
+    /// values close to `0` mean the synthetic record singles out
+    /// a sensitive record (`1` if both distances are `0`)
+    pub distance_ratio: Option<f64>,
+    /// Whether the synthetic record exactly reproduces a
+    /// sensitive record occurring only once
+    pub is_unique_copy: bool,
+}
+
+/// Distances from the synthetic records to the closest sensitive records,
+/// reporting how many synthetic records copy the sensitive ones
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosestRecordDistanceResult {
+    /// Number of columns compared between the sensitive and synthetic records
+    pub number_of_columns: usize,
+    /// Number of sensitive records
+    pub number_of_sensitive_records: usize,
+    /// Number of sensitive records occurring only once
+    pub number_of_unique_sensitive_records: usize,
+    /// Number of synthetic records
+    pub number_of_synthetic_records: usize,
+    /// Mean distance to the closest sensitive record
+    pub mean_distance: f64,
+    /// Number of synthetic records by distance to the closest
+    /// sensitive record (index `i` counts the records at distance `i`)
+    pub distance_histogram: Vec<usize>,
+    /// Mean nearest neighbor distance ratio
+    /// (`None` if there is a single sensitive record)
+    pub mean_distance_ratio: Option<f64>,
+    /// Number of synthetic records exactly reproducing a sensitive record
+    pub number_of_exact_matches: usize,
+    /// `number_of_exact_matches / number_of_synthetic_records`
+    pub exact_matches_proportion: f64,
+    /// Number of synthetic records exactly reproducing a
+    /// sensitive record occurring only once
+    pub number_of_unique_copies: usize,
+    /// `number_of_unique_copies / number_of_synthetic_records`
+    pub unique_copies_proportion: f64,
+    /// Number of sensitive records occurring only once that
+    /// are reproduced by at least one synthetic record
+    pub number_of_copied_unique_records: usize,
+    /// `number_of_copied_unique_records / number_of_unique_sensitive_records`
+    /// (`0` if there are no unique sensitive records)
+    pub copied_unique_records_proportion: f64,
+    /// Distances for every synthetic record
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distances: Vec<ClosestRecordDistance>,
+}
+
+impl ClosestRecordDistanceResult {
+    /// Computes the distances from every synthetic record to the closest
+    /// sensitive records, comparing the columns both data blocks have in common
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data
+    /// * `synthetic_data_block` - Synthesized data
+    pub fn from_sensitive_and_synthetic_data_blocks(
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<ClosestRecordDistanceResult, PrivacyAttackError> {
+        let columns = AlignedRecords::common_columns(&[sensitive_data_block, synthetic_data_block]);
+
+        if columns.is_empty() {
+            return Err(PrivacyAttackError::InvalidInput(
+                "sensitive and synthetic data have no columns in common".to_owned(),
+            ));
+        }
+
+        let sensitive = AlignedRecords::new(sensitive_data_block, &columns);
+        let synthetic = AlignedRecords::new(synthetic_data_block, &columns);
+
+        if sensitive.is_empty() || synthetic.is_empty() {
+            return Err(PrivacyAttackError::InvalidInput(
+                "sensitive and synthetic data must not be empty".to_owned(),
+            ));
+        }
+
+        let mut sensitive_counts: FnvHashMap<&AlignedRecord, usize> = FnvHashMap::default();
+
+        for r in sensitive.records.iter() {
+            *sensitive_counts.entry(r).or_insert(0) += 1;
+        }
+
+        let mut copied_unique_records: FnvHashSet<&AlignedRecord> = FnvHashSet::default();
+        let distances: Vec<ClosestRecordDistance> = synthetic
+            .calc_two_closest_distances(&sensitive)
+            .drain(..)
+            .zip(synthetic.records.iter())
+            .enumerate()
+            .map(|(record_index, ((distance, second_distance), record))| {
+                // sensitive records are not empty, so there is always a closest one
+                let distance = distance.unwrap_or_default();
+                let is_unique_copy = distance == 0 && sensitive_counts.get(record) == Some(&1);
+
+                if is_unique_copy {
+                    copied_unique_records.insert(record);
+                }
+                ClosestRecordDistance {
+                    record_index,
+                    distance,
+                    second_distance,
+                    distance_ratio: second_distance.map(|s| {
+                        if s == 0 {
+                            1.0
+                        } else {
+                            (distance as f64) / (s as f64)
+                        }
+                    }),
+                    is_unique_copy,
+                }
+            })
+            .collect();
+        let number_of_synthetic_records = distances.len() as f64;
+        let number_of_unique_sensitive_records =
+            sensitive_counts.values().filter(|c| **c == 1).count();
+        let number_of_exact_matches = distances.iter().filter(|d| d.distance == 0).count();
+        let number_of_unique_copies = distances.iter().filter(|d| d.is_unique_copy).count();
+        let mut distance_histogram = vec![0; columns.len() + 1];
+
+        for d in distances.iter() {
+            distance_histogram[d.distance] += 1;
+        }
+
+        let result = ClosestRecordDistanceResult {
+            number_of_columns: columns.len(),
+            number_of_sensitive_records: sensitive.len(),
+            number_of_unique_sensitive_records,
+            number_of_synthetic_records: distances.len(),
+            mean_distance: distances.iter().map(|d| d.distance as f64).sum::<f64>()
+                / number_of_synthetic_records,
+            distance_histogram,
+            mean_distance_ratio: if sensitive.len() > 1 {
+                Some(
+                    distances
+                        .iter()
+                        .filter_map(|d| d.distance_ratio)
+                        .sum::<f64>()
+                        / number_of_synthetic_records,
+                )
+            } else {
+                None
+            },
+            number_of_exact_matches,
+            exact_matches_proportion: (number_of_exact_matches as f64)
+                / number_of_synthetic_records,
+            number_of_unique_copies,
+            unique_copies_proportion: (number_of_unique_copies as f64)
+                / number_of_synthetic_records,
+            number_of_copied_unique_records: copied_unique_records.len(),
+            copied_unique_records_proportion: if number_of_unique_sensitive_records > 0 {
+                (copied_unique_records.len() as f64) / (number_of_unique_sensitive_records as f64)
+            } else {
+                0.0
+            },
+            distances,
+        };
+
+        info!(
+            "closest record distance: mean distance = {:.4}, exact matches = {}, unique copies = {}",
+            result.mean_distance, result.number_of_exact_matches, result.number_of_unique_copies
+        );
+
+        Ok(result)
+    }
+
+    /// Writes the distance of every synthetic record to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `distances_path` - File path to be written
+    /// * `distances_delimiter` - Delimiter to use when writing to `distances_path`
+    pub fn write_distances(
+        &self,
+        distances_path: &str,
+        distances_delimiter: char,
+    ) -> Result<(), Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(distances_path)?);
+
+        file.write_all(
+            format!(
+                "record_id{}distance{}second_distance{}distance_ratio{}unique_copy\n",
+                distances_delimiter, distances_delimiter, distances_delimiter, distances_delimiter,
+            )
+            .as_bytes(),
+        )?;
+        for d in self.distances.iter() {
+            file.write_all(
+                format!(
+                    "{}{}{}{}{}{}{}{}{}\n",
+                    d.record_index,
+                    distances_delimiter,
+                    d.distance,
+                    distances_delimiter,
+                    d.second_distance.map(|s| s.to_string()).unwrap_or_default(),
+                    distances_delimiter,
+                    d.distance_ratio
+                        .map(|r| format!("{:.4}", r))
+                        .unwrap_or_default(),
+                    distances_delimiter,
+                    d.is_unique_copy,
+                )
+                .as_bytes(),
+            )?
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Point estimate of a metric alongside its confidence interval
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidenceInterval {
+    /// Point estimate
+    pub estimate: f64,
+    /// Lower bound of the interval
+    pub lower_bound: f64,
+    /// Upper bound of the interval
+    pub upper_bound: f64,
+    /// Probability of the interval containing the true value (e.g. `0.95`)
+    pub confidence_level: f64,
+}
+
+impl ConfidenceInterval {
+    /// Builds a normal approximation interval `estimate +/- z * standard_error`,
+    /// clamped to `[min, max]`
+    /// # Arguments
+    /// * `estimate` - Point estimate
+    /// * `standard_error` - Standard error of the estimate
+    /// * `confidence_level` - Probability of the interval containing the true value
+    /// * `min` - Minimum value the metric can take
+    /// * `max` - Maximum value the metric can take
+    pub fn from_normal_approximation(
+        estimate: f64,
+        standard_error: f64,
+        confidence_level: f64,
+        min: f64,
+        max: f64,
+    ) -> ConfidenceInterval {
+        let z = Normal::new(0.0, 1.0)
+            .unwrap()
+            .inverse_cdf(0.5 + confidence_level / 2.0);
+
+        ConfidenceInterval {
+            estimate,
+            lower_bound: (estimate - z * standard_error).clamp(min, max),
+            upper_bound: (estimate + z * standard_error).clamp(min, max),
+            confidence_level,
+        }
+    }
+
+    /// Builds a percentile interval from samples of the metric (e.g. obtained
+    /// by repeating the computation or by bootstrapping), the bounds are the
+    /// `(1 - confidence_level) / 2` and `(1 + confidence_level) / 2` quantiles,
+    /// linearly interpolated between samples
+    /// # Arguments
+    /// * `estimate` - Point estimate
+    /// * `samples` - Values of the metric on every sample (should not be empty)
+    /// * `confidence_level` - Probability of the interval containing the true value
+    pub fn from_percentiles(
+        estimate: f64,
+        samples: &[f64],
+        confidence_level: f64,
+    ) -> ConfidenceInterval {
+        let mut sorted_samples = samples.to_vec();
+
+        sorted_samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        ConfidenceInterval {
+            estimate,
+            lower_bound: Self::quantile(&sorted_samples, (1.0 - confidence_level) / 2.0),
+            upper_bound: Self::quantile(&sorted_samples, (1.0 + confidence_level) / 2.0),
+            confidence_level,
+        }
+    }
+
+    #[inline]
+    fn quantile(sorted_samples: &[f64], q: f64) -> f64 {
+        if sorted_samples.is_empty() {
+            return f64::NAN;
+        }
+
+        let position = q.clamp(0.0, 1.0) * ((sorted_samples.len() - 1) as f64);
+        let lower_index = position.floor() as usize;
+        let upper_index = position.ceil() as usize;
+
+        sorted_samples[lower_index]
+            + (sorted_samples[upper_index] - sorted_samples[lower_index])
+                * (position - lower_index as f64)
+    }
+}
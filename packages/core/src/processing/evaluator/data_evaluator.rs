@@ -1,16 +1,24 @@
+use super::evaluation_uncertainty::{BootstrapParameters, EvaluationUncertainty};
+use super::evaluation_uncertainty_error::EvaluationUncertaintyError;
+use super::marginal_distances::MarginalDistances;
+use super::pairwise_association::PairwiseAssociation;
 use super::preservation_by_count::{PreservationByCountBucketBins, PreservationByCountBuckets};
 use super::rare_combinations_comparison_data::RareCombinationsComparisonData;
+use super::synthetic_data_comparison::SyntheticDataComparison;
+use super::utility_breakdown::UtilityBreakdown;
 use fnv::{FnvHashMap, FnvHashSet};
 use std::sync::Arc;
 
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 
+use crate::data_block::DataBlock;
 use crate::processing::aggregator::{
     AggregatedCountByLenMap, AggregatedData, AggregatedMetricByLenMap, ValueCombination,
 };
 use crate::processing::evaluator::preservation_bucket::PreservationBucket;
 use crate::processing::evaluator::preservation_by_length::PreservationByLengthBuckets;
+use crate::utils::reporting::ReportProgress;
 
 #[cfg_attr(feature = "pyo3", pyclass)]
 /// Evaluates aggregated, sensitive and synthesized data
@@ -25,6 +33,85 @@ impl Default for Evaluator {
 }
 
 impl Evaluator {
+    /// Evaluates several synthetic data sets against the same sensitive
+    /// aggregates, reporting the metrics side by side together
+    /// with the best synthetic data set for each of them
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Name and calculated aggregated data
+    /// of each synthetic data set
+    /// * `resolution` - Reporting resolution used for data synthesis
+    pub fn compare_synthetic_data(
+        &self,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &[(&str, &AggregatedData)],
+        resolution: usize,
+    ) -> SyntheticDataComparison {
+        SyntheticDataComparison::from_sensitive_and_synthetic_aggregated_data(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+            resolution,
+        )
+    }
+
+    /// Calculates the mean, standard deviation and confidence interval of the
+    /// evaluation metrics over synthetic data generated several times
+    /// from the same sensitive data
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_replicates` - Calculated aggregated data for each synthetic replicate
+    /// (at least `2`)
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `confidence_level` - Confidence level of the metrics intervals
+    pub fn calc_uncertainty_from_replicates(
+        &self,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_replicates: &[&AggregatedData],
+        resolution: usize,
+        confidence_level: f64,
+    ) -> Result<EvaluationUncertainty, EvaluationUncertaintyError> {
+        EvaluationUncertainty::from_generation_replicates(
+            sensitive_aggregated_data,
+            synthetic_replicates,
+            resolution,
+            confidence_level,
+        )
+    }
+
+    /// Calculates the mean, standard deviation and confidence interval of the
+    /// evaluation metrics by bootstrapping the sensitive and synthetic records
+    /// (metrics biased by the resampling are not summarized, see
+    /// `EvaluationUncertaintyMethod::supports`)
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data
+    /// * `synthetic_data_block` - Synthesized data (same headers as the sensitive data)
+    /// * `reporting_length` - Maximum combination length to evaluate
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `parameters` - Bootstrap parameters
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn calc_uncertainty_with_bootstrap<T>(
+        &self,
+        sensitive_data_block: &Arc<DataBlock>,
+        synthetic_data_block: &Arc<DataBlock>,
+        reporting_length: usize,
+        resolution: usize,
+        parameters: &BootstrapParameters,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<EvaluationUncertainty, EvaluationUncertaintyError>
+    where
+        T: ReportProgress,
+    {
+        EvaluationUncertainty::from_record_bootstrap(
+            sensitive_data_block,
+            synthetic_data_block,
+            reporting_length,
+            resolution,
+            parameters,
+            progress_reporter,
+        )
+    }
+
     fn calc_combinations_abs_error_sum_count_by_len(
         &self,
         sensitive_aggregated_data: &AggregatedData,
@@ -398,6 +485,60 @@ impl Evaluator {
         }
     }
 
+    /// Calculates the total variation distance, Jensen-Shannon divergence and
+    /// chi-square statistic between the sensitive and synthetic marginals
+    /// (one for every set of columns up to the reporting length)
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Calculated aggregated data for the synthetic data
+    pub fn calc_marginal_distances(
+        &self,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+    ) -> MarginalDistances {
+        MarginalDistances::from_sensitive_and_synthetic_aggregated_data(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+        )
+    }
+
+    /// Calculates the Cramér's V and normalized mutual information matrices
+    /// for every pair of columns on the sensitive and synthetic data, together
+    /// with their differences and the mean absolute difference
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data
+    /// * `synthetic_data_block` - Synthesized data
+    pub fn calc_pairwise_association(
+        &self,
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> PairwiseAssociation {
+        PairwiseAssociation::from_sensitive_and_synthetic_data_blocks(
+            sensitive_data_block,
+            synthetic_data_block,
+        )
+    }
+
+    /// Breaks down the preservation, proportional error, suppression, fabrication
+    /// and mean absolute error by column and by attribute, so the worst
+    /// preserved ones can be identified
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Calculated aggregated data for the synthetic data
+    /// * `resolution` - Reporting resolution used for data synthesis
+    pub fn calc_utility_breakdown(
+        &self,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+        resolution: usize,
+    ) -> UtilityBreakdown {
+        UtilityBreakdown::from_sensitive_and_synthetic_aggregated_data(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+            resolution,
+        )
+    }
+
     /// Calculates the record expansion percentage
     /// (number of synthetic records / number of sensitive records)
     /// # Arguments
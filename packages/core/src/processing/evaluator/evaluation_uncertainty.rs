@@ -0,0 +1,603 @@
+use super::{
+    marginal_distances::MarginalDistances,
+    preservation_bucket::PreservationBucket,
+    synthetic_data_comparison::{ComparisonMetric, SyntheticDataComparison},
+    typedefs::PreservationBucketsMap,
+    ConfidenceInterval, EvaluationUncertaintyError, Evaluator,
+};
+use crate::{
+    data_block::DataBlock,
+    processing::aggregator::{AggregatedData, AggregatedMetricByLenMap, Aggregator},
+    utils::{math::calc_percentage, reporting::ReportProgress},
+};
+use itertools::Itertools;
+use log::info;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ops::Deref, sync::Arc};
+
+/// Default confidence level used for the evaluation metrics intervals
+pub const DEFAULT_EVALUATION_CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Metric summarized across the replicates
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EvaluationMetric {
+    /// Same as `ComparisonMetric::RecordExpansionPercentage`
+    RecordExpansionPercentage,
+    /// Same as `ComparisonMetric::LeakageCount`
+    LeakageCount,
+    /// Same as `ComparisonMetric::PercentageOfSuppressedCombinations`
+    PercentageOfSuppressedCombinations,
+    /// Same as `ComparisonMetric::PercentageOfFabricatedCombinations`
+    PercentageOfFabricatedCombinations,
+    /// Same as `ComparisonMetric::CombinationsCountMeanAbsError`
+    CombinationsCountMeanAbsError,
+    /// Same as `ComparisonMetric::MeanTotalVariationDistance`
+    MeanTotalVariationDistance,
+    /// Same as `ComparisonMetric::MeanJensenShannonDivergence`
+    MeanJensenShannonDivergence,
+    /// Percentage of the sensitive combinations that appear
+    /// as rare on the synthetic data (only by combination length)
+    LeakagePercentage,
+    /// Mean preservation of a preservation bucket
+    /// (by combination length or by synthetic count)
+    MeanPreservation,
+    /// Mean proportional error of a preservation bucket
+    /// (by combination length or by synthetic count)
+    MeanProportionalError,
+}
+
+impl From<ComparisonMetric> for EvaluationMetric {
+    fn from(metric: ComparisonMetric) -> EvaluationMetric {
+        match metric {
+            ComparisonMetric::RecordExpansionPercentage => {
+                EvaluationMetric::RecordExpansionPercentage
+            }
+            ComparisonMetric::LeakageCount => EvaluationMetric::LeakageCount,
+            ComparisonMetric::PercentageOfSuppressedCombinations => {
+                EvaluationMetric::PercentageOfSuppressedCombinations
+            }
+            ComparisonMetric::PercentageOfFabricatedCombinations => {
+                EvaluationMetric::PercentageOfFabricatedCombinations
+            }
+            ComparisonMetric::CombinationsCountMeanAbsError => {
+                EvaluationMetric::CombinationsCountMeanAbsError
+            }
+            ComparisonMetric::MeanTotalVariationDistance => {
+                EvaluationMetric::MeanTotalVariationDistance
+            }
+            ComparisonMetric::MeanJensenShannonDivergence => {
+                EvaluationMetric::MeanJensenShannonDivergence
+            }
+        }
+    }
+}
+
+/// Metrics also summarized for every combination length
+const METRICS_BY_LEN: [EvaluationMetric; 7] = [
+    EvaluationMetric::LeakageCount,
+    EvaluationMetric::CombinationsCountMeanAbsError,
+    EvaluationMetric::MeanTotalVariationDistance,
+    EvaluationMetric::MeanJensenShannonDivergence,
+    EvaluationMetric::LeakagePercentage,
+    EvaluationMetric::MeanPreservation,
+    EvaluationMetric::MeanProportionalError,
+];
+
+/// Metrics also summarized for every preservation by count bucket
+const METRICS_BY_COUNT_BUCKET: [EvaluationMetric; 2] = [
+    EvaluationMetric::MeanPreservation,
+    EvaluationMetric::MeanProportionalError,
+];
+
+/// How the evaluation replicates were obtained
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EvaluationUncertaintyMethod {
+    /// Synthetic data generated several times from the same sensitive data
+    GenerationReplicates,
+    /// Sensitive and synthetic records resampled with replacement
+    /// (see `EvaluationUncertaintyMethod::supports` for the summarized metrics)
+    RecordBootstrap,
+}
+
+impl EvaluationUncertaintyMethod {
+    /// Whether `metric` is summarized by the method.
+    /// Resampling with replacement repeats some records and drops others, so rare
+    /// combinations stop being rare and sensitive combinations go missing from
+    /// the samples. The metrics depending on the combinations present on both data
+    /// sets and on the resolution would be biased, so `RecordBootstrap` leaves out:
+    /// - the leakage count and percentage
+    /// - the percentages of suppressed and fabricated combinations
+    /// - the preservation buckets (suppressed and fabricated combinations
+    /// count as no preservation and full proportional error)
+    ///
+    /// Generation replicates should be used for them instead
+    #[inline]
+    pub fn supports(&self, metric: EvaluationMetric) -> bool {
+        match self {
+            EvaluationUncertaintyMethod::GenerationReplicates => true,
+            EvaluationUncertaintyMethod::RecordBootstrap => !matches!(
+                metric,
+                EvaluationMetric::LeakageCount
+                    | EvaluationMetric::LeakagePercentage
+                    | EvaluationMetric::PercentageOfSuppressedCombinations
+                    | EvaluationMetric::PercentageOfFabricatedCombinations
+                    | EvaluationMetric::MeanPreservation
+                    | EvaluationMetric::MeanProportionalError
+            ),
+        }
+    }
+}
+
+/// Values of the evaluation metrics computed for a single replicate
+struct MetricValues {
+    /// Values in the same order as `EvaluationUncertainty::metric_keys`
+    /// (`NaN` if the metric is not defined on the replicate)
+    values: Vec<f64>,
+    /// `METRICS_BY_COUNT_BUCKET` values for every non-empty
+    /// preservation by count bucket (keyed by the bucket max count)
+    by_count_bucket: BTreeMap<usize, Vec<f64>>,
+}
+
+/// Parameters used to bootstrap the evaluation metrics over records
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapParameters {
+    /// Number of times the sensitive and synthetic records are resampled
+    pub number_of_samples: usize,
+    /// Seed used to resample the records
+    pub seed: u64,
+    /// Confidence level of the metrics intervals (e.g. `0.95`)
+    #[serde(default = "default_confidence_level")]
+    pub confidence_level: f64,
+}
+
+#[inline]
+fn default_confidence_level() -> f64 {
+    DEFAULT_EVALUATION_CONFIDENCE_LEVEL
+}
+
+impl BootstrapParameters {
+    /// Returns a new BootstrapParameters
+    /// # Arguments
+    /// * `number_of_samples` - Number of times the sensitive and synthetic records are resampled
+    /// * `seed` - Seed used to resample the records
+    /// * `confidence_level` - Confidence level of the metrics intervals,
+    /// if None will be set to `DEFAULT_EVALUATION_CONFIDENCE_LEVEL`
+    #[inline]
+    pub fn new(number_of_samples: usize, seed: u64, confidence_level: Option<f64>) -> Self {
+        BootstrapParameters {
+            number_of_samples,
+            seed,
+            confidence_level: confidence_level.unwrap_or(DEFAULT_EVALUATION_CONFIDENCE_LEVEL),
+        }
+    }
+}
+
+/// Summary of an evaluation metric across replicates
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricUncertainty {
+    /// Summarized metric
+    pub metric: EvaluationMetric,
+    /// Combination length the metric is restricted to
+    /// (`None` if computed over all the combinations)
+    pub combination_length: Option<usize>,
+    /// Max synthetic count of the preservation by count bucket
+    /// the metric is restricted to (`None` if not restricted to a bucket)
+    pub count_bucket: Option<usize>,
+    /// Number of replicates the metric is defined on (preservation buckets
+    /// might be empty on some of them)
+    pub number_of_samples: usize,
+    /// Mean over the replicates
+    pub mean: f64,
+    /// Sample standard deviation over the replicates
+    pub standard_deviation: f64,
+    /// Percentile interval over the replicates, the estimate is the metric
+    /// on the original records when bootstrapping and the mean otherwise
+    pub confidence_interval: ConfidenceInterval,
+}
+
+/// Mean, standard deviation and confidence interval of the evaluation
+/// metrics, so comparisons between parameters are not fooled by the
+/// randomness of synthesis and noise
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationUncertainty {
+    /// How the replicates were obtained
+    pub method: EvaluationUncertaintyMethod,
+    /// Number of replicates each metric was computed on
+    pub number_of_replicates: usize,
+    /// Reporting resolution used to compute the leakage
+    pub resolution: usize,
+    /// Probability of the intervals containing the true value
+    pub confidence_level: f64,
+    /// Summary of every metric supported by the method, first over
+    /// all the combinations, then by combination length and then by
+    /// preservation by count bucket
+    pub metrics: Vec<MetricUncertainty>,
+}
+
+impl EvaluationUncertainty {
+    /// Summarizes the evaluation metrics of synthetic data generated
+    /// several times from the same sensitive data
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_replicates` - Calculated aggregated data for each synthetic replicate
+    /// (at least `2`)
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `confidence_level` - Confidence level of the metrics intervals
+    pub fn from_generation_replicates(
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_replicates: &[&AggregatedData],
+        resolution: usize,
+        confidence_level: f64,
+    ) -> Result<EvaluationUncertainty, EvaluationUncertaintyError> {
+        Self::validate(synthetic_replicates.len(), confidence_level)?;
+
+        let evaluator = Evaluator::default();
+        let reporting_length = sensitive_aggregated_data.reporting_length;
+        let replicates: Vec<MetricValues> = synthetic_replicates
+            .iter()
+            .map(|synthetic| {
+                Self::calc_metric_values(
+                    &evaluator,
+                    sensitive_aggregated_data,
+                    synthetic,
+                    resolution,
+                    reporting_length,
+                )
+            })
+            .collect();
+
+        Ok(Self::summarize(
+            EvaluationUncertaintyMethod::GenerationReplicates,
+            resolution,
+            confidence_level,
+            reporting_length,
+            &replicates,
+            None,
+        ))
+    }
+
+    /// Summarizes the evaluation metrics by resampling, with replacement,
+    /// the sensitive and synthetic records. Metrics biased by the resampling
+    /// are not summarized (see `EvaluationUncertaintyMethod::supports`)
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data
+    /// * `synthetic_data_block` - Synthesized data (same headers as the sensitive data)
+    /// * `reporting_length` - Maximum combination length to evaluate
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `parameters` - Bootstrap parameters
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn from_record_bootstrap<T>(
+        sensitive_data_block: &Arc<DataBlock>,
+        synthetic_data_block: &Arc<DataBlock>,
+        reporting_length: usize,
+        resolution: usize,
+        parameters: &BootstrapParameters,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<EvaluationUncertainty, EvaluationUncertaintyError>
+    where
+        T: ReportProgress,
+    {
+        Self::validate(parameters.number_of_samples, parameters.confidence_level)?;
+
+        if sensitive_data_block.headers != synthetic_data_block.headers {
+            return Err(EvaluationUncertaintyError::InvalidInput(
+                "sensitive and synthetic data must have the same columns in the same order"
+                    .to_owned(),
+            ));
+        }
+        if sensitive_data_block.records.is_empty() || synthetic_data_block.records.is_empty() {
+            return Err(EvaluationUncertaintyError::InvalidInput(
+                "sensitive and synthetic data must have records to be resampled".to_owned(),
+            ));
+        }
+
+        let evaluator = Evaluator::default();
+        let aggregate = |data_block: Arc<DataBlock>| {
+            Aggregator::new(data_block).aggregate(reporting_length, &mut None::<T>)
+        };
+        let sensitive_aggregated_data = aggregate(sensitive_data_block.clone())?;
+        let reporting_length = sensitive_aggregated_data.reporting_length;
+        let estimates = Self::calc_metric_values(
+            &evaluator,
+            &sensitive_aggregated_data,
+            &aggregate(synthetic_data_block.clone())?,
+            resolution,
+            reporting_length,
+        );
+        let mut rng = StdRng::seed_from_u64(parameters.seed);
+        let mut replicates = Vec::with_capacity(parameters.number_of_samples);
+
+        info!(
+            "bootstrapping the evaluation metrics with {} sample(s)",
+            parameters.number_of_samples
+        );
+
+        for sample in 0..parameters.number_of_samples {
+            let sensitive_sample = aggregate(Self::resample(sensitive_data_block, &mut rng))?;
+            let synthetic_sample = aggregate(Self::resample(synthetic_data_block, &mut rng))?;
+
+            replicates.push(Self::calc_metric_values(
+                &evaluator,
+                &sensitive_sample,
+                &synthetic_sample,
+                resolution,
+                reporting_length,
+            ));
+
+            if let Some(r) = progress_reporter.as_mut() {
+                r.report(calc_percentage(
+                    (sample + 1) as f64,
+                    parameters.number_of_samples as f64,
+                ))?;
+            }
+        }
+
+        Ok(Self::summarize(
+            EvaluationUncertaintyMethod::RecordBootstrap,
+            resolution,
+            parameters.confidence_level,
+            reporting_length,
+            &replicates,
+            Some(&estimates),
+        ))
+    }
+
+    #[inline]
+    fn validate(
+        number_of_replicates: usize,
+        confidence_level: f64,
+    ) -> Result<(), EvaluationUncertaintyError> {
+        if number_of_replicates < 2 {
+            return Err(EvaluationUncertaintyError::InvalidInput(
+                "at least 2 replicates are needed".to_owned(),
+            ));
+        }
+        if confidence_level <= 0.0 || confidence_level >= 1.0 {
+            return Err(EvaluationUncertaintyError::InvalidInput(
+                "confidence level must be between 0 and 1".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn resample(data_block: &DataBlock, rng: &mut StdRng) -> Arc<DataBlock> {
+        let n_records = data_block.records.len();
+
+        Arc::new(DataBlock {
+            headers: data_block.headers.clone(),
+            multi_value_column_metadata_map: data_block.multi_value_column_metadata_map.clone(),
+            records: (0..n_records)
+                .map(|_| data_block.records[rng.gen_range(0..n_records)].clone())
+                .collect(),
+            max_records_per_subject: data_block.max_records_per_subject,
+            sampling_rate: data_block.sampling_rate,
+        })
+    }
+
+    /// Metrics keys, in the same order as the values
+    /// returned by `calc_metric_values`
+    fn metric_keys(reporting_length: usize) -> Vec<(EvaluationMetric, Option<usize>)> {
+        ComparisonMetric::all()
+            .into_iter()
+            .map(|metric| (metric.into(), None))
+            .chain(
+                (1..=reporting_length).flat_map(|l| METRICS_BY_LEN.map(|metric| (metric, Some(l)))),
+            )
+            .collect()
+    }
+
+    fn calc_metric_values(
+        evaluator: &Evaluator,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+        resolution: usize,
+        reporting_length: usize,
+    ) -> MetricValues {
+        let marginal_distances = MarginalDistances::from_sensitive_and_synthetic_aggregated_data(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+        );
+        let preservation_by_length = evaluator.calc_preservation_by_length(
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+            resolution,
+        );
+        // same order as `METRICS_BY_LEN`
+        let metrics_by_len: [AggregatedMetricByLenMap; 7] = [
+            evaluator
+                .calc_leakage_count_by_len(
+                    sensitive_aggregated_data,
+                    synthetic_aggregated_data,
+                    resolution,
+                )
+                .iter()
+                .map(|(l, count)| (*l, *count as f64))
+                .collect(),
+            evaluator.calc_combinations_count_mean_abs_error_by_len(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+            ),
+            marginal_distances.calc_mean_total_variation_distance_by_len(),
+            marginal_distances.calc_mean_jensen_shannon_divergence_by_len(),
+            evaluator.calc_leakage_percentage_by_len(
+                sensitive_aggregated_data,
+                synthetic_aggregated_data,
+                resolution,
+            ),
+            Self::calc_non_empty_buckets_metric(&preservation_by_length, |b| {
+                b.get_mean_preservation()
+            }),
+            Self::calc_non_empty_buckets_metric(&preservation_by_length, |b| {
+                b.get_mean_proportional_error()
+            }),
+        ];
+        let mut values = SyntheticDataComparison::calc_metric_values(
+            evaluator,
+            sensitive_aggregated_data,
+            synthetic_aggregated_data,
+            &marginal_distances,
+            resolution,
+        );
+
+        for l in 1..=reporting_length {
+            for (metric, metric_by_len) in METRICS_BY_LEN.iter().zip(metrics_by_len.iter()) {
+                values.push(match metric_by_len.get(&l) {
+                    Some(value) => *value,
+                    // empty preservation buckets have no mean
+                    None if matches!(
+                        metric,
+                        EvaluationMetric::MeanPreservation
+                            | EvaluationMetric::MeanProportionalError
+                    ) =>
+                    {
+                        f64::NAN
+                    }
+                    // lengths missing from the map have no leaks or common combinations
+                    None => 0.0,
+                });
+            }
+        }
+
+        MetricValues {
+            values,
+            by_count_bucket: evaluator
+                .calc_preservation_by_count(
+                    sensitive_aggregated_data,
+                    synthetic_aggregated_data,
+                    resolution,
+                )
+                .iter()
+                .filter(|(_, b)| b.size > 0)
+                // same order as `METRICS_BY_COUNT_BUCKET`
+                .map(|(bucket_max, b)| {
+                    (
+                        *bucket_max,
+                        vec![b.get_mean_preservation(), b.get_mean_proportional_error()],
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[inline]
+    fn calc_non_empty_buckets_metric<B, F>(buckets: &B, metric: F) -> AggregatedMetricByLenMap
+    where
+        B: Deref<Target = PreservationBucketsMap>,
+        F: Fn(&PreservationBucket) -> f64,
+    {
+        buckets
+            .iter()
+            .filter(|(_, b)| b.size > 0)
+            .map(|(l, b)| (*l, metric(b)))
+            .collect()
+    }
+
+    fn summarize(
+        method: EvaluationUncertaintyMethod,
+        resolution: usize,
+        confidence_level: f64,
+        reporting_length: usize,
+        replicates: &[MetricValues],
+        estimates: Option<&MetricValues>,
+    ) -> EvaluationUncertainty {
+        let count_buckets: Vec<usize> = replicates
+            .iter()
+            .flat_map(|r| r.by_count_bucket.keys().cloned())
+            .unique()
+            .sorted()
+            .collect();
+        let by_len = Self::metric_keys(reporting_length)
+            .into_iter()
+            .enumerate()
+            .map(|(metric_index, (metric, combination_length))| {
+                (
+                    metric,
+                    combination_length,
+                    None,
+                    replicates
+                        .iter()
+                        .map(|r| r.values[metric_index])
+                        .collect::<Vec<f64>>(),
+                    estimates.map(|e| e.values[metric_index]),
+                )
+            });
+        let by_count_bucket = count_buckets.into_iter().flat_map(|bucket_max| {
+            METRICS_BY_COUNT_BUCKET
+                .iter()
+                .enumerate()
+                .map(|(metric_index, metric)| {
+                    let value_of = |r: &MetricValues| {
+                        r.by_count_bucket
+                            .get(&bucket_max)
+                            .map(|values| values[metric_index])
+                            .unwrap_or(f64::NAN)
+                    };
+
+                    (
+                        *metric,
+                        None,
+                        Some(bucket_max),
+                        replicates.iter().map(value_of).collect::<Vec<f64>>(),
+                        estimates.map(value_of),
+                    )
+                })
+                .collect_vec()
+        });
+        let metrics = by_len
+            .chain(by_count_bucket)
+            .filter(|(metric, _, _, _, _)| method.supports(*metric))
+            .filter_map(
+                |(metric, combination_length, count_bucket, samples, estimate)| {
+                    // metrics not defined on the replicate (e.g. empty buckets) are skipped
+                    let samples: Vec<f64> = samples.into_iter().filter(|v| !v.is_nan()).collect();
+
+                    if samples.len() < 2 || estimate.is_some_and(|e| e.is_nan()) {
+                        return None;
+                    }
+
+                    let n_samples = samples.len() as f64;
+                    let mean = samples.iter().sum::<f64>() / n_samples;
+                    let standard_deviation =
+                        (samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+                            / (n_samples - 1.0))
+                            .sqrt();
+
+                    Some(MetricUncertainty {
+                        metric,
+                        combination_length,
+                        count_bucket,
+                        number_of_samples: samples.len(),
+                        mean,
+                        standard_deviation,
+                        confidence_interval: ConfidenceInterval::from_percentiles(
+                            estimate.unwrap_or(mean),
+                            &samples,
+                            confidence_level,
+                        ),
+                    })
+                },
+            )
+            .collect();
+
+        info!(
+            "summarized the evaluation metrics over {} replicate(s) with {:?}",
+            replicates.len(),
+            method
+        );
+
+        EvaluationUncertainty {
+            method,
+            number_of_replicates: replicates.len(),
+            resolution,
+            confidence_level,
+            metrics,
+        }
+    }
+}
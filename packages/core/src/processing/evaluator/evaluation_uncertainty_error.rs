@@ -0,0 +1,41 @@
+use crate::utils::reporting::ProcessingStoppedError;
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when estimating the uncertainty of the evaluation metrics
+#[derive(Debug)]
+pub enum EvaluationUncertaintyError {
+    /// Generated when the estimation inputs are not valid
+    InvalidInput(String),
+    /// Generated when the estimation is stopped through the progress reporter
+    Stopped(ProcessingStoppedError),
+}
+
+impl Display for EvaluationUncertaintyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            EvaluationUncertaintyError::InvalidInput(err) => {
+                write!(f, "invalid evaluation uncertainty input: {err}")
+            }
+            EvaluationUncertaintyError::Stopped(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<ProcessingStoppedError> for EvaluationUncertaintyError {
+    fn from(err: ProcessingStoppedError) -> EvaluationUncertaintyError {
+        EvaluationUncertaintyError::Stopped(err)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<EvaluationUncertaintyError> for PyErr {
+    fn from(err: EvaluationUncertaintyError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,25 @@
+use itertools::Itertools;
+use std::cmp::Ordering;
+
+/// Area under the ROC curve computed from the Mann-Whitney U statistic:
+/// the probability that a positive scores higher than a negative
+/// (ties count as half).
+/// Both `positive_scores` and `negative_scores` should not be empty
+pub(crate) fn calc_mann_whitney_auc(positive_scores: &[f64], negative_scores: &[f64]) -> f64 {
+    let sorted_negatives = negative_scores
+        .iter()
+        .cloned()
+        .sorted_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .collect_vec();
+    let u: f64 = positive_scores
+        .iter()
+        .map(|s| {
+            let below = sorted_negatives.partition_point(|n| n < s);
+            let below_or_equal = sorted_negatives.partition_point(|n| n <= s);
+
+            (below as f64) + 0.5 * ((below_or_equal - below) as f64)
+        })
+        .sum();
+
+    u / ((positive_scores.len() * negative_scores.len()) as f64)
+}
@@ -0,0 +1,350 @@
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use std::io::{Error, Write};
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+use crate::processing::aggregator::{AggregatedData, AggregatedMetricByLenMap};
+
+/// Distributional distances between the sensitive and synthetic
+/// counts of a single marginal (set of columns)
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginalDistance {
+    /// Names of the columns forming the marginal
+    pub columns: Vec<String>,
+    /// Number of columns forming the marginal
+    pub combination_length: usize,
+    /// Number of distinct combinations on the sensitive
+    /// or synthetic data for this marginal
+    pub number_of_combinations: usize,
+    /// Total variation distance (`0.5 * sum(|p - q|)`), from `0` to `1`
+    pub total_variation_distance: f64,
+    /// Jensen-Shannon divergence (base 2), from `0` to `1`
+    pub jensen_shannon_divergence: f64,
+    /// Chi-square homogeneity statistic comparing the sensitive
+    /// and synthetic counts
+    pub chi_square: f64,
+    /// Degrees of freedom of the chi-square test (`number_of_combinations - 1`)
+    pub chi_square_degrees_of_freedom: usize,
+    /// P-value of the chi-square test (`None` if the test is not defined,
+    /// i.e. a single combination or no counts on one of the data sets)
+    pub chi_square_p_value: Option<f64>,
+}
+
+impl MarginalDistance {
+    fn new(columns: Vec<String>, counts: &[(usize, usize)]) -> MarginalDistance {
+        let sensitive_total = counts.iter().map(|(sen, _)| *sen as f64).sum::<f64>();
+        let synthetic_total = counts.iter().map(|(_, syn)| *syn as f64).sum::<f64>();
+        let total = sensitive_total + synthetic_total;
+        let mut total_variation_distance = 0.0;
+        let mut jensen_shannon_divergence = 0.0;
+        let mut chi_square = 0.0;
+
+        for (sen, syn) in counts.iter() {
+            let p = Self::proportion(*sen, sensitive_total);
+            let q = Self::proportion(*syn, synthetic_total);
+            let m = (p + q) / 2.0;
+
+            total_variation_distance += (p - q).abs();
+            jensen_shannon_divergence +=
+                Self::kl_divergence_term(p, m) + Self::kl_divergence_term(q, m);
+
+            for (observed, row_total) in [(*sen, sensitive_total), (*syn, synthetic_total)] {
+                let expected = ((*sen + *syn) as f64) * row_total / total;
+
+                if expected > 0.0 {
+                    chi_square += (observed as f64 - expected).powi(2) / expected;
+                }
+            }
+        }
+
+        let (total_variation_distance, jensen_shannon_divergence) =
+            if sensitive_total == 0.0 || synthetic_total == 0.0 {
+                // the marginal only exists on one of the data sets
+                (1.0, 1.0)
+            } else {
+                // clamp to account for floating point errors
+                (
+                    (total_variation_distance / 2.0).min(1.0),
+                    (jensen_shannon_divergence / 2.0).clamp(0.0, 1.0),
+                )
+            };
+        let chi_square_degrees_of_freedom = counts.len().saturating_sub(1);
+
+        MarginalDistance {
+            combination_length: columns.len(),
+            columns,
+            number_of_combinations: counts.len(),
+            total_variation_distance,
+            jensen_shannon_divergence,
+            chi_square,
+            chi_square_degrees_of_freedom,
+            chi_square_p_value: if chi_square_degrees_of_freedom > 0
+                && sensitive_total > 0.0
+                && synthetic_total > 0.0
+            {
+                ChiSquared::new(chi_square_degrees_of_freedom as f64)
+                    .ok()
+                    .map(|dist| 1.0 - dist.cdf(chi_square))
+            } else {
+                None
+            },
+        }
+    }
+
+    #[inline]
+    fn proportion(count: usize, total: f64) -> f64 {
+        if total > 0.0 {
+            (count as f64) / total
+        } else {
+            0.0
+        }
+    }
+
+    #[inline]
+    fn kl_divergence_term(p: f64, m: f64) -> f64 {
+        if p > 0.0 {
+            p * (p / m).log2()
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl MarginalDistance {
+    #[getter]
+    /// Names of the columns forming the marginal
+    fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    #[getter]
+    /// Number of columns forming the marginal
+    fn combination_length(&self) -> usize {
+        self.combination_length
+    }
+
+    #[getter]
+    /// Number of distinct combinations on the sensitive
+    /// or synthetic data for this marginal
+    fn number_of_combinations(&self) -> usize {
+        self.number_of_combinations
+    }
+
+    #[getter]
+    /// Total variation distance (`0.5 * sum(|p - q|)`), from `0` to `1`
+    fn total_variation_distance(&self) -> f64 {
+        self.total_variation_distance
+    }
+
+    #[getter]
+    /// Jensen-Shannon divergence (base 2), from `0` to `1`
+    fn jensen_shannon_divergence(&self) -> f64 {
+        self.jensen_shannon_divergence
+    }
+
+    #[getter]
+    /// Chi-square homogeneity statistic comparing the sensitive
+    /// and synthetic counts
+    fn chi_square(&self) -> f64 {
+        self.chi_square
+    }
+
+    #[getter]
+    /// Degrees of freedom of the chi-square test (`number_of_combinations - 1`)
+    fn chi_square_degrees_of_freedom(&self) -> usize {
+        self.chi_square_degrees_of_freedom
+    }
+
+    #[getter]
+    /// P-value of the chi-square test (`None` if the test is not defined)
+    fn chi_square_p_value(&self) -> Option<f64> {
+        self.chi_square_p_value
+    }
+}
+
+/// Distributional distances between the sensitive and synthetic
+/// marginals (one entry per set of columns up to the reporting length)
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginalDistances {
+    /// Distances for every marginal, sorted by combination length and columns
+    pub marginals: Vec<MarginalDistance>,
+}
+
+impl MarginalDistances {
+    /// Compares the sensitive and synthetic counts of every marginal.
+    /// Counts are normalized by their sum within each marginal and combinations
+    /// missing from one of the data sets are treated as having count `0`
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Calculated aggregated data for the synthetic data
+    pub fn from_sensitive_and_synthetic_aggregated_data(
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+    ) -> MarginalDistances {
+        let mut counts_by_marginal: FnvHashMap<Vec<usize>, Vec<(usize, usize)>> =
+            FnvHashMap::default();
+
+        for (comb, sensitive_count) in sensitive_aggregated_data.aggregates_count.iter() {
+            counts_by_marginal
+                .entry(comb.iter().map(|v| v.column_index).collect())
+                .or_default()
+                .push((
+                    sensitive_count.count,
+                    synthetic_aggregated_data
+                        .aggregates_count
+                        .get(comb)
+                        .map(|c| c.count)
+                        .unwrap_or(0),
+                ));
+        }
+        for (comb, synthetic_count) in synthetic_aggregated_data.aggregates_count.iter() {
+            if !sensitive_aggregated_data
+                .aggregates_count
+                .contains_key(comb)
+            {
+                counts_by_marginal
+                    .entry(comb.iter().map(|v| v.column_index).collect())
+                    .or_default()
+                    .push((0, synthetic_count.count));
+            }
+        }
+
+        MarginalDistances {
+            marginals: counts_by_marginal
+                .drain()
+                .sorted_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+                .map(|(column_indexes, counts)| {
+                    MarginalDistance::new(
+                        column_indexes
+                            .iter()
+                            .map(|i| sensitive_aggregated_data.headers[*i].to_string())
+                            .collect(),
+                        &counts,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[inline]
+    fn calc_mean_by_len<F>(&self, metric: F) -> AggregatedMetricByLenMap
+    where
+        F: Fn(&MarginalDistance) -> f64,
+    {
+        let mut sum_count_by_len: FnvHashMap<usize, (f64, usize)> = FnvHashMap::default();
+
+        for marginal in self.marginals.iter() {
+            let sum_count = sum_count_by_len
+                .entry(marginal.combination_length)
+                .or_insert((0.0, 0));
+
+            sum_count.0 += metric(marginal);
+            sum_count.1 += 1;
+        }
+        sum_count_by_len
+            .drain()
+            .map(|(l, sum_count)| (l, sum_count.0 / (sum_count.1 as f64)))
+            .collect()
+    }
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl MarginalDistances {
+    /// Returns the distances for every marginal.
+    /// This method will clone the data, so its recommended to have its result stored
+    /// in a local variable to avoid it being called multiple times
+    pub fn get_marginals(&self) -> Vec<MarginalDistance> {
+        self.marginals.clone()
+    }
+
+    /// Calculates the mean total variation distance grouped by combination length
+    pub fn calc_mean_total_variation_distance_by_len(&self) -> AggregatedMetricByLenMap {
+        self.calc_mean_by_len(|m| m.total_variation_distance)
+    }
+
+    /// Calculates the mean Jensen-Shannon divergence grouped by combination length
+    pub fn calc_mean_jensen_shannon_divergence_by_len(&self) -> AggregatedMetricByLenMap {
+        self.calc_mean_by_len(|m| m.jensen_shannon_divergence)
+    }
+
+    /// Calculates the mean chi-square statistic grouped by combination length
+    pub fn calc_mean_chi_square_by_len(&self) -> AggregatedMetricByLenMap {
+        self.calc_mean_by_len(|m| m.chi_square)
+    }
+
+    /// Calculates the percentage of marginals where the chi-square test rejects
+    /// that the sensitive and synthetic counts follow the same distribution,
+    /// grouped by combination length
+    /// # Arguments
+    /// * `significance_level` - Significance level of the test (e.g. `0.05`)
+    pub fn calc_percentage_of_different_marginals_by_len(
+        &self,
+        significance_level: f64,
+    ) -> AggregatedMetricByLenMap {
+        self.calc_mean_by_len(|m| match m.chi_square_p_value {
+            Some(p_value) if p_value < significance_level => 100.0,
+            _ => 0.0,
+        })
+    }
+
+    /// Writes the distances for every marginal to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `marginal_distances_path` - File path to be written
+    /// * `marginal_distances_delimiter` - Delimiter to use when writing to `marginal_distances_path`
+    /// * `columns_delimiter` - Delimiter used to join the marginal column names
+    pub fn write_marginal_distances(
+        &self,
+        marginal_distances_path: &str,
+        marginal_distances_delimiter: char,
+        columns_delimiter: &str,
+    ) -> Result<(), Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(marginal_distances_path)?);
+
+        file.write_all(
+            [
+                "columns",
+                "combination_length",
+                "number_of_combinations",
+                "total_variation_distance",
+                "jensen_shannon_divergence",
+                "chi_square",
+                "chi_square_degrees_of_freedom",
+                "chi_square_p_value",
+            ]
+            .join(&marginal_distances_delimiter.to_string())
+            .as_bytes(),
+        )?;
+        file.write_all("\n".as_bytes())?;
+        for m in self.marginals.iter() {
+            file.write_all(
+                [
+                    m.columns.join(columns_delimiter),
+                    m.combination_length.to_string(),
+                    m.number_of_combinations.to_string(),
+                    format!("{:.6}", m.total_variation_distance),
+                    format!("{:.6}", m.jensen_shannon_divergence),
+                    format!("{:.6}", m.chi_square),
+                    m.chi_square_degrees_of_freedom.to_string(),
+                    m.chi_square_p_value
+                        .map(|p| format!("{:.6}", p))
+                        .unwrap_or_default(),
+                ]
+                .join(&marginal_distances_delimiter.to_string())
+                .as_bytes(),
+            )?;
+            file.write_all("\n".as_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use super::{
+    mann_whitney_auc::calc_mann_whitney_auc, AlignedRecords, ConfidenceInterval, PrivacyAttackError,
+};
+use crate::data_block::DataBlock;
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Default confidence level used for the attack metrics intervals
+pub const DEFAULT_MEMBERSHIP_CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// How the attacker scores a target record against the synthetic data
+/// (the higher the score, the more likely the target is a member)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MembershipAttackScore {
+    /// Number of columns matching the closest synthetic record
+    /// (distance-based attack)
+    Distance,
+    /// Proportion of synthetic records within `density_radius`
+    /// hamming distance from the target (density-based attack)
+    Density,
+}
+
+/// Parameters used to run the membership inference game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipInferenceParameters {
+    /// How the attacker scores the target records
+    pub score: MembershipAttackScore,
+    /// Maximum hamming distance for a synthetic record to
+    /// count towards the density score
+    #[serde(default)]
+    pub density_radius: usize,
+    /// Confidence level for the metrics intervals (e.g. `0.95`)
+    #[serde(default = "default_confidence_level")]
+    pub confidence_level: f64,
+}
+
+#[inline]
+fn default_confidence_level() -> f64 {
+    DEFAULT_MEMBERSHIP_CONFIDENCE_LEVEL
+}
+
+impl MembershipInferenceParameters {
+    /// Returns a new MembershipInferenceParameters
+    /// # Arguments
+    /// * `score` - How the attacker scores the target records
+    /// * `density_radius` - Maximum hamming distance for a synthetic record to
+    /// count towards the density score
+    /// * `confidence_level` - Confidence level for the metrics intervals,
+    /// if None will be set to `DEFAULT_MEMBERSHIP_CONFIDENCE_LEVEL`
+    #[inline]
+    pub fn new(
+        score: MembershipAttackScore,
+        density_radius: usize,
+        confidence_level: Option<f64>,
+    ) -> Self {
+        MembershipInferenceParameters {
+            score,
+            density_radius,
+            confidence_level: confidence_level.unwrap_or(DEFAULT_MEMBERSHIP_CONFIDENCE_LEVEL),
+        }
+    }
+}
+
+/// Results of the membership inference game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipInferenceResult {
+    /// Score used by the attacker
+    pub score: MembershipAttackScore,
+    /// Number of training records (members) used as targets
+    pub number_of_training_records: usize,
+    /// Number of holdout records (non-members) used as targets
+    pub number_of_holdout_records: usize,
+    /// Number of columns compared between the targets and the synthetic records
+    pub number_of_columns: usize,
+    /// Area under the ROC curve of the attack (`0.5` means random guessing)
+    pub auc: ConfidenceInterval,
+    /// Maximum `true positive rate - false positive rate` over
+    /// all the score thresholds (`0` means no advantage over random guessing)
+    pub advantage: ConfidenceInterval,
+    /// Score threshold reaching the maximum advantage
+    /// (targets with `score >= threshold` are guessed as members),
+    /// `None` if no threshold has a positive advantage
+    pub advantage_threshold: Option<f64>,
+}
+
+/// Runs a membership inference game against the synthetic data: the attacker
+/// scores training records (members) and holdout records (non-members) using
+/// only the synthetic data, and the attack is evaluated on how well
+/// the scores separate both groups
+pub struct MembershipInferenceAttack {
+    parameters: MembershipInferenceParameters,
+}
+
+impl MembershipInferenceAttack {
+    /// Returns a new MembershipInferenceAttack
+    /// # Arguments
+    /// * `parameters` - Parameters used to run the game
+    #[inline]
+    pub fn new(parameters: MembershipInferenceParameters) -> MembershipInferenceAttack {
+        MembershipInferenceAttack { parameters }
+    }
+
+    /// Runs the game and reports the attack metrics
+    /// # Arguments
+    /// * `training_data_block` - Records used to synthesize the data (members)
+    /// * `holdout_data_block` - Records from the same population not used
+    /// to synthesize the data (non-members)
+    /// * `synthetic_data_block` - Synthesized data
+    pub fn run(
+        &self,
+        training_data_block: &DataBlock,
+        holdout_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<MembershipInferenceResult, PrivacyAttackError> {
+        self.validate()?;
+
+        let columns = AlignedRecords::common_columns(&[
+            training_data_block,
+            holdout_data_block,
+            synthetic_data_block,
+        ]);
+
+        if columns.is_empty() {
+            return Err(PrivacyAttackError::InvalidInput(
+                "training, holdout and synthetic data have no columns in common".to_owned(),
+            ));
+        }
+
+        let training = AlignedRecords::new(training_data_block, &columns);
+        let holdout = AlignedRecords::new(holdout_data_block, &columns);
+        let synthetic = AlignedRecords::new(synthetic_data_block, &columns);
+
+        if training.is_empty() || holdout.is_empty() || synthetic.is_empty() {
+            return Err(PrivacyAttackError::InvalidInput(
+                "training, holdout and synthetic data must not be empty".to_owned(),
+            ));
+        }
+
+        let member_scores = self.calc_scores(&training, &synthetic);
+        let non_member_scores = self.calc_scores(&holdout, &synthetic);
+        let (advantage, advantage_threshold) =
+            Self::calc_max_advantage(&member_scores, &non_member_scores);
+        let result = MembershipInferenceResult {
+            score: self.parameters.score,
+            number_of_training_records: training.len(),
+            number_of_holdout_records: holdout.len(),
+            number_of_columns: columns.len(),
+            auc: Self::calc_auc(
+                &member_scores,
+                &non_member_scores,
+                self.parameters.confidence_level,
+            ),
+            advantage: Self::calc_advantage_interval(
+                advantage,
+                &member_scores,
+                &non_member_scores,
+                advantage_threshold,
+                self.parameters.confidence_level,
+            ),
+            advantage_threshold,
+        };
+
+        info!(
+            "membership inference: auc = {:.4} [{:.4}, {:.4}], advantage = {:.4} [{:.4}, {:.4}]",
+            result.auc.estimate,
+            result.auc.lower_bound,
+            result.auc.upper_bound,
+            result.advantage.estimate,
+            result.advantage.lower_bound,
+            result.advantage.upper_bound
+        );
+
+        Ok(result)
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), PrivacyAttackError> {
+        if self.parameters.confidence_level <= 0.0 || self.parameters.confidence_level >= 1.0 {
+            return Err(PrivacyAttackError::InvalidInput(
+                "confidence level must be > 0 and < 1".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn calc_scores(&self, targets: &AlignedRecords, synthetic: &AlignedRecords) -> Vec<f64> {
+        match self.parameters.score {
+            MembershipAttackScore::Distance => targets
+                .calc_closest_distances(synthetic)
+                .iter()
+                .map(|d| (targets.columns.len() - d.unwrap_or_default()) as f64)
+                .collect(),
+            MembershipAttackScore::Density => targets
+                .records
+                .iter()
+                .map(|t| {
+                    (synthetic
+                        .records
+                        .iter()
+                        .filter(|s| {
+                            AlignedRecords::hamming_distance(t, s) <= self.parameters.density_radius
+                        })
+                        .count() as f64)
+                        / (synthetic.len() as f64)
+                })
+                .collect(),
+        }
+    }
+
+    /// AUC computed from the Mann-Whitney U statistic (ties count as half),
+    /// with the Hanley-McNeil standard error
+    fn calc_auc(
+        member_scores: &[f64],
+        non_member_scores: &[f64],
+        confidence_level: f64,
+    ) -> ConfidenceInterval {
+        let n_members = member_scores.len() as f64;
+        let n_non_members = non_member_scores.len() as f64;
+        let auc = calc_mann_whitney_auc(member_scores, non_member_scores);
+        let q1 = auc / (2.0 - auc);
+        let q2 = 2.0 * auc * auc / (1.0 + auc);
+        let variance = (auc * (1.0 - auc)
+            + (n_members - 1.0) * (q1 - auc * auc)
+            + (n_non_members - 1.0) * (q2 - auc * auc))
+            / (n_members * n_non_members);
+
+        ConfidenceInterval::from_normal_approximation(
+            auc,
+            variance.max(0.0).sqrt(),
+            confidence_level,
+            0.0,
+            1.0,
+        )
+    }
+
+    /// Returns the maximum advantage and the threshold reaching it
+    fn calc_max_advantage(member_scores: &[f64], non_member_scores: &[f64]) -> (f64, Option<f64>) {
+        member_scores
+            .iter()
+            .chain(non_member_scores.iter())
+            .cloned()
+            .sorted_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .dedup()
+            .map(|threshold| {
+                let (tpr, fpr) = Self::calc_rates(member_scores, non_member_scores, threshold);
+                (tpr - fpr, Some(threshold))
+            })
+            .fold(
+                (0.0, None),
+                |best, current| {
+                    if current.0 > best.0 {
+                        current
+                    } else {
+                        best
+                    }
+                },
+            )
+    }
+
+    #[inline]
+    fn calc_rates(member_scores: &[f64], non_member_scores: &[f64], threshold: f64) -> (f64, f64) {
+        (
+            (member_scores.iter().filter(|s| **s >= threshold).count() as f64)
+                / (member_scores.len() as f64),
+            (non_member_scores
+                .iter()
+                .filter(|s| **s >= threshold)
+                .count() as f64)
+                / (non_member_scores.len() as f64),
+        )
+    }
+
+    /// Interval for the advantage at the chosen threshold, using the
+    /// binomial standard errors of the true and false positive rates
+    fn calc_advantage_interval(
+        advantage: f64,
+        member_scores: &[f64],
+        non_member_scores: &[f64],
+        threshold: Option<f64>,
+        confidence_level: f64,
+    ) -> ConfidenceInterval {
+        let (tpr, fpr) = threshold
+            .map(|t| Self::calc_rates(member_scores, non_member_scores, t))
+            .unwrap_or_default();
+        let standard_error = (tpr * (1.0 - tpr) / (member_scores.len() as f64)
+            + fpr * (1.0 - fpr) / (non_member_scores.len() as f64))
+            .sqrt();
+
+        ConfidenceInterval::from_normal_approximation(
+            advantage,
+            standard_error,
+            confidence_level,
+            -1.0,
+            1.0,
+        )
+    }
+}
@@ -0,0 +1,26 @@
+use super::LabeledRecords;
+use crate::processing::evaluator::AlignedRecord;
+
+/// Classifier predicting a categorical target from categorical features
+/// (empty values are a category of their own)
+pub trait CategoricalClassifier {
+    /// Trains the classifier
+    /// # Arguments
+    /// * `records` - Training records
+    fn fit(&mut self, records: &LabeledRecords);
+
+    /// Returns the probability of each class (indexed as in
+    /// `LabeledRecords::classes`) for the given feature values
+    /// # Arguments
+    /// * `features` - Feature values of the record to classify
+    fn predict_proba(&self, features: &AlignedRecord) -> Vec<f64>;
+}
+
+/// Converts scores into probabilities
+pub(super) fn softmax(scores: &[f64]) -> Vec<f64> {
+    let max = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exp: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+    let sum: f64 = exp.iter().sum();
+
+    exp.iter().map(|e| e / sum).collect()
+}
@@ -0,0 +1,209 @@
+use super::{CategoricalClassifier, LabeledRecords};
+use crate::processing::evaluator::AlignedRecord;
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+/// Default maximum depth of the decision tree
+pub const DEFAULT_DECISION_TREE_MAX_DEPTH: usize = 5;
+
+/// Nodes with less records than this are not split
+const MIN_RECORDS_TO_SPLIT: usize = 2;
+
+#[derive(Debug, Clone)]
+enum TreeNode {
+    Leaf {
+        probabilities: Vec<f64>,
+    },
+    Split {
+        feature: usize,
+        children: FnvHashMap<Option<Arc<String>>, TreeNode>,
+        /// Used for values not seen on training
+        probabilities: Vec<f64>,
+    },
+}
+
+/// Decision tree with multiway splits on the categorical features,
+/// chosen by information gain (each feature is used at most once per path)
+#[derive(Debug, Clone)]
+pub struct DecisionTree {
+    max_depth: usize,
+    root: Option<TreeNode>,
+}
+
+impl DecisionTree {
+    /// Returns a new DecisionTree
+    /// # Arguments
+    /// * `max_depth` - Maximum depth of the tree
+    #[inline]
+    pub fn new(max_depth: usize) -> DecisionTree {
+        DecisionTree {
+            max_depth,
+            root: None,
+        }
+    }
+
+    fn build_node(
+        records: &LabeledRecords,
+        indexes: &[usize],
+        available_features: &mut Vec<bool>,
+        depth: usize,
+    ) -> TreeNode {
+        let counts = Self::calc_class_counts(records, indexes);
+        let probabilities = Self::calc_probabilities(&counts);
+
+        if depth == 0
+            || indexes.len() < MIN_RECORDS_TO_SPLIT
+            || counts.iter().filter(|c| **c > 0).count() <= 1
+        {
+            return TreeNode::Leaf { probabilities };
+        }
+
+        let entropy = Self::calc_entropy(&counts);
+        let best_split = available_features
+            .iter()
+            .enumerate()
+            .filter(|(_, available)| **available)
+            .map(|(feature, _)| {
+                let partitions = Self::partition(records, indexes, feature);
+                let split_entropy = partitions
+                    .values()
+                    .map(|p| {
+                        (p.len() as f64) * Self::calc_entropy(&Self::calc_class_counts(records, p))
+                    })
+                    .sum::<f64>()
+                    / (indexes.len() as f64);
+
+                (entropy - split_entropy, feature, partitions)
+            })
+            // impure nodes are split even without gain, so
+            // interactions between features can still be learned
+            .filter(|(_, _, partitions)| partitions.len() > 1)
+            .max_by(|(a, fa, _), (b, fb, _)| {
+                // prefer the first feature on ties, so the tree is deterministic
+                a.partial_cmp(b)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| fb.cmp(fa))
+            });
+
+        match best_split {
+            Some((_, feature, partitions)) => {
+                available_features[feature] = false;
+
+                let children = partitions
+                    .into_iter()
+                    .map(|(value, p)| {
+                        (
+                            value,
+                            Self::build_node(records, &p, available_features, depth - 1),
+                        )
+                    })
+                    .collect();
+
+                available_features[feature] = true;
+                TreeNode::Split {
+                    feature,
+                    children,
+                    probabilities,
+                }
+            }
+            None => TreeNode::Leaf { probabilities },
+        }
+    }
+
+    #[inline]
+    fn partition(
+        records: &LabeledRecords,
+        indexes: &[usize],
+        feature: usize,
+    ) -> FnvHashMap<Option<Arc<String>>, Vec<usize>> {
+        let mut partitions: FnvHashMap<Option<Arc<String>>, Vec<usize>> = FnvHashMap::default();
+
+        for i in indexes.iter() {
+            partitions
+                .entry(records.features[*i][feature].clone())
+                .or_default()
+                .push(*i);
+        }
+        partitions
+    }
+
+    #[inline]
+    fn calc_class_counts(records: &LabeledRecords, indexes: &[usize]) -> Vec<usize> {
+        let mut counts = vec![0; records.number_of_classes];
+
+        for i in indexes.iter() {
+            counts[records.labels[*i]] += 1;
+        }
+        counts
+    }
+
+    #[inline]
+    fn calc_probabilities(counts: &[usize]) -> Vec<f64> {
+        let total = counts.iter().sum::<usize>() as f64;
+
+        counts
+            .iter()
+            .map(|c| {
+                if total > 0.0 {
+                    (*c as f64) / total
+                } else {
+                    1.0 / (counts.len() as f64)
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn calc_entropy(counts: &[usize]) -> f64 {
+        let total = counts.iter().sum::<usize>() as f64;
+
+        counts
+            .iter()
+            .filter(|c| **c > 0)
+            .map(|c| {
+                let p = (*c as f64) / total;
+                -p * p.ln()
+            })
+            .sum()
+    }
+}
+
+impl Default for DecisionTree {
+    /// Returns a new DecisionTree using `DEFAULT_DECISION_TREE_MAX_DEPTH`
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_DECISION_TREE_MAX_DEPTH)
+    }
+}
+
+impl CategoricalClassifier for DecisionTree {
+    fn fit(&mut self, records: &LabeledRecords) {
+        let number_of_features = records.features.first().map(|f| f.len()).unwrap_or(0);
+        let indexes: Vec<usize> = (0..records.len()).collect();
+
+        self.root = Some(Self::build_node(
+            records,
+            &indexes,
+            &mut vec![true; number_of_features],
+            self.max_depth,
+        ));
+    }
+
+    fn predict_proba(&self, features: &AlignedRecord) -> Vec<f64> {
+        let mut node = self.root.as_ref().expect("decision tree is not trained");
+
+        loop {
+            match node {
+                TreeNode::Leaf { probabilities } => return probabilities.clone(),
+                TreeNode::Split {
+                    feature,
+                    children,
+                    probabilities,
+                } => match children.get(&features[*feature]) {
+                    Some(child) => node = child,
+                    None => return probabilities.clone(),
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::processing::evaluator::{AlignedRecord, AlignedRecords};
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+/// Target classes, the position of each class is its label
+pub type TargetClasses = Vec<Option<Arc<String>>>;
+
+/// Records split into the feature values and the target label
+#[derive(Debug, Clone)]
+pub struct LabeledRecords {
+    /// Feature values of every record
+    pub features: Vec<AlignedRecord>,
+    /// Target label of every record (position in `TargetClasses`)
+    pub labels: Vec<usize>,
+    /// Number of possible target classes
+    pub number_of_classes: usize,
+}
+
+impl LabeledRecords {
+    /// Splits aligned records into features and labels
+    /// # Arguments
+    /// * `records` - Records aligned to the features followed by the target column
+    /// * `class_index` - Maps every target class to its label
+    pub fn new(
+        records: &AlignedRecords,
+        class_index: &FnvHashMap<Option<Arc<String>>, usize>,
+    ) -> LabeledRecords {
+        let (features, labels) = records
+            .records
+            .iter()
+            .map(|r| {
+                let (target, features) = r.split_last().unwrap();
+                (features.to_vec(), class_index[target])
+            })
+            .unzip();
+
+        LabeledRecords {
+            features,
+            labels,
+            number_of_classes: class_index.len(),
+        }
+    }
+
+    /// Builds the target classes found on any of the `records`
+    /// (the target is expected to be the last aligned column),
+    /// returning the classes and the label of each of them
+    /// # Arguments
+    /// * `records` - Records aligned to the features followed by the target column
+    pub fn build_classes(
+        records: &[&AlignedRecords],
+    ) -> (TargetClasses, FnvHashMap<Option<Arc<String>>, usize>) {
+        let mut classes = TargetClasses::default();
+        let mut class_index: FnvHashMap<Option<Arc<String>>, usize> = FnvHashMap::default();
+
+        for r in records.iter().flat_map(|r| r.records.iter()) {
+            if let Some(target) = r.last() {
+                if !class_index.contains_key(target) {
+                    class_index.insert(target.clone(), classes.len());
+                    classes.push(target.clone());
+                }
+            }
+        }
+        (classes, class_index)
+    }
+
+    /// Returns the number of records
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns whether there are no records
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Counts how many records have each label
+    #[inline]
+    pub fn calc_class_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.number_of_classes];
+
+        for l in self.labels.iter() {
+            counts[*l] += 1;
+        }
+        counts
+    }
+}
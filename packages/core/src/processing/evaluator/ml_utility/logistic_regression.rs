@@ -0,0 +1,115 @@
+use super::{categorical_classifier::softmax, CategoricalClassifier, LabeledRecords};
+use crate::processing::evaluator::AlignedRecord;
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+/// Default number of gradient descent iterations
+pub const DEFAULT_LOGISTIC_REGRESSION_ITERATIONS: usize = 200;
+
+const LEARNING_RATE: f64 = 1.0;
+
+const L2_REGULARIZATION: f64 = 1e-4;
+
+/// Multinomial logistic regression on the one-hot encoded features,
+/// trained with full batch gradient descent (so training is deterministic)
+#[derive(Debug, Clone)]
+pub struct LogisticRegression {
+    number_of_iterations: usize,
+    number_of_classes: usize,
+    /// Maps a (feature, value) pair to its one-hot position
+    one_hot_index: FnvHashMap<(usize, Option<Arc<String>>), usize>,
+    /// Weights for every one-hot position followed by the bias,
+    /// each with one entry per class
+    weights: Vec<Vec<f64>>,
+}
+
+impl LogisticRegression {
+    /// Returns a new LogisticRegression
+    /// # Arguments
+    /// * `number_of_iterations` - Number of gradient descent iterations
+    #[inline]
+    pub fn new(number_of_iterations: usize) -> LogisticRegression {
+        LogisticRegression {
+            number_of_iterations,
+            number_of_classes: 0,
+            one_hot_index: FnvHashMap::default(),
+            weights: Vec::default(),
+        }
+    }
+
+    /// One-hot positions active for the feature values
+    /// (values not seen on training are ignored)
+    #[inline]
+    fn encode(&self, features: &AlignedRecord) -> Vec<usize> {
+        features
+            .iter()
+            .enumerate()
+            .filter_map(|(f, value)| self.one_hot_index.get(&(f, value.clone())).cloned())
+            .collect()
+    }
+
+    #[inline]
+    fn calc_probabilities(&self, active: &[usize]) -> Vec<f64> {
+        let bias = &self.weights[self.one_hot_index.len()];
+        let scores: Vec<f64> = (0..self.number_of_classes)
+            .map(|c| bias[c] + active.iter().map(|a| self.weights[*a][c]).sum::<f64>())
+            .collect();
+
+        softmax(&scores)
+    }
+}
+
+impl Default for LogisticRegression {
+    /// Returns a new LogisticRegression using `DEFAULT_LOGISTIC_REGRESSION_ITERATIONS`
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_LOGISTIC_REGRESSION_ITERATIONS)
+    }
+}
+
+impl CategoricalClassifier for LogisticRegression {
+    fn fit(&mut self, records: &LabeledRecords) {
+        self.number_of_classes = records.number_of_classes;
+        self.one_hot_index = FnvHashMap::default();
+
+        for features in records.features.iter() {
+            for (f, value) in features.iter().enumerate() {
+                let next_index = self.one_hot_index.len();
+
+                self.one_hot_index
+                    .entry((f, value.clone()))
+                    .or_insert(next_index);
+            }
+        }
+
+        let bias_index = self.one_hot_index.len();
+        let encoded: Vec<Vec<usize>> = records.features.iter().map(|f| self.encode(f)).collect();
+        let n = records.len().max(1) as f64;
+
+        self.weights = vec![vec![0.0; self.number_of_classes]; bias_index + 1];
+
+        for _ in 0..self.number_of_iterations {
+            let mut gradient = vec![vec![0.0; self.number_of_classes]; bias_index + 1];
+
+            for (active, label) in encoded.iter().zip(records.labels.iter()) {
+                let mut error = self.calc_probabilities(active);
+
+                error[*label] -= 1.0;
+                for a in active.iter().chain(std::iter::once(&bias_index)) {
+                    for (g, e) in gradient[*a].iter_mut().zip(error.iter()) {
+                        *g += e;
+                    }
+                }
+            }
+            for (weights, gradient) in self.weights.iter_mut().zip(gradient.iter()) {
+                for (w, g) in weights.iter_mut().zip(gradient.iter()) {
+                    *w -= LEARNING_RATE * (g / n + L2_REGULARIZATION * *w);
+                }
+            }
+        }
+    }
+
+    fn predict_proba(&self, features: &AlignedRecord) -> Vec<f64> {
+        self.calc_probabilities(&self.encode(features))
+    }
+}
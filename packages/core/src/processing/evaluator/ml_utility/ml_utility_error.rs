@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when evaluating the machine learning utility of the synthetic data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MlUtilityError {
+    /// Generated when the evaluation inputs are not valid
+    InvalidInput(String),
+}
+
+impl Display for MlUtilityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            MlUtilityError::InvalidInput(err) => {
+                write!(f, "invalid machine learning utility input: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<MlUtilityError> for PyErr {
+    fn from(err: MlUtilityError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
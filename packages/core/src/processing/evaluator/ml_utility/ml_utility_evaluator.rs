@@ -0,0 +1,305 @@
+use super::{
+    CategoricalClassifier, DecisionTree, LabeledRecords, LogisticRegression, MlUtilityError,
+    MlUtilityMetrics, NaiveBayes, DEFAULT_DECISION_TREE_MAX_DEPTH,
+    DEFAULT_LOGISTIC_REGRESSION_ITERATIONS,
+};
+use crate::{data_block::DataBlock, processing::evaluator::AlignedRecords};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Classifier trained on the real and synthetic data
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MlUtilityClassifier {
+    /// Categorical naive Bayes with Laplace smoothing
+    NaiveBayes,
+    /// Multinomial logistic regression on the one-hot encoded features
+    LogisticRegression,
+    /// Decision tree with multiway splits chosen by information gain
+    DecisionTree,
+}
+
+/// Parameters used to evaluate the machine learning utility
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MlUtilityParameters {
+    /// Column the classifiers predict
+    pub target: String,
+    /// Columns used as features (if empty, all the
+    /// columns in common except the target)
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Classifiers to evaluate
+    #[serde(default = "default_classifiers")]
+    pub classifiers: Vec<MlUtilityClassifier>,
+    /// Maximum depth of the decision tree
+    #[serde(default = "default_max_tree_depth")]
+    pub max_tree_depth: usize,
+    /// Number of gradient descent iterations of the logistic regression
+    #[serde(default = "default_logistic_regression_iterations")]
+    pub logistic_regression_iterations: usize,
+}
+
+#[inline]
+fn default_classifiers() -> Vec<MlUtilityClassifier> {
+    vec![
+        MlUtilityClassifier::NaiveBayes,
+        MlUtilityClassifier::LogisticRegression,
+        MlUtilityClassifier::DecisionTree,
+    ]
+}
+
+#[inline]
+fn default_max_tree_depth() -> usize {
+    DEFAULT_DECISION_TREE_MAX_DEPTH
+}
+
+#[inline]
+fn default_logistic_regression_iterations() -> usize {
+    DEFAULT_LOGISTIC_REGRESSION_ITERATIONS
+}
+
+impl MlUtilityParameters {
+    /// Returns a new MlUtilityParameters evaluating all the classifiers
+    /// with their default settings
+    /// # Arguments
+    /// * `target` - Column the classifiers predict
+    /// * `features` - Columns used as features (if empty, all the
+    /// columns in common except the target)
+    #[inline]
+    pub fn new(target: String, features: Vec<String>) -> MlUtilityParameters {
+        MlUtilityParameters {
+            target,
+            features,
+            classifiers: default_classifiers(),
+            max_tree_depth: DEFAULT_DECISION_TREE_MAX_DEPTH,
+            logistic_regression_iterations: DEFAULT_LOGISTIC_REGRESSION_ITERATIONS,
+        }
+    }
+}
+
+/// Metrics of a classifier trained on the real and on the synthetic data
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MlUtilityClassifierResult {
+    /// Evaluated classifier
+    pub classifier: MlUtilityClassifier,
+    /// Metrics when trained on the real training data
+    pub trained_on_real: MlUtilityMetrics,
+    /// Metrics when trained on the synthetic data
+    pub trained_on_synthetic: MlUtilityMetrics,
+    /// `trained_on_real.accuracy - trained_on_synthetic.accuracy`
+    pub accuracy_gap: f64,
+    /// `trained_on_real.macro_f1 - trained_on_synthetic.macro_f1`
+    pub macro_f1_gap: f64,
+    /// `trained_on_real.auc - trained_on_synthetic.auc`
+    /// (`None` if the AUC is not defined)
+    pub auc_gap: Option<f64>,
+}
+
+/// Results of training on synthetic data and testing on real data
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MlUtilityResult {
+    /// Column the classifiers predict
+    pub target: String,
+    /// Columns used as features
+    pub features: Vec<String>,
+    /// Number of real records used for training
+    pub number_of_training_records: usize,
+    /// Number of synthetic records used for training
+    pub number_of_synthetic_records: usize,
+    /// Number of held-out real records used for testing
+    pub number_of_test_records: usize,
+    /// Number of distinct target values on all the data
+    pub number_of_classes: usize,
+    /// Accuracy of always predicting the most common target
+    /// value on the real training data
+    pub majority_class_accuracy: f64,
+    /// Metrics for every classifier
+    pub classifiers: Vec<MlUtilityClassifierResult>,
+}
+
+/// Trains classifiers on the synthetic data and on the real training data,
+/// then tests both on held-out real records: the smaller the gaps
+/// between both, the more useful the synthetic data is for machine learning
+pub struct MlUtilityEvaluator {
+    parameters: MlUtilityParameters,
+}
+
+impl MlUtilityEvaluator {
+    /// Returns a new MlUtilityEvaluator
+    /// # Arguments
+    /// * `parameters` - Parameters used to evaluate the utility
+    #[inline]
+    pub fn new(parameters: MlUtilityParameters) -> MlUtilityEvaluator {
+        MlUtilityEvaluator { parameters }
+    }
+
+    /// Trains and tests the classifiers
+    /// # Arguments
+    /// * `training_data_block` - Real records used to synthesize the data
+    /// * `test_data_block` - Real records from the same population not used
+    /// to synthesize the data
+    /// * `synthetic_data_block` - Synthesized data
+    pub fn run(
+        &self,
+        training_data_block: &DataBlock,
+        test_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Result<MlUtilityResult, MlUtilityError> {
+        let columns =
+            self.select_columns(&[training_data_block, test_data_block, synthetic_data_block])?;
+        let training = AlignedRecords::new(training_data_block, &columns);
+        let test = AlignedRecords::new(test_data_block, &columns);
+        let synthetic = AlignedRecords::new(synthetic_data_block, &columns);
+
+        if training.is_empty() || test.is_empty() || synthetic.is_empty() {
+            return Err(MlUtilityError::InvalidInput(
+                "training, test and synthetic data must not be empty".to_owned(),
+            ));
+        }
+
+        let (classes, class_index) = LabeledRecords::build_classes(&[&training, &test, &synthetic]);
+        let training = LabeledRecords::new(&training, &class_index);
+        let test = LabeledRecords::new(&test, &class_index);
+        let synthetic = LabeledRecords::new(&synthetic, &class_index);
+        let majority_class = training
+            .calc_class_counts()
+            .iter()
+            .enumerate()
+            .max_by(|(ia, a), (ib, b)| a.cmp(b).then_with(|| ib.cmp(ia)))
+            .map(|(c, _)| c)
+            .unwrap_or(0);
+        let result = MlUtilityResult {
+            target: self.parameters.target.clone(),
+            features: columns[..columns.len() - 1]
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            number_of_training_records: training.len(),
+            number_of_synthetic_records: synthetic.len(),
+            number_of_test_records: test.len(),
+            number_of_classes: classes.len(),
+            majority_class_accuracy: (test.labels.iter().filter(|l| **l == majority_class).count()
+                as f64)
+                / (test.len() as f64),
+            classifiers: self
+                .parameters
+                .classifiers
+                .iter()
+                .map(|classifier| {
+                    self.evaluate_classifier(*classifier, &training, &synthetic, &test)
+                })
+                .collect(),
+        };
+
+        for c in result.classifiers.iter() {
+            info!(
+                "{:?} machine learning utility: accuracy gap = {:.4}, macro F1 gap = {:.4}",
+                c.classifier, c.accuracy_gap, c.macro_f1_gap
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the feature columns followed by the target column
+    fn select_columns(
+        &self,
+        data_blocks: &[&DataBlock],
+    ) -> Result<Vec<Arc<String>>, MlUtilityError> {
+        let common_columns = AlignedRecords::common_columns(data_blocks);
+        let find_column = |name: &String| {
+            common_columns
+                .iter()
+                .find(|c| c.as_str() == name)
+                .cloned()
+                .ok_or_else(|| {
+                    MlUtilityError::InvalidInput(format!(
+                        "column \"{name}\" is not present on the training, test and synthetic data"
+                    ))
+                })
+        };
+        let target = find_column(&self.parameters.target)?;
+        let mut columns = if self.parameters.features.is_empty() {
+            common_columns
+                .iter()
+                .filter(|c| **c != target)
+                .cloned()
+                .collect()
+        } else {
+            self.parameters
+                .features
+                .iter()
+                .map(find_column)
+                .collect::<Result<Vec<Arc<String>>, MlUtilityError>>()?
+        };
+
+        if columns.is_empty() {
+            return Err(MlUtilityError::InvalidInput(
+                "at least one feature column is needed".to_owned(),
+            ));
+        }
+        if columns.contains(&target) {
+            return Err(MlUtilityError::InvalidInput(
+                "the target column can not be a feature".to_owned(),
+            ));
+        }
+
+        columns.push(target);
+        Ok(columns)
+    }
+
+    fn evaluate_classifier(
+        &self,
+        classifier: MlUtilityClassifier,
+        training: &LabeledRecords,
+        synthetic: &LabeledRecords,
+        test: &LabeledRecords,
+    ) -> MlUtilityClassifierResult {
+        let trained_on_real = self.train_and_test(classifier, training, test);
+        let trained_on_synthetic = self.train_and_test(classifier, synthetic, test);
+
+        MlUtilityClassifierResult {
+            classifier,
+            accuracy_gap: trained_on_real.accuracy - trained_on_synthetic.accuracy,
+            macro_f1_gap: trained_on_real.macro_f1 - trained_on_synthetic.macro_f1,
+            auc_gap: trained_on_real
+                .auc
+                .zip(trained_on_synthetic.auc)
+                .map(|(real, synthetic)| real - synthetic),
+            trained_on_real,
+            trained_on_synthetic,
+        }
+    }
+
+    fn train_and_test(
+        &self,
+        classifier: MlUtilityClassifier,
+        training: &LabeledRecords,
+        test: &LabeledRecords,
+    ) -> MlUtilityMetrics {
+        let mut model: Box<dyn CategoricalClassifier> = match classifier {
+            MlUtilityClassifier::NaiveBayes => Box::<NaiveBayes>::default(),
+            MlUtilityClassifier::LogisticRegression => Box::new(LogisticRegression::new(
+                self.parameters.logistic_regression_iterations,
+            )),
+            MlUtilityClassifier::DecisionTree => {
+                Box::new(DecisionTree::new(self.parameters.max_tree_depth))
+            }
+        };
+
+        model.fit(training);
+
+        MlUtilityMetrics::from_predictions(
+            &test
+                .features
+                .iter()
+                .map(|f| model.predict_proba(f))
+                .collect::<Vec<Vec<f64>>>(),
+            &test.labels,
+            test.number_of_classes,
+        )
+    }
+}
@@ -0,0 +1,120 @@
+use crate::processing::evaluator::mann_whitney_auc::calc_mann_whitney_auc;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Classification metrics on the test records
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MlUtilityMetrics {
+    /// Proportion of test records where the most likely class is the actual one
+    pub accuracy: f64,
+    /// Unweighted mean of the F1 score of every class
+    /// occurring on the test records or on the predictions
+    pub macro_f1: f64,
+    /// Unweighted mean of the one-vs-rest area under the ROC curve of every class
+    /// (`None` if no class has both positive and negative test records)
+    pub auc: Option<f64>,
+}
+
+impl MlUtilityMetrics {
+    /// Computes the metrics from the predicted probabilities
+    /// # Arguments
+    /// * `probabilities` - Predicted probability of each class for every test record
+    /// * `labels` - Actual label of every test record
+    /// * `number_of_classes` - Number of possible classes
+    pub fn from_predictions(
+        probabilities: &[Vec<f64>],
+        labels: &[usize],
+        number_of_classes: usize,
+    ) -> MlUtilityMetrics {
+        let predictions: Vec<usize> = probabilities
+            .iter()
+            .map(|p| {
+                // ties are broken by the lowest class
+                p.iter()
+                    .enumerate()
+                    .max_by(|(ia, a), (ib, b)| {
+                        a.partial_cmp(b)
+                            .unwrap_or(Ordering::Equal)
+                            .then_with(|| ib.cmp(ia))
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap_or(0)
+            })
+            .collect();
+        let mut true_positives = vec![0; number_of_classes];
+        let mut false_positives = vec![0; number_of_classes];
+        let mut false_negatives = vec![0; number_of_classes];
+
+        for (predicted, actual) in predictions.iter().zip(labels.iter()) {
+            if predicted == actual {
+                true_positives[*actual] += 1;
+            } else {
+                false_positives[*predicted] += 1;
+                false_negatives[*actual] += 1;
+            }
+        }
+
+        let f1_scores: Vec<f64> = (0..number_of_classes)
+            .filter_map(|c| {
+                let denominator = 2 * true_positives[c] + false_positives[c] + false_negatives[c];
+
+                if denominator > 0 {
+                    Some((2 * true_positives[c]) as f64 / denominator as f64)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let auc_scores: Vec<f64> = (0..number_of_classes)
+            .filter_map(|c| Self::calc_one_vs_rest_auc(probabilities, labels, c))
+            .collect();
+
+        MlUtilityMetrics {
+            accuracy: Self::mean(
+                &predictions
+                    .iter()
+                    .zip(labels.iter())
+                    .map(|(p, l)| if p == l { 1.0 } else { 0.0 })
+                    .collect_vec(),
+            )
+            .unwrap_or(0.0),
+            macro_f1: Self::mean(&f1_scores).unwrap_or(0.0),
+            auc: Self::mean(&auc_scores),
+        }
+    }
+
+    /// AUC computed from the Mann-Whitney U statistic (ties count as half)
+    fn calc_one_vs_rest_auc(
+        probabilities: &[Vec<f64>],
+        labels: &[usize],
+        class: usize,
+    ) -> Option<f64> {
+        let (positives, negatives): (Vec<usize>, Vec<usize>) =
+            (0..labels.len()).partition(|i| labels[*i] == class);
+        if positives.is_empty() || negatives.is_empty() {
+            return None;
+        }
+
+        Some(calc_mann_whitney_auc(
+            &positives
+                .iter()
+                .map(|i| probabilities[*i][class])
+                .collect_vec(),
+            &negatives
+                .iter()
+                .map(|i| probabilities[*i][class])
+                .collect_vec(),
+        ))
+    }
+
+    #[inline]
+    fn mean(values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / (values.len() as f64))
+        }
+    }
+}
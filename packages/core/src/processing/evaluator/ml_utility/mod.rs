@@ -0,0 +1,17 @@
+mod categorical_classifier;
+mod decision_tree;
+mod labeled_records;
+mod logistic_regression;
+mod ml_utility_error;
+mod ml_utility_evaluator;
+mod ml_utility_metrics;
+mod naive_bayes;
+
+pub use categorical_classifier::*;
+pub use decision_tree::*;
+pub use labeled_records::*;
+pub use logistic_regression::*;
+pub use ml_utility_error::*;
+pub use ml_utility_evaluator::*;
+pub use ml_utility_metrics::*;
+pub use naive_bayes::*;
@@ -0,0 +1,57 @@
+use super::{categorical_classifier::softmax, CategoricalClassifier, LabeledRecords};
+use crate::processing::evaluator::AlignedRecord;
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+/// Categorical naive Bayes with Laplace smoothing
+#[derive(Debug, Clone, Default)]
+pub struct NaiveBayes {
+    class_counts: Vec<usize>,
+    /// For every feature, maps a value to its count by class
+    value_counts: Vec<FnvHashMap<Option<Arc<String>>, Vec<usize>>>,
+}
+
+impl CategoricalClassifier for NaiveBayes {
+    fn fit(&mut self, records: &LabeledRecords) {
+        let number_of_features = records.features.first().map(|f| f.len()).unwrap_or(0);
+
+        self.class_counts = records.calc_class_counts();
+        self.value_counts = vec![FnvHashMap::default(); number_of_features];
+
+        for (features, label) in records.features.iter().zip(records.labels.iter()) {
+            for (value_counts, value) in self.value_counts.iter_mut().zip(features.iter()) {
+                value_counts
+                    .entry(value.clone())
+                    .or_insert_with(|| vec![0; records.number_of_classes])[*label] += 1;
+            }
+        }
+    }
+
+    fn predict_proba(&self, features: &AlignedRecord) -> Vec<f64> {
+        let number_of_classes = self.class_counts.len() as f64;
+        let total = self.class_counts.iter().sum::<usize>() as f64;
+        let log_probabilities: Vec<f64> = self
+            .class_counts
+            .iter()
+            .enumerate()
+            .map(|(class, class_count)| {
+                let class_count = *class_count as f64;
+
+                ((class_count + 1.0) / (total + number_of_classes)).ln()
+                    + self
+                        .value_counts
+                        .iter()
+                        .zip(features.iter())
+                        .map(|(value_counts, value)| {
+                            // one extra value accounts for the values not seen on training
+                            let count = value_counts.get(value).map_or(0, |c| c[class]) as f64;
+
+                            ((count + 1.0) / (class_count + (value_counts.len() + 1) as f64)).ln()
+                        })
+                        .sum::<f64>()
+            })
+            .collect();
+
+        softmax(&log_probabilities)
+    }
+}
@@ -1,19 +1,50 @@
+mod aligned_records;
+mod attribute_inference;
+mod closest_record_distance;
+mod confidence_interval;
 mod data_evaluator;
+mod evaluation_uncertainty;
+mod evaluation_uncertainty_error;
+mod mann_whitney_auc;
+mod marginal_distances;
+mod membership_inference;
+mod ml_utility;
+mod pairwise_association;
 mod preservation_bucket;
 mod preservation_by_count;
 mod preservation_by_length;
+mod privacy_attack_error;
+mod privacy_model_error;
+mod privacy_models;
 mod rare_combinations_comparison_data;
+mod synthetic_data_comparison;
 mod typedefs;
+mod utility_breakdown;
 
 #[cfg(feature = "pyo3")]
 mod register_pyo3;
 
+pub use aligned_records::*;
+pub use attribute_inference::*;
+pub use closest_record_distance::*;
+pub use confidence_interval::*;
 pub use data_evaluator::*;
+pub use evaluation_uncertainty::*;
+pub use evaluation_uncertainty_error::*;
+pub use marginal_distances::*;
+pub use membership_inference::*;
+pub use ml_utility::*;
+pub use pairwise_association::*;
 pub use preservation_bucket::*;
 pub use preservation_by_count::*;
 pub use preservation_by_length::*;
+pub use privacy_attack_error::*;
+pub use privacy_model_error::*;
+pub use privacy_models::*;
 pub use rare_combinations_comparison_data::*;
+pub use synthetic_data_comparison::*;
 pub use typedefs::*;
+pub use utility_breakdown::*;
 
 #[cfg(feature = "pyo3")]
 pub use register_pyo3::*;
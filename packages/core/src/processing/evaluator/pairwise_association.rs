@@ -0,0 +1,274 @@
+use super::{AlignedRecord, AlignedRecords};
+use crate::data_block::DataBlock;
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Square matrix indexed by the position of the columns
+/// (`matrix[i][j]` is the association between columns `i` and `j`,
+/// the diagonal is `1`)
+pub type AssociationMatrix = Vec<Vec<f64>>;
+
+type CategoryCounts<'records> = FnvHashMap<&'records Option<Arc<String>>, usize>;
+
+type JointCategoryCounts<'records> =
+    FnvHashMap<(&'records Option<Arc<String>>, &'records Option<Arc<String>>), usize>;
+
+/// Association between every pair of columns on the sensitive
+/// and synthetic data, measured with Cramér's V and normalized mutual information.
+/// Empty values are a category of their own, so each attribute column of
+/// a multi-value column is a present/absent variable
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairwiseAssociation {
+    /// Column names indexing the matrices
+    pub columns: Vec<String>,
+    /// Cramér's V between every pair of columns on the sensitive data
+    pub sensitive_cramers_v: AssociationMatrix,
+    /// Cramér's V between every pair of columns on the synthetic data
+    pub synthetic_cramers_v: AssociationMatrix,
+    /// `synthetic_cramers_v - sensitive_cramers_v`
+    pub cramers_v_difference: AssociationMatrix,
+    /// Normalized mutual information between every pair
+    /// of columns on the sensitive data
+    pub sensitive_normalized_mutual_information: AssociationMatrix,
+    /// Normalized mutual information between every pair
+    /// of columns on the synthetic data
+    pub synthetic_normalized_mutual_information: AssociationMatrix,
+    /// `synthetic_normalized_mutual_information - sensitive_normalized_mutual_information`
+    pub normalized_mutual_information_difference: AssociationMatrix,
+    /// Mean absolute Cramér's V difference over all column pairs
+    /// (`0` means the associations are fully preserved)
+    pub cramers_v_mean_abs_difference: f64,
+    /// Mean absolute normalized mutual information difference over all column pairs
+    /// (`0` means the associations are fully preserved)
+    pub normalized_mutual_information_mean_abs_difference: f64,
+}
+
+impl PairwiseAssociation {
+    /// Computes the association matrices for the sensitive and synthetic data.
+    /// Columns are the ones both data blocks have in common, plus the attribute
+    /// columns of multi-value columns present on either of them
+    /// (an attribute never present on a data block is always empty there)
+    /// # Arguments
+    /// * `sensitive_data_block` - Sensitive data
+    /// * `synthetic_data_block` - Synthesized data
+    pub fn from_sensitive_and_synthetic_data_blocks(
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> PairwiseAssociation {
+        let columns = Self::association_columns(sensitive_data_block, synthetic_data_block);
+        let sensitive = AlignedRecords::new(sensitive_data_block, &columns);
+        let synthetic = AlignedRecords::new(synthetic_data_block, &columns);
+        let (sensitive_cramers_v, sensitive_normalized_mutual_information) =
+            Self::calc_association_matrices(&sensitive);
+        let (synthetic_cramers_v, synthetic_normalized_mutual_information) =
+            Self::calc_association_matrices(&synthetic);
+        let cramers_v_difference = Self::difference(&synthetic_cramers_v, &sensitive_cramers_v);
+        let normalized_mutual_information_difference = Self::difference(
+            &synthetic_normalized_mutual_information,
+            &sensitive_normalized_mutual_information,
+        );
+        let result = PairwiseAssociation {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            cramers_v_mean_abs_difference: Self::mean_abs_off_diagonal(&cramers_v_difference),
+            normalized_mutual_information_mean_abs_difference: Self::mean_abs_off_diagonal(
+                &normalized_mutual_information_difference,
+            ),
+            sensitive_cramers_v,
+            synthetic_cramers_v,
+            cramers_v_difference,
+            sensitive_normalized_mutual_information,
+            synthetic_normalized_mutual_information,
+            normalized_mutual_information_difference,
+        };
+
+        info!(
+            "pairwise association: Cramér's V mean abs difference = {:.4}, normalized mutual information mean abs difference = {:.4}",
+            result.cramers_v_mean_abs_difference,
+            result.normalized_mutual_information_mean_abs_difference
+        );
+
+        result
+    }
+
+    fn association_columns(
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> Vec<Arc<String>> {
+        let mut columns =
+            AlignedRecords::common_columns(&[sensitive_data_block, synthetic_data_block]);
+
+        for data_block in [sensitive_data_block, synthetic_data_block] {
+            for h in data_block.headers.iter() {
+                if data_block.multi_value_column_metadata_map.contains_key(h)
+                    && !columns.contains(h)
+                {
+                    columns.push(h.clone());
+                }
+            }
+        }
+        columns
+    }
+
+    fn calc_association_matrices(
+        records: &AlignedRecords,
+    ) -> (AssociationMatrix, AssociationMatrix) {
+        let n_columns = records.columns.len();
+        let mut cramers_v = vec![vec![1.0; n_columns]; n_columns];
+        let mut normalized_mutual_information = vec![vec![1.0; n_columns]; n_columns];
+
+        for (i, j) in (0..n_columns).tuple_combinations() {
+            let (v, nmi) = Self::calc_association(&records.records, i, j);
+
+            cramers_v[i][j] = v;
+            cramers_v[j][i] = v;
+            normalized_mutual_information[i][j] = nmi;
+            normalized_mutual_information[j][i] = nmi;
+        }
+        (cramers_v, normalized_mutual_information)
+    }
+
+    /// Returns Cramér's V and the normalized mutual information
+    /// (`2 * I(X; Y) / (H(X) + H(Y))`) between columns `i` and `j`,
+    /// both are `0` if any of the columns has a single category
+    fn calc_association(records: &[AlignedRecord], i: usize, j: usize) -> (f64, f64) {
+        let mut joint_counts = JointCategoryCounts::default();
+        let mut i_counts = CategoryCounts::default();
+        let mut j_counts = CategoryCounts::default();
+
+        for r in records.iter() {
+            *joint_counts.entry((&r[i], &r[j])).or_insert(0) += 1;
+            *i_counts.entry(&r[i]).or_insert(0) += 1;
+            *j_counts.entry(&r[j]).or_insert(0) += 1;
+        }
+
+        if i_counts.len() < 2 || j_counts.len() < 2 {
+            return (0.0, 0.0);
+        }
+
+        let total = records.len() as f64;
+        // only non-zero cells are visited, as
+        // `chi_square = sum(observed^2 / expected) - total`
+        let mut squared_observed_ratio_sum = 0.0;
+        let mut mutual_information = 0.0;
+
+        for ((vi, vj), observed) in joint_counts.iter() {
+            let observed = *observed as f64;
+            let expected = (i_counts[vi] as f64) * (j_counts[vj] as f64) / total;
+
+            squared_observed_ratio_sum += observed * observed / expected;
+            mutual_information += (observed / total) * (observed / expected).ln();
+        }
+
+        let chi_square = (squared_observed_ratio_sum - total).max(0.0);
+        let min_dim = (i_counts.len().min(j_counts.len()) - 1) as f64;
+        let entropy_sum = Self::entropy(&i_counts, total) + Self::entropy(&j_counts, total);
+
+        (
+            // clamp to account for floating point errors
+            (chi_square / (total * min_dim)).sqrt().min(1.0),
+            (2.0 * mutual_information / entropy_sum).clamp(0.0, 1.0),
+        )
+    }
+
+    #[inline]
+    fn entropy(counts: &CategoryCounts, total: f64) -> f64 {
+        counts
+            .values()
+            .map(|c| {
+                let p = (*c as f64) / total;
+                -p * p.ln()
+            })
+            .sum()
+    }
+
+    #[inline]
+    fn difference(a: &AssociationMatrix, b: &AssociationMatrix) -> AssociationMatrix {
+        a.iter()
+            .zip(b.iter())
+            .map(|(row_a, row_b)| row_a.iter().zip(row_b.iter()).map(|(a, b)| a - b).collect())
+            .collect()
+    }
+
+    #[inline]
+    fn mean_abs_off_diagonal(matrix: &AssociationMatrix) -> f64 {
+        let n_pairs = matrix.len() * matrix.len().saturating_sub(1) / 2;
+
+        if n_pairs > 0 {
+            (0..matrix.len())
+                .tuple_combinations()
+                .map(|(i, j)| matrix[i][j].abs())
+                .sum::<f64>()
+                / (n_pairs as f64)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl PairwiseAssociation {
+    #[getter]
+    /// Column names indexing the matrices
+    fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    #[getter]
+    /// Cramér's V between every pair of columns on the sensitive data
+    fn sensitive_cramers_v(&self) -> AssociationMatrix {
+        self.sensitive_cramers_v.clone()
+    }
+
+    #[getter]
+    /// Cramér's V between every pair of columns on the synthetic data
+    fn synthetic_cramers_v(&self) -> AssociationMatrix {
+        self.synthetic_cramers_v.clone()
+    }
+
+    #[getter]
+    /// `synthetic_cramers_v - sensitive_cramers_v`
+    fn cramers_v_difference(&self) -> AssociationMatrix {
+        self.cramers_v_difference.clone()
+    }
+
+    #[getter]
+    /// Normalized mutual information between every pair
+    /// of columns on the sensitive data
+    fn sensitive_normalized_mutual_information(&self) -> AssociationMatrix {
+        self.sensitive_normalized_mutual_information.clone()
+    }
+
+    #[getter]
+    /// Normalized mutual information between every pair
+    /// of columns on the synthetic data
+    fn synthetic_normalized_mutual_information(&self) -> AssociationMatrix {
+        self.synthetic_normalized_mutual_information.clone()
+    }
+
+    #[getter]
+    /// `synthetic_normalized_mutual_information - sensitive_normalized_mutual_information`
+    fn normalized_mutual_information_difference(&self) -> AssociationMatrix {
+        self.normalized_mutual_information_difference.clone()
+    }
+
+    #[getter]
+    /// Mean absolute Cramér's V difference over all column pairs
+    fn cramers_v_mean_abs_difference(&self) -> f64 {
+        self.cramers_v_mean_abs_difference
+    }
+
+    #[getter]
+    /// Mean absolute normalized mutual information difference over all column pairs
+    fn normalized_mutual_information_mean_abs_difference(&self) -> f64 {
+        self.normalized_mutual_information_mean_abs_difference
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when running a privacy attack against the synthetic data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivacyAttackError {
+    /// Generated when the attack inputs are not valid
+    InvalidInput(String),
+}
+
+impl Display for PrivacyAttackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PrivacyAttackError::InvalidInput(err) => {
+                write!(f, "invalid privacy attack input: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<PrivacyAttackError> for PyErr {
+    fn from(err: PrivacyAttackError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
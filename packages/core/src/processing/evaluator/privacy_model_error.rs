@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when assessing formal privacy models on a data block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivacyModelError {
+    /// Generated when the assessment inputs are not valid
+    InvalidInput(String),
+}
+
+impl Display for PrivacyModelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PrivacyModelError::InvalidInput(err) => {
+                write!(f, "invalid privacy model input: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<PrivacyModelError> for PyErr {
+    fn from(err: PrivacyModelError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,470 @@
+use super::{AlignedRecords, PrivacyModelError};
+use crate::{
+    data_block::{DataBlock, COLUMN_VALUE_DELIMITER},
+    processing::aggregator::COMBINATIONS_DELIMITER,
+};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Error, Write},
+    sync::Arc,
+};
+
+/// Sensitive values and how many times they occur
+type SensitiveValueCounts<'records> = FnvHashMap<&'records Option<Arc<String>>, usize>;
+
+/// Size and sensitive value counts (one per sensitive column)
+/// of each equivalence class, keyed by the quasi-identifier values
+type EquivalenceClasses<'records> =
+    FnvHashMap<&'records [Option<Arc<String>>], (usize, Vec<SensitiveValueCounts<'records>>)>;
+
+/// How the diversity of the sensitive values within an equivalence class is measured
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LDiversityMeasure {
+    /// Number of distinct sensitive values
+    Distinct,
+    /// Exponential of the entropy of the sensitive values (`exp(H)`),
+    /// so it is comparable to the number of distinct values
+    Entropy,
+}
+
+/// Parameters used to assess k-anonymity, l-diversity and t-closeness
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyModelParameters {
+    /// Columns forming the equivalence classes
+    pub quasi_identifiers: Vec<String>,
+    /// Columns whose values should not be disclosed
+    /// by the equivalence classes
+    pub sensitive_columns: Vec<String>,
+    /// Equivalence classes smaller than this violate k-anonymity
+    pub k: usize,
+    /// Equivalence classes less diverse than this violate l-diversity
+    pub l: f64,
+    /// How the l-diversity is measured
+    pub l_diversity_measure: LDiversityMeasure,
+    /// Equivalence classes whose sensitive distribution is farther than this from
+    /// the overall distribution violate t-closeness
+    pub t: f64,
+}
+
+impl PrivacyModelParameters {
+    /// Returns a new PrivacyModelParameters
+    /// # Arguments
+    /// * `quasi_identifiers` - Columns forming the equivalence classes
+    /// * `sensitive_columns` - Columns whose values should not be disclosed
+    /// * `k` - Equivalence classes smaller than this violate k-anonymity
+    /// * `l` - Equivalence classes less diverse than this violate l-diversity
+    /// * `l_diversity_measure` - How the l-diversity is measured
+    /// * `t` - Equivalence classes farther than this from the overall
+    /// distribution violate t-closeness
+    #[inline]
+    pub fn new(
+        quasi_identifiers: Vec<String>,
+        sensitive_columns: Vec<String>,
+        k: usize,
+        l: f64,
+        l_diversity_measure: LDiversityMeasure,
+        t: f64,
+    ) -> PrivacyModelParameters {
+        PrivacyModelParameters {
+            quasi_identifiers,
+            sensitive_columns,
+            k,
+            l,
+            l_diversity_measure,
+            t,
+        }
+    }
+}
+
+/// L-diversity and t-closeness of a single sensitive column
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveColumnPrivacy {
+    /// Sensitive column name
+    pub column: String,
+    /// Smallest diversity over all the equivalence classes
+    pub l_diversity: f64,
+    /// Largest earth mover's distance between the distribution of an
+    /// equivalence class and the overall distribution, from `0` to `1`
+    pub t_closeness: f64,
+    /// Number of equivalence classes less diverse than `l`
+    pub number_of_l_diversity_violations: usize,
+    /// Number of equivalence classes farther than `t` from the overall distribution
+    pub number_of_t_closeness_violations: usize,
+}
+
+/// Equivalence class violating at least one of the privacy models
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OffendingEquivalenceClass {
+    /// Quasi-identifier values of the class formatted as string
+    pub quasi_identifiers: String,
+    /// Number of records in the class
+    pub size: usize,
+    /// Diversity of each sensitive column
+    /// (same order as `PrivacyModelParameters::sensitive_columns`)
+    pub l_diversity: Vec<f64>,
+    /// Distance to the overall distribution of each sensitive column
+    /// (same order as `PrivacyModelParameters::sensitive_columns`)
+    pub t_closeness: Vec<f64>,
+    /// Whether the class is smaller than `k`
+    pub violates_k_anonymity: bool,
+    /// Sensitive columns less diverse than `l` within the class
+    pub l_diversity_violations: Vec<String>,
+    /// Sensitive columns farther than `t` from the overall distribution within the class
+    pub t_closeness_violations: Vec<String>,
+}
+
+/// K-anonymity, l-diversity and t-closeness of a data block
+/// for the chosen quasi-identifiers and sensitive columns
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyModelAssessment {
+    /// Parameters used for the assessment
+    pub parameters: PrivacyModelParameters,
+    /// Number of records on the data block
+    pub number_of_records: usize,
+    /// Number of distinct quasi-identifier combinations
+    pub number_of_equivalence_classes: usize,
+    /// Size of the smallest equivalence class (`0` if there are no records)
+    pub k_anonymity: usize,
+    /// Number of equivalence classes smaller than `k`
+    pub number_of_k_anonymity_violations: usize,
+    /// Number of records in equivalence classes smaller than `k`
+    pub number_of_records_violating_k_anonymity: usize,
+    /// L-diversity and t-closeness of each sensitive column
+    pub sensitive_columns: Vec<SensitiveColumnPrivacy>,
+    /// Equivalence classes violating any of the models,
+    /// from the smallest to the largest
+    pub offending_classes: Vec<OffendingEquivalenceClass>,
+}
+
+impl PrivacyModelAssessment {
+    /// Writes the offending equivalence classes to the file system in a csv/tsv like format
+    /// # Arguments:
+    /// * `offending_classes_path` - File path to be written
+    /// * `offending_classes_delimiter` - Delimiter to use when writing to `offending_classes_path`
+    pub fn write_offending_classes(
+        &self,
+        offending_classes_path: &str,
+        offending_classes_delimiter: char,
+    ) -> Result<(), Error> {
+        let delimiter = offending_classes_delimiter.to_string();
+        let mut file = std::io::BufWriter::new(std::fs::File::create(offending_classes_path)?);
+
+        file.write_all(
+            ["quasi_identifiers", "size", "violates_k_anonymity"]
+                .iter()
+                .map(|h| h.to_string())
+                .chain(
+                    self.parameters
+                        .sensitive_columns
+                        .iter()
+                        .flat_map(|c| [format!("l_diversity_{c}"), format!("t_closeness_{c}")]),
+                )
+                .join(&delimiter)
+                .as_bytes(),
+        )?;
+        file.write_all("\n".as_bytes())?;
+        for class in self.offending_classes.iter() {
+            file.write_all(
+                [
+                    class.quasi_identifiers.clone(),
+                    class.size.to_string(),
+                    class.violates_k_anonymity.to_string(),
+                ]
+                .into_iter()
+                .chain(
+                    class
+                        .l_diversity
+                        .iter()
+                        .zip(class.t_closeness.iter())
+                        .flat_map(|(l, t)| [format!("{l:.6}"), format!("{t:.6}")]),
+                )
+                .join(&delimiter)
+                .as_bytes(),
+            )?;
+            file.write_all("\n".as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Assesses k-anonymity, l-diversity and t-closeness on sensitive or synthetic data.
+/// Equivalence classes are the records sharing the same quasi-identifier values
+/// (empty values are a value of their own), so they are computed from the records
+/// rather than from the aggregates, which skip empty values
+/// and are limited to the reporting length
+pub struct PrivacyModelAssessor {
+    parameters: PrivacyModelParameters,
+}
+
+impl PrivacyModelAssessor {
+    /// Returns a new PrivacyModelAssessor
+    /// # Arguments
+    /// * `parameters` - Parameters used for the assessment
+    #[inline]
+    pub fn new(parameters: PrivacyModelParameters) -> PrivacyModelAssessor {
+        PrivacyModelAssessor { parameters }
+    }
+
+    /// Groups the records into equivalence classes and assesses every privacy model.
+    /// T-closeness uses the earth mover's distance with equal ground distance between
+    /// categories (i.e. the total variation distance)
+    /// # Arguments
+    /// * `data_block` - Data to assess (sensitive or synthetic)
+    pub fn assess(
+        &self,
+        data_block: &DataBlock,
+    ) -> Result<PrivacyModelAssessment, PrivacyModelError> {
+        let columns = self.validate_and_get_columns(data_block)?;
+        let n_quasi_identifiers = self.parameters.quasi_identifiers.len();
+        let n_sensitive_columns = self.parameters.sensitive_columns.len();
+        let aligned = AlignedRecords::new(data_block, &columns);
+        let mut classes = EquivalenceClasses::default();
+        let mut overall_counts: Vec<SensitiveValueCounts> =
+            vec![SensitiveValueCounts::default(); n_sensitive_columns];
+
+        for record in aligned.records.iter() {
+            let (quasi_identifiers, sensitive_values) = record.split_at(n_quasi_identifiers);
+            let (size, counts) = classes.entry(quasi_identifiers).or_insert_with(|| {
+                (
+                    0,
+                    vec![SensitiveValueCounts::default(); n_sensitive_columns],
+                )
+            });
+
+            *size += 1;
+            for (i, value) in sensitive_values.iter().enumerate() {
+                *counts[i].entry(value).or_insert(0) += 1;
+                *overall_counts[i].entry(value).or_insert(0) += 1;
+            }
+        }
+
+        let number_of_records = aligned.len();
+        let mut sensitive_columns: Vec<SensitiveColumnPrivacy> = self
+            .parameters
+            .sensitive_columns
+            .iter()
+            .map(|column| SensitiveColumnPrivacy {
+                column: column.clone(),
+                l_diversity: if classes.is_empty() {
+                    0.0
+                } else {
+                    f64::INFINITY
+                },
+                t_closeness: 0.0,
+                number_of_l_diversity_violations: 0,
+                number_of_t_closeness_violations: 0,
+            })
+            .collect();
+        let mut k_anonymity = usize::MAX;
+        let mut number_of_k_anonymity_violations = 0;
+        let mut number_of_records_violating_k_anonymity = 0;
+        let mut offending_classes = Vec::default();
+
+        for (quasi_identifiers, (size, counts)) in classes.iter() {
+            let violates_k_anonymity = *size < self.parameters.k;
+            let l_diversity: Vec<f64> = counts
+                .iter()
+                .map(|c| self.calc_l_diversity(c, *size))
+                .collect();
+            let t_closeness: Vec<f64> = counts
+                .iter()
+                .zip(overall_counts.iter())
+                .map(|(c, overall)| Self::calc_t_closeness(c, *size, overall, number_of_records))
+                .collect();
+            let mut l_diversity_violations = Vec::default();
+            let mut t_closeness_violations = Vec::default();
+
+            k_anonymity = k_anonymity.min(*size);
+            if violates_k_anonymity {
+                number_of_k_anonymity_violations += 1;
+                number_of_records_violating_k_anonymity += size;
+            }
+            for (i, column) in sensitive_columns.iter_mut().enumerate() {
+                column.l_diversity = column.l_diversity.min(l_diversity[i]);
+                column.t_closeness = column.t_closeness.max(t_closeness[i]);
+
+                if l_diversity[i] < self.parameters.l {
+                    column.number_of_l_diversity_violations += 1;
+                    l_diversity_violations.push(column.column.clone());
+                }
+                if t_closeness[i] > self.parameters.t {
+                    column.number_of_t_closeness_violations += 1;
+                    t_closeness_violations.push(column.column.clone());
+                }
+            }
+
+            if violates_k_anonymity
+                || !l_diversity_violations.is_empty()
+                || !t_closeness_violations.is_empty()
+            {
+                offending_classes.push(OffendingEquivalenceClass {
+                    quasi_identifiers: Self::format_quasi_identifiers(
+                        &columns[..n_quasi_identifiers],
+                        quasi_identifiers,
+                    ),
+                    size: *size,
+                    l_diversity,
+                    t_closeness,
+                    violates_k_anonymity,
+                    l_diversity_violations,
+                    t_closeness_violations,
+                });
+            }
+        }
+
+        offending_classes.sort_by(|a, b| {
+            a.size
+                .cmp(&b.size)
+                .then_with(|| a.quasi_identifiers.cmp(&b.quasi_identifiers))
+        });
+
+        let assessment = PrivacyModelAssessment {
+            parameters: self.parameters.clone(),
+            number_of_records,
+            number_of_equivalence_classes: classes.len(),
+            k_anonymity: if classes.is_empty() { 0 } else { k_anonymity },
+            number_of_k_anonymity_violations,
+            number_of_records_violating_k_anonymity,
+            sensitive_columns,
+            offending_classes,
+        };
+
+        info!(
+            "privacy models: k = {}, {} out of {} equivalence classes violate at least one model",
+            assessment.k_anonymity,
+            assessment.offending_classes.len(),
+            assessment.number_of_equivalence_classes
+        );
+
+        Ok(assessment)
+    }
+
+    /// Returns the quasi-identifier columns followed by the sensitive columns
+    fn validate_and_get_columns(
+        &self,
+        data_block: &DataBlock,
+    ) -> Result<Vec<Arc<String>>, PrivacyModelError> {
+        if self.parameters.quasi_identifiers.is_empty() {
+            return Err(PrivacyModelError::InvalidInput(
+                "at least one quasi-identifier must be provided".to_owned(),
+            ));
+        }
+        if self.parameters.k < 1 {
+            return Err(PrivacyModelError::InvalidInput(
+                "k must be at least 1".to_owned(),
+            ));
+        }
+        if self.parameters.l < 1.0 {
+            return Err(PrivacyModelError::InvalidInput(
+                "l must be at least 1".to_owned(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.parameters.t) {
+            return Err(PrivacyModelError::InvalidInput(
+                "t must be between 0 and 1".to_owned(),
+            ));
+        }
+        if let Some(column) = self
+            .parameters
+            .sensitive_columns
+            .iter()
+            .find(|c| self.parameters.quasi_identifiers.contains(c))
+        {
+            return Err(PrivacyModelError::InvalidInput(format!(
+                "sensitive column \"{column}\" can not be a quasi-identifier"
+            )));
+        }
+        if !self
+            .parameters
+            .quasi_identifiers
+            .iter()
+            .chain(self.parameters.sensitive_columns.iter())
+            .all_unique()
+        {
+            return Err(PrivacyModelError::InvalidInput(
+                "quasi-identifiers and sensitive columns must not be repeated".to_owned(),
+            ));
+        }
+
+        self.parameters
+            .quasi_identifiers
+            .iter()
+            .chain(self.parameters.sensitive_columns.iter())
+            .map(|name| {
+                data_block
+                    .headers
+                    .iter()
+                    .find(|h| ***h == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        PrivacyModelError::InvalidInput(format!(
+                            "column \"{name}\" not found on the data"
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn calc_l_diversity(&self, counts: &SensitiveValueCounts, size: usize) -> f64 {
+        match self.parameters.l_diversity_measure {
+            LDiversityMeasure::Distinct => counts.len() as f64,
+            LDiversityMeasure::Entropy => counts
+                .values()
+                .map(|c| {
+                    let p = (*c as f64) / (size as f64);
+                    -p * p.ln()
+                })
+                .sum::<f64>()
+                .exp(),
+        }
+    }
+
+    /// Earth mover's distance with equal ground distance between categories,
+    /// which is half the L1 distance between the two distributions
+    #[inline]
+    fn calc_t_closeness(
+        counts: &SensitiveValueCounts,
+        size: usize,
+        overall_counts: &SensitiveValueCounts,
+        number_of_records: usize,
+    ) -> f64 {
+        overall_counts
+            .iter()
+            .map(|(value, overall_count)| {
+                let p = (counts.get(value).cloned().unwrap_or(0) as f64) / (size as f64);
+                let q = (*overall_count as f64) / (number_of_records as f64);
+
+                (p - q).abs()
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    #[inline]
+    fn format_quasi_identifiers(
+        columns: &[Arc<String>],
+        quasi_identifiers: &[Option<Arc<String>>],
+    ) -> String {
+        columns
+            .iter()
+            .zip(quasi_identifiers.iter())
+            .map(|(c, v)| {
+                format!(
+                    "{}{}{}",
+                    c,
+                    COLUMN_VALUE_DELIMITER,
+                    v.as_ref().map(|v| v.as_str()).unwrap_or_default()
+                )
+            })
+            .join(&COMBINATIONS_DELIMITER.to_string())
+    }
+}
@@ -1,7 +1,15 @@
-use super::Evaluator;
+use super::{
+    Evaluator, MarginalDistance, MarginalDistances, PairwiseAssociation, UtilityBreakdown,
+    UtilityBreakdownEntry,
+};
 use pyo3::{types::PyModule, PyResult, Python};
 
 pub fn register_pyo3(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Evaluator>()?;
+    m.add_class::<MarginalDistance>()?;
+    m.add_class::<MarginalDistances>()?;
+    m.add_class::<PairwiseAssociation>()?;
+    m.add_class::<UtilityBreakdown>()?;
+    m.add_class::<UtilityBreakdownEntry>()?;
     Ok(())
 }
@@ -0,0 +1,225 @@
+use super::data_evaluator::Evaluator;
+use super::marginal_distances::{MarginalDistance, MarginalDistances};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::processing::aggregator::AggregatedData;
+
+/// Metric compared across the synthetic data sets
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonMetric {
+    /// Record expansion percentage, the closer to `0` the better
+    RecordExpansionPercentage,
+    /// Total number of sensitive rare combinations that appear
+    /// as rare on the synthetic data, the lower the better
+    LeakageCount,
+    /// Percentage of sensitive combinations missing from the
+    /// synthetic data, the lower the better
+    PercentageOfSuppressedCombinations,
+    /// Percentage of synthetic combinations missing from the
+    /// sensitive data, the lower the better
+    PercentageOfFabricatedCombinations,
+    /// Mean absolute error of the combination counts, the lower the better
+    CombinationsCountMeanAbsError,
+    /// Mean total variation distance over all the marginals, the lower the better
+    MeanTotalVariationDistance,
+    /// Mean Jensen-Shannon divergence over all the marginals, the lower the better
+    MeanJensenShannonDivergence,
+}
+
+impl ComparisonMetric {
+    /// All the metrics, in the order they are reported
+    pub fn all() -> Vec<ComparisonMetric> {
+        vec![
+            ComparisonMetric::RecordExpansionPercentage,
+            ComparisonMetric::LeakageCount,
+            ComparisonMetric::PercentageOfSuppressedCombinations,
+            ComparisonMetric::PercentageOfFabricatedCombinations,
+            ComparisonMetric::CombinationsCountMeanAbsError,
+            ComparisonMetric::MeanTotalVariationDistance,
+            ComparisonMetric::MeanJensenShannonDivergence,
+        ]
+    }
+
+    /// Maps the metric value to a loss, where the lower
+    /// the loss the better the synthetic data set
+    #[inline]
+    fn loss(&self, value: f64) -> f64 {
+        match self {
+            ComparisonMetric::RecordExpansionPercentage => value.abs(),
+            _ => value,
+        }
+    }
+}
+
+/// Values of a single metric for every compared synthetic data set
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonMetricValues {
+    /// Compared metric
+    pub metric: ComparisonMetric,
+    /// Metric value for each synthetic data set
+    /// (same order as `SyntheticDataComparison::names`)
+    pub values: Vec<f64>,
+    /// Names of the synthetic data sets with the best value
+    /// (more than one on ties)
+    pub winners: Vec<String>,
+}
+
+/// Side-by-side comparison of several synthetic data sets
+/// evaluated against the same sensitive aggregates
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntheticDataComparison {
+    /// Names of the compared synthetic data sets
+    pub names: Vec<String>,
+    /// Reporting resolution used to compute the leakage
+    pub resolution: usize,
+    /// Values and winners of every metric
+    pub metrics: Vec<ComparisonMetricValues>,
+    /// Number of metrics won by each synthetic data set
+    /// (same order as `names`)
+    pub number_of_wins: Vec<usize>,
+}
+
+impl SyntheticDataComparison {
+    /// Evaluates every synthetic data set against the same sensitive aggregates
+    /// and reports, for each metric, which of them performed best
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Name and calculated aggregated data
+    /// of each synthetic data set
+    /// * `resolution` - Reporting resolution used for data synthesis
+    pub fn from_sensitive_and_synthetic_aggregated_data(
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &[(&str, &AggregatedData)],
+        resolution: usize,
+    ) -> SyntheticDataComparison {
+        let evaluator = Evaluator::default();
+        let names: Vec<String> = synthetic_aggregated_data
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let values_by_data_set: Vec<Vec<f64>> = synthetic_aggregated_data
+            .iter()
+            .map(|(_, synthetic)| {
+                Self::calc_metric_values(
+                    &evaluator,
+                    sensitive_aggregated_data,
+                    synthetic,
+                    &MarginalDistances::from_sensitive_and_synthetic_aggregated_data(
+                        sensitive_aggregated_data,
+                        synthetic,
+                    ),
+                    resolution,
+                )
+            })
+            .collect();
+        let mut number_of_wins = vec![0; names.len()];
+        let metrics = ComparisonMetric::all()
+            .into_iter()
+            .enumerate()
+            .map(|(metric_index, metric)| {
+                let values: Vec<f64> = values_by_data_set
+                    .iter()
+                    .map(|values| values[metric_index])
+                    .collect();
+                let best_loss = values
+                    .iter()
+                    .map(|v| metric.loss(*v))
+                    .fold(f64::INFINITY, f64::min);
+                let winners = values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| metric.loss(**v) == best_loss)
+                    .map(|(i, _)| {
+                        number_of_wins[i] += 1;
+                        names[i].clone()
+                    })
+                    .collect();
+
+                ComparisonMetricValues {
+                    metric,
+                    values,
+                    winners,
+                }
+            })
+            .collect();
+
+        info!(
+            "compared {} synthetic data sets, number of wins: {:?}",
+            names.len(),
+            number_of_wins
+        );
+
+        SyntheticDataComparison {
+            names,
+            resolution,
+            metrics,
+            number_of_wins,
+        }
+    }
+
+    /// Values for each metric, in the same order as `ComparisonMetric::all()`
+    pub(super) fn calc_metric_values(
+        evaluator: &Evaluator,
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+        marginal_distances: &MarginalDistances,
+        resolution: usize,
+    ) -> Vec<f64> {
+        ComparisonMetric::all()
+            .iter()
+            .map(|metric| match metric {
+                ComparisonMetric::RecordExpansionPercentage => evaluator
+                    .calc_record_expansion_percentage(
+                        sensitive_aggregated_data,
+                        synthetic_aggregated_data,
+                    ),
+                ComparisonMetric::LeakageCount => evaluator
+                    .calc_leakage_count_by_len(
+                        sensitive_aggregated_data,
+                        synthetic_aggregated_data,
+                        resolution,
+                    )
+                    .values()
+                    .sum::<usize>() as f64,
+                ComparisonMetric::PercentageOfSuppressedCombinations => evaluator
+                    .calc_percentage_of_suppressed_combinations(
+                        sensitive_aggregated_data,
+                        synthetic_aggregated_data,
+                    ),
+                ComparisonMetric::PercentageOfFabricatedCombinations => evaluator
+                    .calc_percentage_of_fabricated_combinations(
+                        sensitive_aggregated_data,
+                        synthetic_aggregated_data,
+                    ),
+                ComparisonMetric::CombinationsCountMeanAbsError => evaluator
+                    .calc_combinations_count_mean_abs_error(
+                        sensitive_aggregated_data,
+                        synthetic_aggregated_data,
+                    ),
+                ComparisonMetric::MeanTotalVariationDistance => {
+                    Self::mean(marginal_distances, |m| m.total_variation_distance)
+                }
+                ComparisonMetric::MeanJensenShannonDivergence => {
+                    Self::mean(marginal_distances, |m| m.jensen_shannon_divergence)
+                }
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn mean<F>(marginal_distances: &MarginalDistances, metric: F) -> f64
+    where
+        F: Fn(&MarginalDistance) -> f64,
+    {
+        if marginal_distances.marginals.is_empty() {
+            0.0
+        } else {
+            marginal_distances.marginals.iter().map(metric).sum::<f64>()
+                / (marginal_distances.marginals.len() as f64)
+        }
+    }
+}
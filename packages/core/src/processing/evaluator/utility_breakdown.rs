@@ -0,0 +1,433 @@
+use super::preservation_bucket::PreservationBucket;
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    io::{Error, Write},
+    sync::Arc,
+};
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+use crate::{
+    data_block::DataBlockValue,
+    processing::aggregator::{AggregatedCount, AggregatedData},
+};
+
+/// Metric used to sort the utility breakdown, from the worst to the best preserved
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UtilityBreakdownMetric {
+    /// Mean preservation, ascending
+    MeanPreservation,
+    /// Mean proportional error, descending
+    MeanProportionalError,
+    /// Percentage of suppressed combinations, descending
+    PercentageOfSuppressedCombinations,
+    /// Percentage of fabricated combinations, descending
+    PercentageOfFabricatedCombinations,
+    /// Combinations count mean absolute error, descending
+    CombinationsCountMeanAbsError,
+}
+
+/// Utility metrics for the combinations containing a column (any of its values)
+/// or a single attribute (e.g. all combinations containing `diagnosis:X`)
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtilityBreakdownEntry {
+    /// Column name
+    pub column: String,
+    /// Attribute value (`None` if the entry covers the whole column)
+    pub value: Option<String>,
+    /// Number of distinct sensitive combinations containing the column/attribute
+    pub number_of_sensitive_combinations: usize,
+    /// Number of distinct synthetic combinations containing the column/attribute
+    pub number_of_synthetic_combinations: usize,
+    /// Mean preservation (`min(synthetic_count / sensitive_count, 1)`) of the non-rare
+    /// sensitive combinations and the synthetic combinations
+    pub mean_preservation: f64,
+    /// Mean proportional error (`|synthetic_count - sensitive_count| / sensitive_count`,
+    /// `1` if fabricated) of the non-rare sensitive combinations and the synthetic combinations
+    pub mean_proportional_error: f64,
+    /// Percentage of the sensitive combinations that do not exist on the synthetic data
+    pub percentage_of_suppressed_combinations: f64,
+    /// Number of synthetic combinations that do not exist on the sensitive data,
+    /// as a percentage of the number of sensitive combinations
+    pub percentage_of_fabricated_combinations: f64,
+    /// Mean absolute error of the combinations existing on both data sets
+    pub combinations_count_mean_abs_error: f64,
+}
+
+impl UtilityBreakdownEntry {
+    #[inline]
+    fn metric(&self, metric: UtilityBreakdownMetric) -> f64 {
+        match metric {
+            // lower preservation is worse, so negate to sort descending
+            UtilityBreakdownMetric::MeanPreservation => -self.mean_preservation,
+            UtilityBreakdownMetric::MeanProportionalError => self.mean_proportional_error,
+            UtilityBreakdownMetric::PercentageOfSuppressedCombinations => {
+                self.percentage_of_suppressed_combinations
+            }
+            UtilityBreakdownMetric::PercentageOfFabricatedCombinations => {
+                self.percentage_of_fabricated_combinations
+            }
+            UtilityBreakdownMetric::CombinationsCountMeanAbsError => {
+                self.combinations_count_mean_abs_error
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl UtilityBreakdownEntry {
+    #[getter]
+    /// Column name
+    fn column(&self) -> String {
+        self.column.clone()
+    }
+
+    #[getter]
+    /// Attribute value (`None` if the entry covers the whole column)
+    fn value(&self) -> Option<String> {
+        self.value.clone()
+    }
+
+    #[getter]
+    /// Number of distinct sensitive combinations containing the column/attribute
+    fn number_of_sensitive_combinations(&self) -> usize {
+        self.number_of_sensitive_combinations
+    }
+
+    #[getter]
+    /// Number of distinct synthetic combinations containing the column/attribute
+    fn number_of_synthetic_combinations(&self) -> usize {
+        self.number_of_synthetic_combinations
+    }
+
+    #[getter]
+    /// Mean preservation of the non-rare sensitive combinations and the synthetic combinations
+    fn mean_preservation(&self) -> f64 {
+        self.mean_preservation
+    }
+
+    #[getter]
+    /// Mean proportional error of the non-rare sensitive combinations
+    /// and the synthetic combinations
+    fn mean_proportional_error(&self) -> f64 {
+        self.mean_proportional_error
+    }
+
+    #[getter]
+    /// Percentage of the sensitive combinations that do not exist on the synthetic data
+    fn percentage_of_suppressed_combinations(&self) -> f64 {
+        self.percentage_of_suppressed_combinations
+    }
+
+    #[getter]
+    /// Number of synthetic combinations that do not exist on the sensitive data,
+    /// as a percentage of the number of sensitive combinations
+    fn percentage_of_fabricated_combinations(&self) -> f64 {
+        self.percentage_of_fabricated_combinations
+    }
+
+    #[getter]
+    /// Mean absolute error of the combinations existing on both data sets
+    fn combinations_count_mean_abs_error(&self) -> f64 {
+        self.combinations_count_mean_abs_error
+    }
+}
+
+#[derive(Default)]
+struct UtilityBreakdownAccumulator {
+    number_of_sensitive_combinations: usize,
+    number_of_synthetic_combinations: usize,
+    suppressed: usize,
+    fabricated: usize,
+    preservation: PreservationBucket,
+    abs_error_sum: f64,
+    abs_error_count: usize,
+}
+
+impl UtilityBreakdownAccumulator {
+    #[inline]
+    fn into_entry(self, column: String, value: Option<String>) -> UtilityBreakdownEntry {
+        let percentage_of_sensitive = |n: usize| {
+            if self.number_of_sensitive_combinations > 0 {
+                (n as f64) / (self.number_of_sensitive_combinations as f64) * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        UtilityBreakdownEntry {
+            column,
+            value,
+            number_of_sensitive_combinations: self.number_of_sensitive_combinations,
+            number_of_synthetic_combinations: self.number_of_synthetic_combinations,
+            mean_preservation: self.preservation.get_mean_preservation(),
+            mean_proportional_error: self.preservation.get_mean_proportional_error(),
+            percentage_of_suppressed_combinations: percentage_of_sensitive(self.suppressed),
+            percentage_of_fabricated_combinations: percentage_of_sensitive(self.fabricated),
+            combinations_count_mean_abs_error: if self.abs_error_count > 0 {
+                self.abs_error_sum / (self.abs_error_count as f64)
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct UtilityBreakdownAccumulators {
+    columns: FnvHashMap<usize, UtilityBreakdownAccumulator>,
+    attributes: FnvHashMap<Arc<DataBlockValue>, UtilityBreakdownAccumulator>,
+}
+
+impl UtilityBreakdownAccumulators {
+    /// Updates the accumulator of every column and attribute in `values`
+    #[inline]
+    fn update<F>(&mut self, values: &[Arc<DataBlockValue>], f: F)
+    where
+        F: Fn(&mut UtilityBreakdownAccumulator),
+    {
+        for v in values.iter() {
+            f(self.columns.entry(v.column_index).or_default());
+            f(self.attributes.entry(v.clone()).or_default());
+        }
+    }
+
+    /// Adds the preservation of a combination to the accumulators of its values
+    /// (same definition used by `PreservationByLengthBuckets`)
+    #[inline]
+    fn add_preservation(
+        &mut self,
+        values: &[Arc<DataBlockValue>],
+        sensitive_count: Option<&AggregatedCount>,
+        synthetic_count: Option<&AggregatedCount>,
+    ) {
+        let sen_count = sensitive_count.map(|c| c.count).unwrap_or(0);
+        let syn_count = synthetic_count.map(|c| c.count).unwrap_or(0);
+        let (preservation, proportional_error) = if sen_count > 0 {
+            (
+                f64::min((syn_count as f64) / (sen_count as f64), 1.0),
+                ((syn_count as f64) - (sen_count as f64)).abs() / (sen_count as f64),
+            )
+        } else {
+            (0.0, 1.0)
+        };
+
+        self.update(values, |acc| {
+            acc.preservation
+                .add(preservation, values.len(), syn_count, proportional_error)
+        });
+    }
+}
+
+/// Preservation, fabrication, suppression and mean absolute error
+/// broken down by column and by attribute, so the worst preserved
+/// ones can be identified
+#[cfg_attr(feature = "pyo3", pyclass)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtilityBreakdown {
+    /// Reporting resolution used to exclude the rare sensitive
+    /// combinations from the preservation
+    pub resolution: usize,
+    /// Metrics for the combinations containing each column
+    pub columns: Vec<UtilityBreakdownEntry>,
+    /// Metrics for the combinations containing each attribute
+    pub attributes: Vec<UtilityBreakdownEntry>,
+}
+
+impl UtilityBreakdown {
+    /// Breaks down the utility metrics by column and attribute, a combination
+    /// contributes to the entry of every column and attribute it contains.
+    /// Entries are sorted by column index and value
+    /// # Arguments
+    /// * `sensitive_aggregated_data` - Calculated aggregated data for the sensitive data
+    /// * `synthetic_aggregated_data` - Calculated aggregated data for the synthetic data
+    /// * `resolution` - Reporting resolution used for data synthesis
+    pub fn from_sensitive_and_synthetic_aggregated_data(
+        sensitive_aggregated_data: &AggregatedData,
+        synthetic_aggregated_data: &AggregatedData,
+        resolution: usize,
+    ) -> UtilityBreakdown {
+        let mut accumulators = UtilityBreakdownAccumulators::default();
+
+        for (comb, sensitive_count) in sensitive_aggregated_data.aggregates_count.iter() {
+            let synthetic_count = synthetic_aggregated_data.aggregates_count.get(comb);
+
+            accumulators.update(comb, |acc| {
+                acc.number_of_sensitive_combinations += 1;
+
+                match synthetic_count {
+                    Some(synthetic_count) => {
+                        acc.abs_error_sum +=
+                            (sensitive_count.count as f64 - synthetic_count.count as f64).abs();
+                        acc.abs_error_count += 1;
+                    }
+                    None => acc.suppressed += 1,
+                }
+            });
+            // exclude sensitive rare combinations, the ones
+            // present on the synthetic data are added below
+            if sensitive_count.count >= resolution && synthetic_count.is_none() {
+                accumulators.add_preservation(comb, Some(sensitive_count), None);
+            }
+        }
+        for (comb, synthetic_count) in synthetic_aggregated_data.aggregates_count.iter() {
+            let sensitive_count = sensitive_aggregated_data.aggregates_count.get(comb);
+
+            accumulators.update(comb, |acc| {
+                acc.number_of_synthetic_combinations += 1;
+
+                if sensitive_count.is_none() {
+                    acc.fabricated += 1;
+                }
+            });
+            accumulators.add_preservation(comb, sensitive_count, Some(synthetic_count));
+        }
+
+        let headers = &sensitive_aggregated_data.headers;
+
+        UtilityBreakdown {
+            resolution,
+            columns: accumulators
+                .columns
+                .drain()
+                .sorted_by_key(|(column_index, _)| *column_index)
+                .map(|(column_index, acc)| acc.into_entry(headers[column_index].to_string(), None))
+                .collect(),
+            attributes: accumulators
+                .attributes
+                .drain()
+                .sorted_by(|(a, _), (b, _)| {
+                    a.column_index
+                        .cmp(&b.column_index)
+                        .then_with(|| a.value.cmp(&b.value))
+                })
+                .map(|(value, acc)| {
+                    acc.into_entry(
+                        headers[value.column_index].to_string(),
+                        Some(value.value.to_string()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Sorts the columns and attributes from the worst to the best preserved
+    /// according to `metric` (ties keep their current order)
+    /// # Arguments
+    /// * `metric` - Metric used to sort the entries
+    pub fn sort_by_metric(&mut self, metric: UtilityBreakdownMetric) {
+        let compare = |a: &UtilityBreakdownEntry, b: &UtilityBreakdownEntry| {
+            b.metric(metric)
+                .partial_cmp(&a.metric(metric))
+                .unwrap_or(Ordering::Equal)
+        };
+
+        self.columns.sort_by(compare);
+        self.attributes.sort_by(compare);
+    }
+
+    #[inline]
+    fn write_entries(
+        entries: &[UtilityBreakdownEntry],
+        path: &str,
+        delimiter: char,
+    ) -> Result<(), Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        file.write_all(
+            [
+                "column",
+                "value",
+                "number_of_sensitive_combinations",
+                "number_of_synthetic_combinations",
+                "mean_preservation",
+                "mean_proportional_error",
+                "percentage_of_suppressed_combinations",
+                "percentage_of_fabricated_combinations",
+                "combinations_count_mean_abs_error",
+            ]
+            .join(&delimiter.to_string())
+            .as_bytes(),
+        )?;
+        file.write_all("\n".as_bytes())?;
+        for e in entries.iter() {
+            file.write_all(
+                [
+                    e.column.clone(),
+                    e.value.clone().unwrap_or_default(),
+                    e.number_of_sensitive_combinations.to_string(),
+                    e.number_of_synthetic_combinations.to_string(),
+                    format!("{:.6}", e.mean_preservation),
+                    format!("{:.6}", e.mean_proportional_error),
+                    format!("{:.6}", e.percentage_of_suppressed_combinations),
+                    format!("{:.6}", e.percentage_of_fabricated_combinations),
+                    format!("{:.6}", e.combinations_count_mean_abs_error),
+                ]
+                .join(&delimiter.to_string())
+                .as_bytes(),
+            )?;
+            file.write_all("\n".as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl UtilityBreakdown {
+    /// Returns the metrics for each column.
+    /// This method will clone the data, so its recommended to have its result stored
+    /// in a local variable to avoid it being called multiple times
+    pub fn get_columns(&self) -> Vec<UtilityBreakdownEntry> {
+        self.columns.clone()
+    }
+
+    /// Returns the metrics for each attribute.
+    /// This method will clone the data, so its recommended to have its result stored
+    /// in a local variable to avoid it being called multiple times
+    pub fn get_attributes(&self) -> Vec<UtilityBreakdownEntry> {
+        self.attributes.clone()
+    }
+
+    /// Writes the metrics for each column to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `columns_breakdown_path` - File path to be written
+    /// * `columns_breakdown_delimiter` - Delimiter to use when writing to `columns_breakdown_path`
+    pub fn write_columns_breakdown(
+        &self,
+        columns_breakdown_path: &str,
+        columns_breakdown_delimiter: char,
+    ) -> Result<(), Error> {
+        Self::write_entries(
+            &self.columns,
+            columns_breakdown_path,
+            columns_breakdown_delimiter,
+        )
+    }
+
+    /// Writes the metrics for each attribute to the file system
+    /// in a csv/tsv like format
+    /// # Arguments:
+    /// * `attributes_breakdown_path` - File path to be written
+    /// * `attributes_breakdown_delimiter` - Delimiter to use when writing to `attributes_breakdown_path`
+    pub fn write_attributes_breakdown(
+        &self,
+        attributes_breakdown_path: &str,
+        attributes_breakdown_delimiter: char,
+    ) -> Result<(), Error> {
+        Self::write_entries(
+            &self.attributes,
+            attributes_breakdown_path,
+            attributes_breakdown_delimiter,
+        )
+    }
+}
@@ -0,0 +1,439 @@
+use super::{GeneralizationError, GeneralizationHierarchiesByColumn, GeneralizationHierarchy};
+use crate::{
+    data_block::{DataBlock, DataBlockRecord, DataBlockValue},
+    processing::aggregator::Aggregator,
+    utils::{
+        math::calc_percentage,
+        reporting::{ReportProgress, StoppableResult},
+        time::ElapsedDurationLogger,
+    },
+};
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Maps an original data block value to its generalized value
+type GeneralizedValues = FnvHashMap<Arc<DataBlockValue>, Arc<DataBlockValue>>;
+
+/// Generalization level applied to a column
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnGeneralization {
+    /// Column name
+    pub column: String,
+    /// Generalization level (`0` keeps the original values)
+    pub level: usize,
+    /// Number of levels available on the column hierarchy
+    pub number_of_levels: usize,
+}
+
+/// Result of the search for the least generalized data block
+/// meeting a target share of records with rare combinations
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralizationSearchResult {
+    /// Reporting resolution used to define rare combinations
+    pub resolution: usize,
+    /// Maximum length of the combinations taken into account
+    pub reporting_length: usize,
+    /// Target percentage of records with rare combinations
+    pub target_percentage_of_records_with_rare_combinations: f64,
+    /// Percentage of records with rare combinations before generalization
+    pub original_percentage_of_records_with_rare_combinations: f64,
+    /// Percentage of records with rare combinations after
+    /// applying the chosen generalization
+    pub percentage_of_records_with_rare_combinations: f64,
+    /// Whether the chosen generalization meets the target,
+    /// when `false` the generalization with the lowest percentage is returned
+    pub target_reached: bool,
+    /// How many generalizations were evaluated during the search
+    pub number_of_evaluated_generalizations: usize,
+    /// Chosen level for every column with a hierarchy
+    pub columns: Vec<ColumnGeneralization>,
+}
+
+struct ColumnHierarchy {
+    column_index: usize,
+    column: String,
+    /// Generalized values for every level above the original values
+    generalized_values: Vec<GeneralizedValues>,
+}
+
+/// Generalizes the columns of a data block using user-supplied hierarchies
+pub struct Generalizer {
+    data_block: Arc<DataBlock>,
+    hierarchies: Vec<ColumnHierarchy>,
+}
+
+impl Generalizer {
+    /// Returns a new Generalizer, failing if any value on the data block
+    /// is not covered by the hierarchy of its column
+    /// # Arguments
+    /// * `data_block` - Data block to be generalized
+    /// * `hierarchies` - Maps a column name to its generalization hierarchy
+    /// (multi-value columns can not be generalized)
+    pub fn new(
+        data_block: Arc<DataBlock>,
+        hierarchies: &GeneralizationHierarchiesByColumn,
+    ) -> Result<Generalizer, GeneralizationError> {
+        let column_index_by_name = data_block.calc_column_index_by_name();
+        let mut column_hierarchies = Vec::with_capacity(hierarchies.len());
+
+        for (column, hierarchy) in hierarchies.iter() {
+            if data_block
+                .multi_value_column_metadata_map
+                .values()
+                .any(|metadata| *metadata.src_header_name == *column)
+            {
+                return Err(GeneralizationError::InvalidInput(format!(
+                    "multi-value column \"{column}\" can not be generalized"
+                )));
+            }
+
+            let column_index = *column_index_by_name.get(column).ok_or_else(|| {
+                GeneralizationError::InvalidInput(format!(
+                    "column \"{column}\" not found in the data block"
+                ))
+            })?;
+
+            column_hierarchies.push(ColumnHierarchy {
+                column_index,
+                column: column.clone(),
+                generalized_values: Self::calc_generalized_values(
+                    &data_block,
+                    column_index,
+                    column,
+                    hierarchy,
+                )?,
+            });
+        }
+        column_hierarchies.sort_by_key(|h| h.column_index);
+
+        Ok(Generalizer {
+            data_block,
+            hierarchies: column_hierarchies,
+        })
+    }
+
+    /// Searches for the least generalized data block where the percentage of records
+    /// with rare combinations is at most `target_percentage`.
+    /// Generalizations are evaluated by increasing total level (sum of the column levels),
+    /// and among the ones with the smallest total level meeting the target, the one with the
+    /// lowest percentage is chosen
+    /// # Arguments
+    /// * `reporting_length` - Maximum length of the combinations taken into account
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `target_percentage` - Target percentage (`0` to `100`) of records with rare combinations
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn search<T>(
+        &self,
+        reporting_length: usize,
+        resolution: usize,
+        target_percentage: f64,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<GeneralizationSearchResult, GeneralizationError>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("generalization search");
+
+        if self.hierarchies.is_empty() {
+            return Err(GeneralizationError::InvalidInput(
+                "at least one generalization hierarchy must be provided".to_owned(),
+            ));
+        }
+        if resolution == 0 {
+            return Err(GeneralizationError::InvalidInput(
+                "resolution must be greater than 0".to_owned(),
+            ));
+        }
+        if !(0.0..=100.0).contains(&target_percentage) {
+            return Err(GeneralizationError::InvalidInput(
+                "target percentage must be between 0 and 100".to_owned(),
+            ));
+        }
+
+        let max_levels = self
+            .hierarchies
+            .iter()
+            .map(|h| h.generalized_values.len())
+            .collect_vec();
+        let total_generalizations = max_levels
+            .iter()
+            .fold(1.0, |total, max_level| total * (*max_level + 1) as f64);
+        let mut original_percentage = 0.0;
+        let mut number_of_evaluated = 0;
+        let mut lowest: Option<(Vec<usize>, f64)> = None;
+
+        info!(
+            "searching generalizations for {} column(s), with target percentage of records with rare combinations = {}",
+            self.hierarchies.len(),
+            target_percentage
+        );
+
+        // the candidates with the same total level are only enumerated
+        // when the lower total levels do not meet the target
+        for total_level in 0..=max_levels.iter().sum() {
+            let mut chosen: Option<(Vec<usize>, f64)> = None;
+
+            for levels in LevelsWithTotal::new(&max_levels, total_level) {
+                let percentage = self.calc_percentage_of_records_with_rare_combinations::<T>(
+                    &levels,
+                    reporting_length,
+                    resolution,
+                )?;
+
+                if number_of_evaluated == 0 {
+                    original_percentage = percentage;
+                }
+                number_of_evaluated += 1;
+
+                if let Some(r) = progress_reporter.as_mut() {
+                    r.report(calc_percentage(
+                        number_of_evaluated as f64,
+                        total_generalizations,
+                    ))?;
+                }
+                if percentage <= target_percentage
+                    && chosen.as_ref().is_none_or(|(_, p)| percentage < *p)
+                {
+                    chosen = Some((levels.clone(), percentage));
+                }
+                if lowest.as_ref().is_none_or(|(_, p)| percentage < *p) {
+                    lowest = Some((levels, percentage));
+                }
+            }
+
+            if chosen.is_some() {
+                lowest = chosen;
+                break;
+            }
+        }
+
+        let (levels, percentage) = lowest.unwrap_or_default();
+        let target_reached = percentage <= target_percentage;
+
+        info!(
+            "{} generalization(s) evaluated, chosen levels {:?} result in {:.2}% of records with rare combinations",
+            number_of_evaluated, levels, percentage
+        );
+
+        Ok(GeneralizationSearchResult {
+            resolution,
+            reporting_length: self.data_block.normalize_reporting_length(reporting_length),
+            target_percentage_of_records_with_rare_combinations: target_percentage,
+            original_percentage_of_records_with_rare_combinations: original_percentage,
+            percentage_of_records_with_rare_combinations: percentage,
+            target_reached,
+            number_of_evaluated_generalizations: number_of_evaluated,
+            columns: self
+                .hierarchies
+                .iter()
+                .zip(levels)
+                .map(|(h, level)| ColumnGeneralization {
+                    column: h.column.clone(),
+                    level,
+                    number_of_levels: h.generalized_values.len(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Returns a new data block with the column generalizations applied,
+    /// columns not listed keep their original values
+    /// # Arguments
+    /// * `columns` - Generalization level for each column (e.g. the search result columns)
+    pub fn generalize(
+        &self,
+        columns: &[ColumnGeneralization],
+    ) -> Result<Arc<DataBlock>, GeneralizationError> {
+        let mut levels = vec![0; self.hierarchies.len()];
+
+        for c in columns.iter() {
+            let (index, hierarchy) = self
+                .hierarchies
+                .iter()
+                .find_position(|h| h.column == c.column)
+                .ok_or_else(|| {
+                    GeneralizationError::InvalidInput(format!(
+                        "column \"{}\" has no generalization hierarchy",
+                        c.column
+                    ))
+                })?;
+
+            if c.level > hierarchy.generalized_values.len() {
+                return Err(GeneralizationError::InvalidInput(format!(
+                    "column \"{}\" hierarchy has only {} level(s)",
+                    c.column,
+                    hierarchy.generalized_values.len()
+                )));
+            }
+            levels[index] = c.level;
+        }
+        Ok(self.generalize_levels(&levels))
+    }
+
+    fn calc_generalized_values(
+        data_block: &DataBlock,
+        column_index: usize,
+        column: &str,
+        hierarchy: &GeneralizationHierarchy,
+    ) -> Result<Vec<GeneralizedValues>, GeneralizationError> {
+        let original_values: FnvHashSet<&Arc<DataBlockValue>> = data_block
+            .records
+            .iter()
+            .flat_map(|r| r.values.iter())
+            .filter(|v| v.column_index == column_index)
+            .collect();
+
+        (1..=hierarchy.number_of_levels())
+            .map(|level| {
+                // same generalized values share the same reference
+                let mut interned: FnvHashMap<String, Arc<DataBlockValue>> =
+                    FnvHashMap::default();
+
+                original_values
+                    .iter()
+                    .map(|value| {
+                        let generalized =
+                            hierarchy.generalize(&value.value, level).ok_or_else(|| {
+                                GeneralizationError::InvalidInput(format!(
+                                    "value \"{}\" of column \"{column}\" is not covered by level {level} of its hierarchy",
+                                    value.value
+                                ))
+                            })?;
+
+                        Ok((
+                            (*value).clone(),
+                            interned
+                                .entry(generalized)
+                                .or_insert_with_key(|g| {
+                                    Arc::new(DataBlockValue::new(column_index, Arc::new(g.clone())))
+                                })
+                                .clone(),
+                        ))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn generalize_levels(&self, levels: &[usize]) -> Arc<DataBlock> {
+        let mut generalized_values_by_column: Vec<Option<&GeneralizedValues>> =
+            vec![None; self.data_block.headers.len()];
+
+        for (h, level) in self.hierarchies.iter().zip(levels.iter()) {
+            if *level > 0 {
+                generalized_values_by_column[h.column_index] =
+                    Some(&h.generalized_values[level - 1]);
+            }
+        }
+
+        Arc::new(DataBlock {
+            headers: self.data_block.headers.clone(),
+            multi_value_column_metadata_map: self
+                .data_block
+                .multi_value_column_metadata_map
+                .clone(),
+            records: self
+                .data_block
+                .records
+                .iter()
+                .map(|r| {
+                    Arc::new(DataBlockRecord::new(
+                        r.values
+                            .iter()
+                            .map(|v| {
+                                generalized_values_by_column[v.column_index]
+                                    .and_then(|generalized| generalized.get(v))
+                                    .unwrap_or(v)
+                                    .clone()
+                            })
+                            .collect(),
+                    ))
+                })
+                .collect(),
+            max_records_per_subject: self.data_block.max_records_per_subject,
+            sampling_rate: self.data_block.sampling_rate,
+        })
+    }
+
+    #[inline]
+    fn calc_percentage_of_records_with_rare_combinations<T>(
+        &self,
+        levels: &[usize],
+        reporting_length: usize,
+        resolution: usize,
+    ) -> StoppableResult<f64>
+    where
+        T: ReportProgress,
+    {
+        Ok(Aggregator::new(self.generalize_levels(levels))
+            .aggregate(reporting_length, &mut None::<T>)?
+            .calc_percentage_of_records_with_rare_combinations(resolution))
+    }
+}
+
+/// Lazily enumerates, in lexicographic order, the column levels
+/// bounded by `max_levels` that add up to the same total level
+struct LevelsWithTotal<'max_levels> {
+    max_levels: &'max_levels [usize],
+    next: Option<Vec<usize>>,
+}
+
+impl<'max_levels> LevelsWithTotal<'max_levels> {
+    fn new(max_levels: &'max_levels [usize], total_level: usize) -> LevelsWithTotal<'max_levels> {
+        let mut first = vec![0; max_levels.len()];
+
+        LevelsWithTotal {
+            max_levels,
+            next: if Self::fill_from_right(max_levels, &mut first, 0, total_level) {
+                Some(first)
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Sets `levels[start..]` to the smallest levels (lexicographically)
+    /// adding up to `remaining`, returning `false` if they can not reach it
+    #[inline]
+    fn fill_from_right(
+        max_levels: &[usize],
+        levels: &mut [usize],
+        start: usize,
+        mut remaining: usize,
+    ) -> bool {
+        for i in (start..levels.len()).rev() {
+            levels[i] = remaining.min(max_levels[i]);
+            remaining -= levels[i];
+        }
+        remaining == 0
+    }
+}
+
+impl Iterator for LevelsWithTotal<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        let mut levels = current.clone();
+        let mut suffix_total = 0;
+
+        // increments the rightmost level that can take one
+        // unit from the levels after it
+        for i in (0..levels.len()).rev() {
+            if suffix_total > 0 && levels[i] < self.max_levels[i] {
+                levels[i] += 1;
+                Self::fill_from_right(self.max_levels, &mut levels, i + 1, suffix_total - 1);
+                self.next = Some(levels);
+                break;
+            }
+            suffix_total += levels[i];
+        }
+        Some(current)
+    }
+}
@@ -0,0 +1,41 @@
+use crate::utils::reporting::ProcessingStoppedError;
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyValueError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when generalizing a data block
+#[derive(Debug)]
+pub enum GeneralizationError {
+    /// Generated when the hierarchies or search inputs are not valid
+    InvalidInput(String),
+    /// Generated when the search is stopped through the progress reporter
+    Stopped(ProcessingStoppedError),
+}
+
+impl Display for GeneralizationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            GeneralizationError::InvalidInput(err) => {
+                write!(f, "invalid generalization input: {err}")
+            }
+            GeneralizationError::Stopped(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<ProcessingStoppedError> for GeneralizationError {
+    fn from(err: ProcessingStoppedError) -> GeneralizationError {
+        GeneralizationError::Stopped(err)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<GeneralizationError> for PyErr {
+    fn from(err: GeneralizationError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,147 @@
+use super::GeneralizationError;
+use csv::Reader;
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// Maps an original value to its generalized value
+pub type GeneralizedValueMap = FnvHashMap<String, String>;
+
+/// Maps a column name to the hierarchy used to generalize it
+pub type GeneralizationHierarchiesByColumn = FnvHashMap<String, GeneralizationHierarchy>;
+
+/// User-supplied hierarchy used to generalize the values of a column.
+/// Level `0` keeps the original values, and every following level
+/// should be coarser than the previous one
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum GeneralizationHierarchy {
+    /// Explicit mapping, where the entry `i` maps every original
+    /// value to its generalization at level `i + 1`
+    /// (e.g. zip -> zip3 -> state)
+    Mapping(Vec<GeneralizedValueMap>),
+    /// Numeric intervals, where the entry `i` is the interval width
+    /// used at level `i + 1` (e.g. exact age -> 5-year -> 10-year)
+    Intervals(Vec<f64>),
+}
+
+impl GeneralizationHierarchy {
+    /// Builds a mapping hierarchy from rows where the first entry is the
+    /// original value and each following entry is its generalization
+    /// at the next level (e.g. `98101,981,WA`)
+    /// # Arguments
+    /// * `rows` - Hierarchy rows, all of them with the same number of entries
+    pub fn from_mapping_rows(rows: &[Vec<String>]) -> Result<Self, GeneralizationError> {
+        let number_of_entries = rows.first().map(|row| row.len()).unwrap_or_default();
+
+        if number_of_entries < 2 {
+            return Err(GeneralizationError::InvalidInput(
+                "hierarchy rows must contain the original value and at least one generalization"
+                    .to_owned(),
+            ));
+        }
+
+        let mut levels = vec![GeneralizedValueMap::default(); number_of_entries - 1];
+
+        for row in rows.iter() {
+            if row.len() != number_of_entries {
+                return Err(GeneralizationError::InvalidInput(format!(
+                    "every hierarchy row must have {number_of_entries} entries",
+                )));
+            }
+
+            let original = row[0].trim();
+
+            for (level, generalized) in row[1..].iter().enumerate() {
+                let generalized = generalized.trim();
+
+                if generalized.is_empty() {
+                    return Err(GeneralizationError::InvalidInput(format!(
+                        "value \"{original}\" has an empty generalization at level {}",
+                        level + 1
+                    )));
+                }
+                if let Some(previous) =
+                    levels[level].insert(original.to_owned(), generalized.to_owned())
+                {
+                    if previous != generalized {
+                        return Err(GeneralizationError::InvalidInput(format!(
+                            "value \"{original}\" has more than one generalization at level {}",
+                            level + 1
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(GeneralizationHierarchy::Mapping(levels))
+    }
+
+    /// Builds a mapping hierarchy from a csv/tsv reader, where each line
+    /// contains the original value followed by its generalizations
+    /// (no header line is expected)
+    /// # Arguments
+    /// * `reader` - Reader for the hierarchy file
+    pub fn from_csv_reader<R: Read>(mut reader: Reader<R>) -> Result<Self, GeneralizationError> {
+        let rows = reader
+            .records()
+            .map(|record| {
+                record
+                    .map(|r| r.iter().map(|entry| entry.to_owned()).collect())
+                    .map_err(|err| GeneralizationError::InvalidInput(err.to_string()))
+            })
+            .collect::<Result<Vec<Vec<String>>, GeneralizationError>>()?;
+
+        Self::from_mapping_rows(&rows)
+    }
+
+    /// Builds a hierarchy grouping numeric values into intervals
+    /// # Arguments
+    /// * `widths` - Interval width for each level, in increasing order
+    pub fn from_interval_widths(widths: Vec<f64>) -> Result<Self, GeneralizationError> {
+        if widths.is_empty() {
+            return Err(GeneralizationError::InvalidInput(
+                "at least one interval width must be provided".to_owned(),
+            ));
+        }
+        if widths.iter().any(|w| !w.is_finite() || *w <= 0.0) {
+            return Err(GeneralizationError::InvalidInput(
+                "interval widths must be greater than 0".to_owned(),
+            ));
+        }
+        if widths.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(GeneralizationError::InvalidInput(
+                "interval widths must be in increasing order".to_owned(),
+            ));
+        }
+        Ok(GeneralizationHierarchy::Intervals(widths))
+    }
+
+    /// Number of levels above the original values
+    pub fn number_of_levels(&self) -> usize {
+        match self {
+            GeneralizationHierarchy::Mapping(levels) => levels.len(),
+            GeneralizationHierarchy::Intervals(widths) => widths.len(),
+        }
+    }
+
+    /// Returns the generalization of `value` at `level`, or `None` if
+    /// the value is not covered by the hierarchy.
+    /// Intervals are formatted as `[{start}, {end})`
+    /// # Arguments
+    /// * `value` - Original value
+    /// * `level` - Generalization level (`0` returns the original value)
+    pub fn generalize(&self, value: &str, level: usize) -> Option<String> {
+        if level == 0 {
+            return Some(value.to_owned());
+        }
+        match self {
+            GeneralizationHierarchy::Mapping(levels) => levels.get(level - 1)?.get(value).cloned(),
+            GeneralizationHierarchy::Intervals(widths) => {
+                let width = widths.get(level - 1)?;
+                let value = value.trim().parse::<f64>().ok().filter(|v| v.is_finite())?;
+                let start = (value / width).floor() * width;
+
+                Some(format!("[{}, {})", start, start + width))
+            }
+        }
+    }
+}
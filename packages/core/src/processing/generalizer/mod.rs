@@ -0,0 +1,7 @@
+mod data_generalizer;
+mod generalization_error;
+mod generalization_hierarchy;
+
+pub use data_generalizer::*;
+pub use generalization_error::*;
+pub use generalization_hierarchy::*;
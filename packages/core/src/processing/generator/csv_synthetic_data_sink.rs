@@ -0,0 +1,168 @@
+use super::{SyntheticDataSink, SyntheticDataSinkError};
+use csv::{Writer, WriterBuilder};
+use std::{io::Write, sync::Arc};
+
+use crate::data_block::{
+    CsvIOError, CsvRecordRef, DataBlockHeaders, DataBlockHeadersSlice, MultiValueColumnMetadataMap,
+    RawData, RawDataMultiValueColumnJoiner,
+};
+
+/// Sink that incrementally writes the synthetic records as CSV
+pub struct CsvSyntheticDataSink<W>
+where
+    W: Write,
+{
+    /// CSV writer wrapping the output
+    writer: Writer<W>,
+    /// Empty values will be replaced by this
+    empty_value: Arc<String>,
+    /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
+    multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// Whether multi value columns should be joined back together or not
+    join_multi_value_columns: bool,
+    /// Whether the records should be pivoted to key-value row entries
+    long_form: bool,
+    /// Headers of the synthetic data (before joining multi value columns)
+    headers: DataBlockHeaders,
+    /// Headers as they are written to the output
+    output_headers: DataBlockHeaders,
+    /// Id of the next record written on long form
+    next_record_id: usize,
+}
+
+impl<W> CsvSyntheticDataSink<W>
+where
+    W: Write,
+{
+    /// Returns a new CsvSyntheticDataSink
+    /// # Arguments
+    /// * `writer` - Where the CSV data will be written to
+    /// * `delimiter` - CSV delimiter to use
+    /// * `empty_value` - Empty values will be replaced by this
+    /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name (such as A_a1)
+    /// to its corresponding metadata
+    /// * `join_multi_value_columns` - Whether multi value columns should be joined back together or not
+    /// * `long_form` - Pivots column headers and value pairs to key-value row entries.
+    #[inline]
+    pub fn new(
+        writer: W,
+        delimiter: char,
+        empty_value: &str,
+        multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+        join_multi_value_columns: bool,
+        long_form: bool,
+    ) -> Self {
+        CsvSyntheticDataSink {
+            writer: WriterBuilder::new()
+                .delimiter(delimiter as u8)
+                .from_writer(writer),
+            empty_value: Arc::new(empty_value.to_owned()),
+            multi_value_column_metadata_map,
+            join_multi_value_columns,
+            long_form,
+            headers: DataBlockHeaders::default(),
+            output_headers: DataBlockHeaders::default(),
+            next_record_id: 0,
+        }
+    }
+
+    /// Sets the id used by the next record written on long form
+    /// # Arguments
+    /// * `next_record_id` - Id of the next record
+    #[inline]
+    pub(crate) fn set_next_record_id(&mut self, next_record_id: usize) {
+        self.next_record_id = next_record_id;
+    }
+
+    #[inline]
+    fn join_if_necessary(&self, records: &[CsvRecordRef]) -> RawData {
+        // the joiner expects the headers on index 0
+        let mut raw_data = RawData::with_capacity(records.len() + 1);
+
+        raw_data.push(self.headers.clone());
+        raw_data.extend(records.iter().cloned());
+
+        if self.join_multi_value_columns {
+            RawDataMultiValueColumnJoiner::new(
+                &raw_data,
+                &self.multi_value_column_metadata_map,
+                &self.empty_value,
+            )
+            .join()
+        } else {
+            raw_data
+        }
+    }
+
+    #[inline]
+    fn write_record<I, T>(&mut self, record: I) -> Result<(), CsvIOError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.writer.write_record(record).map_err(CsvIOError::new)
+    }
+
+    /// Writes the CSV headers
+    /// # Arguments
+    /// * `headers` - Headers of the synthetic data
+    pub fn write_csv_headers(&mut self, headers: &DataBlockHeadersSlice) -> Result<(), CsvIOError> {
+        self.headers = headers.to_vec();
+        self.output_headers = self.join_if_necessary(&[]).swap_remove(0);
+
+        if self.long_form {
+            self.write_record(["Id", "Attribute", "Value", "AttributeValue"])
+        } else {
+            let output_headers = self.output_headers.clone();
+
+            self.write_record(output_headers.iter().map(|h| h.as_str()))
+        }
+    }
+
+    /// Writes the records as CSV and flushes the writer
+    /// # Arguments
+    /// * `records` - Records following the headers order
+    pub fn write_csv_records(&mut self, records: &[CsvRecordRef]) -> Result<(), CsvIOError> {
+        for r in self.join_if_necessary(records).iter().skip(1) {
+            if self.long_form {
+                for (col_idx, value) in r.iter().enumerate() {
+                    // do not write empty values to long format
+                    if **value != *self.empty_value {
+                        let col_header = self.output_headers[col_idx].clone();
+
+                        self.write_record([
+                            self.next_record_id.to_string().as_str(),
+                            col_header.as_str(),
+                            value.as_str(),
+                            format!("{}:{}", col_header, value).as_str(),
+                        ])?;
+                    }
+                }
+                self.next_record_id += 1;
+            } else {
+                self.write_record(r.iter().map(|v| v.as_str()))?;
+            }
+        }
+        self.writer
+            .flush()
+            .map_err(|err| CsvIOError::new(csv::Error::from(err)))
+    }
+}
+
+impl<W> SyntheticDataSink for CsvSyntheticDataSink<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn write_headers(
+        &mut self,
+        headers: &DataBlockHeadersSlice,
+    ) -> Result<(), SyntheticDataSinkError> {
+        Ok(self.write_csv_headers(headers)?)
+    }
+
+    #[inline]
+    fn write_records(&mut self, records: &[CsvRecordRef]) -> Result<(), SyntheticDataSinkError> {
+        Ok(self.write_csv_records(records)?)
+    }
+}
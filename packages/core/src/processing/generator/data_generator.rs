@@ -1,9 +1,18 @@
 use super::generated_data::GeneratedData;
+use super::streamed_data_summary::StreamedDataSummary;
+use super::synthesizers::DpMarginalError;
+use super::synthesizers::DpMarginalModel;
+use super::synthesizers::DpMarginalSynthesizer;
 use super::synthesizers::RowSeededSynthesizer;
-use super::synthesizers::SynthesizedRecords;
+use super::synthesizers::SynthesisConstraints;
+use super::synthesizers::SynthesisConstraintsEnforcer;
+use super::synthesizers::TargetDistribution;
 use super::synthesizers::UnseededSynthesizer;
 use super::synthesizers::ValueSeededSynthesizer;
+use super::synthesizers::{SynthesizedRecord, SynthesizedRecords};
+use super::unique_copies_filter::UniqueCopiesFilter;
 use super::OversamplingParameters;
+use super::{SyntheticDataBatcher, SyntheticDataSink, SyntheticDataSinkError};
 use log::info;
 use std::sync::Arc;
 
@@ -11,6 +20,7 @@ use crate::data_block::DataBlock;
 use crate::data_block::DataBlockHeaders;
 use crate::data_block::MultiValueColumnMetadataMap;
 use crate::data_block::RawData;
+use crate::dp::DpMarginalParameters;
 use crate::processing::aggregator::AggregatedData;
 use crate::processing::generator::synthesizers::AggregateSeededSynthesizer;
 use crate::processing::generator::synthesizers::SynthesizerCacheKey;
@@ -20,10 +30,133 @@ use crate::utils::time::ElapsedDurationLogger;
 
 /// Process a data block and generates new synthetic data
 #[derive(Default)]
-pub struct Generator {}
+pub struct Generator {
+    /// Logical constraints the synthesized records should respect
+    synthesis_constraints: Option<SynthesisConstraints>,
+    /// Whether synthesized records exactly reproducing a sensitive
+    /// record that occurs only once should be rejected
+    /// (only applies to modes synthesizing directly from the data block)
+    reject_unique_copies: bool,
+    /// Whether the aggregate seeded synthesis should split the
+    /// record budget across parallel workers
+    parallel_aggregate_seeded: bool,
+}
 
 impl Generator {
+    /// Returns a new Generator
+    /// # Arguments
+    /// * `synthesis_constraints` - Logical constraints the synthesized records should respect
+    /// (`None` means no constraints)
+    /// * `reject_unique_copies` - Whether synthesized records exactly reproducing a sensitive
+    /// record that occurs only once should be rejected (applies to the row seeded,
+    /// unseeded and value seeded modes)
+    /// * `parallel_aggregate_seeded` - Whether the aggregate seeded synthesis should split
+    /// the record budget across parallel workers (requires the `rayon` feature). This is
+    /// faster, but preserves fewer rare combinations than synthesizing on a single thread
+    #[inline]
+    pub fn new(
+        synthesis_constraints: Option<SynthesisConstraints>,
+        reject_unique_copies: bool,
+        parallel_aggregate_seeded: bool,
+    ) -> Generator {
+        Generator {
+            synthesis_constraints,
+            reject_unique_copies,
+            parallel_aggregate_seeded,
+        }
+    }
+
+    #[inline]
+    fn build_unique_copies_filter(&self, data_block: &DataBlock) -> Option<UniqueCopiesFilter> {
+        if self.reject_unique_copies {
+            Some(UniqueCopiesFilter::new(data_block))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn reject_unique_copies_if_necessary(
+        &self,
+        data_block: &DataBlock,
+        synthesized_records: SynthesizedRecords,
+    ) -> (SynthesizedRecords, Option<UniqueCopiesFilter>) {
+        match self.build_unique_copies_filter(data_block) {
+            Some(mut filter) => (filter.filter(synthesized_records), Some(filter)),
+            None => (synthesized_records, None),
+        }
+    }
+
+    /// Wraps `on_record`, so the records copying a unique sensitive
+    /// record are rejected by `unique_copies_filter` (if any) before reaching it
     #[inline]
+    fn reject_unique_copies_on_the_fly<'a, E, F>(
+        unique_copies_filter: &'a mut Option<UniqueCopiesFilter>,
+        mut on_record: F,
+    ) -> impl FnMut(SynthesizedRecord) -> Result<(), E> + 'a
+    where
+        F: FnMut(SynthesizedRecord) -> Result<(), E> + 'a,
+    {
+        move |record| {
+            if let Some(filter) = unique_copies_filter.as_mut() {
+                if !filter.accept(&record) {
+                    return Ok(());
+                }
+            }
+            on_record(record)
+        }
+    }
+
+    #[inline]
+    fn calc_number_of_rejected_unique_copies(
+        unique_copies_filter: Option<UniqueCopiesFilter>,
+    ) -> Option<usize> {
+        unique_copies_filter.map(|filter| {
+            filter.log_report();
+            filter.number_of_rejected_records()
+        })
+    }
+
+    #[inline]
+    fn build_constraints_enforcer(
+        &self,
+        headers: &DataBlockHeaders,
+        empty_value: &Arc<String>,
+    ) -> Option<Arc<SynthesisConstraintsEnforcer>> {
+        self.synthesis_constraints.as_ref().map(|constraints| {
+            Arc::new(SynthesisConstraintsEnforcer::new(
+                constraints,
+                headers,
+                empty_value.clone(),
+            ))
+        })
+    }
+
+    /// Besides the synthesis constraints, also keeps the levels of the
+    /// column hierarchies released with the aggregated data consistent
+    #[inline]
+    fn build_aggregate_seeded_constraints_enforcer(
+        &self,
+        aggregated_data: &AggregatedData,
+        empty_value: &Arc<String>,
+    ) -> Option<Arc<SynthesisConstraintsEnforcer>> {
+        if aggregated_data.column_hierarchies.is_empty() {
+            return self.build_constraints_enforcer(&aggregated_data.headers, empty_value);
+        }
+        Some(Arc::new(
+            SynthesisConstraintsEnforcer::new(
+                self.synthesis_constraints
+                    .as_ref()
+                    .unwrap_or(&SynthesisConstraints::default()),
+                &aggregated_data.headers,
+                empty_value.clone(),
+            )
+            .with_column_hierarchies(&aggregated_data.column_hierarchies, aggregated_data),
+        ))
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn build_generated_data(
         &self,
         headers: &DataBlockHeaders,
@@ -31,6 +164,8 @@ impl Generator {
         number_of_records: usize,
         mut synthesized_records: SynthesizedRecords,
         empty_value: Arc<String>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+        unique_copies_filter: Option<UniqueCopiesFilter>,
     ) -> GeneratedData {
         let mut result = RawData::default();
         let mut records: RawData = synthesized_records
@@ -53,7 +188,56 @@ impl Generator {
 
         info!("expansion ratio: {:.4?}", expansion_ratio);
 
-        GeneratedData::new(result, expansion_ratio, multi_value_column_metadata_map)
+        let constraints_report = constraints_enforcer.map(|enforcer| {
+            let report = enforcer.build_report();
+
+            info!(
+                "synthesis constraints pruned {} sampling choice(s)",
+                report.total_pruned_choices()
+            );
+            report
+        });
+
+        GeneratedData::new(
+            result,
+            expansion_ratio,
+            multi_value_column_metadata_map,
+            constraints_report,
+            Self::calc_number_of_rejected_unique_copies(unique_copies_filter),
+        )
+    }
+
+    #[inline]
+    fn build_streamed_data_summary(
+        &self,
+        number_of_synthetic_records: usize,
+        number_of_records: usize,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+        unique_copies_filter: Option<UniqueCopiesFilter>,
+    ) -> StreamedDataSummary {
+        let expansion_ratio = number_of_synthetic_records as f64 / number_of_records as f64;
+
+        info!(
+            "streamed {} synthetic record(s), expansion ratio: {:.4?}",
+            number_of_synthetic_records, expansion_ratio
+        );
+
+        let constraints_report = constraints_enforcer.map(|enforcer| {
+            let report = enforcer.build_report();
+
+            info!(
+                "synthesis constraints pruned {} sampling choice(s)",
+                report.total_pruned_choices()
+            );
+            report
+        });
+
+        StreamedDataSummary::new(
+            number_of_synthetic_records,
+            expansion_ratio,
+            constraints_report,
+            Self::calc_number_of_rejected_unique_copies(unique_copies_filter),
+        )
     }
 
     /// Synthesize data using the row seeded method
@@ -80,19 +264,27 @@ impl Generator {
         info!("starting row seeded generation...");
 
         let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
         let mut synth = RowSeededSynthesizer::new(
             data_block.clone(),
             Arc::new(data_block.calc_attr_rows()),
             resolution,
             cache_max_size,
+            constraints_enforcer.clone(),
         );
 
+        let (synthesized_records, unique_copies_filter) =
+            self.reject_unique_copies_if_necessary(data_block, synth.run(progress_reporter)?);
+
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
             data_block.number_of_records(),
-            synth.run(progress_reporter)?,
+            synthesized_records,
             empty_value_arc,
+            constraints_enforcer,
+            unique_copies_filter,
         ))
     }
 
@@ -102,6 +294,8 @@ impl Generator {
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache_max_size` - Maximum cache size allowed
     /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `target_distribution` - Target proportions for the values of a single column.
+    /// If `None` the original distribution is kept
     /// * `progress_reporter` - Will be used to report the processing
     /// progress (`ReportProgress` trait). If `None`, nothing will be reported
     pub fn generate_unseeded<T>(
@@ -110,6 +304,7 @@ impl Generator {
         resolution: usize,
         cache_max_size: usize,
         empty_value: &str,
+        target_distribution: Option<TargetDistribution>,
         progress_reporter: &mut Option<T>,
     ) -> StoppableResult<GeneratedData>
     where
@@ -120,20 +315,29 @@ impl Generator {
         info!("starting unseeded generation...");
 
         let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
         let mut synth = UnseededSynthesizer::new(
             data_block.clone(),
             Arc::new(data_block.calc_attr_rows_by_column_with_empty_values(&empty_value_arc)),
             resolution,
             cache_max_size,
             empty_value_arc.clone(),
+            constraints_enforcer.clone(),
+            target_distribution,
         );
 
+        let (synthesized_records, unique_copies_filter) =
+            self.reject_unique_copies_if_necessary(data_block, synth.run(progress_reporter)?);
+
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
             data_block.number_of_records(),
-            synth.run(progress_reporter)?,
+            synthesized_records,
             empty_value_arc,
+            constraints_enforcer,
+            unique_copies_filter,
         ))
     }
 
@@ -164,20 +368,75 @@ impl Generator {
         info!("starting value seeded generation...");
 
         let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
         let mut synth = ValueSeededSynthesizer::new(
             data_block.clone(),
             data_block.calc_attr_rows(),
             resolution,
             cache_max_size,
             oversampling_parameters,
+            constraints_enforcer.clone(),
         );
 
+        let (synthesized_records, unique_copies_filter) =
+            self.reject_unique_copies_if_necessary(data_block, synth.run(progress_reporter)?);
+
         Ok(self.build_generated_data(
             &data_block.headers,
             data_block.multi_value_column_metadata_map.clone(),
             data_block.number_of_records(),
+            synthesized_records,
+            empty_value_arc,
+            constraints_enforcer,
+            unique_copies_filter,
+        ))
+    }
+
+    /// Synthesize data using the value seeded method, sampling from the
+    /// aggregate counts instead of the sensitive data (no microdata is needed,
+    /// so unique copies can not be rejected)
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data where data should be synthesized from
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `oversampling_parameters` - Parameters used to control oversampling
+    /// (if `None`, allow unlimited oversampling)
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn generate_value_seeded_from_aggregates<T>(
+        &self,
+        aggregated_data: Arc<AggregatedData>,
+        resolution: usize,
+        empty_value: &str,
+        oversampling_parameters: Option<OversamplingParameters>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<GeneratedData>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("value seeded generation");
+
+        info!("starting value seeded generation from aggregates...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&aggregated_data.headers, &empty_value_arc);
+        let mut synth = ValueSeededSynthesizer::new_from_aggregates(
+            aggregated_data.clone(),
+            resolution,
+            oversampling_parameters,
+            constraints_enforcer.clone(),
+        );
+
+        Ok(self.build_generated_data(
+            &aggregated_data.headers,
+            aggregated_data.multi_value_column_metadata_map.clone(),
+            aggregated_data.number_of_records,
             synth.run(progress_reporter)?,
             empty_value_arc,
+            constraints_enforcer,
+            None,
         ))
     }
 
@@ -193,6 +452,9 @@ impl Generator {
     /// If `None` use raw counts
     /// * `target_number_of_records` - Total number of records to be synthesized.
     /// If `None` sample from all available counts
+    /// * `target_distribution` - Target proportions for the values of a single column,
+    /// the aggregate counts conditioned on each class are scaled before synthesis.
+    /// If `None` the original distribution is kept
     /// * `progress_reporter` - Will be used to report the processing
     /// progress (`ReportProgress` trait). If `None`, nothing will be reported
     #[allow(clippy::too_many_arguments)]
@@ -204,23 +466,29 @@ impl Generator {
         weight_selection_percentile: Option<usize>,
         aggregate_counts_scale_factor: Option<f64>,
         target_number_of_records: Option<usize>,
+        target_distribution: Option<TargetDistribution>,
         progress_reporter: &mut Option<T>,
     ) -> StoppableResult<GeneratedData>
     where
         T: ReportProgress,
     {
         let _duration_logger = ElapsedDurationLogger::new("aggregate seeded generation");
-        let scaled_aggregated_data =
-            Self::scale_aggregates_if_necessary(aggregated_data, aggregate_counts_scale_factor);
+        let scaled_aggregated_data = Self::scale_aggregates_if_necessary(
+            Self::rebalance_aggregates_if_necessary(aggregated_data, target_distribution),
+            aggregate_counts_scale_factor,
+        );
 
         info!("starting aggregate seeded generation...");
 
         let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer = self
+            .build_aggregate_seeded_constraints_enforcer(&scaled_aggregated_data, &empty_value_arc);
         let mut synth = AggregateSeededSynthesizer::new(
             scaled_aggregated_data.clone(),
             use_synthetic_counts,
             weight_selection_percentile,
             target_number_of_records,
+            constraints_enforcer.clone(),
         );
 
         Ok(self.build_generated_data(
@@ -229,11 +497,409 @@ impl Generator {
                 .multi_value_column_metadata_map
                 .clone(),
             scaled_aggregated_data.number_of_records,
-            synth.run(progress_reporter)?,
+            if self.parallel_aggregate_seeded {
+                synth.run_on_available_threads(progress_reporter)?
+            } else {
+                synth.run(progress_reporter)?
+            },
             empty_value_arc,
+            constraints_enforcer,
+            None,
+        ))
+    }
+
+    /// Fits the model used by the marginal based synthesis with differential privacy.
+    /// The sensitive data is only accessed here, data can then be sampled
+    /// from the model as many times as needed with `generate_dp_marginal`.
+    /// Returns an error if the DP parameters or the data can not be used
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `dp_marginal_parameters` - Differential privacy parameters
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn fit_dp_marginal<T>(
+        &self,
+        data_block: &Arc<DataBlock>,
+        dp_marginal_parameters: DpMarginalParameters,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<DpMarginalModel, DpMarginalError>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("dp marginal fitting");
+
+        info!("starting dp marginal fitting...");
+
+        DpMarginalSynthesizer::new(data_block.clone(), dp_marginal_parameters)
+            .fit(progress_reporter)
+    }
+
+    /// Synthesize data using the marginal based method with differential privacy
+    /// (synthesis constraints are not enforced on this mode)
+    /// # Arguments
+    /// * `dp_marginal_model` - Model fitted with `fit_dp_marginal`
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `target_number_of_records` - Total number of records to be synthesized.
+    /// If `None` the noisy number of records is used
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn generate_dp_marginal<T>(
+        &self,
+        dp_marginal_model: &DpMarginalModel,
+        empty_value: &str,
+        target_number_of_records: Option<usize>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<GeneratedData>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("dp marginal generation");
+
+        info!("starting dp marginal generation...");
+
+        Ok(self.build_generated_data(
+            dp_marginal_model.headers(),
+            dp_marginal_model.multi_value_column_metadata_map().clone(),
+            dp_marginal_model.estimated_number_of_records().max(1),
+            dp_marginal_model.sample(target_number_of_records, progress_reporter)?,
+            Arc::new(empty_value.to_owned()),
+            None,
+            None,
         ))
     }
 
+    /// Synthesize data using the row seeded method, writing the records
+    /// to `sink` in batches as soon as they are generated.
+    /// Records are not sorted and suppression is accounted on the fly
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `batch_size` - Number of records handed to the sink at once
+    /// * `sink` - Receives the headers and batches of synthetic records
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_row_seeded<S, T>(
+        &self,
+        data_block: &Arc<DataBlock>,
+        resolution: usize,
+        cache_max_size: usize,
+        empty_value: &str,
+        batch_size: usize,
+        sink: &mut S,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<StreamedDataSummary, SyntheticDataSinkError>
+    where
+        S: SyntheticDataSink,
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("row seeded streamed generation");
+
+        info!("starting row seeded streamed generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
+        let mut synth = RowSeededSynthesizer::new(
+            data_block.clone(),
+            Arc::new(data_block.calc_attr_rows()),
+            resolution,
+            cache_max_size,
+            constraints_enforcer.clone(),
+        );
+        let mut batcher =
+            SyntheticDataBatcher::new(sink, &data_block.headers, empty_value_arc, batch_size)?;
+        let mut unique_copies_filter = self.build_unique_copies_filter(data_block);
+
+        synth.stream(
+            progress_reporter,
+            &mut Self::reject_unique_copies_on_the_fly(&mut unique_copies_filter, |record| {
+                batcher.push(record)
+            }),
+        )?;
+
+        Ok(self.build_streamed_data_summary(
+            batcher.finish()?,
+            data_block.number_of_records(),
+            constraints_enforcer,
+            unique_copies_filter,
+        ))
+    }
+
+    /// Synthesize data using the unseeded method, writing the records
+    /// to `sink` in batches as soon as they are generated.
+    /// Records are not sorted
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `target_distribution` - Target proportions for the values of a single column.
+    /// If `None` the original distribution is kept
+    /// * `batch_size` - Number of records handed to the sink at once
+    /// * `sink` - Receives the headers and batches of synthetic records
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_unseeded<S, T>(
+        &self,
+        data_block: &Arc<DataBlock>,
+        resolution: usize,
+        cache_max_size: usize,
+        empty_value: &str,
+        target_distribution: Option<TargetDistribution>,
+        batch_size: usize,
+        sink: &mut S,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<StreamedDataSummary, SyntheticDataSinkError>
+    where
+        S: SyntheticDataSink,
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("unseeded streamed generation");
+
+        info!("starting unseeded streamed generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
+        let mut synth = UnseededSynthesizer::new(
+            data_block.clone(),
+            Arc::new(data_block.calc_attr_rows_by_column_with_empty_values(&empty_value_arc)),
+            resolution,
+            cache_max_size,
+            empty_value_arc.clone(),
+            constraints_enforcer.clone(),
+            target_distribution,
+        );
+        let mut batcher =
+            SyntheticDataBatcher::new(sink, &data_block.headers, empty_value_arc, batch_size)?;
+        let mut unique_copies_filter = self.build_unique_copies_filter(data_block);
+
+        synth.stream(
+            progress_reporter,
+            &mut Self::reject_unique_copies_on_the_fly(&mut unique_copies_filter, |record| {
+                batcher.push(record)
+            }),
+        )?;
+
+        Ok(self.build_streamed_data_summary(
+            batcher.finish()?,
+            data_block.number_of_records(),
+            constraints_enforcer,
+            unique_copies_filter,
+        ))
+    }
+
+    /// Synthesize data using the value seeded method, writing the records
+    /// to `sink` in batches as soon as they are generated.
+    /// Records are not sorted and suppression is accounted on the fly
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `cache_max_size` - Maximum cache size allowed
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `oversampling_parameters` - Parameters used to control oversampling
+    /// (if `None`, allow unlimited oversampling)
+    /// * `batch_size` - Number of records handed to the sink at once
+    /// * `sink` - Receives the headers and batches of synthetic records
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_value_seeded<S, T>(
+        &self,
+        data_block: &Arc<DataBlock>,
+        resolution: usize,
+        cache_max_size: usize,
+        empty_value: &str,
+        oversampling_parameters: Option<OversamplingParameters>,
+        batch_size: usize,
+        sink: &mut S,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<StreamedDataSummary, SyntheticDataSinkError>
+    where
+        S: SyntheticDataSink,
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("value seeded streamed generation");
+
+        info!("starting value seeded streamed generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&data_block.headers, &empty_value_arc);
+        let mut synth = ValueSeededSynthesizer::new(
+            data_block.clone(),
+            data_block.calc_attr_rows(),
+            resolution,
+            cache_max_size,
+            oversampling_parameters,
+            constraints_enforcer.clone(),
+        );
+        let mut batcher =
+            SyntheticDataBatcher::new(sink, &data_block.headers, empty_value_arc, batch_size)?;
+        let mut unique_copies_filter = self.build_unique_copies_filter(data_block);
+
+        synth.stream(
+            progress_reporter,
+            &mut Self::reject_unique_copies_on_the_fly(&mut unique_copies_filter, |record| {
+                batcher.push(record)
+            }),
+        )?;
+
+        Ok(self.build_streamed_data_summary(
+            batcher.finish()?,
+            data_block.number_of_records(),
+            constraints_enforcer,
+            unique_copies_filter,
+        ))
+    }
+
+    /// Synthesize data using the value seeded method from the aggregate counts,
+    /// writing the records to `sink` in batches as soon as they are generated.
+    /// Records are not sorted and suppression is accounted on the fly
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data where data should be synthesized from
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `oversampling_parameters` - Parameters used to control oversampling
+    /// (if `None`, allow unlimited oversampling)
+    /// * `batch_size` - Number of records handed to the sink at once
+    /// * `sink` - Receives the headers and batches of synthetic records
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_value_seeded_from_aggregates<S, T>(
+        &self,
+        aggregated_data: Arc<AggregatedData>,
+        resolution: usize,
+        empty_value: &str,
+        oversampling_parameters: Option<OversamplingParameters>,
+        batch_size: usize,
+        sink: &mut S,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<StreamedDataSummary, SyntheticDataSinkError>
+    where
+        S: SyntheticDataSink,
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("value seeded streamed generation");
+
+        info!("starting value seeded streamed generation from aggregates...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer =
+            self.build_constraints_enforcer(&aggregated_data.headers, &empty_value_arc);
+        let mut synth = ValueSeededSynthesizer::new_from_aggregates(
+            aggregated_data.clone(),
+            resolution,
+            oversampling_parameters,
+            constraints_enforcer.clone(),
+        );
+        let mut batcher =
+            SyntheticDataBatcher::new(sink, &aggregated_data.headers, empty_value_arc, batch_size)?;
+
+        synth.stream(progress_reporter, &mut |record| batcher.push(record))?;
+
+        Ok(self.build_streamed_data_summary(
+            batcher.finish()?,
+            aggregated_data.number_of_records,
+            constraints_enforcer,
+            None,
+        ))
+    }
+
+    /// Synthesize data using the aggregate seeded method, writing the records
+    /// to `sink` in batches as soon as they are generated, so
+    /// large `target_number_of_records` do not need to fit in memory.
+    /// Records are not sorted
+    /// # Arguments
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `aggregated_data` - Aggregated data where data should be synthesized from
+    /// * `use_synthetic_counts` - Whether synthetic counts should be used to balance
+    /// the sampling process or not
+    /// * `weight_selection_percentile` - Percentile used for the weight selection
+    ///  (default of 95 if `None`)
+    /// * `aggregate_counts_scale_factor` - Multiplier for aggregate counts before synthesis.
+    /// If `None` use raw counts
+    /// * `target_number_of_records` - Total number of records to be synthesized.
+    /// If `None` sample from all available counts
+    /// * `target_distribution` - Target proportions for the values of a single column,
+    /// the aggregate counts conditioned on each class are scaled before synthesis.
+    /// If `None` the original distribution is kept
+    /// * `batch_size` - Number of records handed to the sink at once
+    /// * `sink` - Receives the headers and batches of synthetic records
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_aggregate_seeded<S, T>(
+        &self,
+        empty_value: &str,
+        aggregated_data: Arc<AggregatedData>,
+        use_synthetic_counts: bool,
+        weight_selection_percentile: Option<usize>,
+        aggregate_counts_scale_factor: Option<f64>,
+        target_number_of_records: Option<usize>,
+        target_distribution: Option<TargetDistribution>,
+        batch_size: usize,
+        sink: &mut S,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<StreamedDataSummary, SyntheticDataSinkError>
+    where
+        S: SyntheticDataSink,
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("aggregate seeded streamed generation");
+        let scaled_aggregated_data = Self::scale_aggregates_if_necessary(
+            Self::rebalance_aggregates_if_necessary(aggregated_data, target_distribution),
+            aggregate_counts_scale_factor,
+        );
+
+        info!("starting aggregate seeded streamed generation...");
+
+        let empty_value_arc = Arc::new(empty_value.to_owned());
+        let constraints_enforcer = self
+            .build_aggregate_seeded_constraints_enforcer(&scaled_aggregated_data, &empty_value_arc);
+        let mut synth = AggregateSeededSynthesizer::new(
+            scaled_aggregated_data.clone(),
+            use_synthetic_counts,
+            weight_selection_percentile,
+            target_number_of_records,
+            constraints_enforcer.clone(),
+        );
+        let mut batcher = SyntheticDataBatcher::new(
+            sink,
+            &scaled_aggregated_data.headers,
+            empty_value_arc,
+            batch_size,
+        )?;
+
+        synth.stream(progress_reporter, &mut |record| batcher.push(record))?;
+
+        Ok(self.build_streamed_data_summary(
+            batcher.finish()?,
+            scaled_aggregated_data.number_of_records,
+            constraints_enforcer,
+            None,
+        ))
+    }
+
+    #[inline]
+    fn rebalance_aggregates_if_necessary(
+        aggregated_data: Arc<AggregatedData>,
+        target_distribution: Option<TargetDistribution>,
+    ) -> Arc<AggregatedData> {
+        if let Some(td) = target_distribution {
+            info!("rebalancing aggregate counts for column \"{}\"", td.column);
+
+            Arc::new(td.rebalance_aggregated_data(&aggregated_data))
+        } else {
+            aggregated_data
+        }
+    }
+
     #[inline]
     fn scale_aggregates_if_necessary(
         aggregated_data: Arc<AggregatedData>,
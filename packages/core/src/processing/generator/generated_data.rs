@@ -1,5 +1,4 @@
-use csv::Writer;
-use csv::WriterBuilder;
+use super::CsvSyntheticDataSink;
 use log::info;
 use std::{io::Write, sync::Arc};
 
@@ -10,9 +9,8 @@ use pyo3::prelude::*;
 use crate::data_block::CsvRecord;
 
 use crate::{
-    data_block::{
-        CsvIOError, DataBlock, MultiValueColumnMetadataMap, RawData, RawDataMultiValueColumnJoiner,
-    },
+    data_block::{CsvIOError, CsvRecordRef, DataBlock, MultiValueColumnMetadataMap, RawData},
+    processing::generator::synthesizers::SynthesisConstraintsReport,
     utils::time::ElapsedDurationLogger,
 };
 
@@ -25,6 +23,12 @@ pub struct GeneratedData {
     pub expansion_ratio: f64,
     /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
     pub multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    /// How many sampling choices were pruned by the synthesis constraints
+    /// (`None` if no constraints were used)
+    pub constraints_report: Option<SynthesisConstraintsReport>,
+    /// Number of synthesized records rejected for copying a unique sensitive
+    /// record (`None` if unique copies were not rejected)
+    pub number_of_rejected_unique_copies: Option<usize>,
 }
 
 impl Default for GeneratedData {
@@ -35,6 +39,8 @@ impl Default for GeneratedData {
             synthetic_data: RawData::default(),
             expansion_ratio: 0.0,
             multi_value_column_metadata_map: MultiValueColumnMetadataMap::default(),
+            constraints_report: None,
+            number_of_rejected_unique_copies: None,
         }
     }
 }
@@ -46,16 +52,24 @@ impl GeneratedData {
     /// * `expansion_ratio` - `Synthetic data length / Sensitive data length` (header not included)
     /// * `multi_value_column_metadata_map` - Maps a normalized multi-value header name (such as A_a1)
     /// to its corresponding metadata
+    /// * `constraints_report` - How many sampling choices were pruned by the synthesis constraints
+    /// (`None` if no constraints were used)
+    /// * `number_of_rejected_unique_copies` - Number of synthesized records rejected for
+    /// copying a unique sensitive record (`None` if unique copies were not rejected)
     #[inline]
     pub fn new(
         synthetic_data: RawData,
         expansion_ratio: f64,
         multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+        constraints_report: Option<SynthesisConstraintsReport>,
+        number_of_rejected_unique_copies: Option<usize>,
     ) -> GeneratedData {
         GeneratedData {
             synthetic_data,
             expansion_ratio,
             multi_value_column_metadata_map,
+            constraints_report,
+            number_of_rejected_unique_copies,
         }
     }
 
@@ -68,79 +82,72 @@ impl GeneratedData {
         join_multi_value_columns: bool,
         long_form: bool,
     ) -> Result<(), CsvIOError> {
-        let mut wtr = WriterBuilder::new()
-            .delimiter(delimiter as u8)
-            .from_writer(writer);
-        let joined_synthetic_data;
-
-        let synthetic_data = if join_multi_value_columns {
-            joined_synthetic_data = RawDataMultiValueColumnJoiner::new(
-                &self.synthetic_data,
-                &self.multi_value_column_metadata_map,
-                &Arc::new(empty_value.to_owned()),
-            )
-            .join();
-            &joined_synthetic_data
-        } else {
-            &self.synthetic_data
-        };
-
-        // write header and records
-        if long_form {
-            self._write_synthetic_data_long_format(&mut wtr, synthetic_data, empty_value)
-        } else {
-            self._write_synthetic_data_raw_format(&mut wtr, synthetic_data)
-        }
+        self._write_synthetic_data_page(
+            writer,
+            0,
+            self.number_of_synthetic_records(),
+            delimiter,
+            empty_value,
+            join_multi_value_columns,
+            long_form,
+        )
     }
 
     #[inline]
-    fn _write_synthetic_data_long_format<T: Write>(
+    #[allow(clippy::too_many_arguments)]
+    fn _write_synthetic_data_page<T: Write>(
         &self,
-        wtr: &mut Writer<&mut T>,
-        synthetic_data: &RawData,
+        writer: &mut T,
+        offset: usize,
+        limit: usize,
+        delimiter: char,
         empty_value: &str,
+        join_multi_value_columns: bool,
+        long_form: bool,
     ) -> Result<(), CsvIOError> {
-        let col_headers = &synthetic_data[0];
-        let long_form_headers = ["Id", "Attribute", "Value", "AttributeValue"];
-
-        match wtr.write_record(long_form_headers) {
-            Ok(_) => {}
-            Err(err) => return Err(CsvIOError::new(err)),
-        };
-
-        for (row_idx, r) in synthetic_data.iter().skip(1).enumerate() {
-            for (col_idx, value) in r.iter().enumerate() {
-                // do not write empty values to long format
-                if (**value) != *empty_value {
-                    let long_form_row = [
-                        &row_idx.to_string(),
-                        &col_headers[col_idx],
-                        value,
-                        &format!("{}:{}", col_headers[col_idx], value),
-                    ];
-                    match wtr.write_record(long_form_row) {
-                        Ok(_) => {}
-                        Err(err) => return Err(CsvIOError::new(err)),
-                    };
-                }
-            }
+        let mut sink = CsvSyntheticDataSink::new(
+            writer,
+            delimiter,
+            empty_value,
+            self.multi_value_column_metadata_map.clone(),
+            join_multi_value_columns,
+            long_form,
+        );
+
+        if let Some(headers) = self.synthetic_data.first() {
+            // keep the long form ids consistent across pages
+            sink.set_next_record_id(offset);
+            sink.write_csv_headers(headers)?;
+            sink.write_csv_records(self.synthetic_records_page(offset, limit))?;
         }
         Ok(())
     }
 
     #[inline]
-    fn _write_synthetic_data_raw_format<T: Write>(
-        &self,
-        wtr: &mut Writer<&mut T>,
-        synthetic_data: &RawData,
-    ) -> Result<(), CsvIOError> {
-        for r in synthetic_data.iter() {
-            match wtr.write_record(r.iter().map(|v| v.as_str())) {
-                Ok(_) => {}
-                Err(err) => return Err(CsvIOError::new(err)),
-            };
+    fn synthetic_records_page(&self, offset: usize, limit: usize) -> &[CsvRecordRef] {
+        // records start at index 1
+        let start = (offset + 1).min(self.synthetic_data.len());
+        let end = start.saturating_add(limit).min(self.synthetic_data.len());
+
+        &self.synthetic_data[start..end]
+    }
+
+    /// Returns a page of the synthetic data, where the first
+    /// entry is the headers, followed by at most `limit` records
+    /// starting from the record at `offset`
+    /// # Arguments
+    /// * `offset` - Index of the first record in the page (headers not included)
+    /// * `limit` - Maximum number of records in the page
+    pub fn synthetic_data_page(&self, offset: usize, limit: usize) -> RawData {
+        match self.synthetic_data.first() {
+            Some(headers) => {
+                let mut page = vec![headers.clone()];
+
+                page.extend(self.synthetic_records_page(offset, limit).iter().cloned());
+                page
+            }
+            None => RawData::default(),
         }
-        Ok(())
     }
 }
 
@@ -164,6 +171,14 @@ impl GeneratedData {
         self.expansion_ratio
     }
 
+    #[cfg(feature = "pyo3")]
+    #[getter]
+    /// Number of synthesized records rejected for copying a unique sensitive
+    /// record (`None` if unique copies were not rejected)
+    fn number_of_rejected_unique_copies(&self) -> Option<usize> {
+        self.number_of_rejected_unique_copies
+    }
+
     /// Writes the synthesized data to the file system
     /// # Arguments
     /// * `path` - File path to be written
@@ -222,6 +237,66 @@ impl GeneratedData {
         Ok(String::from_utf8_lossy(&csv_data).to_string())
     }
 
+    /// Number of synthetic records (headers not included)
+    pub fn number_of_synthetic_records(&self) -> usize {
+        self.synthetic_data.len().saturating_sub(1)
+    }
+
+    /// Generates a CSV string from a page of the synthetic data,
+    /// so large results can be retrieved in smaller pieces
+    /// # Arguments
+    /// * `offset` - Index of the first record in the page (headers not included)
+    /// * `limit` - Maximum number of records in the page
+    /// * `delimiter` - CSV delimiter to use
+    /// * `empty_value` - Empty values will be replaced by this
+    /// * `join_multi_value_columns` - Whether multi value columns should be joined back together or not
+    /// * `long_form` - Pivots column headers and value pairs to key-value row entries.
+    pub fn synthetic_data_page_to_string(
+        &self,
+        offset: usize,
+        limit: usize,
+        delimiter: char,
+        empty_value: &str,
+        join_multi_value_columns: bool,
+        long_form: bool,
+    ) -> Result<String, CsvIOError> {
+        let mut csv_data = Vec::default();
+
+        self._write_synthetic_data_page(
+            &mut csv_data,
+            offset,
+            limit,
+            delimiter,
+            empty_value,
+            join_multi_value_columns,
+            long_form,
+        )?;
+
+        Ok(String::from_utf8_lossy(&csv_data).to_string())
+    }
+
+    /// Clones a page of the raw synthetic data to a `Vec<Vec<String>>`,
+    /// where the first entry is the headers
+    /// # Arguments
+    /// * `offset` - Index of the first record in the page (headers not included)
+    /// * `limit` - Maximum number of records in the page
+    /// * `empty_value` - Empty values will be replaced by this
+    /// * `join_multi_value_columns` - Whether multi value columns should be joined back together or not
+    pub fn synthetic_data_page_to_vec(
+        &self,
+        offset: usize,
+        limit: usize,
+        empty_value: &str,
+        join_multi_value_columns: bool,
+    ) -> Vec<Vec<String>> {
+        DataBlock::raw_data_to_vec(
+            &self.synthetic_data_page(offset, limit),
+            &Arc::new(empty_value.to_owned()),
+            &self.multi_value_column_metadata_map,
+            join_multi_value_columns,
+        )
+    }
+
     /// Clones the raw synthetic data to a `Vec<Vec<String>>`,
     /// where the first entry is the headers
     /// # Arguments
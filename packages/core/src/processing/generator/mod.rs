@@ -1,13 +1,23 @@
+mod csv_synthetic_data_sink;
 mod data_generator;
 mod generated_data;
+mod streamed_data_summary;
 mod synthesizers;
+mod synthetic_data_sink;
+mod synthetic_data_sink_error;
+mod unique_copies_filter;
 
 #[cfg(feature = "pyo3")]
 mod register_pyo3;
 
+pub use csv_synthetic_data_sink::*;
 pub use data_generator::*;
 pub use generated_data::*;
+pub use streamed_data_summary::*;
 pub use synthesizers::*;
+pub use synthetic_data_sink::*;
+pub use synthetic_data_sink_error::*;
+pub use unique_copies_filter::*;
 
 #[cfg(feature = "pyo3")]
 pub use register_pyo3::*;
@@ -1,7 +1,8 @@
-use super::OversamplingParameters;
+use super::{OversamplingParameters, TargetDistribution};
 use pyo3::{types::PyModule, PyResult, Python};
 
 pub fn register_pyo3(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<OversamplingParameters>()?;
+    m.add_class::<TargetDistribution>()?;
     Ok(())
 }
@@ -0,0 +1,41 @@
+use crate::processing::generator::synthesizers::SynthesisConstraintsReport;
+
+/// Summary of a generation that streamed the synthetic records
+/// to a sink instead of keeping them in memory
+pub struct StreamedDataSummary {
+    /// Number of synthetic records written to the sink
+    pub number_of_records: usize,
+    /// `Synthetic data length / Sensitive data length` (header not included)
+    pub expansion_ratio: f64,
+    /// How many sampling choices were pruned by the synthesis constraints
+    /// (`None` if no constraints were used)
+    pub constraints_report: Option<SynthesisConstraintsReport>,
+    /// Number of synthesized records rejected for copying a unique sensitive
+    /// record (`None` if unique copies were not rejected)
+    pub number_of_rejected_unique_copies: Option<usize>,
+}
+
+impl StreamedDataSummary {
+    /// Returns a new StreamedDataSummary
+    /// # Arguments
+    /// * `number_of_records` - Number of synthetic records written to the sink
+    /// * `expansion_ratio` - `Synthetic data length / Sensitive data length` (header not included)
+    /// * `constraints_report` - How many sampling choices were pruned by the synthesis constraints
+    /// (`None` if no constraints were used)
+    /// * `number_of_rejected_unique_copies` - Number of synthesized records rejected for
+    /// copying a unique sensitive record (`None` if unique copies were not rejected)
+    #[inline]
+    pub fn new(
+        number_of_records: usize,
+        expansion_ratio: f64,
+        constraints_report: Option<SynthesisConstraintsReport>,
+        number_of_rejected_unique_copies: Option<usize>,
+    ) -> StreamedDataSummary {
+        StreamedDataSummary {
+            number_of_records,
+            expansion_ratio,
+            constraints_report,
+            number_of_rejected_unique_copies,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use rand::{prelude::SliceRandom, thread_rng};
+use std::{cmp::Reverse, sync::Arc};
+
+use crate::processing::aggregator::{AggregatedCount, AggregatedData, ValueCombination};
+
+/// Splits `count` into `n_parts`, returning the share of `part`.
+/// The remainder goes to the first parts, so a count that is smaller or
+/// equal than another will never get a bigger share for the same part
+#[inline]
+pub fn split_count(count: usize, n_parts: usize, part: usize) -> usize {
+    count / n_parts + usize::from(part < count % n_parts)
+}
+
+/// Partitions the aggregated data into `n_parts`, splitting every
+/// aggregate count (and the number of records) across the parts.
+/// Combinations whose share is zero are not included in the part
+/// # Arguments
+/// * `aggregated_data` - Aggregated data to be partitioned
+/// * `n_parts` - Number of parts
+pub fn partition_aggregated_data(
+    aggregated_data: &AggregatedData,
+    n_parts: usize,
+) -> Vec<Arc<AggregatedData>> {
+    let shares_by_comb = split_aggregates_count(aggregated_data, n_parts);
+
+    (0..n_parts)
+        .map(|part| {
+            Arc::new(AggregatedData::new(
+                aggregated_data.headers.clone(),
+                aggregated_data.multi_value_column_metadata_map.clone(),
+                split_count(aggregated_data.number_of_records, n_parts, part),
+                aggregated_data
+                    .protected_number_of_records
+                    .map(|n| split_count(n, n_parts, part)),
+                shares_by_comb
+                    .iter()
+                    .filter_map(|(comb, shares)| {
+                        if shares[part] > 0 {
+                            Some((
+                                comb.clone(),
+                                AggregatedCount {
+                                    count: shares[part],
+                                    ..Default::default()
+                                },
+                            ))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect(),
+                // sensitivities are not needed for synthesis
+                Default::default(),
+                aggregated_data.reporting_length,
+            ))
+        })
+        .collect()
+}
+
+/// Splits every aggregate count into `n_parts`, returning the share of each part.
+/// Every part gets `count / n_parts`, and the remainder goes to the parts with the
+/// most room left on the sub-combinations (ties are broken randomly), so rare
+/// combinations are spread across all the parts and still kept with their
+/// sub-combinations. The room of a sub-combination on a part is its share minus what
+/// was already taken by the combinations extending it with a value of the same column
+/// (values of the same column can not be on the same record)
+fn split_aggregates_count(
+    aggregated_data: &AggregatedData,
+    n_parts: usize,
+) -> FnvHashMap<Arc<ValueCombination>, Vec<usize>> {
+    let mut rng = thread_rng();
+    let mut shares_by_comb: FnvHashMap<Arc<ValueCombination>, Vec<usize>> = FnvHashMap::default();
+    let mut taken_by_extension: FnvHashMap<(Arc<ValueCombination>, usize), Vec<usize>> =
+        FnvHashMap::default();
+    let mut aggregates_count = aggregated_data.aggregates_count.iter().collect_vec();
+
+    // sub-combinations are split before the combinations containing them
+    aggregates_count.shuffle(&mut rng);
+    aggregates_count.sort_by_key(|(comb, _)| comb.len());
+
+    for (comb, count) in aggregates_count {
+        let mut shares = vec![count.count / n_parts; n_parts];
+        let extended: Vec<(Arc<ValueCombination>, usize)> = if comb.len() > 1 {
+            (0..comb.len())
+                .filter_map(|skip| {
+                    shares_by_comb
+                        .get_key_value(&ValueCombination::new(
+                            comb.iter()
+                                .enumerate()
+                                .filter(|(i, _)| *i != skip)
+                                .map(|(_, v)| v.clone())
+                                .collect(),
+                        ))
+                        .map(|(sub_comb, _)| (sub_comb.clone(), comb[skip].column_index))
+                })
+                .collect()
+        } else {
+            Vec::default()
+        };
+        let room_on_part = |part: usize| {
+            extended
+                .iter()
+                .map(|(sub_comb, column_index)| {
+                    shares_by_comb[sub_comb][part].saturating_sub(
+                        taken_by_extension
+                            .get(&(sub_comb.clone(), *column_index))
+                            .map_or(0, |taken| taken[part]),
+                    )
+                })
+                .min()
+                .unwrap_or(usize::MAX)
+        };
+        let mut parts = (0..n_parts).collect_vec();
+
+        parts.shuffle(&mut rng);
+        parts.sort_by_key(|part| Reverse(room_on_part(*part)));
+
+        for part in parts.into_iter().take(count.count % n_parts) {
+            shares[part] += 1;
+        }
+        for key in extended {
+            let taken = taken_by_extension
+                .entry(key)
+                .or_insert_with(|| vec![0; n_parts]);
+
+            for (t, share) in taken.iter_mut().zip(shares.iter()) {
+                *t += share;
+            }
+        }
+        shares_by_comb.insert(comb.clone(), shares);
+    }
+    shares_by_comb
+}
+
+/// Splits `total` proportionally to `weights`, the remainder of
+/// the integer division goes to the first parts
+/// # Arguments
+/// * `total` - Value to be split
+/// * `weights` - Weight of each part
+pub fn split_proportionally(total: usize, weights: &[usize]) -> Vec<usize> {
+    let total_weight = weights.iter().sum::<usize>();
+
+    if total_weight == 0 {
+        return (0..weights.len())
+            .map(|part| split_count(total, weights.len(), part))
+            .collect();
+    }
+
+    let mut shares: Vec<usize> = weights
+        .iter()
+        .map(|w| ((total as f64) * (*w as f64) / (total_weight as f64)).floor() as usize)
+        .collect();
+    let mut remainder = total - shares.iter().sum::<usize>();
+
+    for share in shares.iter_mut() {
+        if remainder == 0 {
+            break;
+        }
+        *share += 1;
+        remainder -= 1;
+    }
+    shares
+}
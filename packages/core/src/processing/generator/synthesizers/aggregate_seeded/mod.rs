@@ -1,3 +1,9 @@
+#[cfg(feature = "rayon")]
+mod aggregated_data_partition;
+
+#[cfg(feature = "rayon")]
+mod worker_progress_reporter;
+
 use itertools::Itertools;
 use statrs::statistics::{Data, OrderStatistics};
 use std::sync::Arc;
@@ -8,7 +14,8 @@ use crate::{
         aggregator::{AggregatedData, ValueCombination},
         generator::synthesizers::{
             consolidate_parameters::ConsolidateParameters,
-            traits::{Consolidate, ConsolidateContext, SynthesisData},
+            constraints::SynthesisConstraintsEnforcer,
+            traits::{Consolidate, ConsolidateContext, Suppress, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
                 SynthesizedRecords, SynthesizedRecordsSlice,
@@ -18,10 +25,31 @@ use crate::{
     utils::{
         collections::{sample_weighted, sort_unstable_f64},
         math::calc_percentage,
-        reporting::{ReportProgress, StoppableResult},
+        reporting::{ProcessingStoppedError, ReportProgress, StoppableResult},
     },
 };
 
+#[cfg(feature = "rayon")]
+use aggregated_data_partition::{partition_aggregated_data, split_proportionally};
+
+#[cfg(feature = "rayon")]
+use log::info;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use std::sync::Mutex;
+
+#[cfg(feature = "rayon")]
+use worker_progress_reporter::WorkerProgressReporter;
+
+#[cfg(feature = "rayon")]
+use crate::utils::{
+    collections::flat_map_unwrap_or_default, reporting::SendableProgressReporter,
+    threading::get_number_of_threads, time::ElapsedDurationLogger,
+};
+
 const DEFAULT_WEIGHT_SELECTION_PERCENTILE: usize = 95;
 
 /// Represents all the information required to perform aggregated
@@ -46,12 +74,15 @@ impl AggregateSeededSynthesizer {
     /// the sampling process or not
     /// * `target_number_of_records` - Total number of records to be synthesized.
     /// If `None` sample from all available counts
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
     #[inline]
     pub fn new(
         aggregated_data: Arc<AggregatedData>,
         use_synthetic_counts: bool,
         weight_selection_percentile: Option<usize>,
         target_number_of_records: Option<usize>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     ) -> AggregateSeededSynthesizer {
         AggregateSeededSynthesizer {
             single_attr_counts: aggregated_data.calc_single_attribute_counts(),
@@ -63,13 +94,14 @@ impl AggregateSeededSynthesizer {
                 None,
                 target_number_of_records,
                 use_synthetic_counts,
+                constraints_enforcer,
             ),
             consolidate_percentage: 0.0,
         }
     }
 
     /// Performs the synthesis from the aggregates, including the consolidation and suppression
-    /// steps only
+    /// steps only.
     /// Returns the synthesized records
     /// # Arguments
     /// * `progress_reporter` - Will be used to report the processing
@@ -100,6 +132,170 @@ impl AggregateSeededSynthesizer {
         Ok(synthesized_records)
     }
 
+    /// Same as `run`, but when the `rayon` feature is enabled and more than one
+    /// thread is available, the record budget is split across parallel workers
+    /// (see `run_parallel`). This is faster for large targets, but each worker only
+    /// sees its own partition of the aggregates, so fewer rare combinations
+    /// are preserved than with `run`
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn run_on_available_threads<T>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<SynthesizedRecords>
+    where
+        T: ReportProgress,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            let n_workers = self.calc_number_of_workers();
+
+            if n_workers > 1 {
+                return self.run_parallel(n_workers, progress_reporter);
+            }
+        }
+        self.run(progress_reporter)
+    }
+
+    /// Splits the aggregate counts and the target number of records across
+    /// `n_workers`, each one consolidating records with its own partition
+    /// (and its own synthetic counts) in parallel.
+    /// When sampling from all available counts, the merged records are
+    /// reconciled at the end, so no attribute exceeds its aggregate count.
+    /// Returns the synthesized records
+    /// # Arguments
+    /// * `n_workers` - Number of parallel workers
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    #[cfg(feature = "rayon")]
+    pub fn run_parallel<T>(
+        &mut self,
+        n_workers: usize,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<SynthesizedRecords>
+    where
+        T: ReportProgress,
+    {
+        let _duration_logger = ElapsedDurationLogger::new("parallel aggregate seeded synthesis");
+
+        if self
+            .consolidate_parameters
+            .aggregated_data
+            .number_of_records
+            == 0
+        {
+            return Ok(SynthesizedRecords::new());
+        }
+
+        info!(
+            "synthesizing from aggregates with {} parallel worker(s)...",
+            n_workers
+        );
+
+        let mut workers = self.build_workers(n_workers);
+        let sendable_pr =
+            Arc::new(Mutex::new(progress_reporter.as_mut().map(|r| {
+                SendableProgressReporter::new(100.0 * (workers.len() as f64), 1.0, r)
+            })));
+        let mut synthesized_records = flat_map_unwrap_or_default(
+            workers
+                .par_iter_mut()
+                .map(|worker| {
+                    worker.run(&mut Some(WorkerProgressReporter::new(sendable_pr.clone())))
+                })
+                .collect(),
+        )?;
+
+        if self
+            .consolidate_parameters
+            .target_number_of_records
+            .is_none()
+        {
+            info!("reconciling parallel workers results...");
+            self.suppress(&mut synthesized_records, &mut None::<T>)?;
+        }
+        Ok(synthesized_records)
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn calc_number_of_workers(&self) -> usize {
+        // every worker should have at least one attribute to sample from
+        get_number_of_threads().min(self.single_attr_counts.values().max().cloned().unwrap_or(0))
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn build_workers(&self, n_workers: usize) -> Vec<AggregateSeededSynthesizer> {
+        let partitions =
+            partition_aggregated_data(&self.consolidate_parameters.aggregated_data, n_workers);
+        let target_shares = self
+            .consolidate_parameters
+            .target_number_of_records
+            .map(|target| {
+                // split the budget proportionally to the attributes
+                // available to each worker
+                split_proportionally(
+                    target,
+                    &partitions
+                        .iter()
+                        .map(|p| p.calc_single_attribute_counts().values().sum())
+                        .collect::<Vec<usize>>(),
+                )
+            });
+
+        partitions
+            .into_iter()
+            .enumerate()
+            .map(|(i, partition)| {
+                AggregateSeededSynthesizer::new(
+                    partition,
+                    self.consolidate_parameters.use_synthetic_counts,
+                    Some(self.weight_selection_percentile),
+                    target_shares.as_ref().map(|shares| shares[i]),
+                    self.consolidate_parameters.constraints_enforcer.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Performs the same synthesis as `run`, but instead of collecting the records
+    /// hands every synthesized record to `on_record` as soon as it is ready
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    /// * `on_record` - Called for every synthesized record
+    pub fn stream<T, E, F>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+        on_record: &mut F,
+    ) -> Result<(), E>
+    where
+        T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
+    {
+        if self
+            .consolidate_parameters
+            .aggregated_data
+            .number_of_records
+            > 0
+        {
+            let consolidate_context = self.build_consolidate_context(&[]);
+
+            self.consolidate_percentage = 0.0;
+
+            self.consolidate_from_context(
+                consolidate_context,
+                progress_reporter,
+                self.consolidate_parameters.clone(),
+                on_record,
+            )?;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn calc_weight_for_single_combination(
         &self,
@@ -279,3 +475,20 @@ impl Consolidate for AggregateSeededSynthesizer {
             .unwrap_or_else(|| Ok(()))
     }
 }
+
+impl Suppress for AggregateSeededSynthesizer {
+    #[inline]
+    fn update_suppress_progress<T>(
+        &mut self,
+        _n_processed: usize,
+        _total: f64,
+        _progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<()>
+    where
+        T: ReportProgress,
+    {
+        // suppression is only used to reconcile the parallel
+        // workers results, so the progress is already complete
+        Ok(())
+    }
+}
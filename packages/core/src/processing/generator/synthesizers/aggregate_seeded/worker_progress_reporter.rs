@@ -0,0 +1,44 @@
+use crate::utils::reporting::{
+    ReportProgress, SendableProgressReporter, SendableProgressReporterRef, StoppableResult,
+};
+
+/// Receives the percentage reported by a single synthesis worker
+/// and forwards only the progress made since the last report
+/// to the shared reporter
+pub struct WorkerProgressReporter<'main_reporter, T>
+where
+    T: ReportProgress,
+{
+    shared_reporter: SendableProgressReporterRef<'main_reporter, T>,
+    last_percentage: f64,
+}
+
+impl<'main_reporter, T> WorkerProgressReporter<'main_reporter, T>
+where
+    T: ReportProgress,
+{
+    /// Returns a new WorkerProgressReporter
+    /// # Arguments
+    /// * `shared_reporter` - Reporter shared by all the workers
+    #[inline]
+    pub fn new(
+        shared_reporter: SendableProgressReporterRef<'main_reporter, T>,
+    ) -> WorkerProgressReporter<'main_reporter, T> {
+        WorkerProgressReporter {
+            shared_reporter,
+            last_percentage: 0.0,
+        }
+    }
+}
+
+impl<'main_reporter, T> ReportProgress for WorkerProgressReporter<'main_reporter, T>
+where
+    T: ReportProgress,
+{
+    fn report(&mut self, new_progress: f64) -> StoppableResult<()> {
+        let value_to_add = new_progress - self.last_percentage;
+
+        self.last_percentage = new_progress;
+        SendableProgressReporter::update_progress(&mut self.shared_reporter, value_to_add)
+    }
+}
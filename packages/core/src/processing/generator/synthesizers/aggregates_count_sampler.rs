@@ -0,0 +1,93 @@
+use super::typedefs::{
+    AttributeCountMap, NotAllowedAttrSet, SynthesizedRecord, SynthesizerSeedSlice,
+};
+use itertools::Itertools;
+use std::sync::Arc;
+
+use crate::data_block::DataBlockValue;
+use crate::processing::aggregator::{AggregatedData, ValueCombination};
+use crate::utils::collections::sample_weighted;
+
+/// Aggregate counts sampler. This will sample records from a seed,
+/// respecting the aggregate counts distributions, so no microdata is needed
+pub struct AggregatesCountSampler {
+    /// Aggregated data the counts are read from
+    aggregated_data: Arc<AggregatedData>,
+    /// Reporting resolution used for data synthesis
+    resolution: usize,
+}
+
+impl AggregatesCountSampler {
+    /// Returns a new AggregatesCountSampler
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data the counts are read from
+    /// * `resolution` - Reporting resolution used for data synthesis
+    #[inline]
+    pub fn new(aggregated_data: Arc<AggregatedData>, resolution: usize) -> AggregatesCountSampler {
+        AggregatesCountSampler {
+            aggregated_data,
+            resolution,
+        }
+    }
+
+    /// Samples the next attribute from the `current_seed` record.
+    /// Returns `None` if nothing more can be sampled.
+    /// # Arguments
+    /// * `synthesized_record` - Record synthesized so far
+    /// * `current_seed` - Current seed/record used for sampling
+    /// * `not_allowed_attr_set` - Attributes not allowed to be sampled
+    #[inline]
+    pub fn sample_next_attr_from_seed(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        current_seed: &SynthesizerSeedSlice,
+        not_allowed_attr_set: &NotAllowedAttrSet,
+    ) -> Option<Arc<DataBlockValue>> {
+        let counts: AttributeCountMap = current_seed
+            .iter()
+            .filter(|value| {
+                !not_allowed_attr_set.contains(*value)
+                    && !synthesized_record
+                        .iter()
+                        .any(|v| v.column_index == value.column_index)
+            })
+            .filter_map(|value| {
+                self.calc_extended_count(synthesized_record, value)
+                    .filter(|count| *count >= self.resolution)
+                    .map(|count| (value.clone(), count))
+            })
+            .collect();
+
+        sample_weighted(&counts)
+    }
+
+    /// Count of the `synthesized_record` extended with `value`. When the extended
+    /// record is longer than the reporting length, the smallest count among its
+    /// combinations of reporting length containing `value` is used instead
+    /// (upper bound of the actual count)
+    #[inline]
+    fn calc_extended_count(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        value: &Arc<DataBlockValue>,
+    ) -> Option<usize> {
+        let sub_comb_len = synthesized_record
+            .len()
+            .min(self.aggregated_data.reporting_length.saturating_sub(1));
+
+        synthesized_record
+            .iter()
+            .combinations(sub_comb_len)
+            .try_fold(usize::MAX, |min_count, sub_comb| {
+                let mut comb = ValueCombination::default();
+
+                for v in sub_comb.into_iter().chain(std::iter::once(value)) {
+                    comb.extend(v.clone(), &self.aggregated_data.headers);
+                }
+                self.aggregated_data
+                    .aggregates_count
+                    .get(&comb)
+                    .map(|count| min_count.min(count.count))
+            })
+    }
+}
@@ -1,3 +1,4 @@
+use super::constraints::SynthesisConstraintsEnforcer;
 use std::sync::Arc;
 
 use crate::processing::aggregator::AggregatedData;
@@ -17,6 +18,9 @@ pub struct ConsolidateParameters {
     /// Whether or not the synthetic counts should be used to
     /// control the sampling process aggregate seeded
     pub use_synthetic_counts: bool,
+    /// Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
+    pub constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
 }
 
 impl ConsolidateParameters {
@@ -29,6 +33,8 @@ impl ConsolidateParameters {
     /// If `None` sample from all available counts
     /// * `use_synthetic_counts` - Whether or not the synthetic counts should be used to
     /// control the aggregate seeded sampling process
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
     #[inline]
     pub fn new(
         aggregated_data: Arc<AggregatedData>,
@@ -36,6 +42,7 @@ impl ConsolidateParameters {
         oversampling_tries: Option<usize>,
         target_number_of_records: Option<usize>,
         use_synthetic_counts: bool,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     ) -> ConsolidateParameters {
         ConsolidateParameters {
             aggregated_data,
@@ -43,6 +50,7 @@ impl ConsolidateParameters {
             oversampling_tries,
             target_number_of_records,
             use_synthetic_counts,
+            constraints_enforcer,
         }
     }
 }
@@ -55,6 +63,7 @@ impl Default for ConsolidateParameters {
             oversampling_tries: None,
             target_number_of_records: None,
             use_synthetic_counts: false,
+            constraints_enforcer: None,
         }
     }
 }
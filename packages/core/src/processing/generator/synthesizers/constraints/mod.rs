@@ -0,0 +1,11 @@
+mod synthesis_constraint;
+mod synthesis_constraints;
+mod synthesis_constraints_enforcer;
+mod synthesis_constraints_error;
+mod synthesis_constraints_report;
+
+pub use synthesis_constraint::*;
+pub use synthesis_constraints::*;
+pub use synthesis_constraints_enforcer::*;
+pub use synthesis_constraints_error::*;
+pub use synthesis_constraints_report::*;
@@ -0,0 +1,88 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result};
+
+use crate::data_block::COLUMN_VALUE_DELIMITER;
+
+/// Attribute referenced by a synthesis constraint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintAttribute {
+    /// Column (header) name
+    pub column: String,
+    /// Attribute value for `column`
+    pub value: String,
+}
+
+impl ConstraintAttribute {
+    /// Returns a new ConstraintAttribute
+    /// # Arguments
+    /// * `column` - Column (header) name
+    /// * `value` - Attribute value for `column`
+    #[inline]
+    pub fn new(column: String, value: String) -> ConstraintAttribute {
+        ConstraintAttribute { column, value }
+    }
+}
+
+impl Display for ConstraintAttribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}{}{}", self.column, COLUMN_VALUE_DELIMITER, self.value)
+    }
+}
+
+/// Logical constraint that every synthesized record should respect
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SynthesisConstraint {
+    /// The attributes can never appear together in the same record
+    /// (e.g. `sex:male` and `pregnant:yes`), they should come from
+    /// at least two different columns
+    DenyCombination {
+        attributes: Vec<ConstraintAttribute>,
+    },
+    /// If all the `when` attributes are present, `then.column` can only
+    /// be `then.value` or empty (e.g. `age:<10` implies `marital:single`).
+    /// `when` should not be empty nor reference `then.column`
+    Implication {
+        when: Vec<ConstraintAttribute>,
+        then: ConstraintAttribute,
+    },
+    /// If all the `when` attributes are present, `column` can only
+    /// assume one of `values` or be empty.
+    /// `when` should not be empty nor reference `column`
+    AllowedValues {
+        when: Vec<ConstraintAttribute>,
+        column: String,
+        values: Vec<String>,
+    },
+}
+
+impl Display for SynthesisConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SynthesisConstraint::DenyCombination { attributes } => {
+                write!(f, "deny {}", attributes.iter().join(" & "))
+            }
+            SynthesisConstraint::Implication { when, then } => {
+                write!(f, "{} => {}", when.iter().join(" & "), then)
+            }
+            SynthesisConstraint::AllowedValues {
+                when,
+                column,
+                values,
+            } => {
+                if when.is_empty() {
+                    write!(f, "{} in [{}]", column, values.iter().join(", "))
+                } else {
+                    write!(
+                        f,
+                        "{} => {} in [{}]",
+                        when.iter().join(" & "),
+                        column,
+                        values.iter().join(", ")
+                    )
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::{SynthesisConstraint, SynthesisConstraintsEnforcer, SynthesisConstraintsError};
+use fnv::FnvHashMap;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+
+use crate::{data_block::DataBlockHeadersSlice, utils::time::ElapsedDurationLogger};
+
+/// Set of user declared logical constraints enforced during synthesis
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SynthesisConstraints {
+    /// Constraints every synthesized record should respect
+    pub constraints: Vec<SynthesisConstraint>,
+}
+
+impl SynthesisConstraints {
+    /// Returns a new SynthesisConstraints
+    /// # Arguments
+    /// * `constraints` - Constraints every synthesized record should respect
+    #[inline]
+    pub fn new(constraints: Vec<SynthesisConstraint>) -> SynthesisConstraints {
+        SynthesisConstraints { constraints }
+    }
+
+    /// Parses the constraints from a json string, such as:
+    /// ```json
+    /// {
+    ///   "constraints": [
+    ///     {
+    ///       "type": "denyCombination",
+    ///       "attributes": [
+    ///         { "column": "sex", "value": "male" },
+    ///         { "column": "pregnant", "value": "yes" }
+    ///       ]
+    ///     },
+    ///     {
+    ///       "type": "implication",
+    ///       "when": [{ "column": "age", "value": "<10" }],
+    ///       "then": { "column": "marital", "value": "single" }
+    ///     },
+    ///     {
+    ///       "type": "allowedValues",
+    ///       "when": [{ "column": "employment", "value": "student" }],
+    ///       "column": "income",
+    ///       "values": ["<10k", "10k-20k"]
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    /// # Arguments
+    /// * `json` - Json string to parse
+    pub fn from_json_str(json: &str) -> Result<SynthesisConstraints, SynthesisConstraintsError> {
+        serde_json::from_str(json)
+            .map_err(|err| SynthesisConstraintsError::ParsingError(err.to_string()))
+    }
+
+    /// Reads the constraints from a json file
+    /// (check `from_json_str` for the expected format)
+    /// # Arguments
+    /// * `file_path` - File path to read from
+    pub fn read_from_json(
+        file_path: &str,
+    ) -> Result<SynthesisConstraints, SynthesisConstraintsError> {
+        info!("reading file: {}", file_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("read synthesis constraints");
+
+        serde_json::from_reader(BufReader::new(
+            std::fs::File::open(file_path)
+                .map_err(|err| SynthesisConstraintsError::ParsingError(err.to_string()))?,
+        ))
+        .map_err(|err| SynthesisConstraintsError::ParsingError(err.to_string()))
+    }
+
+    /// Checks whether all the constraints are valid for the data `headers`
+    /// (every referenced column should exist and deny combinations
+    /// should not be empty)
+    /// # Arguments
+    /// * `headers` - Headers of the data being synthesized
+    pub fn validate(
+        &self,
+        headers: &DataBlockHeadersSlice,
+    ) -> Result<(), SynthesisConstraintsError> {
+        let column_index_by_name: FnvHashMap<&str, usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (h.as_str(), i))
+            .collect();
+
+        for constraint in self.constraints.iter() {
+            SynthesisConstraintsEnforcer::validate_constraint(constraint, &column_index_by_name)?;
+        }
+        Ok(())
+    }
+}
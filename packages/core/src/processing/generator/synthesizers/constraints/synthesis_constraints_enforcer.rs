@@ -0,0 +1,478 @@
+use super::{
+    ConstraintAttribute, ConstraintPruningCount, SynthesisConstraint, SynthesisConstraints,
+    SynthesisConstraintsError, SynthesisConstraintsReport,
+};
+use fnv::{FnvHashMap, FnvHashSet};
+use itertools::Itertools;
+use log::warn;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    data_block::{DataBlockHeadersSlice, DataBlockValue},
+    dp::DpColumnHierarchy,
+    processing::{
+        aggregator::AggregatedData,
+        generator::synthesizers::typedefs::{
+            NotAllowedAttrSet, SynthesizedRecord, SynthesizerSeedSlice,
+        },
+    },
+};
+
+/// Pair of values from two columns of the same hierarchy,
+/// ordered by column index
+type HierarchyValuePair = (Arc<DataBlockValue>, Arc<DataBlockValue>);
+
+/// Constraint resolved against the data headers
+struct ResolvedConstraint {
+    /// Attributes that should all be present for the constraint to apply
+    when: Vec<DataBlockValue>,
+    /// Column restricted by the constraint (`None` means the `when`
+    /// attributes themselves are denied)
+    restricted_column: Option<usize>,
+    /// Values allowed on `restricted_column`
+    allowed_values: FnvHashSet<String>,
+}
+
+/// Column hierarchy resolved against the aggregated data
+struct ResolvedHierarchy {
+    /// Index of the hierarchy on the descriptions and pruned counts
+    description_index: usize,
+    /// Column indexes of the hierarchy levels
+    column_indexes: Vec<usize>,
+    /// Pairs of values released together on the aggregated data
+    allowed_pairs: FnvHashSet<HierarchyValuePair>,
+}
+
+/// Enforces the synthesis constraints while attributes are sampled,
+/// keeping track of how many sampling choices were pruned by each constraint
+pub struct SynthesisConstraintsEnforcer {
+    /// Human readable representation of each constraint
+    descriptions: Vec<String>,
+    /// Constraints resolved against the data headers
+    /// (`None` if the constraint could not be resolved)
+    resolved_constraints: Vec<Option<ResolvedConstraint>>,
+    /// Maps a column index to the constraints referencing it
+    constraints_by_column: FnvHashMap<usize, Vec<usize>>,
+    /// Empty values on the synthetic data are represented by this,
+    /// they never violate a constraint
+    empty_value: Arc<String>,
+    /// Number of pruned choices for each constraint
+    pruned_counts: Vec<AtomicUsize>,
+    /// Column hierarchies whose levels should be consistent
+    hierarchies: Vec<ResolvedHierarchy>,
+    /// Maps a column index to the hierarchy containing it
+    hierarchy_by_column: FnvHashMap<usize, usize>,
+}
+
+impl SynthesisConstraintsEnforcer {
+    /// Returns a new SynthesisConstraintsEnforcer.
+    /// Constraints that can not be resolved against `headers` are ignored
+    /// (use `SynthesisConstraints::validate` to check them in advance)
+    /// # Arguments
+    /// * `synthesis_constraints` - Constraints to be enforced
+    /// * `headers` - Headers of the data being synthesized
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    pub fn new(
+        synthesis_constraints: &SynthesisConstraints,
+        headers: &DataBlockHeadersSlice,
+        empty_value: Arc<String>,
+    ) -> SynthesisConstraintsEnforcer {
+        let column_index_by_name: FnvHashMap<&str, usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| (h.as_str(), i))
+            .collect();
+        let mut resolved_constraints = Vec::with_capacity(synthesis_constraints.constraints.len());
+        let mut constraints_by_column: FnvHashMap<usize, Vec<usize>> = FnvHashMap::default();
+
+        for (constraint_index, constraint) in synthesis_constraints.constraints.iter().enumerate() {
+            match Self::resolve_constraint(constraint, &column_index_by_name) {
+                Ok(resolved) => {
+                    let mut columns: FnvHashSet<usize> =
+                        resolved.when.iter().map(|w| w.column_index).collect();
+
+                    if let Some(column_index) = resolved.restricted_column {
+                        columns.insert(column_index);
+                    }
+                    for column_index in columns {
+                        constraints_by_column
+                            .entry(column_index)
+                            .or_default()
+                            .push(constraint_index);
+                    }
+                    resolved_constraints.push(Some(resolved));
+                }
+                Err(err) => {
+                    warn!("ignoring constraint \"{}\": {}", constraint, err);
+                    resolved_constraints.push(None);
+                }
+            }
+        }
+
+        SynthesisConstraintsEnforcer {
+            descriptions: synthesis_constraints
+                .constraints
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            pruned_counts: resolved_constraints
+                .iter()
+                .map(|_| AtomicUsize::new(0))
+                .collect(),
+            resolved_constraints,
+            constraints_by_column,
+            empty_value,
+            hierarchies: Vec::default(),
+            hierarchy_by_column: FnvHashMap::default(),
+        }
+    }
+
+    /// Also keeps the levels of each column hierarchy consistent, so a synthesized
+    /// value can only be combined with values from the other hierarchy levels
+    /// it has been released with on the aggregated data (e.g. a county only
+    /// appears with its own state). Hierarchies that can not be resolved
+    /// against the aggregated data are ignored
+    /// # Arguments
+    /// * `column_hierarchies` - Hierarchies to be enforced
+    /// * `aggregated_data` - Aggregated data used for synthesis
+    pub fn with_column_hierarchies(
+        mut self,
+        column_hierarchies: &[DpColumnHierarchy],
+        aggregated_data: &AggregatedData,
+    ) -> SynthesisConstraintsEnforcer {
+        for hierarchy in column_hierarchies.iter() {
+            let column_indexes = match hierarchy.column_indexes(&aggregated_data.headers) {
+                Some(column_indexes) => column_indexes,
+                None => {
+                    warn!(
+                        "ignoring column hierarchy {:?}: column not found in the data headers",
+                        hierarchy.columns
+                    );
+                    continue;
+                }
+            };
+            let allowed_pairs: FnvHashSet<HierarchyValuePair> = aggregated_data
+                .aggregates_count
+                .keys()
+                .filter(|comb| {
+                    comb.len() >= 2
+                        && comb
+                            .iter()
+                            .all(|v| column_indexes.contains(&v.column_index))
+                })
+                .flat_map(|comb| {
+                    comb.iter()
+                        .tuple_combinations()
+                        .map(|(a, b)| Self::hierarchy_value_pair(a, b))
+                })
+                .collect();
+
+            if allowed_pairs.is_empty() {
+                warn!(
+                    "ignoring column hierarchy {:?}: no combination of its levels has been released",
+                    hierarchy.columns
+                );
+                continue;
+            }
+
+            let hierarchy_index = self.hierarchies.len();
+
+            for column_index in column_indexes.iter() {
+                self.hierarchy_by_column
+                    .insert(*column_index, hierarchy_index);
+            }
+            self.hierarchies.push(ResolvedHierarchy {
+                description_index: self.descriptions.len(),
+                column_indexes,
+                allowed_pairs,
+            });
+            self.descriptions
+                .push(format!("hierarchy {}", hierarchy.columns.join(" > ")));
+            self.pruned_counts.push(AtomicUsize::new(0));
+        }
+        self
+    }
+
+    /// Returns whether `candidate` can be added to `synthesized_record`
+    /// without violating any constraint. If not, the pruning count of the
+    /// violated constraint is incremented
+    /// # Arguments
+    /// * `synthesized_record` - Record synthesized so far
+    /// * `candidate` - Attribute that might be added to the record
+    #[inline]
+    pub fn is_allowed(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        candidate: &Arc<DataBlockValue>,
+    ) -> bool {
+        match self.find_violated_constraint(synthesized_record, candidate) {
+            Some(constraint_index) => {
+                self.pruned_counts[constraint_index].fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Adds to `not_allowed_attr_set` the `candidates` that would violate
+    /// a constraint if added to `synthesized_record`.
+    ///
+    /// A record only grows while being synthesized, so a pruned candidate stays
+    /// pruned until the record is done. Keeping the same `not_allowed_attr_set`
+    /// for the whole record means each candidate is only checked
+    /// (and counted on the report) once per record
+    /// # Arguments
+    /// * `synthesized_record` - Record synthesized so far
+    /// * `candidates` - Attributes that might be sampled next
+    /// * `not_allowed_attr_set` - Attributes not allowed to be sampled
+    /// for `synthesized_record`
+    #[inline]
+    pub fn extend_not_allowed_attr_set(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        candidates: &SynthesizerSeedSlice,
+        not_allowed_attr_set: &mut NotAllowedAttrSet,
+    ) {
+        let used_columns: FnvHashSet<usize> =
+            synthesized_record.iter().map(|v| v.column_index).collect();
+        let pruned: Vec<Arc<DataBlockValue>> = candidates
+            .iter()
+            .filter(|c| {
+                !used_columns.contains(&c.column_index)
+                    && !not_allowed_attr_set.contains(*c)
+                    && !self.is_allowed(synthesized_record, c)
+            })
+            .cloned()
+            .collect();
+
+        not_allowed_attr_set.extend(pruned);
+    }
+
+    /// Builds a report with how many sampling choices were pruned by each constraint
+    pub fn build_report(&self) -> SynthesisConstraintsReport {
+        SynthesisConstraintsReport::new(
+            self.descriptions
+                .iter()
+                .zip(self.pruned_counts.iter())
+                .map(|(constraint, count)| ConstraintPruningCount {
+                    constraint: constraint.clone(),
+                    pruned_choices: count.load(Ordering::Relaxed),
+                })
+                .collect(),
+        )
+    }
+
+    #[inline]
+    fn find_violated_constraint(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        candidate: &Arc<DataBlockValue>,
+    ) -> Option<usize> {
+        if *candidate.value == *self.empty_value {
+            return None;
+        }
+
+        // the record synthesized so far is already valid, so only
+        // the constraints referencing the candidate column need to be checked
+        self.constraints_by_column
+            .get(&candidate.column_index)
+            .and_then(|constraint_indexes| {
+                constraint_indexes
+                    .iter()
+                    .find(|constraint_index| {
+                        self.resolved_constraints[**constraint_index]
+                            .as_ref()
+                            .is_some_and(|constraint| {
+                                self.violates(constraint, synthesized_record, candidate)
+                            })
+                    })
+                    .cloned()
+            })
+            .or_else(|| self.find_violated_hierarchy(synthesized_record, candidate))
+    }
+
+    #[inline]
+    fn find_violated_hierarchy(
+        &self,
+        synthesized_record: &SynthesizedRecord,
+        candidate: &Arc<DataBlockValue>,
+    ) -> Option<usize> {
+        let hierarchy =
+            &self.hierarchies[*self.hierarchy_by_column.get(&candidate.column_index)?];
+        let violated = synthesized_record.iter().any(|v| {
+            v.column_index != candidate.column_index
+                && *v.value != *self.empty_value
+                && hierarchy.column_indexes.contains(&v.column_index)
+                && !hierarchy
+                    .allowed_pairs
+                    .contains(&Self::hierarchy_value_pair(v, candidate))
+        });
+
+        if violated {
+            Some(hierarchy.description_index)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn hierarchy_value_pair(
+        a: &Arc<DataBlockValue>,
+        b: &Arc<DataBlockValue>,
+    ) -> HierarchyValuePair {
+        if a.column_index <= b.column_index {
+            (a.clone(), b.clone())
+        } else {
+            (b.clone(), a.clone())
+        }
+    }
+
+    #[inline]
+    fn violates(
+        &self,
+        constraint: &ResolvedConstraint,
+        synthesized_record: &SynthesizedRecord,
+        candidate: &Arc<DataBlockValue>,
+    ) -> bool {
+        let all_when_present = constraint
+            .when
+            .iter()
+            .all(|w| **candidate == *w || synthesized_record.contains(w));
+
+        if !all_when_present {
+            return false;
+        }
+
+        match constraint.restricted_column {
+            None => true,
+            Some(column_index) => {
+                let restricted_value = if candidate.column_index == column_index {
+                    Some(candidate)
+                } else {
+                    synthesized_record
+                        .iter()
+                        .find(|v| v.column_index == column_index)
+                };
+
+                match restricted_value {
+                    Some(v) => {
+                        *v.value != *self.empty_value
+                            && !constraint.allowed_values.contains(v.value.as_str())
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn resolve_column(
+        column: &str,
+        column_index_by_name: &FnvHashMap<&str, usize>,
+    ) -> Result<usize, SynthesisConstraintsError> {
+        column_index_by_name.get(column).cloned().ok_or_else(|| {
+            SynthesisConstraintsError::InvalidConstraint(format!(
+                "column \"{column}\" not found in the data headers"
+            ))
+        })
+    }
+
+    #[inline]
+    fn resolve_attributes(
+        attributes: &[ConstraintAttribute],
+        column_index_by_name: &FnvHashMap<&str, usize>,
+    ) -> Result<Vec<DataBlockValue>, SynthesisConstraintsError> {
+        attributes
+            .iter()
+            .map(|attr| {
+                Ok(DataBlockValue::new(
+                    Self::resolve_column(&attr.column, column_index_by_name)?,
+                    Arc::new(attr.value.clone()),
+                ))
+            })
+            .collect()
+    }
+
+    /// Checks whether `constraint` can be resolved against the data headers
+    #[inline]
+    pub(crate) fn validate_constraint(
+        constraint: &SynthesisConstraint,
+        column_index_by_name: &FnvHashMap<&str, usize>,
+    ) -> Result<(), SynthesisConstraintsError> {
+        Self::resolve_constraint(constraint, column_index_by_name).map(|_| ())
+    }
+
+    /// Constraints that do not depend on any other attribute would deny a value
+    /// in every record, so it could never be consumed during synthesis.
+    /// Such values should be removed from the data instead
+    fn resolve_constraint(
+        constraint: &SynthesisConstraint,
+        column_index_by_name: &FnvHashMap<&str, usize>,
+    ) -> Result<ResolvedConstraint, SynthesisConstraintsError> {
+        match constraint {
+            SynthesisConstraint::DenyCombination { attributes } => {
+                let when = Self::resolve_attributes(attributes, column_index_by_name)?;
+
+                if when.iter().map(|w| w.column_index).unique().count() < 2 {
+                    return Err(SynthesisConstraintsError::InvalidConstraint(
+                        "deny combination constraint should have attributes from at least two different columns".to_owned(),
+                    ));
+                }
+                Ok(ResolvedConstraint {
+                    when,
+                    restricted_column: None,
+                    allowed_values: FnvHashSet::default(),
+                })
+            }
+            SynthesisConstraint::Implication { when, then } => {
+                let restricted_column = Self::resolve_column(&then.column, column_index_by_name)?;
+
+                Ok(ResolvedConstraint {
+                    when: Self::resolve_conditions(when, restricted_column, column_index_by_name)?,
+                    restricted_column: Some(restricted_column),
+                    allowed_values: [then.value.clone()].into_iter().collect(),
+                })
+            }
+            SynthesisConstraint::AllowedValues {
+                when,
+                column,
+                values,
+            } => {
+                let restricted_column = Self::resolve_column(column, column_index_by_name)?;
+
+                Ok(ResolvedConstraint {
+                    when: Self::resolve_conditions(when, restricted_column, column_index_by_name)?,
+                    restricted_column: Some(restricted_column),
+                    allowed_values: values.iter().cloned().collect(),
+                })
+            }
+        }
+    }
+
+    #[inline]
+    fn resolve_conditions(
+        when: &[ConstraintAttribute],
+        restricted_column: usize,
+        column_index_by_name: &FnvHashMap<&str, usize>,
+    ) -> Result<Vec<DataBlockValue>, SynthesisConstraintsError> {
+        let resolved_when = Self::resolve_attributes(when, column_index_by_name)?;
+
+        if resolved_when.is_empty() {
+            return Err(SynthesisConstraintsError::InvalidConstraint(
+                "constraint should have at least one \"when\" attribute".to_owned(),
+            ));
+        }
+        if resolved_when
+            .iter()
+            .any(|w| w.column_index == restricted_column)
+        {
+            return Err(SynthesisConstraintsError::InvalidConstraint(
+                "\"when\" attributes should not reference the restricted column".to_owned(),
+            ));
+        }
+        Ok(resolved_when)
+    }
+}
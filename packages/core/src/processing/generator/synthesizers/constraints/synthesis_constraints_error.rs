@@ -0,0 +1,36 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyIOError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when loading or validating synthesis constraints
+#[derive(Debug)]
+pub enum SynthesisConstraintsError {
+    /// Generated when the constraints file can not be read or parsed
+    ParsingError(String),
+    /// Generated when a constraint is not valid for the data headers
+    InvalidConstraint(String),
+}
+
+impl Display for SynthesisConstraintsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SynthesisConstraintsError::ParsingError(err) => {
+                write!(f, "error parsing synthesis constraints: {err}")
+            }
+            SynthesisConstraintsError::InvalidConstraint(err) => {
+                write!(f, "invalid synthesis constraint: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<SynthesisConstraintsError> for PyErr {
+    fn from(err: SynthesisConstraintsError) -> PyErr {
+        PyIOError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,68 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, Write};
+
+use crate::utils::time::ElapsedDurationLogger;
+
+/// How many sampling choices were pruned by a single constraint
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintPruningCount {
+    /// Human readable representation of the constraint
+    pub constraint: String,
+    /// Number of times an attribute was removed from the sampling
+    /// choices because it would violate the constraint
+    pub pruned_choices: usize,
+}
+
+/// Report on how the synthesis constraints affected the sampling process
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SynthesisConstraintsReport {
+    /// Pruning counts for each declared constraint (same order as declared)
+    pub pruned_choices_by_constraint: Vec<ConstraintPruningCount>,
+}
+
+impl SynthesisConstraintsReport {
+    /// Returns a new SynthesisConstraintsReport
+    /// # Arguments
+    /// * `pruned_choices_by_constraint` - Pruning counts for each declared constraint
+    #[inline]
+    pub fn new(
+        pruned_choices_by_constraint: Vec<ConstraintPruningCount>,
+    ) -> SynthesisConstraintsReport {
+        SynthesisConstraintsReport {
+            pruned_choices_by_constraint,
+        }
+    }
+
+    /// Total number of sampling choices pruned by all the constraints
+    #[inline]
+    pub fn total_pruned_choices(&self) -> usize {
+        self.pruned_choices_by_constraint
+            .iter()
+            .map(|c| c.pruned_choices)
+            .sum()
+    }
+
+    /// Writes the report to the file system in a csv/tsv like format
+    /// # Arguments:
+    /// * `report_path` - File path to be written
+    /// * `report_delimiter` - Delimiter to use when writing to `report_path`
+    pub fn write_report(&self, report_path: &str, report_delimiter: char) -> Result<(), Error> {
+        info!("writing file: {}", report_path);
+
+        let _duration_logger = ElapsedDurationLogger::new("write synthesis constraints report");
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(report_path)?);
+
+        file.write_all(format!("constraint{report_delimiter}pruned_choices\n").as_bytes())?;
+
+        for c in self.pruned_choices_by_constraint.iter() {
+            file.write_all(
+                format!("{}{}{}\n", c.constraint, report_delimiter, c.pruned_choices).as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+}
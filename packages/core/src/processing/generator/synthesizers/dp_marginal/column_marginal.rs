@@ -0,0 +1,92 @@
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+use crate::data_block::DataBlockValue;
+
+/// Index used to represent an empty value (or a value
+/// removed from the domain) on every column
+pub const EMPTY_VALUE_INDEX: usize = 0;
+
+/// Noisy counts for the values of a single column.
+///
+/// The domain of the column is composed by the empty value
+/// (always on index 0) followed by the values that survived
+/// the noisy threshold
+pub struct ColumnMarginal {
+    /// Values in the column domain (index `i` here maps to index `i + 1` on `counts`)
+    pub values: Vec<Arc<DataBlockValue>>,
+    /// Maps a value to its index on `counts`
+    pub index_by_value: FnvHashMap<Arc<DataBlockValue>, usize>,
+    /// Estimated count for every value in the domain
+    pub counts: Vec<f64>,
+}
+
+impl ColumnMarginal {
+    /// Returns a new ColumnMarginal
+    /// # Arguments
+    /// * `values` - Values in the column domain (not including the empty value)
+    /// * `counts` - Estimated count for the empty value (index 0) followed by the count
+    /// for each element in `values`
+    #[inline]
+    pub fn new(values: Vec<Arc<DataBlockValue>>, counts: Vec<f64>) -> ColumnMarginal {
+        ColumnMarginal {
+            index_by_value: values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (v.clone(), i + 1))
+                .collect(),
+            values,
+            counts,
+        }
+    }
+
+    /// Number of values in the domain (including the empty value)
+    #[inline]
+    pub fn domain_size(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the index of `value` on the domain, values that are
+    /// not part of the domain are mapped to the empty value
+    #[inline]
+    pub fn index_of(&self, value: &Arc<DataBlockValue>) -> usize {
+        self.index_by_value
+            .get(value)
+            .cloned()
+            .unwrap_or(EMPTY_VALUE_INDEX)
+    }
+
+    /// Returns the value at `index` on the domain (`None` for the empty value)
+    #[inline]
+    pub fn value_at(&self, index: usize) -> Option<&Arc<DataBlockValue>> {
+        if index == EMPTY_VALUE_INDEX {
+            None
+        } else {
+            self.values.get(index - 1)
+        }
+    }
+
+    /// Replaces negative counts by zero and scales the counts
+    /// so they sum up to `total` (falling back to a uniform distribution
+    /// when nothing is left)
+    #[inline]
+    pub fn normalize(&mut self, total: f64) {
+        for c in self.counts.iter_mut() {
+            *c = c.max(0.0);
+        }
+
+        let sum: f64 = self.counts.iter().sum();
+
+        if sum > 0.0 {
+            for c in self.counts.iter_mut() {
+                *c *= total / sum;
+            }
+        } else {
+            let uniform = total / (self.counts.len() as f64);
+
+            for c in self.counts.iter_mut() {
+                *c = uniform;
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::utils::reporting::ProcessingStoppedError;
+use std::fmt::{Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::{PyIOError, PyValueError};
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+/// Error generated when fitting the marginal based DP model
+#[derive(Debug)]
+pub enum DpMarginalError {
+    /// Generated when the DP parameters or the sensitive data
+    /// can not be used to fit the model
+    InvalidInput(String),
+    /// Generated when the fitting is stopped through the progress reporter
+    Stopped(ProcessingStoppedError),
+}
+
+impl Display for DpMarginalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DpMarginalError::InvalidInput(err) => {
+                write!(f, "invalid dp marginal input: {err}")
+            }
+            DpMarginalError::Stopped(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<ProcessingStoppedError> for DpMarginalError {
+    fn from(err: ProcessingStoppedError) -> DpMarginalError {
+        DpMarginalError::Stopped(err)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<DpMarginalError> for PyErr {
+    fn from(err: DpMarginalError) -> PyErr {
+        match err {
+            DpMarginalError::InvalidInput(_) => PyValueError::new_err(err.to_string()),
+            DpMarginalError::Stopped(_) => PyIOError::new_err(err.to_string()),
+        }
+    }
+}
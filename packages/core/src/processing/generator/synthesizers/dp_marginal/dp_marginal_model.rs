@@ -0,0 +1,179 @@
+use super::{
+    column_marginal::{ColumnMarginal, EMPTY_VALUE_INDEX},
+    pair_marginal::PairMarginal,
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution as rand_dist, thread_rng};
+use std::collections::VecDeque;
+
+use crate::{
+    data_block::{DataBlockHeaders, MultiValueColumnMetadataMap},
+    processing::generator::synthesizers::typedefs::{SynthesizedRecord, SynthesizedRecords},
+    utils::{
+        math::calc_percentage,
+        reporting::{ReportProgress, StoppableResult},
+    },
+};
+
+/// Tree model fitted from the differentially private marginals,
+/// synthetic records are sampled from it without accessing the sensitive data
+pub struct DpMarginalModel {
+    headers: DataBlockHeaders,
+    multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+    columns: Vec<ColumnMarginal>,
+    pairs: Vec<PairMarginal>,
+    sampling_order: Vec<(usize, Option<(usize, usize)>)>,
+    estimated_number_of_records: f64,
+}
+
+impl DpMarginalModel {
+    #[inline]
+    pub(super) fn new(
+        headers: DataBlockHeaders,
+        multi_value_column_metadata_map: MultiValueColumnMetadataMap,
+        columns: Vec<ColumnMarginal>,
+        pairs: Vec<PairMarginal>,
+        estimated_number_of_records: f64,
+    ) -> DpMarginalModel {
+        DpMarginalModel {
+            sampling_order: Self::build_sampling_order(columns.len(), &pairs),
+            headers,
+            multi_value_column_metadata_map,
+            columns,
+            pairs,
+            estimated_number_of_records,
+        }
+    }
+
+    /// Headers of the data the model was fitted on
+    #[inline]
+    pub fn headers(&self) -> &DataBlockHeaders {
+        &self.headers
+    }
+
+    /// Maps a normalized multi-value header name (such as A_a1) to its corresponding metadata
+    #[inline]
+    pub fn multi_value_column_metadata_map(&self) -> &MultiValueColumnMetadataMap {
+        &self.multi_value_column_metadata_map
+    }
+
+    /// Number of records estimated from the noisy single column counts
+    #[inline]
+    pub fn estimated_number_of_records(&self) -> usize {
+        self.estimated_number_of_records.round() as usize
+    }
+
+    /// Pairs of columns (by index) measured by the model
+    #[inline]
+    pub fn selected_pairs(&self) -> Vec<(usize, usize)> {
+        self.pairs
+            .iter()
+            .map(|pair| (pair.column_a, pair.column_b))
+            .collect()
+    }
+
+    /// Samples synthetic records from the model
+    /// # Arguments
+    /// * `number_of_records` - Total number of records to be synthesized.
+    /// If `None` the estimated number of records is used
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn sample<T>(
+        &self,
+        number_of_records: Option<usize>,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<SynthesizedRecords>
+    where
+        T: ReportProgress,
+    {
+        let number_of_records =
+            number_of_records.unwrap_or_else(|| self.estimated_number_of_records());
+        let mut records = SynthesizedRecords::with_capacity(number_of_records);
+
+        for i in 0..number_of_records {
+            records.push(self.sample_record());
+
+            progress_reporter
+                .as_mut()
+                .map(|r| r.report(calc_percentage((i + 1) as f64, number_of_records as f64)))
+                .unwrap_or_else(|| Ok(()))?;
+        }
+
+        Ok(records)
+    }
+
+    #[inline]
+    fn sample_index(weights: &[f64]) -> Option<usize> {
+        WeightedIndex::new(weights)
+            .ok()
+            .map(|dist| dist.sample(&mut thread_rng()))
+    }
+
+    #[inline]
+    fn sample_record(&self) -> SynthesizedRecord {
+        let mut sampled = vec![EMPTY_VALUE_INDEX; self.columns.len()];
+
+        for (column, parent) in self.sampling_order.iter() {
+            let conditional_index = parent.and_then(|(parent_column, pair_index)| {
+                Self::sample_index(
+                    &self.pairs[pair_index]
+                        .conditional_counts(parent_column, sampled[parent_column]),
+                )
+            });
+
+            sampled[*column] = conditional_index
+                .or_else(|| Self::sample_index(&self.columns[*column].counts))
+                .unwrap_or(EMPTY_VALUE_INDEX);
+        }
+
+        sampled
+            .iter()
+            .enumerate()
+            .filter_map(|(column, index)| self.columns[column].value_at(*index).cloned())
+            .collect()
+    }
+
+    /// Orders the columns so every column (except the roots of the tree)
+    /// comes after the column it is conditioned on
+    fn build_sampling_order(
+        n_columns: usize,
+        pairs: &[PairMarginal],
+    ) -> Vec<(usize, Option<(usize, usize)>)> {
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::default(); n_columns];
+        let mut visited = vec![false; n_columns];
+        let mut order = Vec::with_capacity(n_columns);
+
+        for (pair_index, pair) in pairs.iter().enumerate() {
+            adjacency[pair.column_a].push(pair_index);
+            adjacency[pair.column_b].push(pair_index);
+        }
+
+        for root in 0..n_columns {
+            if visited[root] {
+                continue;
+            }
+
+            let mut queue = VecDeque::from([root]);
+
+            visited[root] = true;
+            order.push((root, None));
+
+            while let Some(column) = queue.pop_front() {
+                for pair_index in adjacency[column].iter() {
+                    let pair = &pairs[*pair_index];
+                    let other = if pair.column_a == column {
+                        pair.column_b
+                    } else {
+                        pair.column_a
+                    };
+
+                    if !visited[other] {
+                        visited[other] = true;
+                        order.push((other, Some((column, *pair_index))));
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+        order
+    }
+}
@@ -0,0 +1,518 @@
+mod column_marginal;
+mod dp_marginal_error;
+mod dp_marginal_model;
+mod pair_marginal;
+
+pub use dp_marginal_error::*;
+pub use dp_marginal_model::*;
+
+use column_marginal::{ColumnMarginal, EMPTY_VALUE_INDEX};
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::{debug, info};
+use pair_marginal::PairMarginal;
+use rand::{prelude::Distribution as rand_dist, thread_rng, Rng};
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::sync::Arc;
+
+use crate::{
+    data_block::{DataBlock, DataBlockValue},
+    dp::{DpMarginalParameters, NoiseParameters},
+    utils::{
+        math::calc_percentage,
+        reporting::{ReportProgress, StoppableResult},
+    },
+};
+
+/// Number of iterative proportional fitting iterations used
+/// to make the pair counts consistent with the column counts
+const FIT_ITERATIONS: usize = 20;
+
+/// Percentage of the progress at the end of each synthesis step
+const MEASURE_COLUMNS_PROGRESS: f64 = 10.0;
+const SELECT_PAIRS_PROGRESS: f64 = 85.0;
+const MEASURE_PAIRS_PROGRESS: f64 = 95.0;
+
+/// Represents all the information required to fit the model used by the
+/// marginal based synthesis with differential privacy.
+///
+/// This follows the MST approach: all the single column counts are measured with
+/// gaussian noise, a spanning tree of informative column pairs is privately selected
+/// with the exponential mechanism, the selected pairs are measured with gaussian noise,
+/// made consistent with iterative proportional fitting and the records are later
+/// sampled from the resulting tree model (`DpMarginalModel`).
+///
+/// Each record is assumed to represent a single individual
+/// (use a subject id to join records from the same individual)
+pub struct DpMarginalSynthesizer {
+    data_block: Arc<DataBlock>,
+    dp_marginal_parameters: DpMarginalParameters,
+}
+
+impl DpMarginalSynthesizer {
+    /// Returns a new DpMarginalSynthesizer
+    /// # Arguments
+    /// * `data_block` - Sensitive data to be synthesized
+    /// * `dp_marginal_parameters` - Differential privacy parameters
+    #[inline]
+    pub fn new(
+        data_block: Arc<DataBlock>,
+        dp_marginal_parameters: DpMarginalParameters,
+    ) -> DpMarginalSynthesizer {
+        DpMarginalSynthesizer {
+            data_block,
+            dp_marginal_parameters,
+        }
+    }
+
+    /// Privately measures the marginals and fits the model synthetic records
+    /// can be sampled from (the sensitive data is not accessed after this).
+    /// Returns an error if the DP parameters or the data can not be used
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    pub fn fit<T>(
+        &self,
+        progress_reporter: &mut Option<T>,
+    ) -> Result<DpMarginalModel, DpMarginalError>
+    where
+        T: ReportProgress,
+    {
+        self.validate()?;
+
+        let n_columns = self.data_block.headers.len();
+
+        if n_columns == 0 {
+            return Ok(DpMarginalModel::new(
+                self.data_block.headers.clone(),
+                self.data_block.multi_value_column_metadata_map.clone(),
+                Vec::default(),
+                Vec::default(),
+                0.0,
+            ));
+        }
+
+        let (columns_rho, selection_rho, pairs_rho, delta) = self.split_budget();
+        // a single subject can contribute with up to `max_records_per_subject` records
+        let max_records_per_subject = self.data_block.max_records_per_subject as f64;
+        let columns_sigma =
+            max_records_per_subject * (n_columns as f64 / (2.0 * columns_rho)).sqrt();
+        let (mut columns, estimated_number_of_records) = self.measure_columns(columns_sigma, delta);
+
+        info!(
+            "measured single column counts with sigma = {}, estimated number of records = {}",
+            columns_sigma, estimated_number_of_records
+        );
+
+        for column in columns.iter_mut() {
+            column.normalize(estimated_number_of_records);
+        }
+
+        Self::update_progress(progress_reporter, MEASURE_COLUMNS_PROGRESS)?;
+
+        let encoded_records = self.encode_records(&columns);
+        let mut pairs = if n_columns > 1 {
+            let selection_epsilon =
+                (8.0 * selection_rho / ((n_columns - 1) as f64)).sqrt() / max_records_per_subject;
+            let selected = self.select_pairs(
+                &columns,
+                &encoded_records,
+                estimated_number_of_records,
+                selection_epsilon,
+                progress_reporter,
+            )?;
+            let pairs_sigma =
+                max_records_per_subject * ((n_columns - 1) as f64 / (2.0 * pairs_rho)).sqrt();
+
+            info!(
+                "measuring {} selected column pair(s) with sigma = {}",
+                selected.len(),
+                pairs_sigma
+            );
+
+            self.measure_pairs(&columns, &encoded_records, &selected, pairs_sigma)
+        } else {
+            Vec::default()
+        };
+
+        Self::update_progress(progress_reporter, MEASURE_PAIRS_PROGRESS)?;
+
+        Self::make_consistent(&mut columns, &mut pairs, estimated_number_of_records);
+
+        Self::update_progress(progress_reporter, 100.0)?;
+
+        Ok(DpMarginalModel::new(
+            self.data_block.headers.clone(),
+            self.data_block.multi_value_column_metadata_map.clone(),
+            columns,
+            pairs,
+            estimated_number_of_records,
+        ))
+    }
+
+    #[inline]
+    fn update_progress<T>(progress_reporter: &mut Option<T>, percentage: f64) -> StoppableResult<()>
+    where
+        T: ReportProgress,
+    {
+        progress_reporter
+            .as_mut()
+            .map(|r| r.report(percentage))
+            .unwrap_or_else(|| Ok(()))
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), DpMarginalError> {
+        self.dp_marginal_parameters
+            .validate()
+            .map_err(|err| DpMarginalError::InvalidInput(err.to_string()))?;
+
+        let number_of_records = self.data_block.number_of_records();
+
+        if number_of_records == 0 {
+            return Err(DpMarginalError::InvalidInput(
+                "the sensitive data has no records".to_owned(),
+            ));
+        }
+        if self.dp_marginal_parameters.delta.is_none() && number_of_records < 2 {
+            return Err(DpMarginalError::InvalidInput(
+                "delta should be provided when the sensitive data has a single record".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn split_budget(&self) -> (f64, f64, f64, f64) {
+        let single_attributes_proportion = self
+            .dp_marginal_parameters
+            .single_attributes_budget_proportion;
+        let selection_proportion = self.dp_marginal_parameters.selection_budget_proportion;
+
+        // user provided parameters are checked on `validate`
+        assert!(
+            single_attributes_proportion > 0.0
+                && selection_proportion > 0.0
+                && single_attributes_proportion + selection_proportion < 1.0,
+            "single attributes and selection budget proportions must be > 0 and sum up to < 1"
+        );
+
+        let delta = NoiseParameters::delta_value_or_default(
+            &self.dp_marginal_parameters.delta,
+            self.data_block.number_of_records(),
+        );
+        let rho = NoiseParameters::calc_rho(self.dp_marginal_parameters.epsilon, delta);
+
+        info!(
+            "splitting rho = {} (epsilon = {}, delta = {}) between column counts, pair selection and pair counts",
+            rho, self.dp_marginal_parameters.epsilon, delta
+        );
+
+        (
+            rho * single_attributes_proportion,
+            rho * selection_proportion,
+            rho * (1.0 - single_attributes_proportion - selection_proportion),
+            delta,
+        )
+    }
+
+    #[inline]
+    fn sample_normal(sigma: f64) -> f64 {
+        sigma
+            * Normal::new(0.0, 1.0)
+                .expect("error generating Normal noise")
+                .sample(&mut thread_rng())
+    }
+
+    /// Measures the noisy counts of every column, only keeping in the domain the values
+    /// with noisy counts above the threshold (the remaining values are merged into the empty value).
+    /// Returns the column marginals and the estimated number of records
+    fn measure_columns(&self, sigma: f64, delta: f64) -> (Vec<ColumnMarginal>, f64) {
+        let n_columns = self.data_block.headers.len();
+        let number_of_records = self.data_block.number_of_records();
+        let max_records_per_subject = self.data_block.max_records_per_subject as f64;
+        let mut counts_by_column: Vec<FnvHashMap<Arc<DataBlockValue>, usize>> =
+            vec![FnvHashMap::default(); n_columns];
+        // each record contributes to one count per column (and each subject with up to
+        // `max_records_per_subject` records), so values need to pass
+        // this threshold to be part of the released domain
+        let threshold = max_records_per_subject
+            + sigma
+                * Normal::new(0.0, 1.0)
+                    .expect("error creating Normal for inverse CDF")
+                    .inverse_cdf(
+                        (1.0 - (delta / 2.0))
+                            .powf(1.0 / (max_records_per_subject * n_columns as f64)),
+                    );
+
+        debug!("threshold for single column counts = {}", threshold);
+
+        for record in self.data_block.records.iter() {
+            for value in record.values.iter() {
+                *counts_by_column[value.column_index]
+                    .entry(value.clone())
+                    .or_default() += 1;
+            }
+        }
+
+        let mut total_estimate = 0.0;
+        let columns = counts_by_column
+            .drain(..)
+            .map(|counts| {
+                let empty_count = number_of_records - counts.values().sum::<usize>();
+                let mut empty_noisy_count = empty_count as f64 + Self::sample_normal(sigma);
+                let mut values = Vec::default();
+                let mut noisy_counts = Vec::default();
+
+                total_estimate += empty_noisy_count;
+
+                for (value, count) in counts
+                    .into_iter()
+                    .sorted_by_key(|(v, _)| v.as_str_using_headers(&self.data_block.headers))
+                {
+                    let noisy_count = count as f64 + Self::sample_normal(sigma);
+
+                    total_estimate += noisy_count;
+
+                    if noisy_count > threshold {
+                        values.push(value);
+                        noisy_counts.push(noisy_count);
+                    } else {
+                        // the value is not part of the domain anymore,
+                        // so it will be synthesized as empty
+                        empty_noisy_count += noisy_count.max(0.0);
+                    }
+                }
+
+                let mut column_counts = Vec::with_capacity(noisy_counts.len() + 1);
+
+                column_counts.push(empty_noisy_count);
+                column_counts.extend(noisy_counts);
+
+                ColumnMarginal::new(values, column_counts)
+            })
+            .collect();
+
+        (columns, (total_estimate / n_columns as f64).max(1.0))
+    }
+
+    #[inline]
+    fn encode_records(&self, columns: &[ColumnMarginal]) -> Vec<Vec<usize>> {
+        self.data_block
+            .records
+            .iter()
+            .map(|record| {
+                let mut encoded = vec![EMPTY_VALUE_INDEX; columns.len()];
+
+                for value in record.values.iter() {
+                    encoded[value.column_index] = columns[value.column_index].index_of(value);
+                }
+                encoded
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn count_pair(
+        columns: &[ColumnMarginal],
+        encoded_records: &[Vec<usize>],
+        column_a: usize,
+        column_b: usize,
+    ) -> PairMarginal {
+        let mut pair = PairMarginal::new(
+            column_a,
+            column_b,
+            columns[column_a].domain_size(),
+            columns[column_b].domain_size(),
+        );
+
+        for record in encoded_records.iter() {
+            pair.increment(record[column_a], record[column_b]);
+        }
+        pair
+    }
+
+    /// L1 distance between the pair counts and the counts
+    /// expected if the columns were independent
+    #[inline]
+    fn calc_pair_score(
+        columns: &[ColumnMarginal],
+        pair: &PairMarginal,
+        estimated_number_of_records: f64,
+    ) -> f64 {
+        let counts_a = &columns[pair.column_a].counts;
+        let counts_b = &columns[pair.column_b].counts;
+        let mut score = 0.0;
+
+        for (a, count_a) in counts_a.iter().enumerate() {
+            for (b, count_b) in counts_b.iter().enumerate() {
+                score += (pair.get(a, b) - count_a * count_b / estimated_number_of_records).abs();
+            }
+        }
+        score
+    }
+
+    #[inline]
+    fn find_component(components: &mut [usize], column: usize) -> usize {
+        let mut root = column;
+
+        while components[root] != root {
+            root = components[root];
+        }
+        components[column] = root;
+        root
+    }
+
+    /// Selects the pairs forming a spanning tree over the columns, each edge is selected
+    /// with the exponential mechanism, favoring the pairs that are poorly
+    /// explained by the single column counts
+    fn select_pairs<T>(
+        &self,
+        columns: &[ColumnMarginal],
+        encoded_records: &[Vec<usize>],
+        estimated_number_of_records: f64,
+        selection_epsilon: f64,
+        progress_reporter: &mut Option<T>,
+    ) -> StoppableResult<Vec<(usize, usize)>>
+    where
+        T: ReportProgress,
+    {
+        let n_columns = columns.len();
+        let n_pairs = n_columns * (n_columns - 1) / 2;
+        let mut scores = Vec::with_capacity(n_pairs);
+
+        for (column_a, column_b) in (0..n_columns).tuple_combinations() {
+            let pair = Self::count_pair(columns, encoded_records, column_a, column_b);
+
+            scores.push((
+                (column_a, column_b),
+                Self::calc_pair_score(columns, &pair, estimated_number_of_records),
+            ));
+
+            Self::update_progress(
+                progress_reporter,
+                MEASURE_COLUMNS_PROGRESS
+                    + (SELECT_PAIRS_PROGRESS - MEASURE_COLUMNS_PROGRESS)
+                        * calc_percentage(scores.len() as f64, n_pairs as f64)
+                        / 100.0,
+            )?;
+        }
+
+        let mut components: Vec<usize> = (0..n_columns).collect();
+        let mut selected = Vec::with_capacity(n_columns - 1);
+
+        while selected.len() < n_columns - 1 {
+            // exponential mechanism (score sensitivity is 1) using the gumbel max trick
+            let mut best: Option<((usize, usize), f64)> = None;
+
+            for ((column_a, column_b), score) in scores.iter() {
+                if Self::find_component(&mut components, *column_a)
+                    != Self::find_component(&mut components, *column_b)
+                {
+                    let u: f64 = thread_rng().gen_range(f64::EPSILON..1.0);
+                    let noisy_score = selection_epsilon * score / 2.0 - (-u.ln()).ln();
+
+                    if best.is_none_or(|(_, best_score)| noisy_score > best_score) {
+                        best = Some(((*column_a, *column_b), noisy_score));
+                    }
+                }
+            }
+
+            match best {
+                Some(((column_a, column_b), _)) => {
+                    let root_a = Self::find_component(&mut components, column_a);
+                    let root_b = Self::find_component(&mut components, column_b);
+
+                    components[root_a] = root_b;
+                    selected.push((column_a, column_b));
+                }
+                None => break,
+            }
+        }
+
+        info!(
+            "selected column pairs: {}",
+            selected
+                .iter()
+                .map(|(a, b)| format!(
+                    "({}, {})",
+                    self.data_block.headers[*a], self.data_block.headers[*b]
+                ))
+                .join(", ")
+        );
+
+        Ok(selected)
+    }
+
+    #[inline]
+    fn measure_pairs(
+        &self,
+        columns: &[ColumnMarginal],
+        encoded_records: &[Vec<usize>],
+        selected: &[(usize, usize)],
+        sigma: f64,
+    ) -> Vec<PairMarginal> {
+        selected
+            .iter()
+            .map(|(column_a, column_b)| {
+                let mut pair = Self::count_pair(columns, encoded_records, *column_a, *column_b);
+
+                for c in pair.counts.iter_mut() {
+                    *c += Self::sample_normal(sigma);
+                }
+                pair
+            })
+            .collect()
+    }
+
+    /// Combines the column counts with the counts implied by the pairs
+    /// (weighting by the inverse of their variances) and makes the pairs consistent
+    /// with the resulting column counts
+    fn make_consistent(
+        columns: &mut [ColumnMarginal],
+        pairs: &mut [PairMarginal],
+        estimated_number_of_records: f64,
+    ) {
+        if pairs.is_empty() {
+            return;
+        }
+
+        for (column_index, column) in columns.iter_mut().enumerate() {
+            // single column counts are already normalized, so give
+            // them the same weight as one pair
+            let mut weighted_sum = column.counts.clone();
+            let mut total_weight = 1.0;
+
+            for pair in pairs.iter() {
+                if pair.column_a == column_index || pair.column_b == column_index {
+                    let other_domain_size = if pair.column_a == column_index {
+                        pair.domain_size_b
+                    } else {
+                        pair.domain_size_a
+                    };
+                    // the variance of the summed counts grows with the other column domain
+                    let weight = 1.0 / other_domain_size as f64;
+
+                    for (s, c) in weighted_sum
+                        .iter_mut()
+                        .zip(pair.calc_column_counts(column_index))
+                    {
+                        *s += weight * c;
+                    }
+                    total_weight += weight;
+                }
+            }
+
+            column.counts = weighted_sum.iter().map(|s| s / total_weight).collect();
+            column.normalize(estimated_number_of_records);
+        }
+
+        for pair in pairs.iter_mut() {
+            pair.fit(
+                &columns[pair.column_a].counts,
+                &columns[pair.column_b].counts,
+                FIT_ITERATIONS,
+            );
+        }
+    }
+}
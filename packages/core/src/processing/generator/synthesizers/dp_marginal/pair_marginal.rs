@@ -0,0 +1,118 @@
+/// Minimum count assigned to every cell before fitting, so
+/// the iterative proportional fitting is able to reach every value
+const MIN_CELL_COUNT: f64 = 1e-6;
+
+/// Counts for the combinations of values of a pair of columns.
+///
+/// Cells are stored row-wise, where rows are indexed by the domain of `column_a`
+/// and columns by the domain of `column_b`
+pub struct PairMarginal {
+    /// Index of the first column
+    pub column_a: usize,
+    /// Index of the second column
+    pub column_b: usize,
+    /// Domain size of the first column
+    pub domain_size_a: usize,
+    /// Domain size of the second column
+    pub domain_size_b: usize,
+    /// Count for each cell (`domain_size_a * domain_size_b` cells)
+    pub counts: Vec<f64>,
+}
+
+impl PairMarginal {
+    /// Returns a new PairMarginal with all the counts set to zero
+    /// # Arguments
+    /// * `column_a` - Index of the first column
+    /// * `column_b` - Index of the second column
+    /// * `domain_size_a` - Domain size of the first column
+    /// * `domain_size_b` - Domain size of the second column
+    #[inline]
+    pub fn new(
+        column_a: usize,
+        column_b: usize,
+        domain_size_a: usize,
+        domain_size_b: usize,
+    ) -> PairMarginal {
+        PairMarginal {
+            column_a,
+            column_b,
+            domain_size_a,
+            domain_size_b,
+            counts: vec![0.0; domain_size_a * domain_size_b],
+        }
+    }
+
+    /// Increments the count for the cell (`index_a`, `index_b`)
+    #[inline]
+    pub fn increment(&mut self, index_a: usize, index_b: usize) {
+        self.counts[index_a * self.domain_size_b + index_b] += 1.0;
+    }
+
+    /// Returns the count for the cell (`index_a`, `index_b`)
+    #[inline]
+    pub fn get(&self, index_a: usize, index_b: usize) -> f64 {
+        self.counts[index_a * self.domain_size_b + index_b]
+    }
+
+    /// Returns the marginal counts for `column` (summing up the other column),
+    /// `column` must be either `column_a` or `column_b`
+    pub fn calc_column_counts(&self, column: usize) -> Vec<f64> {
+        if column == self.column_a {
+            (0..self.domain_size_a)
+                .map(|a| (0..self.domain_size_b).map(|b| self.get(a, b)).sum())
+                .collect()
+        } else {
+            (0..self.domain_size_b)
+                .map(|b| (0..self.domain_size_a).map(|a| self.get(a, b)).sum())
+                .collect()
+        }
+    }
+
+    /// Returns the counts for the values of the column on the other side of the pair,
+    /// given `column` is set to the value at `index`
+    pub fn conditional_counts(&self, column: usize, index: usize) -> Vec<f64> {
+        if column == self.column_a {
+            (0..self.domain_size_b)
+                .map(|b| self.get(index, b))
+                .collect()
+        } else {
+            (0..self.domain_size_a)
+                .map(|a| self.get(a, index))
+                .collect()
+        }
+    }
+
+    /// Makes the pair counts consistent with the single column counts using
+    /// iterative proportional fitting
+    /// # Arguments
+    /// * `counts_a` - Target counts for `column_a`
+    /// * `counts_b` - Target counts for `column_b`
+    /// * `iterations` - How many fitting iterations should be performed
+    pub fn fit(&mut self, counts_a: &[f64], counts_b: &[f64], iterations: usize) {
+        for c in self.counts.iter_mut() {
+            *c = c.max(0.0) + MIN_CELL_COUNT;
+        }
+
+        for _ in 0..iterations {
+            for (a, target) in counts_a.iter().enumerate() {
+                let row = &mut self.counts[a * self.domain_size_b..(a + 1) * self.domain_size_b];
+                let sum: f64 = row.iter().sum();
+
+                if sum > 0.0 {
+                    for c in row.iter_mut() {
+                        *c *= target / sum;
+                    }
+                }
+            }
+            for (b, target) in counts_b.iter().enumerate() {
+                let sum: f64 = (0..self.domain_size_a).map(|a| self.get(a, b)).sum();
+
+                if sum > 0.0 {
+                    for a in 0..self.domain_size_a {
+                        self.counts[a * self.domain_size_b + b] *= target / sum;
+                    }
+                }
+            }
+        }
+    }
+}
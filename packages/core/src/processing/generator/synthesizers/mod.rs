@@ -1,9 +1,13 @@
 mod aggregate_seeded;
+mod aggregates_count_sampler;
 mod attribute_rows_sampler;
 mod cache;
 mod consolidate_parameters;
+mod constraints;
+mod dp_marginal;
 mod oversampling_parameters;
 mod row_seeded;
+mod target_distribution;
 mod traits;
 mod typedefs;
 mod unseeded;
@@ -11,8 +15,11 @@ mod value_seeded;
 
 pub use aggregate_seeded::*;
 pub use cache::*;
+pub use constraints::*;
+pub use dp_marginal::*;
 pub use oversampling_parameters::*;
 pub use row_seeded::*;
+pub use target_distribution::*;
 pub use typedefs::*;
 pub use unseeded::*;
 pub use value_seeded::*;
@@ -2,6 +2,7 @@ mod seeded_rows_synthesizer;
 
 use itertools::{izip, Itertools};
 use log::info;
+use rand::{prelude::SliceRandom, thread_rng};
 use seeded_rows_synthesizer::SeededRowsSynthesizer;
 use std::sync::Arc;
 
@@ -13,6 +14,7 @@ use crate::{
             attribute_rows_sampler::AttributeRowsSampler,
             cache::SynthesizerCache,
             consolidate_parameters::ConsolidateParameters,
+            constraints::SynthesisConstraintsEnforcer,
             traits::{Consolidate, ConsolidateContext, Suppress, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
@@ -22,7 +24,7 @@ use crate::{
     },
     utils::{
         math::calc_percentage,
-        reporting::{ReportProgress, StoppableResult},
+        reporting::{ProcessingStoppedError, ReportProgress, StoppableResult},
         threading::get_number_of_threads,
         time::ElapsedDurationLogger,
     },
@@ -42,6 +44,8 @@ pub struct RowSeededSynthesizer {
     cache_max_size: usize,
     /// Sampler that keeps the attribute rows distributions
     consolidate_sampler: AttributeRowsSampler,
+    /// Constraints that should be respected while sampling attributes
+    constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     /// Percentage already completed on the row synthesis step
     synthesize_percentage: f64,
     /// Percentage already completed on the consolidation step
@@ -57,12 +61,15 @@ impl RowSeededSynthesizer {
     /// * `attr_rows_map` - Maps a data block value to all the rows where it occurs
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache_max_size` - Maximum cache size allowed
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
         attr_rows_map: Arc<AttributeRowsMap>,
         resolution: usize,
         cache_max_size: usize,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     ) -> RowSeededSynthesizer {
         let consolidate_sampler = AttributeRowsSampler::new(
             data_block.clone(),
@@ -80,6 +87,7 @@ impl RowSeededSynthesizer {
             resolution,
             cache_max_size,
             consolidate_sampler,
+            constraints_enforcer,
             synthesize_percentage: 0.0,
             consolidate_percentage: 0.0,
             suppress_percentage: 0.0,
@@ -117,13 +125,83 @@ impl RowSeededSynthesizer {
             self.consolidate(
                 &mut synthesized_records,
                 progress_reporter,
-                ConsolidateParameters::default(),
+                ConsolidateParameters {
+                    constraints_enforcer: self.constraints_enforcer.clone(),
+                    ..Default::default()
+                },
             )?;
             self.suppress(&mut synthesized_records, progress_reporter)?;
         }
         Ok(synthesized_records)
     }
 
+    /// Performs the same synthesis as `run`, but hands every record to `on_record`
+    /// as soon as it is ready, instead of returning all of them at once.
+    /// The synthesized rows are kept in memory only until the consolidation
+    /// step starts, consolidated records are never collected, and suppression
+    /// is accounted on the fly
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    /// * `on_record` - Called for every synthesized record
+    pub fn stream<T, E, F>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+        on_record: &mut F,
+    ) -> Result<(), E>
+    where
+        T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
+    {
+        if !self.data_block.records.is_empty() {
+            let mut synthesized_records: SynthesizedRecords = SynthesizedRecords::new();
+            let mut rows_synthesizers: Vec<SeededRowsSynthesizer> = self.build_rows_synthesizers();
+            let mut suppressor = self.build_on_the_fly_suppressor();
+
+            self.synthesize_percentage = 0.0;
+            self.consolidate_percentage = 0.0;
+            self.suppress_percentage = 0.0;
+
+            self.synthesize_rows(
+                &mut synthesized_records,
+                &mut rows_synthesizers,
+                progress_reporter,
+            )?;
+
+            // the context needs the synthesized rows in the same order
+            // as the original ones, so build it before shuffling
+            let consolidate_context = self.build_consolidate_context(&synthesized_records);
+
+            // shuffle so the on the fly suppression is not biased
+            // towards the last rows
+            synthesized_records.shuffle(&mut thread_rng());
+
+            for record in synthesized_records.drain(..) {
+                if let Some(suppressed_record) = suppressor.suppress_record(record) {
+                    on_record(suppressed_record)?;
+                }
+            }
+
+            self.consolidate_sampler.clear_cache();
+            self.consolidate_from_context(
+                consolidate_context,
+                progress_reporter,
+                ConsolidateParameters {
+                    constraints_enforcer: self.constraints_enforcer.clone(),
+                    ..Default::default()
+                },
+                &mut |record| match suppressor.suppress_record(record) {
+                    Some(suppressed_record) => on_record(suppressed_record),
+                    None => Ok(()),
+                },
+            )?;
+            // suppression already happened along with the consolidation
+            self.update_suppress_progress(1, 1.0, progress_reporter)?;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn build_rows_synthesizers(&self) -> Vec<SeededRowsSynthesizer> {
         let chunk_size = ((self.data_block.records.len() as f64) / (get_number_of_threads() as f64))
@@ -139,6 +217,7 @@ impl RowSeededSynthesizer {
                 ),
                 c.cloned().collect(),
                 self.attr_rows_map.clone(),
+                self.constraints_enforcer.clone(),
             ));
         }
         rows_synthesizers
@@ -4,6 +4,7 @@ use crate::{
     data_block::{AttributeRowsMap, DataBlockRecord, DataBlockRecords},
     processing::generator::{
         synthesizers::attribute_rows_sampler::AttributeRowsSampler,
+        synthesizers::constraints::SynthesisConstraintsEnforcer,
         synthesizers::typedefs::{
             NotAllowedAttrSet, SynthesizedRecord, SynthesizedRecords, SynthesizerSeed,
         },
@@ -26,6 +27,7 @@ pub struct SeededRowsSynthesizer {
     pub sampler: AttributeRowsSampler,
     pub records: DataBlockRecords,
     pub attr_rows_map: Arc<AttributeRowsMap>,
+    pub constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
 }
 
 impl SeededRowsSynthesizer {
@@ -34,11 +36,13 @@ impl SeededRowsSynthesizer {
         sampler: AttributeRowsSampler,
         records: DataBlockRecords,
         attr_rows_map: Arc<AttributeRowsMap>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     ) -> SeededRowsSynthesizer {
         SeededRowsSynthesizer {
             sampler,
             records,
             attr_rows_map,
+            constraints_enforcer,
         }
     }
 
@@ -116,9 +120,17 @@ impl SeededRowsSynthesizer {
     fn synthesize_row(&mut self, seed: &DataBlockRecord) -> SynthesizedRecord {
         let current_seed: &SynthesizerSeed = &seed.values;
         let mut synthesized_record = SynthesizedRecord::default();
-        let not_allowed_attr_set = NotAllowedAttrSet::default();
+        let mut not_allowed_attr_set = NotAllowedAttrSet::default();
 
         loop {
+            if let Some(enforcer) = &self.constraints_enforcer {
+                enforcer.extend_not_allowed_attr_set(
+                    &synthesized_record,
+                    current_seed,
+                    &mut not_allowed_attr_set,
+                );
+            }
+
             let next = self.sampler.sample_next_attr_from_seed(
                 &synthesized_record,
                 current_seed,
@@ -0,0 +1,217 @@
+use fnv::FnvHashMap;
+use itertools::Itertools;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+use crate::{
+    data_block::{DataBlockHeadersSlice, DataBlockValue},
+    processing::{
+        aggregator::{AggregatedData, ValueCombination},
+        generator::synthesizers::typedefs::AttributeCountMap,
+    },
+};
+
+/// Maps a class value to the factor its counts should be scaled by
+pub type ClassScaleFactorMap = FnvHashMap<Arc<DataBlockValue>, f64>;
+
+/// Target distribution for the values of a single column, used to
+/// rebalance the synthetic data (e.g. a label column with 50/50 classes
+/// instead of 95/5) while keeping the within-class attribute relationships
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "pyo3", pyclass)]
+pub struct TargetDistribution {
+    /// Column (header) name that should be rebalanced
+    pub column: String,
+    /// Maps the column values to their target proportions
+    /// (proportions are normalized, so they do not need to sum up to 1).
+    /// Values not present here will not be synthesized
+    pub proportions: HashMap<String, f64>,
+}
+
+impl TargetDistribution {
+    /// Returns a new TargetDistribution
+    /// # Arguments
+    /// * `column` - Column (header) name that should be rebalanced
+    /// * `proportions` - Maps the column values to their target proportions
+    /// (proportions are normalized, so they do not need to sum up to 1)
+    #[inline]
+    pub fn new(column: String, proportions: HashMap<String, f64>) -> TargetDistribution {
+        TargetDistribution {
+            column,
+            proportions,
+        }
+    }
+
+    /// Returns the index of the rebalanced column in `headers` (if any)
+    /// # Arguments
+    /// * `headers` - Headers of the data being synthesized
+    #[inline]
+    pub fn calc_column_index(&self, headers: &DataBlockHeadersSlice) -> Option<usize> {
+        let column_index = headers.iter().position(|h| **h == self.column);
+
+        if column_index.is_none() {
+            warn!(
+                "column \"{}\" not found, the synthetic data will not be rebalanced",
+                self.column
+            );
+        }
+        column_index
+    }
+
+    /// Calculates the factor each class count should be scaled by, so the
+    /// classes of the rebalanced column match the target proportions
+    /// (the total number of records with the column defined is kept)
+    /// # Arguments
+    /// * `column_index` - Index of the rebalanced column
+    /// * `single_attr_counts` - Counts for every single attribute
+    pub fn calc_class_scale_factors(
+        &self,
+        column_index: usize,
+        single_attr_counts: &AttributeCountMap,
+    ) -> ClassScaleFactorMap {
+        let class_counts: Vec<(&Arc<DataBlockValue>, usize)> = single_attr_counts
+            .iter()
+            .filter(|(attr, _)| attr.column_index == column_index)
+            .map(|(attr, count)| (attr, *count))
+            .collect();
+        let total_count = class_counts.iter().map(|(_, count)| *count).sum::<usize>() as f64;
+        let total_proportion = self
+            .proportions
+            .iter()
+            .filter(|(value, _)| {
+                let found = class_counts
+                    .iter()
+                    .any(|(attr, count)| *attr.value == **value && *count > 0);
+
+                if !found {
+                    warn!(
+                        "value \"{}\" not found for column \"{}\", it can not be rebalanced",
+                        value, self.column
+                    );
+                }
+                found
+            })
+            .map(|(_, proportion)| proportion.max(0.0))
+            .sum::<f64>();
+
+        class_counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(attr, count)| {
+                let proportion = if total_proportion > 0.0 {
+                    self.proportions
+                        .get(attr.value.as_str())
+                        .map_or(0.0, |p| p.max(0.0) / total_proportion)
+                } else {
+                    0.0
+                };
+                let factor = proportion * total_count / (*count as f64);
+
+                info!(
+                    "rebalancing \"{}\" to {:.4} of the records (scale factor of {:.4})",
+                    attr.value, proportion, factor
+                );
+                ((*attr).clone(), factor)
+            })
+            .collect()
+    }
+
+    /// Rebalances the aggregated data by scaling the counts of
+    /// the combinations conditioned on each class.
+    ///
+    /// Combinations containing a class value are scaled by the class factor.
+    /// Combinations not containing the rebalanced column are recomputed from their
+    /// extensions with each class (when those are available - shorter than the
+    /// reporting length), otherwise the average rescaling ratio of their
+    /// sub-combinations is used
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data to be rebalanced
+    pub fn rebalance_aggregated_data(&self, aggregated_data: &AggregatedData) -> AggregatedData {
+        let mut new_aggregated_data = aggregated_data.clone();
+        let column_index = match self.calc_column_index(&aggregated_data.headers) {
+            Some(column_index) => column_index,
+            None => return new_aggregated_data,
+        };
+        let factors = self.calc_class_scale_factors(
+            column_index,
+            &aggregated_data.calc_single_attribute_counts(),
+        );
+        let mut new_counts: FnvHashMap<Arc<ValueCombination>, f64> = FnvHashMap::default();
+
+        // process from the smallest to the biggest combinations, so
+        // the rescaling ratio of the sub-combinations is already known
+        for (comb, count) in aggregated_data
+            .aggregates_count
+            .iter()
+            .sorted_by_key(|(comb, _)| comb.len())
+        {
+            let count_f64 = count.count as f64;
+            let new_count =
+                if let Some(class) = comb.iter().find(|attr| attr.column_index == column_index) {
+                    count_f64 * factors.get(class).cloned().unwrap_or(0.0)
+                } else if comb.len() < aggregated_data.reporting_length {
+                    let mut new_count = count_f64;
+
+                    for (class, factor) in factors.iter() {
+                        let mut comb_with_class = (**comb).clone();
+
+                        comb_with_class.extend(class.clone(), &aggregated_data.headers);
+
+                        if let Some(class_count) =
+                            aggregated_data.aggregates_count.get(&comb_with_class)
+                        {
+                            new_count += (factor - 1.0) * (class_count.count as f64);
+                        }
+                    }
+                    new_count
+                } else {
+                    let ratios = comb
+                        .iter()
+                        .combinations(comb.len() - 1)
+                        .filter_map(|sub_comb| {
+                            let sub_comb = Arc::new(ValueCombination::new(
+                                sub_comb.into_iter().cloned().collect(),
+                            ));
+                            let old_count = aggregated_data.aggregates_count.get(&sub_comb)?.count;
+
+                            if old_count > 0 {
+                                Some(new_counts.get(&sub_comb)? / (old_count as f64))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect_vec();
+
+                    if ratios.is_empty() {
+                        count_f64
+                    } else {
+                        count_f64 * ratios.iter().sum::<f64>() / (ratios.len() as f64)
+                    }
+                };
+
+            new_counts.insert(comb.clone(), new_count.max(0.0));
+        }
+
+        for (comb, count) in new_aggregated_data.aggregates_count.iter_mut() {
+            count.count = new_counts[comb].round() as usize;
+        }
+        new_aggregated_data
+            .aggregates_count
+            .retain(|_, count| count.count > 0);
+        new_aggregated_data
+    }
+}
+
+#[cfg(feature = "pyo3")]
+#[cfg_attr(feature = "pyo3", pymethods)]
+impl TargetDistribution {
+    #[inline]
+    #[new]
+    pub fn constructor(column: String, proportions: HashMap<String, f64>) -> TargetDistribution {
+        Self::new(column, proportions)
+    }
+}
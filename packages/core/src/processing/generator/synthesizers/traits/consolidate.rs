@@ -17,7 +17,7 @@ use crate::{
     },
     utils::{
         math::iround_down,
-        reporting::{ReportProgress, StoppableResult},
+        reporting::{ProcessingStoppedError, ReportProgress, StoppableResult},
         time::ElapsedDurationLogger,
     },
 };
@@ -167,6 +167,15 @@ pub trait Consolidate: SynthesisData {
         let mut n_tries = oversampling_tries;
 
         loop {
+            if let Some(enforcer) = &parameters.constraints_enforcer {
+                // also prune the attributes that would violate the constraints
+                enforcer.extend_not_allowed_attr_set(
+                    &synthesized_record,
+                    &consolidate_context.current_seed,
+                    &mut not_allowed_attr_set,
+                );
+            }
+
             let next = self.sample_next_attr(
                 consolidate_context,
                 &last_processed,
@@ -209,15 +218,17 @@ pub trait Consolidate: SynthesisData {
     }
 
     #[inline]
-    fn consolidate_with_available_attributes<T>(
+    fn consolidate_with_available_attributes<T, E, F>(
         &mut self,
-        synthesized_records: &mut SynthesizedRecords,
+        on_record: &mut F,
         parameters: ConsolidateParameters,
         mut consolidate_context: ConsolidateContext,
         progress_reporter: &mut Option<T>,
-    ) -> StoppableResult<()>
+    ) -> Result<(), E>
     where
         T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
     {
         info!("consolidating based on available attributes...");
 
@@ -227,8 +238,7 @@ pub trait Consolidate: SynthesisData {
 
         while !consolidate_context.available_attrs.is_empty() {
             self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
-            synthesized_records
-                .push(self.consolidate_record(&mut consolidate_context, &parameters));
+            on_record(self.consolidate_record(&mut consolidate_context, &parameters))?;
             n_processed =
                 (total - consolidate_context.available_attrs.values().sum::<isize>()) as usize;
         }
@@ -238,16 +248,18 @@ pub trait Consolidate: SynthesisData {
     }
 
     #[inline]
-    fn consolidate_with_target_number_of_records<T>(
+    fn consolidate_with_target_number_of_records<T, E, F>(
         &mut self,
-        synthesized_records: &mut SynthesizedRecords,
+        on_record: &mut F,
         parameters: ConsolidateParameters,
         original_available_attrs: AvailableAttrsMap,
         mut consolidate_context: ConsolidateContext,
         progress_reporter: &mut Option<T>,
-    ) -> StoppableResult<()>
+    ) -> Result<(), E>
     where
         T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
     {
         let total = parameters.target_number_of_records.unwrap();
 
@@ -266,8 +278,7 @@ pub trait Consolidate: SynthesisData {
             }
 
             self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
-            synthesized_records
-                .push(self.consolidate_record(&mut consolidate_context, &parameters));
+            on_record(self.consolidate_record(&mut consolidate_context, &parameters))?;
             n_processed += 1;
         }
         self.update_consolidate_progress(n_processed, total_f64, progress_reporter)?;
@@ -275,6 +286,21 @@ pub trait Consolidate: SynthesisData {
         Ok(())
     }
 
+    #[inline]
+    fn build_consolidate_context(
+        &self,
+        synthesized_records: &SynthesizedRecordsSlice,
+    ) -> ConsolidateContext {
+        let available_attrs = self.calc_available_attrs(synthesized_records);
+        let current_seed: SynthesizerSeed = available_attrs.keys().cloned().collect();
+
+        ConsolidateContext {
+            current_seed,
+            available_attrs,
+            synthetic_counts: RawCombinationsCountMap::default(),
+        }
+    }
+
     fn consolidate<T>(
         &mut self,
         synthesized_records: &mut SynthesizedRecords,
@@ -283,29 +309,48 @@ pub trait Consolidate: SynthesisData {
     ) -> StoppableResult<()>
     where
         T: ReportProgress,
+    {
+        let consolidate_context = self.build_consolidate_context(synthesized_records);
+
+        self.consolidate_from_context(
+            consolidate_context,
+            progress_reporter,
+            parameters,
+            &mut |record| {
+                synthesized_records.push(record);
+                Ok(())
+            },
+        )
+    }
+
+    /// Consolidates starting from the already built `consolidate_context`,
+    /// handing every consolidated record to `on_record` as soon as it is ready
+    fn consolidate_from_context<T, E, F>(
+        &mut self,
+        consolidate_context: ConsolidateContext,
+        progress_reporter: &mut Option<T>,
+        parameters: ConsolidateParameters,
+        on_record: &mut F,
+    ) -> Result<(), E>
+    where
+        T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
     {
         let _duration_logger = ElapsedDurationLogger::new("consolidation");
 
         info!("consolidating...");
 
-        let available_attrs = self.calc_available_attrs(synthesized_records);
-        let current_seed: SynthesizerSeed = available_attrs.keys().cloned().collect();
-        let consolidate_context = ConsolidateContext {
-            current_seed,
-            available_attrs,
-            synthetic_counts: RawCombinationsCountMap::default(),
-        };
-
         if parameters.target_number_of_records.is_none() {
             self.consolidate_with_available_attributes(
-                synthesized_records,
+                on_record,
                 parameters,
                 consolidate_context,
                 progress_reporter,
             )
         } else {
             self.consolidate_with_target_number_of_records(
-                synthesized_records,
+                on_record,
                 parameters,
                 consolidate_context.available_attrs.clone(),
                 consolidate_context,
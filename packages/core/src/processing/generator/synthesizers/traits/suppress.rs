@@ -14,6 +14,55 @@ use crate::{
     },
 };
 
+/// Suppresses attributes from records that are handed over one at a time,
+/// keeping track of how many times each attribute can still be emitted
+pub struct OnTheFlySuppressor {
+    /// Maps an attribute to how many more times it can be emitted
+    remaining_attr_counts: FnvHashMap<Arc<DataBlockValue>, isize>,
+}
+
+impl OnTheFlySuppressor {
+    /// Returns a new OnTheFlySuppressor
+    /// # Arguments
+    /// * `remaining_attr_counts` - Maps an attribute to how many times it can be emitted
+    #[inline]
+    pub fn new(remaining_attr_counts: FnvHashMap<Arc<DataBlockValue>, isize>) -> Self {
+        OnTheFlySuppressor {
+            remaining_attr_counts,
+        }
+    }
+
+    /// Removes from `record` the attributes that have already been emitted
+    /// as many times as allowed.
+    /// Returns `None` if the record ends up empty
+    /// # Arguments
+    /// * `record` - Record to be suppressed
+    #[inline]
+    pub fn suppress_record(&mut self, record: SynthesizedRecord) -> Option<SynthesizedRecord> {
+        let new_record: SynthesizedRecord = record
+            .into_iter()
+            .filter(|attr| match self.remaining_attr_counts.get_mut(attr) {
+                // attributes without a limit are always kept
+                None => true,
+                Some(remaining) => {
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        true
+                    } else {
+                        false
+                    }
+                }
+            })
+            .collect();
+
+        if new_record.is_empty() {
+            None
+        } else {
+            Some(new_record)
+        }
+    }
+}
+
 pub trait Suppress: SynthesisData {
     #[inline]
     fn count_synthesized_records_attrs(
@@ -50,6 +99,28 @@ pub trait Suppress: SynthesisData {
         targets
     }
 
+    /// Builds a suppressor that limits every attribute to its count rounded
+    /// down to the resolution, so suppression can be performed while the records
+    /// are being emitted, instead of after all of them have been synthesized
+    #[inline]
+    fn build_on_the_fly_suppressor(&self) -> OnTheFlySuppressor {
+        OnTheFlySuppressor::new(
+            self.get_single_attr_counts()
+                .iter()
+                .filter_map(|(attr, n_rows)| {
+                    if *n_rows >= self.get_resolution() {
+                        Some((
+                            attr.clone(),
+                            iround_down(*n_rows as f64, self.get_resolution() as f64),
+                        ))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
+
     fn suppress<T>(
         &mut self,
         synthesized_records: &mut SynthesizedRecords,
@@ -6,15 +6,23 @@ use unseeded_rows_synthesizer::UnseededRowsSynthesizer;
 
 use crate::{
     data_block::{AttributeRowsByColumnMap, DataBlock},
-    processing::generator::synthesizers::{cache::SynthesizerCache, typedefs::SynthesizedRecords},
+    processing::generator::synthesizers::{
+        cache::SynthesizerCache,
+        constraints::SynthesisConstraintsEnforcer,
+        target_distribution::{ClassScaleFactorMap, TargetDistribution},
+        typedefs::{SynthesizedRecord, SynthesizedRecords},
+    },
     utils::{
         math::calc_percentage,
-        reporting::{ReportProgress, StoppableResult},
+        reporting::{ProcessingStoppedError, ReportProgress, StoppableResult},
         threading::get_number_of_threads,
         time::ElapsedDurationLogger,
     },
 };
 
+/// Number of rows each thread synthesizes before the records are streamed
+const STREAMED_ROWS_PER_THREAD: usize = 10000;
+
 /// Represents all the information required to perform the unseeded data synthesis
 pub struct UnseededSynthesizer {
     /// Reference to the original data block
@@ -27,6 +35,12 @@ pub struct UnseededSynthesizer {
     cache_max_size: usize,
     /// Empty values on the synthetic data will be represented by this
     empty_value: Arc<String>,
+    /// Constraints that should be respected while sampling attributes
+    constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+    /// Column rebalanced to match a target distribution (if any)
+    rebalanced_column: Option<usize>,
+    /// Factor each class of `rebalanced_column` should have its weight scaled by
+    class_scale_factors: Arc<ClassScaleFactorMap>,
     /// Percentage already completed on the row synthesis step
     synthesize_percentage: f64,
 }
@@ -39,6 +53,11 @@ impl UnseededSynthesizer {
     /// * `resolution` - Reporting resolution used for data synthesis
     /// * `cache_max_size` - Maximum cache size allowed
     /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
+    /// * `target_distribution` - Target distribution used to rebalance a column
+    /// (`None` means no rebalancing)
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
@@ -46,13 +65,38 @@ impl UnseededSynthesizer {
         resolution: usize,
         cache_max_size: usize,
         empty_value: Arc<String>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+        target_distribution: Option<TargetDistribution>,
     ) -> UnseededSynthesizer {
+        let rebalanced_column = target_distribution
+            .as_ref()
+            .and_then(|td| td.calc_column_index(&data_block.headers));
+        let class_scale_factors = match (target_distribution, rebalanced_column) {
+            (Some(td), Some(column_index)) => td.calc_class_scale_factors(
+                column_index,
+                &attr_rows_map_by_column
+                    .get(&column_index)
+                    .map(|attr_rows| {
+                        attr_rows
+                            .iter()
+                            .filter(|(attr, _)| *attr.value != *empty_value)
+                            .map(|(attr, rows)| (attr.clone(), rows.len()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            _ => ClassScaleFactorMap::default(),
+        };
+
         UnseededSynthesizer {
             data_block,
             attr_rows_map_by_column,
             resolution,
             cache_max_size,
             empty_value,
+            constraints_enforcer,
+            rebalanced_column,
+            class_scale_factors: Arc::new(class_scale_factors),
             synthesize_percentage: 0.0,
         }
     }
@@ -73,7 +117,7 @@ impl UnseededSynthesizer {
 
         if !self.data_block.records.is_empty() {
             let mut rows_synthesizers: Vec<UnseededRowsSynthesizer> =
-                self.build_rows_synthesizers();
+                self.build_rows_synthesizers(self.data_block.records.len());
 
             self.synthesize_percentage = 0.0;
 
@@ -86,9 +130,77 @@ impl UnseededSynthesizer {
         Ok(synthesized_records)
     }
 
+    /// Performs the same synthesis as `run`, but hands every synthesized record
+    /// to `on_record` instead of returning all of them at once.
+    /// Records are synthesized in rounds, so only the records of
+    /// the current round are kept in memory
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    /// * `on_record` - Called for every synthesized record
+    pub fn stream<T, E, F>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+        on_record: &mut F,
+    ) -> Result<(), E>
+    where
+        T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
+    {
+        let total = self.data_block.records.len();
+
+        if total == 0 {
+            return Ok(());
+        }
+
+        // rows are synthesized in rounds of parallel chunks, so at most
+        // one round of records is held in memory before being streamed
+        let round_size = get_number_of_threads() * STREAMED_ROWS_PER_THREAD;
+        let mut rows_synthesizers: Vec<UnseededRowsSynthesizer> =
+            self.build_rows_synthesizers(total.min(round_size));
+        let mut n_synthesized = 0;
+
+        self.synthesize_percentage = 0.0;
+
+        while n_synthesized < total {
+            let current_round_size = (total - n_synthesized).min(round_size);
+            let mut synthesized_records = SynthesizedRecords::new();
+
+            Self::split_chunks(current_round_size, &mut rows_synthesizers);
+            UnseededRowsSynthesizer::synthesize_all(
+                current_round_size as f64,
+                &mut synthesized_records,
+                &mut rows_synthesizers,
+                &mut None::<T>,
+            )?;
+            n_synthesized += current_round_size;
+            self.update_synthesize_progress(n_synthesized, total as f64, progress_reporter)?;
+
+            for record in synthesized_records.drain(..) {
+                on_record(record)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits `total_size` rows between the `rows_synthesizers`
+    /// (as many rows as possible to the first ones)
+    #[inline]
+    fn split_chunks(total_size: usize, rows_synthesizers: &mut [UnseededRowsSynthesizer]) {
+        let chunk_size = ((total_size as f64) / (rows_synthesizers.len() as f64)).ceil() as usize;
+        let mut remaining = total_size;
+
+        for rs in rows_synthesizers.iter_mut() {
+            let current_chunk_size = remaining.min(chunk_size);
+
+            rs.set_chunk_size(current_chunk_size);
+            remaining -= current_chunk_size;
+        }
+    }
+
     #[inline]
-    fn build_rows_synthesizers(&self) -> Vec<UnseededRowsSynthesizer> {
-        let mut total_size = self.data_block.records.len();
+    fn build_rows_synthesizers(&self, mut total_size: usize) -> Vec<UnseededRowsSynthesizer> {
         let chunk_size = ((total_size as f64) / (get_number_of_threads() as f64)).ceil() as usize;
         let mut rows_synthesizers: Vec<UnseededRowsSynthesizer> = Vec::default();
 
@@ -101,6 +213,9 @@ impl UnseededSynthesizer {
                     chunk_size,
                     self.attr_rows_map_by_column.clone(),
                     self.empty_value.clone(),
+                    self.constraints_enforcer.clone(),
+                    self.rebalanced_column,
+                    self.class_scale_factors.clone(),
                 ));
                 total_size -= chunk_size;
             } else {
@@ -111,6 +226,9 @@ impl UnseededSynthesizer {
                     total_size,
                     self.attr_rows_map_by_column.clone(),
                     self.empty_value.clone(),
+                    self.constraints_enforcer.clone(),
+                    self.rebalanced_column,
+                    self.class_scale_factors.clone(),
                 ));
                 break;
             }
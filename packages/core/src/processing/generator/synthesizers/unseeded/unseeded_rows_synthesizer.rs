@@ -10,6 +10,8 @@ use crate::{
         aggregator::RecordsSet,
         generator::synthesizers::{
             cache::{SynthesizerCache, SynthesizerCacheKey},
+            constraints::SynthesisConstraintsEnforcer,
+            target_distribution::ClassScaleFactorMap,
             typedefs::{AttributeCountMap, SynthesizedRecord, SynthesizedRecords},
         },
     },
@@ -35,9 +37,16 @@ pub struct UnseededRowsSynthesizer {
     column_indexes: Vec<usize>,
     attr_rows_map_by_column: Arc<AttributeRowsByColumnMap>,
     empty_value: Arc<String>,
+    constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+    rebalanced_column: Option<usize>,
+    class_scale_factors: Arc<ClassScaleFactorMap>,
 }
 
+/// Precision used to turn the rebalanced (floating point) weights into counts
+const REBALANCE_WEIGHT_PRECISION: f64 = 1000.0;
+
 impl UnseededRowsSynthesizer {
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     pub fn new(
         cache: SynthesizerCache<Arc<AttributeRows>>,
@@ -46,6 +55,9 @@ impl UnseededRowsSynthesizer {
         chunk_size: usize,
         attr_rows_map_by_column: Arc<AttributeRowsByColumnMap>,
         empty_value: Arc<String>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+        rebalanced_column: Option<usize>,
+        class_scale_factors: Arc<ClassScaleFactorMap>,
     ) -> UnseededRowsSynthesizer {
         UnseededRowsSynthesizer {
             cache,
@@ -55,9 +67,18 @@ impl UnseededRowsSynthesizer {
             column_indexes: attr_rows_map_by_column.keys().cloned().collect(),
             attr_rows_map_by_column,
             empty_value,
+            constraints_enforcer,
+            rebalanced_column,
+            class_scale_factors,
         }
     }
 
+    /// Sets how many rows the next `synthesize_all` call will synthesize
+    #[inline]
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size;
+    }
+
     #[cfg(feature = "rayon")]
     #[inline]
     pub fn synthesize_all<T>(
@@ -123,6 +144,16 @@ impl UnseededRowsSynthesizer {
 
         for _ in 0..self.chunk_size {
             shuffled_column_indexes.shuffle(&mut thread_rng());
+            // the rebalanced column is sampled first, so the remaining
+            // attributes are sampled conditioned on the class
+            if let Some(rebalanced_column) = self.rebalanced_column {
+                if let Some(pos) = shuffled_column_indexes
+                    .iter()
+                    .position(|column_index| *column_index == rebalanced_column)
+                {
+                    shuffled_column_indexes.swap(0, pos);
+                }
+            }
             synthesized_records.push(self.synthesize_row(&shuffled_column_indexes));
             SendableProgressReporter::update_progress(progress_reporter, 1.0)?;
         }
@@ -182,9 +213,15 @@ impl UnseededRowsSynthesizer {
             .collect();
 
         for (value, rows) in values_to_sample.iter() {
-            if rows.len() < self.resolution {
+            if rows.len() < self.resolution
+                || self
+                    .constraints_enforcer
+                    .as_ref()
+                    .is_some_and(|enforcer| !enforcer.is_allowed(synthesized_record, value))
+            {
                 // if the combination containing the attribute appears in less
-                // than resolution rows, we can't use it so we tag it as an empty value
+                // than resolution rows or violates a constraint, we can't use it
+                // so we tag it as an empty value
                 rows_with_empty_values.extend(rows.iter());
             } else if **value != *empty_block_value {
                 // if we can use the combination containing the attribute
@@ -195,7 +232,11 @@ impl UnseededRowsSynthesizer {
 
         // if there are empty values that can be sampled, add them for sampling
         if !rows_with_empty_values.is_empty() {
-            counts.insert(empty_block_value, rows_with_empty_values.len());
+            counts.insert(empty_block_value.clone(), rows_with_empty_values.len());
+        }
+
+        if self.rebalanced_column == Some(column_index) {
+            self.rebalance_counts(&mut counts, &empty_block_value);
         }
 
         sample_weighted(&counts).map(|sampled_value| {
@@ -205,4 +246,21 @@ impl UnseededRowsSynthesizer {
             )
         })
     }
+
+    #[inline]
+    fn rebalance_counts(
+        &self,
+        counts: &mut AttributeCountMap,
+        empty_block_value: &Arc<DataBlockValue>,
+    ) {
+        for (value, count) in counts.iter_mut() {
+            let factor = if **value == **empty_block_value {
+                1.0
+            } else {
+                self.class_scale_factors.get(value).cloned().unwrap_or(0.0)
+            };
+
+            *count = ((*count as f64) * factor * REBALANCE_WEIGHT_PRECISION).round() as usize;
+        }
+    }
 }
@@ -4,11 +4,13 @@ use std::sync::Arc;
 use crate::{
     data_block::{AttributeRowsMap, DataBlock, DataBlockHeaders, DataBlockValue},
     processing::{
-        aggregator::ValueCombination,
+        aggregator::{AggregatedData, ValueCombination},
         generator::synthesizers::{
+            aggregates_count_sampler::AggregatesCountSampler,
             attribute_rows_sampler::AttributeRowsSampler,
             cache::SynthesizerCache,
             consolidate_parameters::ConsolidateParameters,
+            constraints::SynthesisConstraintsEnforcer,
             traits::{Consolidate, ConsolidateContext, Suppress, SynthesisData},
             typedefs::{
                 AttributeCountMap, AvailableAttrsMap, NotAllowedAttrSet, SynthesizedRecord,
@@ -18,24 +20,40 @@ use crate::{
     },
     utils::{
         math::calc_percentage,
-        reporting::{ReportProgress, StoppableResult},
+        reporting::{ProcessingStoppedError, ReportProgress, StoppableResult},
     },
 };
 
+/// Where the counts used to sample the next attribute come from
+enum ValueSeededSource {
+    /// Rows of the sensitive data where the attributes occur
+    SensitiveData {
+        /// Reference to the original data block
+        data_block: Arc<DataBlock>,
+        /// Maps a data block value to all the rows where it occurs
+        attr_rows_map: AttributeRowsMap,
+        /// Sampler that keeps the attribute rows distributions
+        sampler: AttributeRowsSampler,
+    },
+    /// Aggregate counts, when no microdata is available
+    AggregatedData {
+        /// Aggregated data to synthesize from
+        aggregated_data: Arc<AggregatedData>,
+        /// Sampler that keeps the aggregate counts distributions
+        sampler: AggregatesCountSampler,
+    },
+}
+
 /// Represents all the information required to perform the value seeded synthesis
 pub struct ValueSeededSynthesizer {
-    /// Reference to the original data block
-    data_block: Arc<DataBlock>,
-    /// Maps a data block value to all the rows where it occurs
-    attr_rows_map: AttributeRowsMap,
+    /// Source of the counts used for sampling
+    source: ValueSeededSource,
     /// Cached single attribute counts
     single_attr_counts: AttributeCountMap,
     /// Reporting resolution used for data synthesis
     resolution: usize,
     /// Parameters used for data consolidation
     consolidate_parameters: ConsolidateParameters,
-    /// Sampler that keeps the attribute rows distributions
-    consolidate_sampler: AttributeRowsSampler,
     /// Percentage already completed on the consolidation step
     consolidate_percentage: f64,
     /// Percentage already completed on the suppression step
@@ -51,6 +69,8 @@ impl ValueSeededSynthesizer {
     /// * `cache_max_size` - Maximum cache size allowed
     /// * `oversampling_parameters` - Parameters used to control oversampling
     /// (if `None`, oversampling will be unlimited)
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
     #[inline]
     pub fn new(
         data_block: Arc<DataBlock>,
@@ -58,22 +78,72 @@ impl ValueSeededSynthesizer {
         resolution: usize,
         cache_max_size: usize,
         oversampling_parameters: Option<OversamplingParameters>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
     ) -> ValueSeededSynthesizer {
-        let consolidate_sampler = AttributeRowsSampler::new(
+        let sampler = AttributeRowsSampler::new(
             data_block.clone(),
             resolution,
             SynthesizerCache::new(cache_max_size),
         );
 
-        ValueSeededSynthesizer {
-            data_block,
-            single_attr_counts: attr_rows_map
+        Self::new_from_source(
+            attr_rows_map
                 .iter()
                 .map(|(attr, rows)| (attr.clone(), rows.len()))
                 .collect(),
-            attr_rows_map,
+            ValueSeededSource::SensitiveData {
+                data_block,
+                attr_rows_map,
+                sampler,
+            },
+            resolution,
+            oversampling_parameters,
+            constraints_enforcer,
+        )
+    }
+
+    /// Returns a new ValueSeededSynthesizer sampling from the aggregate counts
+    /// instead of the sensitive data, so no microdata is needed. When the synthesized
+    /// record gets longer than the reporting length, the count of the record is
+    /// estimated by the smallest count among its combinations
+    /// # Arguments
+    /// * `aggregated_data` - Aggregated data to synthesize from
+    /// * `resolution` - Reporting resolution used for data synthesis
+    /// * `oversampling_parameters` - Parameters used to control oversampling
+    /// (if `None`, oversampling will be unlimited)
+    /// * `constraints_enforcer` - Constraints that should be respected while sampling attributes
+    /// (`None` means no constraints)
+    #[inline]
+    pub fn new_from_aggregates(
+        aggregated_data: Arc<AggregatedData>,
+        resolution: usize,
+        oversampling_parameters: Option<OversamplingParameters>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+    ) -> ValueSeededSynthesizer {
+        Self::new_from_source(
+            aggregated_data.calc_single_attribute_counts(),
+            ValueSeededSource::AggregatedData {
+                sampler: AggregatesCountSampler::new(aggregated_data.clone(), resolution),
+                aggregated_data,
+            },
+            resolution,
+            oversampling_parameters,
+            constraints_enforcer,
+        )
+    }
+
+    #[inline]
+    fn new_from_source(
+        single_attr_counts: AttributeCountMap,
+        source: ValueSeededSource,
+        resolution: usize,
+        oversampling_parameters: Option<OversamplingParameters>,
+        constraints_enforcer: Option<Arc<SynthesisConstraintsEnforcer>>,
+    ) -> ValueSeededSynthesizer {
+        ValueSeededSynthesizer {
+            source,
+            single_attr_counts,
             resolution,
-            consolidate_sampler,
             consolidate_parameters: oversampling_parameters
                 .map(|params| {
                     ConsolidateParameters::new(
@@ -82,9 +152,13 @@ impl ValueSeededSynthesizer {
                         params.oversampling_tries,
                         None,
                         false,
+                        constraints_enforcer.clone(),
                     )
                 })
-                .unwrap_or_default(),
+                .unwrap_or_else(|| ConsolidateParameters {
+                    constraints_enforcer,
+                    ..Default::default()
+                }),
             consolidate_percentage: 0.0,
             suppress_percentage: 0.0,
         }
@@ -105,10 +179,10 @@ impl ValueSeededSynthesizer {
     {
         let mut synthesized_records: SynthesizedRecords = SynthesizedRecords::new();
 
-        if !self.data_block.records.is_empty() {
+        if self.has_records() {
             self.consolidate_percentage = 0.0;
             self.suppress_percentage = 0.0;
-            self.consolidate_sampler.clear_cache();
+            self.clear_cache();
 
             self.consolidate(
                 &mut synthesized_records,
@@ -120,6 +194,64 @@ impl ValueSeededSynthesizer {
         Ok(synthesized_records)
     }
 
+    /// Performs the same synthesis as `run`, but instead of collecting the records
+    /// hands every synthesized record to `on_record` as soon as it is ready.
+    /// Suppression is accounted on the fly, so records are not shuffled before
+    /// being suppressed
+    /// # Arguments
+    /// * `progress_reporter` - Will be used to report the processing
+    /// progress (`ReportProgress` trait). If `None`, nothing will be reported
+    /// * `on_record` - Called for every synthesized record
+    pub fn stream<T, E, F>(
+        &mut self,
+        progress_reporter: &mut Option<T>,
+        on_record: &mut F,
+    ) -> Result<(), E>
+    where
+        T: ReportProgress,
+        E: From<ProcessingStoppedError>,
+        F: FnMut(SynthesizedRecord) -> Result<(), E>,
+    {
+        if self.has_records() {
+            let consolidate_context = self.build_consolidate_context(&[]);
+            let mut suppressor = self.build_on_the_fly_suppressor();
+
+            self.consolidate_percentage = 0.0;
+            self.suppress_percentage = 0.0;
+            self.clear_cache();
+
+            self.consolidate_from_context(
+                consolidate_context,
+                progress_reporter,
+                self.consolidate_parameters.clone(),
+                &mut |record| match suppressor.suppress_record(record) {
+                    Some(suppressed_record) => on_record(suppressed_record),
+                    None => Ok(()),
+                },
+            )?;
+            // suppression already happened along with the consolidation
+            self.update_suppress_progress(1, 1.0, progress_reporter)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn has_records(&self) -> bool {
+        match &self.source {
+            ValueSeededSource::SensitiveData { data_block, .. } => !data_block.records.is_empty(),
+            ValueSeededSource::AggregatedData {
+                aggregated_data, ..
+            } => aggregated_data.number_of_records > 0,
+        }
+    }
+
+    #[inline]
+    fn clear_cache(&mut self) {
+        if let ValueSeededSource::SensitiveData { sampler, .. } = &mut self.source {
+            sampler.clear_cache();
+        }
+    }
+
     #[inline]
     fn calc_overall_progress(&self) -> f64 {
         self.consolidate_percentage * 0.7 + self.suppress_percentage * 0.3
@@ -129,7 +261,12 @@ impl ValueSeededSynthesizer {
 impl SynthesisData for ValueSeededSynthesizer {
     #[inline]
     fn get_headers(&self) -> &DataBlockHeaders {
-        &self.data_block.headers
+        match &self.source {
+            ValueSeededSource::SensitiveData { data_block, .. } => &data_block.headers,
+            ValueSeededSource::AggregatedData {
+                aggregated_data, ..
+            } => &aggregated_data.headers,
+        }
     }
 
     #[inline]
@@ -170,9 +307,9 @@ impl Consolidate for ValueSeededSynthesizer {
                 .collect()
         } else {
             // get all the single attribute counts
-            self.attr_rows_map
+            self.single_attr_counts
                 .iter()
-                .map(|(attr, rows)| (attr.clone(), rows.len() as isize))
+                .map(|(attr, count)| (attr.clone(), *count as isize))
                 .collect()
         }
     }
@@ -185,12 +322,24 @@ impl Consolidate for ValueSeededSynthesizer {
         synthesized_record: &SynthesizedRecord,
         not_allowed_attr_set: &NotAllowedAttrSet,
     ) -> Option<Arc<DataBlockValue>> {
-        self.consolidate_sampler.sample_next_attr_from_seed(
-            synthesized_record,
-            &consolidate_context.current_seed,
-            not_allowed_attr_set,
-            &self.attr_rows_map,
-        )
+        match &mut self.source {
+            ValueSeededSource::SensitiveData {
+                attr_rows_map,
+                sampler,
+                ..
+            } => sampler.sample_next_attr_from_seed(
+                synthesized_record,
+                &consolidate_context.current_seed,
+                not_allowed_attr_set,
+                attr_rows_map,
+            ),
+            ValueSeededSource::AggregatedData { sampler, .. } => sampler
+                .sample_next_attr_from_seed(
+                    synthesized_record,
+                    &consolidate_context.current_seed,
+                    not_allowed_attr_set,
+                ),
+        }
     }
 
     #[inline]
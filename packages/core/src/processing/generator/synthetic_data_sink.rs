@@ -0,0 +1,95 @@
+use super::synthesizers::{SynthesizedRecord, SynthesizerCacheKey};
+use super::SyntheticDataSinkError;
+use std::sync::Arc;
+
+use crate::data_block::{CsvRecordRef, DataBlockHeadersSlice, RawData};
+
+/// Implement this trait to receive the synthetic records
+/// in batches, as soon as they are generated
+pub trait SyntheticDataSink {
+    /// Called once, before any record is written
+    /// # Arguments
+    /// * `headers` - Headers of the synthetic data
+    fn write_headers(
+        &mut self,
+        headers: &DataBlockHeadersSlice,
+    ) -> Result<(), SyntheticDataSinkError>;
+
+    /// Called for every batch of synthesized records
+    /// # Arguments
+    /// * `records` - Batch of records, following the headers order
+    fn write_records(&mut self, records: &[CsvRecordRef]) -> Result<(), SyntheticDataSinkError>;
+}
+
+/// Groups synthesized records into batches of a fixed size
+/// before handing them to a sink
+pub(crate) struct SyntheticDataBatcher<'sink, S>
+where
+    S: SyntheticDataSink,
+{
+    sink: &'sink mut S,
+    batch: RawData,
+    batch_size: usize,
+    headers_len: usize,
+    empty_value: Arc<String>,
+    number_of_records: usize,
+}
+
+impl<'sink, S> SyntheticDataBatcher<'sink, S>
+where
+    S: SyntheticDataSink,
+{
+    /// Returns a new SyntheticDataBatcher, writing the headers to the sink
+    /// # Arguments
+    /// * `sink` - Sink receiving the batches
+    /// * `headers` - Headers of the synthetic data
+    /// * `empty_value` - Empty values on the synthetic data will be represented by this
+    /// * `batch_size` - Number of records on every batch (at least 1)
+    pub fn new(
+        sink: &'sink mut S,
+        headers: &DataBlockHeadersSlice,
+        empty_value: Arc<String>,
+        batch_size: usize,
+    ) -> Result<Self, SyntheticDataSinkError> {
+        let batch_size = batch_size.max(1);
+
+        sink.write_headers(headers)?;
+
+        Ok(SyntheticDataBatcher {
+            sink,
+            batch: RawData::with_capacity(batch_size),
+            batch_size,
+            headers_len: headers.len(),
+            empty_value,
+            number_of_records: 0,
+        })
+    }
+
+    /// Formats and buffers a synthesized record, flushing
+    /// the current batch to the sink when it is full
+    /// # Arguments
+    /// * `record` - Synthesized record
+    #[inline]
+    pub fn push(&mut self, record: SynthesizedRecord) -> Result<(), SyntheticDataSinkError> {
+        self.batch.push(
+            SynthesizerCacheKey::new(self.headers_len, &record).format_record(&self.empty_value),
+        );
+        self.number_of_records += 1;
+
+        if self.batch.len() >= self.batch_size {
+            self.sink.write_records(&self.batch)?;
+            self.batch.clear();
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining records to the sink.
+    /// Returns the total number of records written
+    pub fn finish(mut self) -> Result<usize, SyntheticDataSinkError> {
+        if !self.batch.is_empty() {
+            self.sink.write_records(&self.batch)?;
+            self.batch.clear();
+        }
+        Ok(self.number_of_records)
+    }
+}
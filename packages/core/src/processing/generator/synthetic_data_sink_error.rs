@@ -0,0 +1,53 @@
+use std::fmt::{Debug, Display, Formatter, Result};
+
+#[cfg(feature = "pyo3")]
+use pyo3::exceptions::PyIOError;
+
+#[cfg(feature = "pyo3")]
+use pyo3::prelude::*;
+
+use crate::{data_block::CsvIOError, utils::reporting::ProcessingStoppedError};
+
+/// Error that might happen while streaming synthetic records to a sink
+pub enum SyntheticDataSinkError {
+    /// The generation has been stopped by the progress reporter
+    ProcessingStopped(ProcessingStoppedError),
+    /// The sink failed to write a batch of records
+    WriteError(String),
+}
+
+impl Display for SyntheticDataSinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            SyntheticDataSinkError::ProcessingStopped(err) => write!(f, "{}", err),
+            SyntheticDataSinkError::WriteError(err) => {
+                write!(f, "error writing synthetic data: {}", err)
+            }
+        }
+    }
+}
+
+impl Debug for SyntheticDataSinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl From<ProcessingStoppedError> for SyntheticDataSinkError {
+    fn from(err: ProcessingStoppedError) -> SyntheticDataSinkError {
+        SyntheticDataSinkError::ProcessingStopped(err)
+    }
+}
+
+impl From<CsvIOError> for SyntheticDataSinkError {
+    fn from(err: CsvIOError) -> SyntheticDataSinkError {
+        SyntheticDataSinkError::WriteError(err.to_string())
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl From<SyntheticDataSinkError> for PyErr {
+    fn from(err: SyntheticDataSinkError) -> PyErr {
+        PyIOError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,72 @@
+use super::synthesizers::{SynthesizedRecord, SynthesizedRecords};
+use crate::data_block::DataBlock;
+use fnv::{FnvHashMap, FnvHashSet};
+use log::info;
+
+/// Rejects synthesized records that exactly reproduce a
+/// sensitive record occurring only once in the data block
+pub struct UniqueCopiesFilter {
+    unique_records: FnvHashSet<SynthesizedRecord>,
+    number_of_rejected_records: usize,
+}
+
+impl UniqueCopiesFilter {
+    /// Returns a new UniqueCopiesFilter
+    /// # Arguments
+    /// * `data_block` - Sensitive data the unique records are taken from
+    pub fn new(data_block: &DataBlock) -> UniqueCopiesFilter {
+        let mut counts: FnvHashMap<SynthesizedRecord, usize> = FnvHashMap::default();
+
+        for r in data_block.records.iter() {
+            *counts
+                .entry(r.values.iter().cloned().collect())
+                .or_insert(0) += 1;
+        }
+
+        UniqueCopiesFilter {
+            unique_records: counts
+                .drain()
+                .filter_map(|(record, count)| if count == 1 { Some(record) } else { None })
+                .collect(),
+            number_of_rejected_records: 0,
+        }
+    }
+
+    /// Returns whether `record` should be kept
+    /// (`false` if it is a copy of a unique sensitive record)
+    /// # Arguments
+    /// * `record` - Synthesized record to check
+    #[inline]
+    pub fn accept(&mut self, record: &SynthesizedRecord) -> bool {
+        if self.unique_records.contains(record) {
+            self.number_of_rejected_records += 1;
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Removes from `synthesized_records` the copies of unique sensitive records
+    /// # Arguments
+    /// * `synthesized_records` - Synthesized records to be filtered
+    #[inline]
+    pub fn filter(&mut self, mut synthesized_records: SynthesizedRecords) -> SynthesizedRecords {
+        synthesized_records.retain(|r| self.accept(r));
+        synthesized_records
+    }
+
+    /// Number of synthesized records rejected so far
+    #[inline]
+    pub fn number_of_rejected_records(&self) -> usize {
+        self.number_of_rejected_records
+    }
+
+    /// Logs how many synthesized records were rejected
+    #[inline]
+    pub fn log_report(&self) {
+        info!(
+            "rejected {} synthetic record(s) copying unique sensitive records",
+            self.number_of_rejected_records
+        );
+    }
+}
@@ -2,5 +2,7 @@
 pub mod aggregator;
 /// Module to perform data evaluation
 pub mod evaluator;
+/// Module to generalize data using user-supplied hierarchies
+pub mod generalizer;
 /// Module to perform data generation (synthesis)
 pub mod generator;
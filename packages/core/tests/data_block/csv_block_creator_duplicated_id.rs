@@ -1,7 +1,10 @@
 use csv::ReaderBuilder;
 use itertools::Itertools;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlockCreator, RawData, RawDataMultiValueColumnJoiner},
+    data_block::{
+        CsvDataBlockCreator, DataBlockCreator, RawData, RawDataMultiValueColumnJoiner,
+        RecordSampling,
+    },
     processing::generator::SynthesizerCacheKey,
 };
 use std::sync::Arc;
@@ -25,6 +28,7 @@ fn invalid_duplicated_id_missing_id() {
         &[].iter().cloned().collect(),
         &[],
         0,
+        RecordSampling::First,
     );
 
     assert!(data_block.is_err());
@@ -45,6 +49,7 @@ fn invalid_duplicated_id_missing_multi_value_cols() {
         &[].iter().cloned().collect(),
         &[],
         0,
+        RecordSampling::First,
     );
 
     assert!(data_block.is_err());
@@ -73,6 +78,7 @@ fn valid_duplicated_id() {
         .collect(),
         &[],
         0,
+        RecordSampling::First,
     )
     .unwrap();
     let mut raw_data = RawData::default();
@@ -108,3 +114,94 @@ fn valid_duplicated_id() {
             == expected
     );
 }
+
+#[test]
+fn valid_max_records_per_subject() {
+    for (max_records_per_subject, expected_number_of_records) in [(1, 9), (2, 11), (3, 12)] {
+        let data_block = CsvDataBlockCreator::create_with_max_records_per_subject(
+            ReaderBuilder::new()
+                .delimiter(DELIMITER)
+                .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
+            "ID".to_owned(),
+            max_records_per_subject,
+            &[],
+            &[].iter().cloned().collect(),
+            &[],
+            0,
+            RecordSampling::First,
+        )
+        .unwrap();
+
+        // records are not joined, so no multi value columns are needed
+        assert!(data_block.number_of_records() == expected_number_of_records);
+        assert!(data_block.max_records_per_subject == max_records_per_subject);
+    }
+}
+
+#[test]
+fn invalid_max_records_per_subject_missing_id() {
+    let data_block = CsvDataBlockCreator::create_with_max_records_per_subject(
+        ReaderBuilder::new()
+            .delimiter(DELIMITER)
+            .from_path(get_path_on_resources(MISSING_ID_CSV_FILE_PATH)),
+        "ID".to_owned(),
+        2,
+        &[],
+        &[].iter().cloned().collect(),
+        &[],
+        0,
+        RecordSampling::First,
+    );
+
+    assert!(data_block.is_err());
+    if let Err(err) = data_block {
+        let s = err.to_string();
+        assert!(s == "records with missing/empty Subject ID")
+    }
+}
+
+#[test]
+fn valid_uniform_record_sampling_by_subject() {
+    for record_limit in [3, 0, 9] {
+        let data_block = CsvDataBlockCreator::create_with_max_records_per_subject(
+            ReaderBuilder::new()
+                .delimiter(DELIMITER)
+                .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
+            "ID".to_owned(),
+            1,
+            &[],
+            &[].iter().cloned().collect(),
+            &[],
+            record_limit,
+            RecordSampling::Uniform,
+        )
+        .unwrap();
+
+        // each sampled subject contributes a single record
+        assert!(data_block.number_of_records() == if record_limit == 3 { 3 } else { 9 });
+        // the sampling rate would reveal the number of subjects
+        assert!(data_block.sampling_rate.is_none());
+    }
+}
+
+#[test]
+fn invalid_poisson_record_sampling_rate() {
+    for rate in [0.0, 1.5] {
+        let data_block = CsvDataBlockCreator::create(
+            ReaderBuilder::new()
+                .delimiter(DELIMITER)
+                .from_path(get_path_on_resources(VALID_CSV_FILE_PATH)),
+            None,
+            &[],
+            &[].iter().cloned().collect(),
+            &[],
+            0,
+            RecordSampling::Poisson(rate),
+        );
+
+        assert!(data_block.is_err());
+        if let Err(err) = data_block {
+            assert!(err.to_string() == "poisson sampling rate must be > 0 and <= 1");
+        }
+    }
+}
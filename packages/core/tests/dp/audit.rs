@@ -0,0 +1,144 @@
+use rand::{rngs::StdRng, SeedableRng};
+use sds_core::{
+    dp::{
+        audit::{DpAuditParameters, DpAuditor, EmpiricalEpsilonEstimator, NeighboringDataBlocks},
+        DpParameters, InputValueByLen, NoisyCountThreshold,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use statrs::distribution::{Laplace, Normal};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
+
+fn get_neighbors(record_index: usize) -> NeighboringDataBlocks {
+    NeighboringDataBlocks::by_removing_record(
+        read_test_data_block(
+            TEST_FILE_PATH,
+            DELIMITER,
+            None,
+            &[],
+            &HashMap::default(),
+            &[],
+            0,
+        ),
+        record_index,
+    )
+    .unwrap()
+}
+
+#[test]
+fn validate_noise_aggregator_audit() {
+    let result = DpAuditor::new(DpAuditParameters::new(300, 42, None))
+        .audit_noise_aggregator::<LoggerProgressReporter>(
+            &get_neighbors(0),
+            1,
+            &DpParameters::new(1.0, 99, 0.1, Some(0.001), None, None, None, None),
+            NoisyCountThreshold::Fixed(InputValueByLen::default()),
+            &mut None,
+        )
+        .unwrap();
+
+    assert!(result.passed);
+    assert!(result.empirical_epsilon.lower_bound <= 1.0);
+}
+
+#[test]
+fn validate_noise_aggregator_audit_is_reproducible() {
+    let auditor = DpAuditor::new(DpAuditParameters::new(50, 7, None));
+    let dp_parameters = DpParameters::new(1.0, 99, 0.1, Some(0.001), None, None, None, None);
+    let neighbors = get_neighbors(0);
+    let audit = || {
+        auditor
+            .audit_noise_aggregator::<LoggerProgressReporter>(
+                &neighbors,
+                1,
+                &dp_parameters,
+                NoisyCountThreshold::Fixed(InputValueByLen::default()),
+                &mut None,
+            )
+            .unwrap()
+            .empirical_epsilon
+            .lower_bound
+    };
+
+    assert_eq!(audit(), audit());
+}
+
+#[test]
+fn validate_percentile_audit() {
+    let result = DpAuditor::new(DpAuditParameters::new(2000, 42, None))
+        .audit_percentile::<LoggerProgressReporter>(&get_neighbors(2), 50, 1.0, &mut None)
+        .unwrap();
+
+    assert!(result.passed);
+    assert!(result.empirical_epsilon.lower_bound <= 1.0);
+}
+
+#[test]
+fn validate_percentile_audit_removing_max() {
+    // the removed record is the only one holding the maximum number of attributes,
+    // the range of selectable values should still be the same on both data blocks
+    let result = DpAuditor::new(DpAuditParameters::new(2000, 42, None))
+        .audit_percentile::<LoggerProgressReporter>(&get_neighbors(0), 50, 1.0, &mut None)
+        .unwrap();
+
+    assert!(result.passed);
+    assert!(result.empirical_epsilon.lower_bound <= 1.0);
+}
+
+#[test]
+fn validate_estimator_detects_broken_guarantee() {
+    use rand::distributions::Distribution;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    // counts differing by 1 with laplace noise calibrated to epsilon = 4,
+    // while claiming epsilon = 1
+    let noise = Laplace::new(0.0, 1.0 / 4.0).unwrap();
+    let with_record: Vec<f64> = (0..2000).map(|_| 1.0 + noise.sample(&mut rng)).collect();
+    let without_record: Vec<f64> = (0..2000).map(|_| noise.sample(&mut rng)).collect();
+    let estimate = EmpiricalEpsilonEstimator::new(0.0, 0.95)
+        .unwrap()
+        .estimate(&with_record, &without_record)
+        .unwrap();
+
+    assert!(estimate.lower_bound > 1.0);
+    assert!(estimate.event.is_some());
+}
+
+#[test]
+fn validate_estimator_on_identical_distributions() {
+    use rand::distributions::Distribution;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let noise = Normal::new(0.0, 1.0).unwrap();
+    let with_record: Vec<f64> = (0..2000).map(|_| noise.sample(&mut rng)).collect();
+    let without_record: Vec<f64> = (0..2000).map(|_| noise.sample(&mut rng)).collect();
+    let estimate = EmpiricalEpsilonEstimator::new(0.0, 0.95)
+        .unwrap()
+        .estimate(&with_record, &without_record)
+        .unwrap();
+
+    assert!(estimate.lower_bound < 0.2);
+}
+
+#[test]
+fn invalid_neighbors_record_index() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let number_of_records = data_block.number_of_records();
+    let neighbors = NeighboringDataBlocks::by_removing_record(data_block, number_of_records);
+
+    assert!(neighbors.is_err());
+}
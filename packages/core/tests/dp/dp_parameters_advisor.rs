@@ -0,0 +1,106 @@
+use sds_core::{
+    dp::{
+        DpAdvisorError, DpAdvisorInputs, DpParametersAdvisor, NoiseAggregator, NoisyCountThreshold,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::read_test_data_block;
+
+fn get_inputs(target_relative_errors: Vec<f64>, max_epsilon: Option<f64>) -> DpAdvisorInputs {
+    DpAdvisorInputs::new(
+        10000,
+        vec![2, 3, 4, 5],
+        3,
+        target_relative_errors,
+        Some(1e-6),
+        max_epsilon,
+    )
+}
+
+#[test]
+pub fn validate_recommendation_meets_targets() {
+    let targets = vec![0.05, 0.1, 0.2];
+    let recommendation = DpParametersAdvisor::new(get_inputs(targets.clone(), None))
+        .recommend()
+        .unwrap();
+
+    assert_eq!(recommendation.sensitivities, vec![4, 6, 4]);
+    assert!(recommendation
+        .expected_relative_errors
+        .iter()
+        .zip(targets.iter())
+        .all(|(e, t)| *e <= *t + 1e-9));
+    assert!(matches!(
+        recommendation.threshold,
+        NoisyCountThreshold::Adaptive(_)
+    ));
+
+    // the aggregator should use the recommended sigmas - the test data is too small
+    // to protect the number of records, so spend only the marginals budget
+    let mut dp_parameters = recommendation.dp_parameters.clone();
+
+    dp_parameters.epsilon *= 1.0 - dp_parameters.number_of_records_epsilon_proportion.unwrap();
+    dp_parameters.number_of_records_epsilon_proportion = None;
+
+    let mut na = NoiseAggregator::new(
+        read_test_data_block(
+            "test_noise_aggregator.csv",
+            b',',
+            None,
+            &[],
+            &HashMap::default(),
+            &[],
+            0,
+        ),
+        3,
+        &dp_parameters,
+        recommendation.threshold.clone(),
+    );
+    let allocation = na
+        .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+        .unwrap()
+        .dp_budget_allocation
+        .unwrap();
+
+    for (share, sigma) in allocation.shares.iter().zip(recommendation.sigmas.iter()) {
+        assert!((share.sigma - sigma).abs() / sigma < 1e-6);
+    }
+}
+
+#[test]
+pub fn validate_tighter_targets_require_more_budget() {
+    let loose = DpParametersAdvisor::new(get_inputs(vec![0.1, 0.2, 0.4], None))
+        .recommend()
+        .unwrap();
+    let tight = DpParametersAdvisor::new(get_inputs(vec![0.05, 0.1, 0.2], None))
+        .recommend()
+        .unwrap();
+
+    assert!(tight.dp_parameters.epsilon > loose.dp_parameters.epsilon);
+}
+
+#[test]
+pub fn validate_unreachable_targets() {
+    assert!(matches!(
+        DpParametersAdvisor::new(get_inputs(vec![0.01, 0.01, 0.01], Some(1.0))).recommend(),
+        Err(DpAdvisorError::Unreachable(_))
+    ));
+    assert!(matches!(
+        DpParametersAdvisor::new(DpAdvisorInputs::new(
+            100,
+            vec![50, 50, 50],
+            3,
+            vec![0.1, 0.1, 0.1],
+            None,
+            None
+        ))
+        .recommend(),
+        Err(DpAdvisorError::Unreachable(_))
+    ));
+    assert!(matches!(
+        DpParametersAdvisor::new(get_inputs(vec![0.1, 0.1], None)).recommend(),
+        Err(DpAdvisorError::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,304 @@
+use itertools::Itertools;
+use sds_core::{
+    data_block::DataBlock,
+    dp::{DpColumnHierarchy, DpParameters, InputValueByLen, NoiseAggregator, NoisyCountThreshold},
+    processing::{aggregator::AggregatedData, generator::Generator},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::sync::Arc;
+
+use crate::utils::create_data_block;
+
+const COUNTRY: usize = 0;
+const STATE: usize = 1;
+const COUNTY: usize = 2;
+
+fn gen_data_block_with_counts(header: &str, counts: &[(String, usize)]) -> Arc<DataBlock> {
+    let mut data = format!("{header}\n");
+
+    for (record, count) in counts.iter() {
+        for _ in 0..*count {
+            data.push_str(record);
+            data.push('\n');
+        }
+    }
+
+    create_data_block(&data)
+}
+
+fn gen_data_block() -> Arc<DataBlock> {
+    gen_data_block_with_counts(
+        "country,state,county,sex",
+        &[
+            ("US,WA,King,F", 40),
+            ("US,WA,King,M", 35),
+            ("US,WA,Pierce,F", 20),
+            ("US,WA,Pierce,M", 15),
+            ("US,OR,Multnomah,F", 25),
+            ("US,OR,Multnomah,M", 20),
+            ("CA,BC,Vancouver,F", 30),
+            ("CA,BC,Vancouver,M", 25),
+        ]
+        .map(|(record, count)| (record.to_owned(), count)),
+    )
+}
+
+/// WA has 2 large counties and a long tail of counties with a single record,
+/// which are suppressed by the threshold, ID has a single record
+fn gen_long_tail_data_block() -> Arc<DataBlock> {
+    let mut counts = vec![
+        ("US,WA,King".to_owned(), 40),
+        ("US,WA,Pierce".to_owned(), 30),
+        ("US,OR,Multnomah".to_owned(), 45),
+        ("US,ID,Ada".to_owned(), 1),
+    ];
+
+    counts.extend((0..30).map(|i| (format!("US,WA,County{i}"), 1)));
+
+    gen_data_block_with_counts("country,state,county", &counts)
+}
+
+fn gen_hierarchy() -> DpColumnHierarchy {
+    DpColumnHierarchy::new(vec![
+        "country".to_owned(),
+        "state".to_owned(),
+        "county".to_owned(),
+    ])
+}
+
+fn aggregate_with_hierarchy(seed: u64) -> AggregatedData {
+    aggregate_with_threshold(
+        gen_data_block(),
+        NoisyCountThreshold::Fixed(InputValueByLen::default()),
+        seed,
+    )
+}
+
+fn aggregate_with_threshold(
+    data_block: Arc<DataBlock>,
+    threshold: NoisyCountThreshold,
+    seed: u64,
+) -> AggregatedData {
+    NoiseAggregator::new(
+        data_block,
+        3,
+        &DpParameters::new(
+            4.0,
+            99,
+            0.1,
+            Some(0.001),
+            None,
+            None,
+            None,
+            Some(vec![gen_hierarchy()]),
+        ),
+        threshold,
+    )
+    .with_seed(seed)
+    .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+    .unwrap()
+}
+
+fn count_of(
+    aggregated_data: &AggregatedData,
+    columns: &[usize],
+    filter: &[(usize, &str)],
+) -> usize {
+    aggregated_data
+        .aggregates_count
+        .iter()
+        .filter(|(comb, _)| {
+            comb.len() == columns.len()
+                && comb.iter().all(|v| columns.contains(&v.column_index))
+                && filter.iter().all(|(column_index, value)| {
+                    comb.iter()
+                        .any(|v| v.column_index == *column_index && *v.value == *value)
+                })
+        })
+        .map(|(_, count)| count.count)
+        .sum()
+}
+
+/// Checks the released children do not add up to more than their
+/// parent and that no child is released without its parent
+fn assert_children_within_parent(aggregated_data: &AggregatedData) -> usize {
+    let mut number_of_parents = 0;
+
+    for (comb, count) in aggregated_data.aggregates_count.iter() {
+        let filter: Vec<(usize, &str)> = comb
+            .iter()
+            .map(|v| (v.column_index, v.value.as_str()))
+            .collect();
+        let (columns, children_columns) = match comb.len() {
+            1 if comb[0].column_index == COUNTRY => (vec![COUNTRY], vec![COUNTRY, STATE]),
+            2 if count_of(aggregated_data, &[COUNTRY, STATE], &filter) > 0 => {
+                (vec![COUNTRY, STATE], vec![COUNTRY, STATE, COUNTY])
+            }
+            3 if comb.iter().all(|v| v.column_index <= COUNTY) => {
+                let parent_filter = filter
+                    .iter()
+                    .filter(|(column_index, _)| *column_index != COUNTY)
+                    .cloned()
+                    .collect_vec();
+
+                assert!(count_of(aggregated_data, &[COUNTRY, STATE], &parent_filter) > 0);
+                continue;
+            }
+            _ => continue,
+        };
+        let children_count = count_of(aggregated_data, &children_columns, &filter);
+
+        assert_eq!(count_of(aggregated_data, &columns, &filter), count.count);
+        if children_count > 0 {
+            assert!(children_count <= count.count);
+            number_of_parents += 1;
+        }
+    }
+    number_of_parents
+}
+
+#[test]
+fn validate_children_within_parent() {
+    for seed in 0..5 {
+        assert!(assert_children_within_parent(&aggregate_with_hierarchy(seed)) > 0);
+    }
+}
+
+#[test]
+fn validate_suppressed_children_do_not_bias_parents() {
+    let number_of_seeds = 10;
+    let (mut wa_sum, mut large_counties_sum, mut released_tail) = (0, 0, 0);
+
+    for seed in 0..number_of_seeds {
+        let aggregated_data = aggregate_with_threshold(
+            gen_long_tail_data_block(),
+            NoisyCountThreshold::Adaptive([(2, 0.1), (3, 0.1)].into_iter().collect()),
+            seed,
+        );
+
+        assert!(assert_children_within_parent(&aggregated_data) > 0);
+
+        wa_sum += count_of(&aggregated_data, &[COUNTRY, STATE], &[(STATE, "WA")]);
+        large_counties_sum += ["King", "Pierce"]
+            .iter()
+            .map(|county| {
+                count_of(
+                    &aggregated_data,
+                    &[COUNTRY, STATE, COUNTY],
+                    &[(COUNTY, *county)],
+                )
+            })
+            .sum::<usize>();
+        released_tail += count_of(
+            &aggregated_data,
+            &[COUNTRY, STATE, COUNTY],
+            &[(STATE, "WA")],
+        ) - count_of(
+            &aggregated_data,
+            &[COUNTRY, STATE, COUNTY],
+            &[(COUNTY, "King")],
+        ) - count_of(
+            &aggregated_data,
+            &[COUNTRY, STATE, COUNTY],
+            &[(COUNTY, "Pierce")],
+        );
+    }
+
+    let wa_mean = wa_sum as f64 / number_of_seeds as f64;
+    let large_counties_mean = large_counties_sum as f64 / number_of_seeds as f64;
+
+    // most of the tail is suppressed
+    assert!((released_tail as f64 / number_of_seeds as f64) < 15.0);
+    // WA keeps its 100 records instead of being pulled
+    // down to the released counties
+    assert!((wa_mean - 100.0).abs() < 8.0);
+    // and the released counties are not inflated to fill the gap
+    assert!((large_counties_mean - 70.0).abs() < 8.0);
+}
+
+#[test]
+fn validate_deeper_levels_match_the_tree() {
+    let aggregated_data = aggregate_with_hierarchy(0);
+
+    for county in ["King", "Pierce", "Multnomah", "Vancouver"] {
+        let tree_count = count_of(
+            &aggregated_data,
+            &[COUNTRY, STATE, COUNTY],
+            &[(COUNTY, county)],
+        );
+
+        assert_eq!(
+            count_of(&aggregated_data, &[COUNTY], &[(COUNTY, county)]),
+            tree_count
+        );
+        assert_eq!(
+            count_of(&aggregated_data, &[STATE, COUNTY], &[(COUNTY, county)]),
+            tree_count
+        );
+    }
+    assert_eq!(aggregated_data.column_hierarchies, vec![gen_hierarchy()]);
+}
+
+#[test]
+fn validate_synthesis_keeps_levels_consistent() {
+    let aggregated_data = Arc::new(aggregate_with_hierarchy(1));
+    let released_with_county = |column_index: usize, value: &str, county: &str| {
+        aggregated_data.aggregates_count.keys().any(|comb| {
+            comb.len() == 2
+                && comb
+                    .iter()
+                    .any(|v| v.column_index == column_index && *v.value == *value)
+                && comb
+                    .iter()
+                    .any(|v| v.column_index == COUNTY && *v.value == *county)
+        })
+    };
+    let generated_data = Generator::default()
+        .generate_aggregate_seeded::<LoggerProgressReporter>(
+            "",
+            aggregated_data.clone(),
+            false,
+            None,
+            None,
+            None,
+            None,
+            &mut None,
+        )
+        .unwrap();
+
+    assert!(generated_data.constraints_report.is_some());
+    assert!(generated_data
+        .synthetic_data
+        .iter()
+        .skip(1)
+        .filter(|r| !r[COUNTY].is_empty())
+        .all(|r| {
+            (r[COUNTRY].is_empty() || released_with_county(COUNTRY, &r[COUNTRY], &r[COUNTY]))
+                && (r[STATE].is_empty() || released_with_county(STATE, &r[STATE], &r[COUNTY]))
+        }));
+}
+
+#[test]
+fn validate_invalid_hierarchies() {
+    let data_block = gen_data_block();
+    let validate = |hierarchies: &[DpColumnHierarchy]| {
+        DpColumnHierarchy::validate_all(
+            hierarchies,
+            &data_block.headers,
+            &data_block.multi_value_column_metadata_map,
+        )
+    };
+
+    assert!(validate(&[gen_hierarchy()]).is_ok());
+    assert!(validate(&[DpColumnHierarchy::new(vec!["country".to_owned()])]).is_err());
+    assert!(validate(&[DpColumnHierarchy::new(vec![
+        "country".to_owned(),
+        "region".to_owned()
+    ])])
+    .is_err());
+    assert!(validate(&[
+        gen_hierarchy(),
+        DpColumnHierarchy::new(vec!["county".to_owned(), "sex".to_owned()])
+    ])
+    .is_err());
+}
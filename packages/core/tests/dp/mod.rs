@@ -1,3 +1,9 @@
+mod audit;
+
+mod dp_parameters_advisor;
+
+mod hierarchical_consistency;
+
 mod noise_aggregator;
 
 mod percentile;
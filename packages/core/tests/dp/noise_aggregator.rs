@@ -1,5 +1,9 @@
-use sds_core::dp::{
-    CombinationsCountMapByLen, DpParameters, InputValueByLen, NoiseAggregator, NoisyCountThreshold,
+use sds_core::{
+    dp::{
+        CombinationsCountMapByLen, DpParameters, DpWorkload, DpWorkloadQuery, InputValueByLen,
+        NoiseAggregator, NoisyCountThreshold,
+    },
+    utils::reporting::LoggerProgressReporter,
 };
 use std::collections::HashMap;
 
@@ -12,6 +16,25 @@ const DELIMITER: u8 = b',';
 const TEST_FILE_PATH: &str = "test_noise_aggregator.csv";
 
 fn get_noise_aggregator() -> NoiseAggregator {
+    get_noise_aggregator_with_workload(1.0, None)
+}
+
+fn get_noise_aggregator_with_workload(
+    epsilon: f64,
+    workload: Option<DpWorkload>,
+) -> NoiseAggregator {
+    get_noise_aggregator_with_threshold(
+        epsilon,
+        workload,
+        NoisyCountThreshold::Fixed(InputValueByLen::default()),
+    )
+}
+
+fn get_noise_aggregator_with_threshold(
+    epsilon: f64,
+    workload: Option<DpWorkload>,
+    threshold: NoisyCountThreshold,
+) -> NoiseAggregator {
     NoiseAggregator::new(
         read_test_data_block(
             TEST_FILE_PATH,
@@ -23,8 +46,8 @@ fn get_noise_aggregator() -> NoiseAggregator {
             0,
         ),
         3,
-        &DpParameters::new(1.0, 99, 0.1, Some(0.001), None, None),
-        NoisyCountThreshold::Fixed(InputValueByLen::default()),
+        &DpParameters::new(epsilon, 99, 0.1, Some(0.001), None, None, workload, None),
+        threshold,
     )
 }
 
@@ -192,3 +215,178 @@ pub fn validate_gen_all_current_aggregates_with_removed_combs() {
         ]),
     );
 }
+
+#[test]
+pub fn validate_workload_skips_uncovered_combinations() {
+    let mut na = get_noise_aggregator_with_workload(
+        1000.0,
+        Some(DpWorkload::new(
+            vec![DpWorkloadQuery::new(
+                vec!["A".to_owned(), "B".to_owned()],
+                2.0,
+            )],
+            Some(0.0),
+        )),
+    );
+    let aggregated_data = na
+        .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+        .unwrap();
+    let allocation = aggregated_data.dp_budget_allocation.unwrap();
+
+    // only columns A (0) and B (1) should be reported
+    assert!(aggregated_data
+        .aggregates_count
+        .keys()
+        .all(|comb| comb.iter().all(|v| v.column_index <= 1)));
+    assert_eq!(
+        allocation
+            .shares
+            .iter()
+            .map(|s| (s.combination_length, s.weight))
+            .collect::<Vec<(usize, f64)>>(),
+        vec![(1, 2.0), (2, 2.0)]
+    );
+    assert!(
+        (allocation
+            .shares
+            .iter()
+            .map(|s| s.budget_proportion)
+            .sum::<f64>()
+            - 1.0)
+            .abs()
+            < 1e-6
+    );
+}
+
+#[test]
+pub fn validate_workload_reduces_sigma_of_covered_combinations() {
+    let mut na = get_noise_aggregator_with_workload(
+        1.0,
+        Some(DpWorkload::new(
+            vec![DpWorkloadQuery::new(
+                vec!["A".to_owned(), "B".to_owned()],
+                2.0,
+            )],
+            None,
+        )),
+    );
+    let allocation = na
+        .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+        .unwrap()
+        .dp_budget_allocation
+        .unwrap();
+    let single_attribute_shares: Vec<_> = allocation
+        .shares
+        .iter()
+        .filter(|s| s.combination_length == 1)
+        .collect();
+
+    assert_eq!(allocation.shares.len(), 5);
+    assert_eq!(single_attribute_shares.len(), 2);
+    assert_eq!(single_attribute_shares[0].weight, 2.0);
+    assert_eq!(single_attribute_shares[1].weight, 1.0);
+    assert!(
+        (single_attribute_shares[1].sigma - 2.0 * single_attribute_shares[0].sigma).abs() < 1e-9
+    );
+}
+
+#[test]
+pub fn validate_amplified_budget_for_poisson_sampling() {
+    let mut data_block = std::sync::Arc::try_unwrap(read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    ))
+    .unwrap();
+
+    data_block.sampling_rate = Some(0.1);
+
+    let allocation = NoiseAggregator::new(
+        std::sync::Arc::new(data_block),
+        3,
+        &DpParameters::new(1.0, 99, 0.1, Some(0.001), None, None, None, None),
+        NoisyCountThreshold::Fixed(InputValueByLen::default()),
+    )
+    .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+    .unwrap()
+    .dp_budget_allocation
+    .unwrap();
+
+    assert_eq!(allocation.sampling_rate, Some(0.1));
+    // ln(1 + 0.1 * (e - 1)) and 0.1 * delta
+    assert!((allocation.amplified_epsilon - 0.158_565_1).abs() < 1e-6);
+    assert!((allocation.amplified_delta - 0.000_1).abs() < 1e-12);
+    assert!(get_noise_aggregator()
+        .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+        .unwrap()
+        .dp_budget_allocation
+        .is_some_and(|a| a.sampling_rate.is_none() && a.amplified_epsilon == a.epsilon));
+}
+
+#[test]
+pub fn validate_invalid_workloads() {
+    let headers = ["A", "B", "C"]
+        .iter()
+        .map(|h| std::sync::Arc::new(h.to_string()))
+        .collect::<Vec<_>>();
+    let validate = |workload: DpWorkload| workload.validate(&headers, &HashMap::default());
+
+    assert!(validate(DpWorkload::new(Vec::default(), None)).is_ok());
+    assert!(validate(DpWorkload::new(
+        vec![DpWorkloadQuery::new(vec!["A".to_owned()], 1.0)],
+        Some(0.0)
+    ))
+    .is_ok());
+    // nothing would be reported
+    assert!(validate(DpWorkload::new(Vec::default(), Some(0.0))).is_err());
+    assert!(validate(DpWorkload::new(
+        vec![DpWorkloadQuery::new(vec!["D".to_owned()], 1.0)],
+        None
+    ))
+    .is_err());
+    assert!(DpWorkload::new(Vec::default(), Some(-1.0))
+        .validate_weights()
+        .is_err());
+}
+
+#[test]
+pub fn validate_top_k_limits_reported_combinations() {
+    let top_k: InputValueByLen<usize> = [(1, 2), (2, 1)].iter().cloned().collect();
+    let mut na =
+        get_noise_aggregator_with_threshold(1000.0, None, NoisyCountThreshold::TopK(top_k.clone()));
+    let aggregated_data = na
+        .generate_noisy_aggregates::<LoggerProgressReporter>(&mut None)
+        .unwrap();
+    let allocation = aggregated_data.dp_budget_allocation.unwrap();
+
+    for l in 1..=2 {
+        let reported = aggregated_data
+            .aggregates_count
+            .keys()
+            .filter(|comb| comb.len() == l)
+            .count();
+
+        assert!(reported <= top_k[&l]);
+        assert_eq!(
+            allocation
+                .shares
+                .iter()
+                .filter(|s| s.combination_length == l)
+                .map(|s| s.number_of_combinations)
+                .sum::<usize>(),
+            reported
+        );
+    }
+
+    // with a high epsilon, the most frequent single attributes should be kept
+    assert!(aggregated_data
+        .aggregates_count
+        .contains_key(&gen_value_combination("a1")));
+    assert!(aggregated_data
+        .aggregates_count
+        .contains_key(&gen_value_combination("c1")));
+}
@@ -33,3 +33,19 @@ pub fn validate_quality_score_iter() {
         .collect_vec()
         .is_empty());
 }
+
+#[test]
+pub fn validate_quality_score_iter_with_max_value() {
+    // the range goes up to the max value, even if it is not on the data
+    assert!(
+        DpPercentile::new_with_max_value([4, 4, 4, 4, 4, 4, 4, 4, 5, 5].to_vec(), 7)
+            .kth_percentile_quality_scores_iter(60)
+            .eq([-6, -6, -6, -6, 0, -2, -2, -4])
+    );
+    // values above the max value are clamped to it
+    assert!(
+        DpPercentile::new_with_max_value([4, 4, 4, 4, 4, 4, 4, 4, 9, 9].to_vec(), 5)
+            .kth_percentile_quality_scores_iter(60)
+            .eq([-6, -6, -6, -6, 0, -4])
+    );
+}
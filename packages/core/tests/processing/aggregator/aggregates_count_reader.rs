@@ -0,0 +1,153 @@
+use itertools::Itertools;
+use sds_core::{
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        generator::Generator,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::{assert_map_equals, create_data_block, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_target_distribution.csv";
+
+#[test]
+fn validate_read_aggregates_count_round_trip() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let mut aggregated_data = Aggregator::new(data_block)
+        .aggregate::<LoggerProgressReporter>(2, &mut None)
+        .unwrap();
+
+    aggregated_data.protect_with_k_anonymity(2);
+
+    let read_aggregated_data = AggregatedData::from_aggregates_count_str(
+        &aggregated_data
+            .write_aggregates_to_string('\t', ";", None)
+            .unwrap(),
+        '\t',
+        ";",
+    )
+    .unwrap();
+
+    assert_eq!(read_aggregated_data.headers, aggregated_data.headers);
+    assert_eq!(read_aggregated_data.reporting_length, 2);
+    assert_eq!(read_aggregated_data.number_of_records, 8);
+    assert_eq!(read_aggregated_data.protected_number_of_records, Some(8));
+    assert_map_equals(
+        &read_aggregated_data.aggregates_count_as_str(";"),
+        &aggregated_data.aggregates_count_as_str(";"),
+    );
+
+    let generated_data = Generator::default()
+        .generate_aggregate_seeded::<LoggerProgressReporter>(
+            "",
+            read_aggregated_data.into(),
+            false,
+            None,
+            None,
+            Some(10),
+            None,
+            &mut None,
+        )
+        .unwrap();
+
+    assert_eq!(generated_data.number_of_synthetic_records(), 10);
+}
+
+#[test]
+fn validate_read_inconsistent_aggregates_count() {
+    // "A:a1" is reported with a smaller count than "A:a1;B:b1"
+    assert!(AggregatedData::from_aggregates_count_str(
+        "selections\tcount\nrecord_count\t5\nA:a1\t2\nB:b1\t4\nA:a1;B:b1\t3\n",
+        '\t',
+        ";",
+    )
+    .is_err());
+
+    // "B:b1" is missing
+    assert!(AggregatedData::from_aggregates_count_str(
+        "selections\tcount\nrecord_count\t5\nA:a1\t3\nA:a1;B:b1\t3\n",
+        '\t',
+        ";",
+    )
+    .is_err());
+}
+
+#[test]
+fn validate_value_seeded_from_read_aggregates_count() {
+    let mut data = "A,B,C,D\n".to_owned();
+
+    for (record, count) in [
+        ("a1,b1,c1,d1", 5),
+        ("a1,b2,c1,d2", 4),
+        ("a2,b1,c2,d1", 3),
+        ("a2,b2,c2,d2", 3),
+        ("a1,b1,c2,", 2),
+    ] {
+        for _ in 0..count {
+            data.push_str(record);
+            data.push('\n');
+        }
+    }
+
+    let data_block = create_data_block(&data);
+    let mut aggregated_data = Aggregator::new(data_block)
+        .aggregate::<LoggerProgressReporter>(2, &mut None)
+        .unwrap();
+
+    aggregated_data.protect_with_k_anonymity(2);
+
+    let read_aggregated_data = AggregatedData::from_aggregates_count_str(
+        &aggregated_data
+            .write_aggregates_to_string('\t', ";", None)
+            .unwrap(),
+        '\t',
+        ";",
+    )
+    .unwrap();
+    let aggregates_count = read_aggregated_data.aggregates_count_as_str(";");
+    let generated_data = Generator::default()
+        .generate_value_seeded_from_aggregates::<LoggerProgressReporter>(
+            read_aggregated_data.into(),
+            1,
+            "",
+            None,
+            &mut None,
+        )
+        .unwrap();
+
+    assert!(generated_data.number_of_synthetic_records() > 0);
+    // records longer than the reporting length are synthesized as well
+    assert!(generated_data.synthetic_data.iter().skip(1).any(|r| r
+        .iter()
+        .filter(|v| !v.is_empty())
+        .count()
+        > 2));
+
+    // every attribute pair on the synthetic records
+    // should be on the aggregate count table
+    for record in generated_data.synthetic_data.iter().skip(1) {
+        let values: Vec<String> = record
+            .iter()
+            .zip(generated_data.synthetic_data[0].iter())
+            .filter(|(value, _)| !value.is_empty())
+            .map(|(value, header)| format!("{header}:{value}"))
+            .sorted()
+            .collect();
+
+        for pair in values.iter().combinations(2) {
+            assert!(aggregates_count.contains_key(&pair.iter().join(";")));
+        }
+    }
+}
@@ -1 +1,3 @@
+mod aggregates_count_reader;
+mod records_risk;
 mod value_combination;
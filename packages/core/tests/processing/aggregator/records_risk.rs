@@ -0,0 +1,51 @@
+use crate::utils::aggregate;
+
+#[test]
+fn validate_records_risk() {
+    let records_risk = aggregate("A,B\na1,b1\na1,b1\na2,b1\na1,b2\n").calc_records_risk(3, 1, ";");
+
+    assert_eq!(records_risk.number_of_records, 4);
+    assert_eq!(records_risk.number_of_risky_records, 4);
+    assert_eq!(
+        records_risk
+            .records
+            .iter()
+            .map(|r| r.record_index)
+            .collect::<Vec<usize>>(),
+        vec![2, 3, 0, 1]
+    );
+
+    // a2 is unique, so the record is fully exposed
+    let most_at_risk = &records_risk.records[0];
+
+    assert!((most_at_risk.risk_score - 1.0).abs() < 1e-9);
+    assert_eq!(most_at_risk.smallest_combination_count, 1);
+    assert_eq!(most_at_risk.number_of_unique_combinations, 2);
+    assert_eq!(most_at_risk.number_of_rare_combinations, 0);
+    assert_eq!(
+        most_at_risk.risky_columns,
+        vec!["A".to_owned(), "B".to_owned()]
+    );
+    assert_eq!(most_at_risk.top_combinations.len(), 1);
+    assert_eq!(most_at_risk.top_combinations[0].combination, "A:a2");
+
+    // only (a1, b1) is rare: 1 - (1 - 1 / (2 * 2))
+    let least_at_risk = &records_risk.records[3];
+
+    assert!((least_at_risk.risk_score - 0.25).abs() < 1e-9);
+    assert_eq!(least_at_risk.smallest_combination_count, 2);
+    assert_eq!(least_at_risk.number_of_unique_combinations, 0);
+    assert_eq!(least_at_risk.number_of_rare_combinations, 1);
+    assert_eq!(least_at_risk.top_combinations[0].combination, "A:a1;B:b1");
+}
+
+#[test]
+fn validate_records_risk_without_rare_combinations() {
+    let records_risk = aggregate("A,B\na1,b1\na1,b1\na1,b1\n").calc_records_risk(2, 5, ";");
+
+    assert_eq!(records_risk.number_of_records, 3);
+    assert_eq!(records_risk.number_of_risky_records, 0);
+    assert!(records_risk.records.is_empty());
+    assert!(records_risk.number_of_risky_records_by_column.is_empty());
+    assert!((records_risk.percentage_of_risky_records - 0.0).abs() < 1e-9);
+}
@@ -0,0 +1,74 @@
+use sds_core::processing::evaluator::{AttributeInferenceAttack, AttributeInferenceParameters};
+
+use crate::utils::create_data_block;
+
+fn gen_sensitive_data() -> String {
+    let mut data = "A,B,T\n".to_owned();
+
+    // (a1, b1) is common and always has t1, (a2, b2) is unique and has t2
+    for _ in 0..9 {
+        data += "a1,b1,t1\n";
+    }
+    data += "a2,b2,t2\n";
+    data
+}
+
+fn create_attack(quasi_identifiers: &[&str], target: &str) -> AttributeInferenceAttack {
+    AttributeInferenceAttack::new(AttributeInferenceParameters::new(
+        quasi_identifiers.iter().map(|qi| qi.to_string()).collect(),
+        target.to_owned(),
+        2,
+    ))
+}
+
+#[test]
+fn validate_attribute_inference_on_copied_records() {
+    let sensitive = create_data_block(&gen_sensitive_data());
+    let result = create_attack(&["A", "B"], "T")
+        .run(&sensitive, &create_data_block(&gen_sensitive_data()))
+        .unwrap();
+
+    assert_eq!(result.overall.number_of_records, 10);
+    assert_eq!(result.overall.number_of_matched_records, 10);
+    assert!((result.overall.accuracy - 1.0).abs() < 1e-9);
+    assert!((result.overall.baseline_accuracy - 0.9).abs() < 1e-9);
+    assert_eq!(result.rare.number_of_records, 1);
+    assert!((result.rare.accuracy - 1.0).abs() < 1e-9);
+    assert!(result.rare.baseline_accuracy.abs() < 1e-9);
+    assert_eq!(result.common.number_of_records, 9);
+
+    let unique = &result.inferences[9];
+
+    assert_eq!(unique.quasi_identifiers, "A:a2;B:b2");
+    assert_eq!(unique.sensitive_count, 1);
+    assert_eq!(unique.synthetic_count, 1);
+    assert_eq!(unique.inferred_value, "t2");
+}
+
+#[test]
+fn validate_attribute_inference_falls_back_to_marginal() {
+    let sensitive = create_data_block(&gen_sensitive_data());
+    // the unique record was not synthesized
+    let synthetic = create_data_block("A,B,T\na1,b1,t1\na1,b1,t1\n");
+    let result = create_attack(&["A", "B"], "T")
+        .run(&sensitive, &synthetic)
+        .unwrap();
+
+    assert_eq!(result.rare.number_of_matched_records, 0);
+    assert!(result.rare.accuracy.abs() < 1e-9);
+    assert_eq!(result.inferences[9].inferred_value, "t1");
+    assert!((result.overall.accuracy - result.overall.baseline_accuracy).abs() < 1e-9);
+}
+
+#[test]
+fn invalid_attribute_inference_columns() {
+    let sensitive = create_data_block(&gen_sensitive_data());
+
+    assert!(create_attack(&["A", "T"], "T")
+        .run(&sensitive, &sensitive)
+        .is_err());
+    assert!(create_attack(&["A", "C"], "T")
+        .run(&sensitive, &sensitive)
+        .is_err());
+    assert!(create_attack(&[], "T").run(&sensitive, &sensitive).is_err());
+}
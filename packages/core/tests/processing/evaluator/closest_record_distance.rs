@@ -0,0 +1,53 @@
+use sds_core::processing::evaluator::ClosestRecordDistanceResult;
+
+use crate::utils::create_data_block;
+
+#[test]
+fn validate_closest_record_distances() {
+    // (a1, b1, c1) is repeated and (a2, b2, c2) is unique
+    let sensitive = create_data_block("A,B,C\na1,b1,c1\na1,b1,c1\na2,b2,c2\n");
+    let synthetic = create_data_block("A,B,C\na1,b1,c1\na2,b2,c2\na2,b2,c3\na3,b3,c3\n");
+    let result = ClosestRecordDistanceResult::from_sensitive_and_synthetic_data_blocks(
+        &sensitive, &synthetic,
+    )
+    .unwrap();
+
+    assert_eq!(result.number_of_columns, 3);
+    assert_eq!(result.number_of_unique_sensitive_records, 1);
+    assert_eq!(result.distance_histogram, vec![2, 1, 0, 1]);
+    assert!((result.mean_distance - 1.0).abs() < 1e-9);
+    assert_eq!(result.number_of_exact_matches, 2);
+    assert_eq!(result.number_of_unique_copies, 1);
+    assert!((result.unique_copies_proportion - 0.25).abs() < 1e-9);
+    assert_eq!(result.number_of_copied_unique_records, 1);
+    assert!((result.copied_unique_records_proportion - 1.0).abs() < 1e-9);
+
+    // the repeated record has two closest records at distance 0
+    assert_eq!(result.distances[0].second_distance, Some(0));
+    assert_eq!(result.distances[0].distance_ratio, Some(1.0));
+    assert!(!result.distances[0].is_unique_copy);
+
+    // the unique record is singled out
+    assert_eq!(result.distances[1].second_distance, Some(3));
+    assert_eq!(result.distances[1].distance_ratio, Some(0.0));
+    assert!(result.distances[1].is_unique_copy);
+
+    assert_eq!(result.distances[2].distance, 1);
+    assert!(!result.distances[2].is_unique_copy);
+}
+
+#[test]
+fn validate_closest_record_distances_without_common_columns() {
+    let result = ClosestRecordDistanceResult::from_sensitive_and_synthetic_data_blocks(
+        &create_data_block("A\na1\n"),
+        &create_data_block("B\nb1\n"),
+    );
+
+    assert!(result.is_err());
+    if let Err(err) = result {
+        assert_eq!(
+            err.to_string(),
+            "invalid privacy attack input: sensitive and synthetic data have no columns in common"
+        );
+    }
+}
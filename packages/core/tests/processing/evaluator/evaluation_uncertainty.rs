@@ -0,0 +1,245 @@
+use sds_core::{
+    processing::evaluator::{
+        BootstrapParameters, ComparisonMetric, ConfidenceInterval, EvaluationMetric,
+        EvaluationUncertainty, EvaluationUncertaintyMethod, Evaluator, MetricUncertainty,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+
+use crate::utils::{aggregate, create_data_block};
+
+const SENSITIVE_DATA: &str = "A,B\na1,b1\na1,b2\na2,b1\na2,b2\na1,b1\na2,b2\n";
+
+fn find_metric(
+    uncertainty: &EvaluationUncertainty,
+    metric: EvaluationMetric,
+    combination_length: Option<usize>,
+    count_bucket: Option<usize>,
+) -> &MetricUncertainty {
+    uncertainty
+        .metrics
+        .iter()
+        .find(|m| {
+            m.metric == metric
+                && m.combination_length == combination_length
+                && m.count_bucket == count_bucket
+        })
+        .unwrap()
+}
+
+#[test]
+fn validate_percentile_confidence_interval() {
+    let ci = ConfidenceInterval::from_percentiles(3.0, &[5.0, 1.0, 4.0, 2.0, 3.0], 0.5);
+
+    assert_eq!(ci.estimate, 3.0);
+    assert!((ci.lower_bound - 2.0).abs() < 1e-9);
+    assert!((ci.upper_bound - 4.0).abs() < 1e-9);
+
+    // interpolated between samples
+    let ci = ConfidenceInterval::from_percentiles(0.5, &[0.0, 1.0], 0.5);
+
+    assert!((ci.lower_bound - 0.25).abs() < 1e-9);
+    assert!((ci.upper_bound - 0.75).abs() < 1e-9);
+}
+
+#[test]
+fn validate_uncertainty_from_replicates() {
+    let sensitive = aggregate(SENSITIVE_DATA);
+    let first = aggregate(SENSITIVE_DATA);
+    let second = aggregate("A,B\na1,b1\na1,b2\na2,b1\na2,b2\n");
+    let uncertainty = Evaluator::default()
+        .calc_uncertainty_from_replicates(&sensitive, &[&first, &second], 1, 0.95)
+        .unwrap();
+
+    assert_eq!(
+        uncertainty.method,
+        EvaluationUncertaintyMethod::GenerationReplicates
+    );
+    assert_eq!(uncertainty.number_of_replicates, 2);
+    // overall metrics + 7 metrics for lengths 1 and 2 + 2 metrics for the only count bucket
+    assert_eq!(
+        uncertainty.metrics.len(),
+        ComparisonMetric::all().len() + 2 * 7 + 2
+    );
+    assert!(uncertainty.metrics.iter().all(|m| m.number_of_samples == 2));
+
+    // expansion is 0% and -33.33%
+    let expansion = find_metric(
+        &uncertainty,
+        EvaluationMetric::RecordExpansionPercentage,
+        None,
+        None,
+    );
+
+    assert!((expansion.mean + 100.0 / 6.0).abs() < 1e-9);
+    assert!((expansion.standard_deviation - (100.0 / 3.0) / 2.0_f64.sqrt()).abs() < 1e-9);
+    assert!((expansion.confidence_interval.estimate - expansion.mean).abs() < 1e-9);
+    assert!(expansion.confidence_interval.lower_bound < expansion.mean);
+    assert!(expansion.confidence_interval.upper_bound > expansion.mean);
+
+    // identical data on both replicates has no error on single attributes
+    assert_eq!(
+        find_metric(
+            &uncertainty,
+            EvaluationMetric::MeanTotalVariationDistance,
+            Some(1),
+            None
+        )
+        .mean,
+        0.0
+    );
+
+    // nothing is rare on the synthetic data
+    assert_eq!(
+        find_metric(
+            &uncertainty,
+            EvaluationMetric::LeakagePercentage,
+            Some(2),
+            None
+        )
+        .mean,
+        0.0
+    );
+
+    // single attributes are fully preserved on the first replicate
+    // and 2 out of 3 times on the second
+    let preservation = find_metric(
+        &uncertainty,
+        EvaluationMetric::MeanPreservation,
+        Some(1),
+        None,
+    );
+
+    assert!((preservation.mean - (1.0 + 2.0 / 3.0) / 2.0).abs() < 1e-9);
+
+    let error = find_metric(
+        &uncertainty,
+        EvaluationMetric::MeanProportionalError,
+        Some(1),
+        None,
+    );
+
+    assert!((error.mean - (1.0 / 3.0) / 2.0).abs() < 1e-9);
+
+    // every combination falls on the first count bucket
+    let preservation_by_count = find_metric(
+        &uncertainty,
+        EvaluationMetric::MeanPreservation,
+        None,
+        Some(10),
+    );
+
+    assert!(preservation_by_count.confidence_interval.lower_bound < 1.0);
+    assert!(preservation_by_count.mean < 1.0);
+}
+
+#[test]
+fn validate_uncertainty_from_identical_replicates() {
+    let sensitive = aggregate(SENSITIVE_DATA);
+    let synthetic = aggregate("A,B\na1,b1\na1,b1\na2,b2\n");
+    let uncertainty = Evaluator::default()
+        .calc_uncertainty_from_replicates(&sensitive, &[&synthetic, &synthetic, &synthetic], 2, 0.9)
+        .unwrap();
+
+    for m in uncertainty.metrics.iter() {
+        assert!(m.standard_deviation.abs() < 1e-9);
+        assert!((m.confidence_interval.lower_bound - m.mean).abs() < 1e-9);
+        assert!((m.confidence_interval.upper_bound - m.mean).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn validate_uncertainty_with_bootstrap() {
+    let sensitive = create_data_block(SENSITIVE_DATA);
+    let synthetic = create_data_block("A,B\na1,b1\na1,b1\na2,b2\na2,b1\na1,b2\n");
+    let parameters = BootstrapParameters::new(30, 7, None);
+    let run = || {
+        Evaluator::default()
+            .calc_uncertainty_with_bootstrap::<LoggerProgressReporter>(
+                &sensitive,
+                &synthetic,
+                2,
+                1,
+                &parameters,
+                &mut None,
+            )
+            .unwrap()
+    };
+    let uncertainty = run();
+    let original = Evaluator::default()
+        .compare_synthetic_data(
+            &aggregate(SENSITIVE_DATA),
+            &[(
+                "synthetic",
+                &aggregate("A,B\na1,b1\na1,b1\na2,b2\na2,b1\na1,b2\n"),
+            )],
+            1,
+        )
+        .metrics;
+
+    assert_eq!(
+        uncertainty.method,
+        EvaluationUncertaintyMethod::RecordBootstrap
+    );
+    assert_eq!(uncertainty.number_of_replicates, 30);
+    assert_eq!(uncertainty.confidence_level, 0.95);
+
+    // metrics biased by resampling records are not summarized
+    assert!(uncertainty.metrics.iter().all(|m| !matches!(
+        m.metric,
+        EvaluationMetric::LeakageCount
+            | EvaluationMetric::LeakagePercentage
+            | EvaluationMetric::PercentageOfSuppressedCombinations
+            | EvaluationMetric::PercentageOfFabricatedCombinations
+            | EvaluationMetric::MeanPreservation
+            | EvaluationMetric::MeanProportionalError
+    ) && m.count_bucket.is_none()));
+    assert_eq!(
+        uncertainty.metrics.len(),
+        // 4 metrics over all the combinations and 3 by length, for lengths 1 and 2
+        4 + 3 * 2
+    );
+
+    // estimates are the metrics on the original records
+    for (m, o) in uncertainty.metrics.iter().zip(
+        original
+            .iter()
+            .filter(|o| EvaluationUncertaintyMethod::RecordBootstrap.supports(o.metric.into())),
+    ) {
+        assert_eq!(m.metric, o.metric.into());
+        assert_eq!(m.combination_length, None);
+        assert!((m.confidence_interval.estimate - o.values[0]).abs() < 1e-9);
+    }
+    for m in uncertainty.metrics.iter() {
+        assert!(m.confidence_interval.lower_bound <= m.confidence_interval.upper_bound);
+    }
+
+    // same seed, same samples
+    for (a, b) in uncertainty.metrics.iter().zip(run().metrics.iter()) {
+        assert_eq!(a.mean, b.mean);
+        assert_eq!(a.confidence_interval, b.confidence_interval);
+    }
+}
+
+#[test]
+fn validate_uncertainty_invalid_inputs() {
+    let sensitive = aggregate(SENSITIVE_DATA);
+    let synthetic = aggregate(SENSITIVE_DATA);
+
+    assert!(Evaluator::default()
+        .calc_uncertainty_from_replicates(&sensitive, &[&synthetic], 1, 0.95)
+        .is_err());
+    assert!(Evaluator::default()
+        .calc_uncertainty_from_replicates(&sensitive, &[&synthetic, &synthetic], 1, 1.0)
+        .is_err());
+    assert!(Evaluator::default()
+        .calc_uncertainty_with_bootstrap::<LoggerProgressReporter>(
+            &create_data_block(SENSITIVE_DATA),
+            &create_data_block("A,C\na1,c1\n"),
+            2,
+            1,
+            &BootstrapParameters::new(10, 0, None),
+            &mut None,
+        )
+        .is_err());
+}
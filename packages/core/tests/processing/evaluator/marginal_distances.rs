@@ -0,0 +1,75 @@
+use sds_core::processing::evaluator::Evaluator;
+
+use crate::utils::aggregate;
+
+#[test]
+fn validate_marginal_distances_of_identical_data() {
+    let data = "A,B\na1,b1\na1,b2\na2,b1\na2,b2\n";
+    let marginal_distances =
+        Evaluator::default().calc_marginal_distances(&aggregate(data), &aggregate(data));
+
+    // A, B and (A, B)
+    assert_eq!(marginal_distances.marginals.len(), 3);
+
+    for m in marginal_distances.marginals.iter() {
+        assert!(m.total_variation_distance.abs() < 1e-9);
+        assert!(m.jensen_shannon_divergence.abs() < 1e-9);
+        assert!(m.chi_square.abs() < 1e-9);
+        assert!((m.chi_square_p_value.unwrap() - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn validate_marginal_distances() {
+    let sensitive = aggregate("A,B\na1,b1\na1,b1\na2,b1\na2,b2\n");
+    let synthetic = aggregate("A,B\na1,b1\na1,b1\na1,b1\na1,b1\n");
+    let marginal_distances = Evaluator::default().calc_marginal_distances(&sensitive, &synthetic);
+    let marginals = marginal_distances.get_marginals();
+
+    // sorted by length and columns
+    assert_eq!(marginals[0].columns, vec!["A".to_owned()]);
+    assert_eq!(marginals[1].columns, vec!["B".to_owned()]);
+    assert_eq!(marginals[2].columns, vec!["A".to_owned(), "B".to_owned()]);
+
+    // A: p = (0.5, 0.5), q = (1, 0)
+    assert_eq!(marginals[0].combination_length, 1);
+    assert_eq!(marginals[0].number_of_combinations, 2);
+    assert!((marginals[0].total_variation_distance - 0.5).abs() < 1e-9);
+    // 0.5 * (0.5 * log2(0.5 / 0.75) + 0.5 * log2(0.5 / 0.25)) + 0.5 * log2(1 / 0.75)
+    assert!((marginals[0].jensen_shannon_divergence - 0.311278).abs() < 1e-6);
+    // expected counts are (3, 1) for both data sets
+    assert!((marginals[0].chi_square - 8.0 / 3.0).abs() < 1e-9);
+    assert_eq!(marginals[0].chi_square_degrees_of_freedom, 1);
+    assert!((marginals[0].chi_square_p_value.unwrap() - 0.102470).abs() < 1e-5);
+
+    // (A, B): p = (0.5, 0.25, 0.25), q = (1, 0, 0)
+    assert_eq!(marginals[2].number_of_combinations, 3);
+    assert!((marginals[2].total_variation_distance - 0.5).abs() < 1e-9);
+
+    let tvd_by_len = marginal_distances.calc_mean_total_variation_distance_by_len();
+
+    assert!((tvd_by_len[&1] - 0.375).abs() < 1e-9);
+    assert!((tvd_by_len[&2] - 0.5).abs() < 1e-9);
+
+    let different_by_len = marginal_distances.calc_percentage_of_different_marginals_by_len(0.5);
+
+    assert!((different_by_len[&1] - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_marginal_distances_with_missing_marginal() {
+    let sensitive = aggregate("A,B\na1,b1\na2,\n");
+    let synthetic = aggregate("A,B\na1,\na2,\n");
+    let marginals = Evaluator::default()
+        .calc_marginal_distances(&sensitive, &synthetic)
+        .marginals;
+    let b = marginals
+        .iter()
+        .find(|m| m.columns == vec!["B".to_owned()])
+        .unwrap();
+
+    // B only exists on the sensitive data
+    assert!((b.total_variation_distance - 1.0).abs() < 1e-9);
+    assert!((b.jensen_shannon_divergence - 1.0).abs() < 1e-9);
+    assert_eq!(b.chi_square_p_value, None);
+}
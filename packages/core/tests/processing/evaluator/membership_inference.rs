@@ -0,0 +1,83 @@
+use sds_core::{
+    data_block::DataBlock,
+    processing::evaluator::{
+        MembershipAttackScore, MembershipInferenceAttack, MembershipInferenceParameters,
+    },
+};
+use std::sync::Arc;
+
+use crate::utils::create_data_block;
+
+fn gen_data_block(prefix: &str, range: std::ops::Range<usize>) -> Arc<DataBlock> {
+    let mut data = "A,B,C,D\n".to_owned();
+
+    for i in range {
+        data += &format!(
+            "{prefix}a{},{prefix}b{},{prefix}c{},{prefix}d{}\n",
+            i % 7,
+            i % 5,
+            i % 3,
+            i
+        );
+    }
+    create_data_block(&data)
+}
+
+#[test]
+fn validate_membership_inference_on_copied_records() {
+    let training = gen_data_block("", 0..100);
+    let holdout = gen_data_block("", 100..200);
+    // the synthetic data is a verbatim copy of the training data
+    let synthetic = gen_data_block("", 0..100);
+
+    for score in [
+        MembershipAttackScore::Distance,
+        MembershipAttackScore::Density,
+    ] {
+        let result =
+            MembershipInferenceAttack::new(MembershipInferenceParameters::new(score, 0, None))
+                .run(&training, &holdout, &synthetic)
+                .unwrap();
+
+        assert_eq!(result.number_of_training_records, 100);
+        assert_eq!(result.number_of_holdout_records, 100);
+        assert_eq!(result.number_of_columns, 4);
+        assert!((result.auc.estimate - 1.0).abs() < 1e-9);
+        assert!((result.advantage.estimate - 1.0).abs() < 1e-9);
+        assert!(result.auc.lower_bound <= result.auc.estimate);
+        assert!(result.auc.confidence_level == 0.95);
+    }
+}
+
+#[test]
+fn validate_membership_inference_on_unrelated_records() {
+    let training = gen_data_block("", 0..100);
+    let holdout = gen_data_block("", 100..200);
+    // the synthetic data shares no value with training or holdout
+    let synthetic = gen_data_block("x", 0..100);
+    let result = MembershipInferenceAttack::new(MembershipInferenceParameters::new(
+        MembershipAttackScore::Distance,
+        0,
+        Some(0.9),
+    ))
+    .run(&training, &holdout, &synthetic)
+    .unwrap();
+
+    assert!((result.auc.estimate - 0.5).abs() < 1e-9);
+    assert!(result.auc.lower_bound < 0.5 && result.auc.upper_bound > 0.5);
+    assert!(result.advantage.estimate.abs() < 1e-9);
+    assert!(result.advantage_threshold.is_none());
+}
+
+#[test]
+fn invalid_membership_inference_confidence_level() {
+    let data_block = gen_data_block("", 0..10);
+    let result = MembershipInferenceAttack::new(MembershipInferenceParameters::new(
+        MembershipAttackScore::Distance,
+        0,
+        Some(1.0),
+    ))
+    .run(&data_block, &data_block, &data_block);
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,138 @@
+use sds_core::processing::evaluator::{
+    MlUtilityClassifier, MlUtilityEvaluator, MlUtilityMetrics, MlUtilityParameters,
+};
+
+use crate::utils::create_data_block;
+
+fn repeat_records(header: &str, records: &[&str], times: usize) -> String {
+    let mut data = format!("{header}\n");
+
+    for _ in 0..times {
+        for r in records.iter() {
+            data.push_str(r);
+            data.push('\n');
+        }
+    }
+    data
+}
+
+#[test]
+fn validate_ml_utility_with_preserved_relationship() {
+    // T is determined by A, B is noise
+    let records = ["a1,b1,t1", "a1,b2,t1", "a2,b1,t2", "a2,b2,t2"];
+    let training = create_data_block(&repeat_records("A,B,T", &records, 5));
+    let test = create_data_block(&repeat_records("A,B,T", &records, 2));
+    let synthetic = create_data_block(&repeat_records("A,B,T", &records, 3));
+    let result = MlUtilityEvaluator::new(MlUtilityParameters::new("T".to_owned(), vec![]))
+        .run(&training, &test, &synthetic)
+        .unwrap();
+
+    assert_eq!(result.features, vec!["A", "B"]);
+    assert_eq!(result.number_of_training_records, 20);
+    assert_eq!(result.number_of_synthetic_records, 12);
+    assert_eq!(result.number_of_test_records, 8);
+    assert_eq!(result.number_of_classes, 2);
+    assert!((result.majority_class_accuracy - 0.5).abs() < 1e-9);
+    assert_eq!(result.classifiers.len(), 3);
+
+    for c in result.classifiers.iter() {
+        assert!((c.trained_on_real.accuracy - 1.0).abs() < 1e-9);
+        assert!((c.trained_on_synthetic.accuracy - 1.0).abs() < 1e-9);
+        assert!((c.trained_on_synthetic.macro_f1 - 1.0).abs() < 1e-9);
+        assert!((c.trained_on_synthetic.auc.unwrap() - 1.0).abs() < 1e-9);
+        assert!(c.accuracy_gap.abs() < 1e-9);
+        assert!(c.auc_gap.unwrap().abs() < 1e-9);
+    }
+}
+
+#[test]
+fn validate_ml_utility_with_broken_relationship() {
+    let records = ["a1,b1,t1", "a1,b2,t1", "a2,b1,t2", "a2,b2,t2"];
+    let training = create_data_block(&repeat_records("A,B,T", &records, 5));
+    let test = create_data_block(&repeat_records("A,B,T", &records, 2));
+    // the synthetic data inverts the relationship between A and T
+    let synthetic = create_data_block(&repeat_records(
+        "A,B,T",
+        &["a1,b1,t2", "a1,b2,t2", "a2,b1,t1", "a2,b2,t1"],
+        3,
+    ));
+    let result = MlUtilityEvaluator::new(MlUtilityParameters::new(
+        "T".to_owned(),
+        vec!["A".to_owned()],
+    ))
+    .run(&training, &test, &synthetic)
+    .unwrap();
+
+    assert_eq!(result.features, vec!["A"]);
+
+    for c in result.classifiers.iter() {
+        assert!(c.trained_on_synthetic.accuracy.abs() < 1e-9);
+        assert!((c.accuracy_gap - 1.0).abs() < 1e-9);
+        assert!((c.macro_f1_gap - 1.0).abs() < 1e-9);
+        assert!((c.auc_gap.unwrap() - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn validate_decision_tree_learns_interactions() {
+    // T is the XOR of A and B, which needs both features together
+    let records = ["a1,b1,t1", "a1,b2,t2", "a2,b1,t2", "a2,b2,t1"];
+    let data_block = create_data_block(&repeat_records("A,B,T", &records, 5));
+    let mut parameters = MlUtilityParameters::new("T".to_owned(), vec![]);
+
+    parameters.classifiers = vec![
+        MlUtilityClassifier::NaiveBayes,
+        MlUtilityClassifier::DecisionTree,
+    ];
+
+    let result = MlUtilityEvaluator::new(parameters)
+        .run(&data_block, &data_block, &data_block)
+        .unwrap();
+
+    assert_eq!(
+        result.classifiers[0].classifier,
+        MlUtilityClassifier::NaiveBayes
+    );
+    assert!((result.classifiers[0].trained_on_real.auc.unwrap() - 0.5).abs() < 1e-9);
+    assert_eq!(
+        result.classifiers[1].classifier,
+        MlUtilityClassifier::DecisionTree
+    );
+    assert!((result.classifiers[1].trained_on_real.accuracy - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_ml_utility_with_invalid_target() {
+    let data_block = create_data_block("A,B\na1,b1\na2,b2\n");
+
+    assert!(
+        MlUtilityEvaluator::new(MlUtilityParameters::new("C".to_owned(), vec![]))
+            .run(&data_block, &data_block, &data_block)
+            .is_err()
+    );
+    assert!(MlUtilityEvaluator::new(MlUtilityParameters::new(
+        "A".to_owned(),
+        vec!["A".to_owned()]
+    ))
+    .run(&data_block, &data_block, &data_block)
+    .is_err());
+}
+
+#[test]
+fn validate_ml_utility_metrics() {
+    let metrics = MlUtilityMetrics::from_predictions(
+        &[
+            vec![0.9, 0.1],
+            vec![0.6, 0.4],
+            vec![0.3, 0.7],
+            vec![0.8, 0.2],
+        ],
+        &[0, 1, 1, 0],
+        2,
+    );
+
+    assert!((metrics.accuracy - 0.75).abs() < 1e-9);
+    // class 0: tp = 2, fp = 1, fn = 0; class 1: tp = 1, fp = 0, fn = 1
+    assert!((metrics.macro_f1 - (0.8 + 2.0 / 3.0) / 2.0).abs() < 1e-9);
+    assert!((metrics.auc.unwrap() - 1.0).abs() < 1e-9);
+}
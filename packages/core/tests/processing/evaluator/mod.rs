@@ -0,0 +1,19 @@
+mod attribute_inference;
+
+mod closest_record_distance;
+
+mod evaluation_uncertainty;
+
+mod marginal_distances;
+
+mod membership_inference;
+
+mod ml_utility;
+
+mod pairwise_association;
+
+mod privacy_models;
+
+mod synthetic_data_comparison;
+
+mod utility_breakdown;
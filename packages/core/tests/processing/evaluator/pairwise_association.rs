@@ -0,0 +1,62 @@
+use sds_core::processing::evaluator::Evaluator;
+use std::collections::HashMap;
+
+use crate::utils::{create_data_block, create_data_block_with_multi_value_columns};
+
+#[test]
+fn validate_pairwise_association() {
+    // A and B are fully associated, C is independent from both
+    let sensitive = create_data_block("A,B,C\na1,b1,c1\na1,b1,c2\na2,b2,c1\na2,b2,c2\n");
+    // A and B become independent, while B and C become associated
+    let synthetic = create_data_block("A,B,C\na1,b1,c1\na1,b2,c2\na2,b1,c1\na2,b2,c2\n");
+    let association = Evaluator::default().calc_pairwise_association(&sensitive, &synthetic);
+
+    assert_eq!(association.columns, vec!["A", "B", "C"]);
+
+    assert!((association.sensitive_cramers_v[0][0] - 1.0).abs() < 1e-9);
+    assert!((association.sensitive_cramers_v[0][1] - 1.0).abs() < 1e-9);
+    assert!((association.sensitive_normalized_mutual_information[1][0] - 1.0).abs() < 1e-9);
+    assert!(association.sensitive_cramers_v[0][2].abs() < 1e-9);
+    assert!(association.sensitive_normalized_mutual_information[0][2].abs() < 1e-9);
+
+    assert!(association.synthetic_cramers_v[0][1].abs() < 1e-9);
+    assert!((association.cramers_v_difference[0][1] + 1.0).abs() < 1e-9);
+    assert!((association.normalized_mutual_information_difference[1][0] + 1.0).abs() < 1e-9);
+
+    // in the synthetic data B and C become fully associated
+    assert!(association.cramers_v_difference[0][2].abs() < 1e-9);
+    assert!((association.cramers_v_difference[1][2] - 1.0).abs() < 1e-9);
+    assert!((association.cramers_v_mean_abs_difference - 2.0 / 3.0).abs() < 1e-9);
+    assert!(
+        (association.normalized_mutual_information_mean_abs_difference - 2.0 / 3.0).abs() < 1e-9
+    );
+}
+
+#[test]
+fn validate_pairwise_association_with_multi_value_columns() {
+    let multi_value_columns: HashMap<String, String> =
+        [("B".to_owned(), ";".to_owned())].into_iter().collect();
+    let sensitive = create_data_block_with_multi_value_columns(
+        "A,B\na1,b1;b2\na1,b1;b2\na2,b3\na2,b3\n",
+        &multi_value_columns,
+    );
+    // b3 is never synthesized, so its column is absent on the synthetic data
+    let synthetic = create_data_block_with_multi_value_columns(
+        "A,B\na1,b1;b2\na1,b1\na2,b2\na2,\n",
+        &multi_value_columns,
+    );
+    let association = Evaluator::default().calc_pairwise_association(&sensitive, &synthetic);
+
+    assert_eq!(association.columns, vec!["A", "B_b1", "B_b2", "B_b3"]);
+
+    // every attribute is fully associated with A on the sensitive data
+    for i in 1..4 {
+        assert!((association.sensitive_cramers_v[0][i] - 1.0).abs() < 1e-9);
+    }
+
+    // b3 is always absent on the synthetic data
+    assert!(association.synthetic_cramers_v[0][3].abs() < 1e-9);
+    assert!((association.cramers_v_difference[0][3] + 1.0).abs() < 1e-9);
+    assert!((association.synthetic_cramers_v[0][1] - 1.0).abs() < 1e-9);
+    assert!(association.synthetic_cramers_v[0][2].abs() < 1e-9);
+}
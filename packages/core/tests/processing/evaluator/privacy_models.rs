@@ -0,0 +1,99 @@
+use sds_core::processing::evaluator::{
+    LDiversityMeasure, PrivacyModelAssessor, PrivacyModelError, PrivacyModelParameters,
+};
+
+use crate::utils::create_data_block;
+
+const DATA: &str = "Q,S\nq1,s1\nq1,s2\nq2,s1\nq2,s1\nq3,s2\n";
+
+fn create_assessor(
+    quasi_identifiers: &[&str],
+    sensitive_columns: &[&str],
+    l_diversity_measure: LDiversityMeasure,
+    t: f64,
+) -> PrivacyModelAssessor {
+    PrivacyModelAssessor::new(PrivacyModelParameters::new(
+        quasi_identifiers.iter().map(|c| c.to_string()).collect(),
+        sensitive_columns.iter().map(|c| c.to_string()).collect(),
+        2,
+        2.0,
+        l_diversity_measure,
+        t,
+    ))
+}
+
+#[test]
+fn validate_privacy_models() {
+    let assessment = create_assessor(&["Q"], &["S"], LDiversityMeasure::Distinct, 0.2)
+        .assess(&create_data_block(DATA))
+        .unwrap();
+
+    assert_eq!(assessment.number_of_records, 5);
+    assert_eq!(assessment.number_of_equivalence_classes, 3);
+    assert_eq!(assessment.k_anonymity, 1);
+    assert_eq!(assessment.number_of_k_anonymity_violations, 1);
+    assert_eq!(assessment.number_of_records_violating_k_anonymity, 1);
+
+    let s = &assessment.sensitive_columns[0];
+
+    assert!((s.l_diversity - 1.0).abs() < 1e-9);
+    assert_eq!(s.number_of_l_diversity_violations, 2);
+    // q3 only has s2, which is 40% of the overall records
+    assert!((s.t_closeness - 0.6).abs() < 1e-9);
+    assert_eq!(s.number_of_t_closeness_violations, 2);
+
+    // q1 has two distinct values and is close to the overall distribution
+    assert_eq!(
+        assessment
+            .offending_classes
+            .iter()
+            .map(|c| c.quasi_identifiers.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["Q:q3", "Q:q2"]
+    );
+
+    let q3 = &assessment.offending_classes[0];
+
+    assert!(q3.violates_k_anonymity);
+    assert_eq!(q3.l_diversity_violations, vec!["S".to_owned()]);
+    assert_eq!(q3.t_closeness_violations, vec!["S".to_owned()]);
+
+    let q2 = &assessment.offending_classes[1];
+
+    assert!(!q2.violates_k_anonymity);
+    assert!((q2.t_closeness[0] - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn validate_entropy_l_diversity() {
+    let assessment = create_assessor(&["Q"], &["S"], LDiversityMeasure::Entropy, 1.0)
+        .assess(&create_data_block("Q,S\nq1,s1\nq1,s2\nq2,s1\nq2,s2\n"))
+        .unwrap();
+
+    // both classes have two equally likely values
+    assert!((assessment.sensitive_columns[0].l_diversity - 2.0).abs() < 1e-9);
+    assert_eq!(assessment.k_anonymity, 2);
+    assert!(assessment.offending_classes.is_empty());
+}
+
+#[test]
+fn validate_privacy_models_invalid_input() {
+    let data_block = create_data_block(DATA);
+
+    assert!(matches!(
+        create_assessor(&[], &["S"], LDiversityMeasure::Distinct, 0.2).assess(&data_block),
+        Err(PrivacyModelError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        create_assessor(&["Q"], &["Q"], LDiversityMeasure::Distinct, 0.2).assess(&data_block),
+        Err(PrivacyModelError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        create_assessor(&["Q"], &["X"], LDiversityMeasure::Distinct, 0.2).assess(&data_block),
+        Err(PrivacyModelError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        create_assessor(&["Q"], &["S"], LDiversityMeasure::Distinct, 1.5).assess(&data_block),
+        Err(PrivacyModelError::InvalidInput(_))
+    ));
+}
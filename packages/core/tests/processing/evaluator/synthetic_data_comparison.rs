@@ -0,0 +1,72 @@
+use sds_core::processing::evaluator::{ComparisonMetric, Evaluator};
+
+use crate::utils::aggregate;
+
+#[test]
+fn validate_synthetic_data_comparison() {
+    let sensitive_data = "A,B\na1,b1\na1,b2\na2,b1\na2,b2\n";
+    let sensitive = aggregate(sensitive_data);
+    let identical = aggregate(sensitive_data);
+    let different = aggregate("A,B\na1,b1\na3,b1\na3,b1\na3,b1\na3,b1\na3,b1\n");
+    let comparison = Evaluator::default().compare_synthetic_data(
+        &sensitive,
+        &[("identical", &identical), ("different", &different)],
+        2,
+    );
+
+    assert_eq!(
+        comparison.names,
+        vec!["identical".to_owned(), "different".to_owned()]
+    );
+    assert_eq!(comparison.metrics.len(), ComparisonMetric::all().len());
+
+    for metric in comparison.metrics.iter() {
+        assert_eq!(metric.values.len(), 2);
+
+        if metric.metric == ComparisonMetric::LeakageCount {
+            // all the sensitive pairs are rare and copied by the identical data,
+            // while only (a1, b1) is copied by the different data
+            assert_eq!(metric.values, vec![4.0, 1.0]);
+            assert_eq!(metric.winners, vec!["different".to_owned()]);
+        } else {
+            assert_eq!(metric.winners, vec!["identical".to_owned()]);
+        }
+    }
+    assert_eq!(
+        comparison.number_of_wins,
+        vec![comparison.metrics.len() - 1, 1]
+    );
+
+    let expansion = comparison
+        .metrics
+        .iter()
+        .find(|m| m.metric == ComparisonMetric::RecordExpansionPercentage)
+        .unwrap();
+
+    assert!(expansion.values[0].abs() < 1e-9);
+    assert!((expansion.values[1] - 50.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_synthetic_data_comparison_ties() {
+    let sensitive_data = "A,B\na1,b1\na1,b2\na2,b1\na2,b2\n";
+    let sensitive = aggregate(sensitive_data);
+    let first = aggregate(sensitive_data);
+    let second = aggregate(sensitive_data);
+    let comparison = Evaluator::default().compare_synthetic_data(
+        &sensitive,
+        &[("first", &first), ("second", &second)],
+        1,
+    );
+
+    for metric in comparison.metrics.iter() {
+        assert_eq!(
+            metric.winners,
+            vec!["first".to_owned(), "second".to_owned()]
+        );
+    }
+    assert_eq!(
+        comparison.number_of_wins,
+        vec![comparison.metrics.len(), comparison.metrics.len()]
+    );
+}
@@ -0,0 +1,114 @@
+use sds_core::processing::evaluator::{Evaluator, UtilityBreakdownEntry, UtilityBreakdownMetric};
+
+use crate::utils::aggregate;
+
+const SENSITIVE_DATA: &str = "A,B\na1,b1\na1,b1\na1,b2\na2,b1\n";
+
+const SYNTHETIC_DATA: &str = "A,B\na1,b1\na1,b1\na1,b2\na3,b1\n";
+
+fn find_attribute<'entries>(
+    entries: &'entries [UtilityBreakdownEntry],
+    column: &str,
+    value: &str,
+) -> &'entries UtilityBreakdownEntry {
+    entries
+        .iter()
+        .find(|e| e.column == column && e.value.as_deref() == Some(value))
+        .unwrap()
+}
+
+#[test]
+fn validate_utility_breakdown_by_column() {
+    let breakdown = Evaluator::default().calc_utility_breakdown(
+        &aggregate(SENSITIVE_DATA),
+        &aggregate(SYNTHETIC_DATA),
+        1,
+    );
+
+    assert_eq!(
+        breakdown
+            .columns
+            .iter()
+            .map(|e| (e.column.as_str(), e.value.is_none()))
+            .collect::<Vec<_>>(),
+        vec![("A", true), ("B", true)]
+    );
+
+    // a2 and (a2, b1) are suppressed, a3 and (a3, b1) are fabricated
+    let a = &breakdown.columns[0];
+
+    assert_eq!(a.number_of_sensitive_combinations, 5);
+    assert_eq!(a.number_of_synthetic_combinations, 5);
+    assert!((a.percentage_of_suppressed_combinations - 40.0).abs() < 1e-9);
+    assert!((a.percentage_of_fabricated_combinations - 40.0).abs() < 1e-9);
+    assert!((a.mean_preservation - 3.0 / 7.0).abs() < 1e-9);
+    assert!((a.combinations_count_mean_abs_error - 0.0).abs() < 1e-9);
+
+    // only (a2, b1) is suppressed and (a3, b1) is fabricated
+    let b = &breakdown.columns[1];
+
+    assert_eq!(b.number_of_sensitive_combinations, 5);
+    assert!((b.percentage_of_suppressed_combinations - 20.0).abs() < 1e-9);
+    assert!((b.percentage_of_fabricated_combinations - 20.0).abs() < 1e-9);
+    assert!((b.mean_preservation - 4.0 / 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_utility_breakdown_by_attribute() {
+    let breakdown = Evaluator::default().calc_utility_breakdown(
+        &aggregate(SENSITIVE_DATA),
+        &aggregate(SYNTHETIC_DATA),
+        1,
+    );
+
+    assert_eq!(
+        breakdown
+            .attributes
+            .iter()
+            .map(|e| format!("{}:{}", e.column, e.value.as_deref().unwrap()))
+            .collect::<Vec<_>>(),
+        vec!["A:a1", "A:a2", "A:a3", "B:b1", "B:b2"]
+    );
+
+    let a1 = find_attribute(&breakdown.attributes, "A", "a1");
+
+    assert!((a1.mean_preservation - 1.0).abs() < 1e-9);
+    assert!((a1.percentage_of_suppressed_combinations - 0.0).abs() < 1e-9);
+
+    let a2 = find_attribute(&breakdown.attributes, "A", "a2");
+
+    assert_eq!(a2.number_of_sensitive_combinations, 2);
+    assert_eq!(a2.number_of_synthetic_combinations, 0);
+    assert!((a2.percentage_of_suppressed_combinations - 100.0).abs() < 1e-9);
+    assert!((a2.mean_preservation - 0.0).abs() < 1e-9);
+
+    let a3 = find_attribute(&breakdown.attributes, "A", "a3");
+
+    assert_eq!(a3.number_of_sensitive_combinations, 0);
+    assert_eq!(a3.number_of_synthetic_combinations, 2);
+    assert!((a3.mean_proportional_error - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn validate_utility_breakdown_sorting() {
+    let mut breakdown = Evaluator::default().calc_utility_breakdown(
+        &aggregate(SENSITIVE_DATA),
+        &aggregate(SYNTHETIC_DATA),
+        1,
+    );
+
+    breakdown.sort_by_metric(UtilityBreakdownMetric::PercentageOfSuppressedCombinations);
+
+    assert_eq!(breakdown.columns[0].column, "A");
+    assert_eq!(breakdown.attributes[0].value.as_deref(), Some("a2"));
+
+    breakdown.sort_by_metric(UtilityBreakdownMetric::MeanPreservation);
+
+    // worst preserved first, ties keep their previous order
+    assert_eq!(breakdown.columns[0].column, "A");
+    assert_eq!(breakdown.columns[1].column, "B");
+    assert!(
+        breakdown.attributes[0].mean_preservation
+            <= breakdown.attributes[breakdown.attributes.len() - 1].mean_preservation
+    );
+}
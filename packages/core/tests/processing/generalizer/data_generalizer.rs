@@ -0,0 +1,179 @@
+use csv::ReaderBuilder;
+use itertools::Itertools;
+use sds_core::{
+    data_block::DataBlock,
+    processing::generalizer::{
+        ColumnGeneralization, GeneralizationHierarchiesByColumn, GeneralizationHierarchy,
+        Generalizer,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+
+use crate::utils::create_data_block;
+
+const DATA: &str = "Z,A\n98101,20\n98102,21\n98103,22\n98104,23\n";
+
+const ZIP_HIERARCHY: &str = "98101,981,WA\n98102,981,WA\n98103,981,WA\n98104,981,WA\n";
+
+fn create_zip_hierarchy(hierarchy: &str) -> GeneralizationHierarchy {
+    GeneralizationHierarchy::from_csv_reader(
+        ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(hierarchy.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn get_column_values(data_block: &DataBlock, column_index: usize) -> Vec<String> {
+    data_block
+        .records
+        .iter()
+        .flat_map(|r| r.values.iter())
+        .filter(|v| v.column_index == column_index)
+        .map(|v| (*v.value).clone())
+        .collect()
+}
+
+#[test]
+fn validate_hierarchies() {
+    let zip = create_zip_hierarchy(ZIP_HIERARCHY);
+    let age = GeneralizationHierarchy::from_interval_widths(vec![5.0, 10.0]).unwrap();
+
+    assert_eq!(zip.number_of_levels(), 2);
+    assert_eq!(zip.generalize("98101", 0), Some("98101".to_owned()));
+    assert_eq!(zip.generalize("98101", 1), Some("981".to_owned()));
+    assert_eq!(zip.generalize("98101", 2), Some("WA".to_owned()));
+    assert_eq!(zip.generalize("10001", 1), None);
+    assert_eq!(age.generalize("23", 1), Some("[20, 25)".to_owned()));
+    assert_eq!(age.generalize("27", 2), Some("[20, 30)".to_owned()));
+    assert_eq!(age.generalize("unknown", 1), None);
+
+    assert!(GeneralizationHierarchy::from_interval_widths(vec![10.0, 5.0]).is_err());
+    assert!(GeneralizationHierarchy::from_interval_widths(vec![]).is_err());
+    assert!(GeneralizationHierarchy::from_mapping_rows(&[
+        vec!["98101".to_owned(), "981".to_owned()],
+        vec!["98101".to_owned(), "982".to_owned()],
+    ])
+    .is_err());
+}
+
+#[test]
+fn validate_search_least_generalized() {
+    let mut hierarchies = GeneralizationHierarchiesByColumn::default();
+
+    hierarchies.insert("Z".to_owned(), create_zip_hierarchy(ZIP_HIERARCHY));
+    hierarchies.insert(
+        "A".to_owned(),
+        GeneralizationHierarchy::from_interval_widths(vec![2.0, 10.0]).unwrap(),
+    );
+
+    let generalizer = Generalizer::new(create_data_block(DATA), &hierarchies).unwrap();
+    let result = generalizer
+        .search(2, 2, 0.0, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+
+    assert!(result.target_reached);
+    assert_eq!(
+        result.original_percentage_of_records_with_rare_combinations,
+        100.0
+    );
+    assert_eq!(result.percentage_of_records_with_rare_combinations, 0.0);
+    // 1 generalization with total level 0, 2 with total level 1 and 3 with total level 2
+    assert_eq!(result.number_of_evaluated_generalizations, 6);
+    assert_eq!(
+        result.columns,
+        vec![
+            ColumnGeneralization {
+                column: "Z".to_owned(),
+                level: 1,
+                number_of_levels: 2,
+            },
+            ColumnGeneralization {
+                column: "A".to_owned(),
+                level: 1,
+                number_of_levels: 2,
+            },
+        ]
+    );
+
+    let generalized = generalizer.generalize(&result.columns).unwrap();
+
+    assert_eq!(get_column_values(&generalized, 0), vec!["981"; 4]);
+    assert_eq!(
+        get_column_values(&generalized, 1),
+        vec!["[20, 22)", "[20, 22)", "[22, 24)", "[22, 24)"]
+    );
+}
+
+#[test]
+fn validate_search_target_not_reached() {
+    let mut hierarchies = GeneralizationHierarchiesByColumn::default();
+
+    hierarchies.insert("Z".to_owned(), create_zip_hierarchy(ZIP_HIERARCHY));
+
+    let generalizer = Generalizer::new(create_data_block(DATA), &hierarchies).unwrap();
+    let result = generalizer
+        .search(2, 2, 0.0, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+
+    // ages stay unique, so the least generalized level is kept
+    assert!(!result.target_reached);
+    assert_eq!(result.percentage_of_records_with_rare_combinations, 100.0);
+    assert_eq!(result.number_of_evaluated_generalizations, 3);
+    assert_eq!(result.columns[0].level, 0);
+}
+
+#[test]
+fn validate_search_with_many_hierarchies() {
+    let columns = (0..12).map(|i| format!("C{i}")).collect_vec();
+    let mut hierarchies = GeneralizationHierarchiesByColumn::default();
+
+    for c in columns.iter() {
+        hierarchies.insert(
+            c.clone(),
+            GeneralizationHierarchy::from_interval_widths((2..=11).map(|w| w as f64).collect())
+                .unwrap(),
+        );
+    }
+
+    // only the first column has unique values
+    let data = format!(
+        "{}\n{}",
+        columns.join(","),
+        (20..24)
+            .map(|age| format!("{age},{}", ["20"; 11].join(",")))
+            .join("\n")
+    );
+    let generalizer = Generalizer::new(create_data_block(&data), &hierarchies).unwrap();
+    // 11^12 candidates, only the ones up to total level 1 are enumerated
+    let result = generalizer
+        .search(2, 2, 0.0, &mut None::<LoggerProgressReporter>)
+        .unwrap();
+
+    assert!(result.target_reached);
+    assert_eq!(
+        result.original_percentage_of_records_with_rare_combinations,
+        100.0
+    );
+    assert_eq!(result.number_of_evaluated_generalizations, 1 + 12);
+    assert_eq!(result.columns[0].level, 1);
+    assert!(result.columns[1..].iter().all(|c| c.level == 0));
+}
+
+#[test]
+fn validate_values_not_covered_by_hierarchy() {
+    let mut hierarchies = GeneralizationHierarchiesByColumn::default();
+
+    hierarchies.insert(
+        "Z".to_owned(),
+        create_zip_hierarchy("98101,981\n98102,981\n98103,981\n"),
+    );
+    assert!(Generalizer::new(create_data_block(DATA), &hierarchies).is_err());
+
+    hierarchies.clear();
+    hierarchies.insert(
+        "B".to_owned(),
+        GeneralizationHierarchy::from_interval_widths(vec![5.0]).unwrap(),
+    );
+    assert!(Generalizer::new(create_data_block(DATA), &hierarchies).is_err());
+}
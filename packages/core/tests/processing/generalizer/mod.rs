@@ -0,0 +1 @@
+mod data_generalizer;
@@ -0,0 +1,103 @@
+use sds_core::{
+    dp::DpMarginalParameters, processing::generator::Generator,
+    utils::reporting::LoggerProgressReporter,
+};
+
+use crate::utils::create_data_block;
+
+fn gen_correlated_data() -> String {
+    let mut data = "A,B,C\n".to_owned();
+
+    for i in 0..600 {
+        // B always follows A, C is independent
+        let a = ["a1", "a2", "a3"][i % 3];
+        let b = ["b1", "b2", "b3"][i % 3];
+        let c = ["c1", "c2"][(i / 3) % 2];
+
+        data += &format!("{a},{b},{c}\n");
+    }
+    data
+}
+
+#[test]
+fn validate_dp_marginal_keeps_correlated_pair() {
+    let data = gen_correlated_data();
+    let data_block = create_data_block(&data);
+    let generator = Generator::default();
+    let model = generator
+        .fit_dp_marginal::<LoggerProgressReporter>(
+            &data_block,
+            DpMarginalParameters::new(100.0, None, None, None),
+            &mut None,
+        )
+        .unwrap();
+
+    assert!(model.selected_pairs().contains(&(0, 1)));
+    assert_eq!(model.selected_pairs().len(), 2);
+
+    let generated_data = generator
+        .generate_dp_marginal::<LoggerProgressReporter>(&model, "", Some(300), &mut None)
+        .unwrap();
+
+    assert_eq!(generated_data.number_of_synthetic_records(), 300);
+
+    // with a large budget, B should follow A on almost every record
+    let consistent = generated_data
+        .synthetic_data
+        .iter()
+        .skip(1)
+        .filter(|r| !r[0].is_empty() && r[0].get(1..) == r[1].get(1..))
+        .count();
+
+    assert!(consistent >= 290);
+}
+
+#[test]
+fn validate_dp_marginal_parameters() {
+    assert!(DpMarginalParameters::new(1.0, None, None, None)
+        .validate()
+        .is_ok());
+    assert!(DpMarginalParameters::new(0.0, None, None, None)
+        .validate()
+        .is_err());
+    assert!(DpMarginalParameters::new(1.0, Some(1.0), None, None)
+        .validate()
+        .is_err());
+    assert!(DpMarginalParameters::new(1.0, None, Some(0.0), None)
+        .validate()
+        .is_err());
+    assert!(DpMarginalParameters::new(1.0, None, Some(0.5), Some(0.5))
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn validate_dp_marginal_fitting_errors() {
+    let generator = Generator::default();
+    let data_block = create_data_block(&gen_correlated_data());
+
+    // invalid parameters are reported instead of panicking
+    assert!(generator
+        .fit_dp_marginal::<LoggerProgressReporter>(
+            &data_block,
+            DpMarginalParameters::new(1.0, None, Some(0.5), Some(0.5)),
+            &mut None,
+        )
+        .is_err());
+    assert!(generator
+        .fit_dp_marginal::<LoggerProgressReporter>(
+            &data_block,
+            DpMarginalParameters::new(-1.0, None, None, None),
+            &mut None,
+        )
+        .is_err());
+
+    // so is data without records
+    assert!(generator
+        .fit_dp_marginal::<LoggerProgressReporter>(
+            &create_data_block("A,B,C\n"),
+            DpMarginalParameters::new(1.0, None, None, None),
+            &mut None,
+        )
+        .is_err());
+}
@@ -0,0 +1,12 @@
+mod dp_marginal;
+
+mod streaming;
+
+mod synthesis_constraints;
+
+mod target_distribution;
+
+mod unique_copies;
+
+#[cfg(feature = "rayon")]
+mod parallel_aggregate_seeded;
@@ -0,0 +1,182 @@
+use fnv::FnvHashMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sds_core::{
+    data_block::{DataBlock, DataBlockRecord},
+    processing::{
+        aggregator::{AggregatedData, Aggregator},
+        evaluator::Evaluator,
+        generator::{AggregateSeededSynthesizer, SynthesizedRecord},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::{create_data_block, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_target_distribution.csv";
+
+#[test]
+fn validate_parallel_aggregate_seeded_synthesis() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let aggregated_data = Arc::new(
+        Aggregator::new(data_block)
+            .aggregate::<LoggerProgressReporter>(0, &mut None)
+            .unwrap(),
+    );
+    let single_attr_counts = aggregated_data.calc_single_attribute_counts();
+
+    for _ in 0..10 {
+        // sampling from all available counts
+        let records =
+            AggregateSeededSynthesizer::new(aggregated_data.clone(), true, None, None, None)
+                .run_parallel::<LoggerProgressReporter>(3, &mut None)
+                .unwrap();
+        let mut synthetic_counts = FnvHashMap::default();
+
+        for r in records.iter() {
+            for attr in r.iter() {
+                *synthetic_counts.entry(attr.clone()).or_insert(0) += 1;
+            }
+        }
+        // partitions and reconciliation never oversample a single attribute
+        assert!(synthetic_counts
+            .iter()
+            .all(|(attr, count)| *count <= single_attr_counts[attr]));
+        assert_eq!(
+            synthetic_counts.values().sum::<usize>(),
+            single_attr_counts.values().sum::<usize>()
+        );
+
+        // splitting the target number of records
+        assert_eq!(
+            AggregateSeededSynthesizer::new(aggregated_data.clone(), true, None, Some(25), None)
+                .run_parallel::<LoggerProgressReporter>(3, &mut None)
+                .unwrap()
+                .len(),
+            25
+        );
+    }
+}
+
+const RARE_COUNT: usize = 2;
+
+fn gen_skewed_data_block() -> Arc<DataBlock> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut data = "A,B,C,D\n".to_owned();
+
+    for _ in 0..400 {
+        // squaring skews the values towards the first ones,
+        // so the last ones are part of rare combinations
+        let mut gen_value = |prefix: char, cardinality: usize| {
+            let r: f64 = rng.gen();
+
+            format!("{prefix}{}", ((r * r) * (cardinality as f64)) as usize)
+        };
+        let record = [
+            gen_value('a', 6),
+            gen_value('b', 4),
+            gen_value('c', 5),
+            gen_value('d', 3),
+        ];
+
+        data.push_str(&record.join(","));
+        data.push('\n');
+    }
+
+    create_data_block(&data)
+}
+
+/// Returns the mean absolute error of the combination counts and the percentage
+/// of rare sensitive combinations also present on the synthetic data
+fn calc_fidelity(
+    sensitive_data_block: &Arc<DataBlock>,
+    sensitive_aggregated_data: &AggregatedData,
+    records: &[SynthesizedRecord],
+) -> (f64, f64) {
+    let synthetic_aggregated_data = Aggregator::new(Arc::new(DataBlock::new(
+        sensitive_data_block.headers.clone(),
+        sensitive_data_block.multi_value_column_metadata_map.clone(),
+        records
+            .iter()
+            .map(|r| Arc::new(DataBlockRecord::new(r.iter().cloned().collect())))
+            .collect(),
+    )))
+    .aggregate::<LoggerProgressReporter>(3, &mut None)
+    .unwrap();
+    let rare_combs: Vec<_> = sensitive_aggregated_data
+        .aggregates_count
+        .iter()
+        .filter(|(comb, count)| comb.len() > 1 && count.count <= RARE_COUNT)
+        .map(|(comb, _)| comb)
+        .collect();
+    let preserved_rare_combs = rare_combs
+        .iter()
+        .filter(|comb| {
+            synthetic_aggregated_data
+                .aggregates_count
+                .contains_key(**comb)
+        })
+        .count();
+
+    (
+        Evaluator::default().calc_combinations_count_mean_abs_error(
+            sensitive_aggregated_data,
+            &synthetic_aggregated_data,
+        ),
+        100.0 * (preserved_rare_combs as f64) / (rare_combs.len() as f64),
+    )
+}
+
+#[test]
+fn validate_parallel_fidelity_against_sequential() {
+    let data_block = gen_skewed_data_block();
+    let aggregated_data = Arc::new(
+        Aggregator::new(data_block.clone())
+            .aggregate::<LoggerProgressReporter>(3, &mut None)
+            .unwrap(),
+    );
+    let runs = 10;
+    let (mut sequential_error, mut sequential_preservation) = (0.0, 0.0);
+    let (mut parallel_error, mut parallel_preservation) = (0.0, 0.0);
+
+    for _ in 0..runs {
+        let (error, preservation) = calc_fidelity(
+            &data_block,
+            &aggregated_data,
+            &AggregateSeededSynthesizer::new(aggregated_data.clone(), false, None, None, None)
+                .run::<LoggerProgressReporter>(&mut None)
+                .unwrap(),
+        );
+
+        sequential_error += error / (runs as f64);
+        sequential_preservation += preservation / (runs as f64);
+
+        let (error, preservation) = calc_fidelity(
+            &data_block,
+            &aggregated_data,
+            &AggregateSeededSynthesizer::new(aggregated_data.clone(), false, None, None, None)
+                .run_parallel::<LoggerProgressReporter>(4, &mut None)
+                .unwrap(),
+        );
+
+        parallel_error += error / (runs as f64);
+        parallel_preservation += preservation / (runs as f64);
+    }
+
+    // the counts are as accurate as when synthesizing on a single worker...
+    assert!((parallel_error - sequential_error).abs() <= sequential_error * 0.1);
+    // ...but splitting the aggregates across workers loses some rare combinations,
+    // that is why the parallel synthesis is opt-in
+    assert!(parallel_preservation < sequential_preservation);
+    assert!(parallel_preservation >= sequential_preservation * 0.65);
+}
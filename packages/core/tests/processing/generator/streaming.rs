@@ -0,0 +1,207 @@
+use sds_core::{
+    data_block::{CsvRecordRef, DataBlockHeadersSlice, RawData},
+    processing::{
+        aggregator::Aggregator,
+        generator::{CsvSyntheticDataSink, Generator, SyntheticDataSink, SyntheticDataSinkError},
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_block.csv";
+
+#[derive(Default)]
+struct CollectingSink {
+    headers: Vec<String>,
+    batches: Vec<RawData>,
+}
+
+impl SyntheticDataSink for CollectingSink {
+    fn write_headers(
+        &mut self,
+        headers: &DataBlockHeadersSlice,
+    ) -> Result<(), SyntheticDataSinkError> {
+        self.headers = headers.iter().map(|h| (**h).clone()).collect();
+        Ok(())
+    }
+
+    fn write_records(&mut self, records: &[CsvRecordRef]) -> Result<(), SyntheticDataSinkError> {
+        self.batches.push(records.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn validate_row_seeded_streaming_suppresses_on_the_fly() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let generator = Generator::default();
+
+    for _ in 0..10 {
+        let mut sink = CollectingSink::default();
+        let summary = generator
+            .stream_row_seeded::<CollectingSink, LoggerProgressReporter>(
+                &data_block,
+                2,
+                100,
+                "",
+                1,
+                &mut sink,
+                &mut None,
+            )
+            .unwrap();
+        let records: RawData = sink.batches.iter().flatten().cloned().collect();
+
+        assert_eq!(sink.headers, vec!["A", "B", "C", "D"]);
+        assert!(sink.batches.iter().all(|b| b.len() == 1));
+        assert_eq!(summary.number_of_records, records.len());
+        // only a1 and b2 reach the resolution of 2, and each
+        // can be emitted at most twice
+        assert!(records
+            .iter()
+            .all(|r| r.iter().all(|v| v.is_empty() || **v == "a1" || **v == "b2")));
+        assert!(records.iter().filter(|r| *r[0] == "a1").count() <= 2);
+        assert!(records.iter().filter(|r| *r[1] == "b2").count() <= 2);
+    }
+}
+
+#[test]
+fn validate_unseeded_streaming_emits_every_record() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let mut sink = CollectingSink::default();
+    let summary = Generator::default()
+        .stream_unseeded::<CollectingSink, LoggerProgressReporter>(
+            &data_block,
+            1,
+            100,
+            "",
+            None,
+            3,
+            &mut sink,
+            &mut None,
+        )
+        .unwrap();
+
+    assert_eq!(sink.headers, vec!["A", "B", "C", "D"]);
+    assert!(sink.batches.iter().all(|b| b.len() <= 3));
+    assert_eq!(
+        summary.number_of_records,
+        sink.batches.iter().map(|b| b.len()).sum::<usize>()
+    );
+    assert!(summary.number_of_records <= data_block.number_of_records());
+}
+
+#[test]
+fn validate_aggregate_seeded_streaming_to_csv() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let aggregated_data = Aggregator::new(data_block.clone())
+        .aggregate::<LoggerProgressReporter>(0, &mut None)
+        .unwrap();
+    let mut csv_data = Vec::default();
+    let mut sink = CsvSyntheticDataSink::new(
+        &mut csv_data,
+        ',',
+        "",
+        data_block.multi_value_column_metadata_map.clone(),
+        false,
+        false,
+    );
+    let summary = Generator::default()
+        .stream_aggregate_seeded::<CsvSyntheticDataSink<&mut Vec<u8>>, LoggerProgressReporter>(
+            "",
+            Arc::new(aggregated_data),
+            true,
+            None,
+            None,
+            Some(10),
+            None,
+            4,
+            &mut sink,
+            &mut None,
+        )
+        .unwrap();
+
+    drop(sink);
+
+    let csv_string = String::from_utf8(csv_data).unwrap();
+    let lines: Vec<&str> = csv_string.lines().collect();
+
+    assert_eq!(summary.number_of_records, 10);
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "A,B,C,D");
+}
+
+#[test]
+fn validate_generated_data_paging() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let generated_data = Generator::default()
+        .generate_row_seeded::<LoggerProgressReporter>(&data_block, 1, 100, "", &mut None)
+        .unwrap();
+    let all_data = generated_data.synthetic_data_to_vec("", false);
+    let n_records = generated_data.number_of_synthetic_records();
+
+    assert_eq!(n_records, all_data.len() - 1);
+
+    let mut paged_records = Vec::default();
+
+    for offset in (0..n_records).step_by(2) {
+        let page = generated_data.synthetic_data_page_to_vec(offset, 2, "", false);
+
+        assert_eq!(page[0], all_data[0]);
+        assert!(page.len() <= 3);
+        paged_records.extend(page.into_iter().skip(1));
+    }
+    assert_eq!(paged_records, all_data[1..].to_vec());
+    assert_eq!(
+        generated_data
+            .synthetic_data_page_to_vec(n_records, 2, "", false)
+            .len(),
+        1
+    );
+    let page_string = generated_data
+        .synthetic_data_page_to_string(0, n_records, ',', "", false, false)
+        .ok();
+
+    assert!(page_string.is_some());
+    assert_eq!(
+        page_string,
+        generated_data
+            .synthetic_data_to_string(',', "", false, false)
+            .ok()
+    );
+}
@@ -0,0 +1,261 @@
+use sds_core::{
+    data_block::DataBlockValue,
+    processing::generator::{
+        ConstraintAttribute, Generator, NotAllowedAttrSet, SynthesisConstraint,
+        SynthesisConstraints, SynthesisConstraintsEnforcer, SynthesizedRecord,
+    },
+    utils::reporting::LoggerProgressReporter,
+};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use crate::utils::read_test_data_block;
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_block.csv";
+
+fn attr(column: &str, value: &str) -> ConstraintAttribute {
+    ConstraintAttribute::new(column.to_owned(), value.to_owned())
+}
+
+fn get_headers() -> Vec<Arc<String>> {
+    ["A", "B", "C", "D"]
+        .iter()
+        .map(|h| Arc::new((*h).to_owned()))
+        .collect()
+}
+
+fn gen_record(values: &[&str]) -> SynthesizedRecord {
+    values
+        .iter()
+        .map(|v| Arc::new(DataBlockValue::from_str(v).unwrap()))
+        .collect()
+}
+
+#[test]
+fn validate_constraints_parsing_and_validation() {
+    let constraints = SynthesisConstraints::from_json_str(
+        r#"{
+            "constraints": [
+                {
+                    "type": "denyCombination",
+                    "attributes": [{ "column": "A", "value": "a1" }, { "column": "B", "value": "b2" }]
+                },
+                {
+                    "type": "implication",
+                    "when": [{ "column": "A", "value": "a2" }],
+                    "then": { "column": "D", "value": "d2" }
+                },
+                {
+                    "type": "allowedValues",
+                    "when": [{ "column": "B", "value": "b1" }],
+                    "column": "C",
+                    "values": ["c1"]
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        constraints,
+        SynthesisConstraints::new(vec![
+            SynthesisConstraint::DenyCombination {
+                attributes: vec![attr("A", "a1"), attr("B", "b2")]
+            },
+            SynthesisConstraint::Implication {
+                when: vec![attr("A", "a2")],
+                then: attr("D", "d2")
+            },
+            SynthesisConstraint::AllowedValues {
+                when: vec![attr("B", "b1")],
+                column: "C".to_owned(),
+                values: vec!["c1".to_owned()]
+            },
+        ])
+    );
+    assert!(constraints.validate(&get_headers()).is_ok());
+    assert!(
+        SynthesisConstraints::new(vec![SynthesisConstraint::Implication {
+            when: vec![attr("A", "a2")],
+            then: attr("E", "e1")
+        }])
+        .validate(&get_headers())
+        .is_err()
+    );
+    assert!(
+        SynthesisConstraints::new(vec![SynthesisConstraint::DenyCombination {
+            attributes: vec![]
+        }])
+        .validate(&get_headers())
+        .is_err()
+    );
+}
+
+#[test]
+fn validate_unconditional_constraints_are_rejected() {
+    let unconditional_constraints = vec![
+        SynthesisConstraint::DenyCombination {
+            attributes: vec![attr("A", "a1")],
+        },
+        SynthesisConstraint::DenyCombination {
+            attributes: vec![attr("A", "a1"), attr("A", "a1")],
+        },
+        SynthesisConstraint::Implication {
+            when: vec![],
+            then: attr("D", "d2"),
+        },
+        SynthesisConstraint::Implication {
+            when: vec![attr("D", "d1")],
+            then: attr("D", "d2"),
+        },
+        SynthesisConstraint::AllowedValues {
+            when: vec![],
+            column: "C".to_owned(),
+            values: vec!["c1".to_owned()],
+        },
+    ];
+
+    for constraint in unconditional_constraints.iter() {
+        assert!(SynthesisConstraints::new(vec![constraint.clone()])
+            .validate(&get_headers())
+            .is_err());
+    }
+
+    // the values these constraints would deny can not be consumed during
+    // consolidation, so synthesis should finish ignoring them
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let generated_data = Generator::new(
+        Some(SynthesisConstraints::new(unconditional_constraints)),
+        false,
+        false,
+    )
+    .generate_unseeded::<LoggerProgressReporter>(&data_block, 1, 100, "", None, &mut None)
+    .unwrap();
+
+    assert_eq!(
+        generated_data
+            .constraints_report
+            .unwrap()
+            .total_pruned_choices(),
+        0
+    );
+}
+
+#[test]
+fn validate_constraints_enforcer() {
+    let enforcer = SynthesisConstraintsEnforcer::new(
+        &SynthesisConstraints::new(vec![
+            SynthesisConstraint::DenyCombination {
+                attributes: vec![attr("A", "a1"), attr("B", "b2")],
+            },
+            SynthesisConstraint::Implication {
+                when: vec![attr("A", "a2")],
+                then: attr("D", "d2"),
+            },
+            SynthesisConstraint::AllowedValues {
+                when: vec![attr("B", "b1")],
+                column: "C".to_owned(),
+                values: vec!["c1".to_owned()],
+            },
+        ]),
+        &get_headers(),
+        Arc::new(String::default()),
+    );
+    let candidates: Vec<Arc<DataBlockValue>> = ["0:a1", "1:b1", "1:b2", "2:c2", "3:d1", "3:d2"]
+        .iter()
+        .map(|v| Arc::new(DataBlockValue::from_str(v).unwrap()))
+        .collect();
+    let mut pruned = NotAllowedAttrSet::default();
+
+    enforcer.extend_not_allowed_attr_set(&gen_record(&["0:a1"]), &candidates, &mut pruned);
+    assert!(pruned.len() == 1 && pruned.contains(&candidates[2]));
+
+    // already pruned choices for the same record are not counted again
+    enforcer.extend_not_allowed_attr_set(&gen_record(&["0:a1"]), &candidates, &mut pruned);
+    assert!(pruned.len() == 1);
+
+    let mut pruned = NotAllowedAttrSet::default();
+
+    enforcer.extend_not_allowed_attr_set(&gen_record(&["0:a2"]), &candidates, &mut pruned);
+    assert!(pruned.len() == 1 && pruned.contains(&candidates[4]));
+
+    // contrapositive of the implication and the conditioned allowed values
+    let mut pruned = NotAllowedAttrSet::default();
+
+    enforcer.extend_not_allowed_attr_set(&gen_record(&["2:c2", "3:d1"]), &candidates, &mut pruned);
+    assert!(pruned.len() == 1 && pruned.contains(&candidates[1]));
+
+    // empty values never violate constraints
+    assert!(enforcer.is_allowed(
+        &gen_record(&["0:a2"]),
+        &Arc::new(DataBlockValue::from_str("3:").unwrap())
+    ));
+
+    let report = enforcer.build_report();
+
+    assert_eq!(
+        report
+            .pruned_choices_by_constraint
+            .iter()
+            .map(|c| c.pruned_choices)
+            .collect::<Vec<usize>>(),
+        vec![1, 1, 1]
+    );
+    assert_eq!(report.total_pruned_choices(), 3);
+}
+
+#[test]
+fn validate_generation_respects_constraints() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let generator = Generator::new(
+        Some(SynthesisConstraints::new(vec![
+            SynthesisConstraint::DenyCombination {
+                attributes: vec![attr("A", "a1"), attr("B", "b2")],
+            },
+        ])),
+        false,
+        false,
+    );
+
+    for _ in 0..10 {
+        for generated_data in [
+            generator
+                .generate_unseeded::<LoggerProgressReporter>(
+                    &data_block,
+                    1,
+                    100,
+                    "",
+                    None,
+                    &mut None,
+                )
+                .unwrap(),
+            generator
+                .generate_row_seeded::<LoggerProgressReporter>(&data_block, 1, 100, "", &mut None)
+                .unwrap(),
+        ] {
+            assert!(generated_data.constraints_report.is_some());
+            assert!(generated_data
+                .synthetic_data
+                .iter()
+                .skip(1)
+                .all(|r| !(*r[0] == "a1" && *r[1] == "b2")));
+        }
+    }
+}
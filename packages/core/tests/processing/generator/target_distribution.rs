@@ -0,0 +1,105 @@
+use sds_core::{
+    processing::{aggregator::Aggregator, generator::Generator, generator::TargetDistribution},
+    utils::reporting::LoggerProgressReporter,
+};
+use std::collections::HashMap;
+
+use crate::utils::{assert_map_equals, read_test_data_block};
+
+const DELIMITER: u8 = b',';
+
+const TEST_FILE_PATH: &str = "test_target_distribution.csv";
+
+fn get_target_distribution() -> TargetDistribution {
+    TargetDistribution::new(
+        "L".to_owned(),
+        [("yes".to_owned(), 1.0), ("no".to_owned(), 1.0)]
+            .into_iter()
+            .collect(),
+    )
+}
+
+#[test]
+fn validate_rebalance_aggregated_data() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let aggregated_data = Aggregator::new(data_block)
+        .aggregate::<LoggerProgressReporter>(2, &mut None)
+        .unwrap();
+    let rebalanced = get_target_distribution().rebalance_aggregated_data(&aggregated_data);
+
+    assert_map_equals(
+        &rebalanced
+            .aggregates_count
+            .iter()
+            .map(|(comb, count)| {
+                (
+                    comb.as_str_using_headers(&rebalanced.headers, ";"),
+                    count.count,
+                )
+            })
+            .collect(),
+        &[
+            ("L:yes".to_owned(), 4),
+            ("L:no".to_owned(), 4),
+            ("B:b1".to_owned(), 4),
+            ("B:b2".to_owned(), 4),
+            ("B:b1;L:yes".to_owned(), 2),
+            ("B:b2;L:yes".to_owned(), 2),
+            ("B:b1;L:no".to_owned(), 2),
+            ("B:b2;L:no".to_owned(), 2),
+        ]
+        .into_iter()
+        .collect(),
+    );
+}
+
+#[test]
+fn validate_unseeded_rebalancing() {
+    let data_block = read_test_data_block(
+        TEST_FILE_PATH,
+        DELIMITER,
+        None,
+        &[],
+        &HashMap::default(),
+        &[],
+        0,
+    );
+    let generator = Generator::default();
+    let mut n_yes = 0;
+    let mut n_total = 0;
+
+    for _ in 0..50 {
+        let generated_data = generator
+            .generate_unseeded::<LoggerProgressReporter>(
+                &data_block,
+                1,
+                100,
+                "",
+                Some(get_target_distribution()),
+                &mut None,
+            )
+            .unwrap();
+
+        for r in generated_data.synthetic_data.iter().skip(1) {
+            n_total += 1;
+            if *r[0] == "yes" {
+                n_yes += 1;
+            }
+        }
+    }
+
+    let yes_proportion = n_yes as f64 / n_total as f64;
+
+    assert!(
+        (0.4..=0.6).contains(&yes_proportion),
+        "yes proportion should be close to 0.5, got {yes_proportion}"
+    );
+}
@@ -0,0 +1,47 @@
+use sds_core::{
+    processing::generator::{Generator, UniqueCopiesFilter},
+    utils::reporting::LoggerProgressReporter,
+};
+
+use crate::utils::create_data_block;
+
+#[test]
+fn validate_unique_copies_filter() {
+    let sensitive = create_data_block("A,B\na1,b1\na1,b1\na2,b2\n");
+    let mut filter = UniqueCopiesFilter::new(&sensitive);
+    let records = sensitive
+        .records
+        .iter()
+        .map(|r| r.values.iter().cloned().collect())
+        .collect();
+    let filtered = filter.filter(records);
+
+    // only the unique record is rejected
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(filter.number_of_rejected_records(), 1);
+}
+
+#[test]
+fn validate_row_seeded_rejects_unique_copies() {
+    let sensitive = create_data_block("A,B\na1,b1\na1,b1\na2,b2\na3,b3\n");
+    let generator = Generator::new(None, true, false);
+
+    for _ in 0..10 {
+        let generated_data = generator
+            .generate_row_seeded::<LoggerProgressReporter>(&sensitive, 1, 100, "", &mut None)
+            .unwrap();
+
+        assert!(generated_data
+            .synthetic_data
+            .iter()
+            .skip(1)
+            .all(|r| !(*r[0] == "a2" && *r[1] == "b2" || *r[0] == "a3" && *r[1] == "b3")));
+        assert!(generated_data.number_of_rejected_unique_copies.is_some());
+    }
+
+    let generated_data = Generator::new(None, false, false)
+        .generate_row_seeded::<LoggerProgressReporter>(&sensitive, 1, 100, "", &mut None)
+        .unwrap();
+
+    assert!(generated_data.number_of_rejected_unique_copies.is_none());
+}
@@ -1 +1,7 @@
 mod aggregator;
+
+mod evaluator;
+
+mod generalizer;
+
+mod generator;
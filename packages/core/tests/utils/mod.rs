@@ -1,9 +1,14 @@
 use csv::ReaderBuilder;
 use fnv::FnvHashMap;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, DataBlock, DataBlockCreator, DataBlockValue},
+    data_block::{
+        CsvDataBlockCreator, DataBlock, DataBlockCreator, DataBlockValue, RecordSampling,
+    },
     dp::CombinationsCountMap,
-    processing::aggregator::{ValueCombination, COMBINATIONS_DELIMITER},
+    processing::aggregator::{
+        AggregatedData, Aggregator, ValueCombination, COMBINATIONS_DELIMITER,
+    },
+    utils::reporting::LoggerProgressReporter,
 };
 use std::{
     collections::HashMap,
@@ -37,10 +42,37 @@ pub fn read_test_data_block<S: AsRef<Path>>(
         multi_value_columns,
         sensitive_zeros,
         record_limit,
+        RecordSampling::First,
     )
     .unwrap()
 }
 
+pub fn create_data_block_with_multi_value_columns(
+    data: &str,
+    multi_value_columns: &HashMap<String, String>,
+) -> Arc<DataBlock> {
+    CsvDataBlockCreator::create(
+        Ok(ReaderBuilder::new().from_reader(data.as_bytes())),
+        None,
+        &[],
+        multi_value_columns,
+        &[],
+        0,
+        RecordSampling::First,
+    )
+    .unwrap()
+}
+
+pub fn create_data_block(data: &str) -> Arc<DataBlock> {
+    create_data_block_with_multi_value_columns(data, &HashMap::default())
+}
+
+pub fn aggregate(data: &str) -> AggregatedData {
+    Aggregator::new(create_data_block(data))
+        .aggregate::<LoggerProgressReporter>(0, &mut None)
+        .unwrap()
+}
+
 pub fn gen_value_combination(combination_str: &str) -> ValueCombination {
     ValueCombination::new(
         combination_str
@@ -1,5 +1,9 @@
 use super::{AccuracyMode, DpAggregateSeededParameters, FabricationMode};
 use pyo3::{exceptions::PyValueError, prelude::*};
+use sds_core::dp::{
+    DpAdvisorInputs, DpColumnHierarchy, DpParametersAdvisor, DpWorkload, DpWorkloadQuery,
+    NoisyCountThreshold,
+};
 use serde::Serialize;
 
 #[pyclass]
@@ -21,6 +25,8 @@ use serde::Serialize;
 ///     - use_synthetic_counts: false
 ///     - weight_selection_percentile: 95
 ///     - aggregate_counts_scale_factor: None
+///     - workload: None
+///     - column_hierarchies: None
 ///
 /// Calling the builder methods will update the parameters in place and return
 /// a reference to the builder itself (so method calls can be chained for configuration).
@@ -42,6 +48,8 @@ pub struct DpAggregateSeededParametersBuilder {
     _use_synthetic_counts: bool,
     _weight_selection_percentile: usize,
     _aggregate_counts_scale_factor: Option<f64>,
+    _workload: Option<DpWorkload>,
+    _column_hierarchies: Option<Vec<DpColumnHierarchy>>,
 }
 
 #[pymethods]
@@ -62,6 +70,8 @@ impl DpAggregateSeededParametersBuilder {
             _use_synthetic_counts: false,
             _weight_selection_percentile: 95,
             _aggregate_counts_scale_factor: None,
+            _workload: None,
+            _column_hierarchies: None,
         }
     }
 
@@ -294,6 +304,148 @@ impl DpAggregateSeededParametersBuilder {
         slf
     }
 
+    #[inline]
+    #[pyo3(text_signature = "(self, queries, uncovered_weight=None)")]
+    /// Expected analysis workload, used to split the privacy budget within each
+    /// combination length (on top of the accuracy mode).
+    ///
+    /// Each query is a group of columns usually analyzed together, with an importance weight.
+    /// Combinations made of any subset of the query columns are covered by it, and the sigma
+    /// used to add noise to them is divided by the (highest) covering query weight.
+    ///
+    /// Example:
+    ///     .workload([(['age', 'region', 'diagnosis'], 4.0), (['sex', 'age'], 2.0)], uncovered_weight=0.5)
+    ///
+    /// The resulting budget split can be checked with `DpAggregateSeededSynthesizer.get_budget_allocation()`.
+    ///
+    /// Arguments:
+    ///     * queries: list[tuple[list[str], float]] - column groups with their weights (weights must be > 0)
+    ///     * uncovered_weight: Optional[float] - weight of the combinations not covered by any query
+    ///         - if 0.0, these combinations are not reported at all
+    ///         - if not provided, 1.0 will be used
+    ///
+    /// Returns:
+    ///     Self reference to the builder - DpAggregateSeededParametersBuilder
+    pub fn workload(
+        slf: Py<Self>,
+        py: Python,
+        queries: Vec<(Vec<String>, f64)>,
+        uncovered_weight: Option<f64>,
+    ) -> Py<Self> {
+        slf.borrow_mut(py)._workload = Some(DpWorkload::new(
+            queries
+                .into_iter()
+                .map(|(columns, weight)| DpWorkloadQuery::new(columns, weight))
+                .collect(),
+            uncovered_weight,
+        ));
+        slf
+    }
+
+    #[inline]
+    #[pyo3(text_signature = "(self, hierarchies)")]
+    /// Columns forming hierarchies, such as country/state/county.
+    ///
+    /// The noisy counts of the combinations made of the hierarchy levels are post-processed
+    /// to be consistent (children never adding up to more than their parent), and the synthesized records
+    /// only combine hierarchy values released together (e.g. a county with its own state).
+    ///
+    /// Example:
+    ///     .column_hierarchies([['country', 'state', 'county']])
+    ///
+    /// Arguments:
+    ///     * hierarchies: list[list[str]] - columns of each hierarchy, from the coarsest
+    ///     to the finest level (at least two columns, each column used at most once)
+    ///
+    /// Returns:
+    ///     Self reference to the builder - DpAggregateSeededParametersBuilder
+    pub fn column_hierarchies(
+        slf: Py<Self>,
+        py: Python,
+        hierarchies: Vec<Vec<String>>,
+    ) -> Py<Self> {
+        slf.borrow_mut(py)._column_hierarchies = Some(
+            hierarchies
+                .into_iter()
+                .map(DpColumnHierarchy::new)
+                .collect(),
+        );
+        slf
+    }
+
+    #[pyo3(
+        text_signature = "(self, number_of_records, attribute_cardinalities, target_relative_errors, delta=None, max_epsilon=None)"
+    )]
+    /// Configures the DP parameters to meet target relative errors for the reported counts,
+    /// without accessing the sensitive data.
+    ///
+    /// This will set the reporting_length, epsilon, delta, percentile_percentage,
+    /// percentile_epsilon_proportion, number_of_records_epsilon_proportion, accuracy_mode and
+    /// fabrication_mode. The noise added to the counts is compared to the mean count expected
+    /// for each combination length, assuming every record has a value for every column.
+    ///
+    /// If the targets can not be reached, an exception explaining why is raised.
+    ///
+    /// Example:
+    ///     .recommend_for_target_errors(10000, [2, 3, 4, 5], [0.05, 0.1, 0.2], max_epsilon=4.0)
+    ///
+    /// Arguments:
+    ///     * number_of_records: int - number of records in the dataset (or a DP estimate of it)
+    ///     * attribute_cardinalities: list[int] - number of distinct values of each column
+    ///     * target_relative_errors: list[float] - target relative error by combination length
+    ///         - the reporting_length will be set to the length of this list
+    ///     * delta: Optional[float] - delta value to be used
+    ///         - if not provided, `1 / (ln(number_of_records) * number_of_records)` will be used
+    ///     * max_epsilon: Optional[float] - maximum acceptable privacy budget
+    ///
+    /// Returns:
+    ///     Self reference to the builder - DpAggregateSeededParametersBuilder
+    pub fn recommend_for_target_errors(
+        slf: Py<Self>,
+        py: Python,
+        number_of_records: usize,
+        attribute_cardinalities: Vec<usize>,
+        target_relative_errors: Vec<f64>,
+        delta: Option<f64>,
+        max_epsilon: Option<f64>,
+    ) -> PyResult<Py<Self>> {
+        let reporting_length = target_relative_errors.len();
+        let recommendation = DpParametersAdvisor::new(DpAdvisorInputs::new(
+            number_of_records,
+            attribute_cardinalities,
+            reporting_length,
+            target_relative_errors,
+            delta,
+            max_epsilon,
+        ))
+        .recommend()?;
+
+        {
+            let mut builder = slf.borrow_mut(py);
+            let dp_parameters = recommendation.dp_parameters;
+
+            builder._reporting_length = reporting_length;
+            builder._epsilon = dp_parameters.epsilon;
+            builder._delta = dp_parameters.delta;
+            builder._percentile_percentage = dp_parameters.percentile_percentage;
+            builder._percentile_epsilon_proportion = dp_parameters.percentile_epsilon_proportion;
+            if let Some(sigma_proportions) = dp_parameters.sigma_proportions {
+                builder._accuracy_mode = AccuracyMode::custom(sigma_proportions);
+            }
+            if let Some(proportion) = dp_parameters.number_of_records_epsilon_proportion {
+                builder._number_of_records_epsilon_proportion = proportion;
+            }
+            builder._fabrication_mode = match recommendation.threshold {
+                NoisyCountThreshold::Adaptive(thresholds) => {
+                    FabricationMode::custom_adaptive(thresholds)
+                }
+                NoisyCountThreshold::Fixed(thresholds) => FabricationMode::custom_fixed(thresholds),
+                NoisyCountThreshold::TopK(top_k) => FabricationMode::custom_top_k(top_k),
+            };
+        }
+        Ok(slf)
+    }
+
     #[pyo3(text_signature = "(self)")]
     /// Validates if the configured parameters, if validation does not pass, throws
     /// an exception.
@@ -349,6 +501,12 @@ impl DpAggregateSeededParametersBuilder {
             }
         }
 
+        if let Some(workload) = &self._workload {
+            workload
+                .validate_weights()
+                .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        }
+
         Ok(())
     }
 
@@ -377,6 +535,8 @@ impl DpAggregateSeededParametersBuilder {
             use_synthetic_counts: self._use_synthetic_counts,
             weight_selection_percentile: self._weight_selection_percentile,
             aggregate_counts_scale_factor: self._aggregate_counts_scale_factor,
+            workload: self._workload.clone(),
+            column_hierarchies: self._column_hierarchies.clone(),
         })
     }
 
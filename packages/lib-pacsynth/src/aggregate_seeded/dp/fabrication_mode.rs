@@ -154,6 +154,35 @@ impl FabricationMode {
             mode: FabricationModeEnum::Custom(NoisyCountThreshold::Adaptive(thresholds)),
         }
     }
+
+    #[inline]
+    #[staticmethod]
+    #[pyo3(text_signature = "(top_k)")]
+    /// This mode let's you specify how many attribute combinations should be
+    /// reported per combination length. Only the combinations with the
+    /// highest noisy counts are kept, so fabrication is controlled by the
+    /// number of released combinations instead of a threshold.
+    ///
+    /// Selecting the highest noisy counts is a post-processing of the noise
+    /// already added to the counts, so no additional privacy budget is spent.
+    /// For the 1-counts, the threshold that guarantees differential privacy is
+    /// still applied before the selection.
+    ///
+    /// Example:
+    ///     - for a reporting_length=3: top_k = {2: 1000, 3: 10000}
+    ///
+    /// Arguments:
+    ///     * top_k: dict[int, int] - the key is the target combination length while
+    ///                               the value is the maximum number of combinations to report
+    ///         - if not set for a particular length, every positive noisy count is kept
+    ///
+    /// Returns:
+    ///     FabricationMode
+    pub fn custom_top_k(top_k: InputValueByLen<usize>) -> Self {
+        Self {
+            mode: FabricationModeEnum::Custom(NoisyCountThreshold::TopK(top_k)),
+        }
+    }
 }
 
 impl FabricationMode {
@@ -184,6 +213,17 @@ impl FabricationMode {
                     }
                     Ok(())
                 }
+                NoisyCountThreshold::TopK(values) => {
+                    if values.keys().any(|v| *v < 1 || *v > reporting_length) {
+                        return Err(PyValueError::new_err(
+                            "top k keys must be >= 1 and <= reporting_length",
+                        ));
+                    }
+                    if values.values().any(|v| *v == 0) {
+                        return Err(PyValueError::new_err("top k values must be > 0"));
+                    }
+                    Ok(())
+                }
             },
             _ => Ok(()),
         }
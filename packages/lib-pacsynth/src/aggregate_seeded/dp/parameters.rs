@@ -1,5 +1,5 @@
 use pyo3::prelude::*;
-use sds_core::dp::NoisyCountThreshold;
+use sds_core::dp::{DpColumnHierarchy, DpWorkload, NoisyCountThreshold};
 use serde::Serialize;
 
 #[pyclass]
@@ -22,6 +22,8 @@ pub struct DpAggregateSeededParameters {
     pub(crate) use_synthetic_counts: bool,
     pub(crate) weight_selection_percentile: usize,
     pub(crate) aggregate_counts_scale_factor: Option<f64>,
+    pub(crate) workload: Option<DpWorkload>,
+    pub(crate) column_hierarchies: Option<Vec<DpColumnHierarchy>>,
 }
 
 #[pymethods]
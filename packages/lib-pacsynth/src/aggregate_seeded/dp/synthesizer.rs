@@ -1,7 +1,7 @@
 use super::{DpAggregateSeededParameters, DpAggregateSeededParametersBuilder};
 use pyo3::{exceptions::PyRuntimeError, prelude::*};
 use sds_core::{
-    dp::DpParameters,
+    dp::{DpColumnHierarchy, DpParameters},
     processing::{
         aggregator::{AggregatedData, AggregatesCountStringMap, Aggregator},
         generator::Generator,
@@ -66,6 +66,20 @@ impl DpAggregateSeededSynthesizer {
     /// Arguments:
     ///     * dataset: Dataset - dataset to compute the aggregates for
     pub fn fit(&mut self, dataset: &Dataset) -> PyResult<()> {
+        if let Some(workload) = &self._parameters.workload {
+            workload.validate(
+                &dataset.data_block.headers,
+                &dataset.data_block.multi_value_column_metadata_map,
+            )?;
+        }
+        if let Some(column_hierarchies) = &self._parameters.column_hierarchies {
+            DpColumnHierarchy::validate_all(
+                column_hierarchies,
+                &dataset.data_block.headers,
+                &dataset.data_block.multi_value_column_metadata_map,
+            )?;
+        }
+
         self._aggregated_data = Some(Arc::new(
             Aggregator::new(dataset.data_block.clone()).aggregate_with_dp(
                 self._parameters.reporting_length,
@@ -76,6 +90,8 @@ impl DpAggregateSeededSynthesizer {
                     self._parameters.delta,
                     Some(self._parameters.sigma_proportions.clone()),
                     Some(self._parameters.number_of_records_epsilon_proportion),
+                    self._parameters.workload.clone(),
+                    self._parameters.column_hierarchies.clone(),
                 ),
                 self._parameters.threshold.clone(),
                 &mut create_progress_reporter(),
@@ -85,6 +101,39 @@ impl DpAggregateSeededSynthesizer {
         Ok(())
     }
 
+    #[pyo3(
+        text_signature = "(self, aggregates_path, aggregates_delimiter=None, combination_delimiter=None)"
+    )]
+    /// Loads previously published aggregates to synthesize data from, instead of
+    /// computing them with `.fit` (no sensitive dataset is needed).
+    ///
+    /// The aggregates are expected to be on the same format produced by the
+    /// aggregate step: a `selections<delimiter>count` (or `protected_count`) header line,
+    /// followed by a `record_count<delimiter><n>` line and one line per attribute combination.
+    /// Headers, single attribute counts and reporting length are reconstructed from the
+    /// table, and the counts are validated for consistency between combination lengths.
+    ///
+    /// Arguments:
+    ///     * aggregates_path: str - path to the aggregate count table
+    ///     * aggregates_delimiter: Optional[str] - delimiter between the combination and its count
+    ///         - if not provided, a tab will be used
+    ///     * combination_delimiter: Optional[str] - delimiter used to join the attributes of a combination
+    ///         - if not provided, ';' will be used
+    pub fn fit_from_aggregates(
+        &mut self,
+        aggregates_path: &str,
+        aggregates_delimiter: Option<char>,
+        combination_delimiter: Option<&str>,
+    ) -> PyResult<()> {
+        self._aggregated_data = Some(Arc::new(AggregatedData::read_aggregates_count(
+            aggregates_path,
+            aggregates_delimiter.unwrap_or('\t'),
+            combination_delimiter.unwrap_or(";"),
+        )?));
+
+        Ok(())
+    }
+
     #[pyo3(text_signature = "(self, target_number_of_records=None, join_multi_value_columns=None)")]
     /// Sample records from the differentially private aggregates.
     ///
@@ -113,6 +162,7 @@ impl DpAggregateSeededSynthesizer {
                 Some(self._parameters.weight_selection_percentile),
                 self._parameters.aggregate_counts_scale_factor,
                 target_number_of_records,
+                None,
                 &mut create_progress_reporter(),
             )?;
             Ok(generated_data.synthetic_data_to_vec(
@@ -164,6 +214,26 @@ impl DpAggregateSeededSynthesizer {
                 PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
             })
     }
+
+    #[pyo3(text_signature = "(self)")]
+    /// Gets how the privacy budget was split by the `.fit` method, across combination
+    /// lengths and workload weights.
+    ///
+    /// Each share contains the combination length, the workload weight, the epsilon used by the
+    /// sensitivity percentile filter, the sigma used to add noise, the allowed sensitivity, the
+    /// zCDP rho spent, the proportion of the marginals budget spent and the number of reported combinations.
+    ///
+    /// Returns:
+    ///     Budget allocation serialized to a JSON String - str
+    pub fn get_budget_allocation(&self) -> PyResult<String> {
+        self._aggregated_data
+            .as_ref()
+            .and_then(|ad| ad.dp_budget_allocation.as_ref())
+            .map(|allocation| serde_json::to_string_pretty(allocation).unwrap())
+            .ok_or_else(|| {
+                PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
+            })
+    }
 }
 
 pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
@@ -1,7 +1,7 @@
 use self::dataset_data_block_creator::DatasetDataBlockCreator;
 use pyo3::{exceptions::PyValueError, prelude::*, types::IntoPyDict};
 use sds_core::{
-    data_block::{DataBlock, DataBlockCreator},
+    data_block::{DataBlock, DataBlockCreator, RecordSampling},
     processing::aggregator::{AggregatesCountStringMap, Aggregator},
 };
 use std::{collections::HashMap, sync::Arc};
@@ -14,7 +14,7 @@ pub type DatasetRawData = Vec<Vec<String>>;
 
 #[pyclass]
 #[pyo3(
-    text_signature = "(raw_data, subject_id=None, use_columns=None, multi_value_columns=None, sensitive_zeros=None, record_limit=None)"
+    text_signature = "(raw_data, subject_id=None, use_columns=None, multi_value_columns=None, sensitive_zeros=None, record_limit=None, max_records_per_subject=None, record_sampling=None, sampling_rate=None)"
 )]
 /// Creates a new Dataset, which is optimized for the internal algorithms to run.
 ///
@@ -40,6 +40,18 @@ pub type DatasetRawData = Vec<Vec<String>>;
 ///           so they will be treated the same way as positive values
 ///     * record_limit: Optional[int] - number of records to use (starting from the first one)
 ///         - if not set or 0, use all records
+///     * max_records_per_subject: Optional[int] - keeps one record per row instead of joining the
+///                                                records with the same subject_id
+///         - at most this many records are randomly sampled for each subject, and the DP noise is scaled
+///           accordingly, so the privacy guarantee holds at the subject level (user-level DP)
+///         - requires subject_id to be set
+///     * record_sampling: Optional[str] - how the records are selected
+///         - 'first' (default): take the first record_limit records
+///         - 'uniform': randomly sample record_limit subjects (or records, if no subject_id is set)
+///         - 'poisson': keep each subject (or record) independently with probability sampling_rate
+///         - poisson sampling amplifies the differential privacy guarantees of the
+///           DP aggregation, which will report the amplified epsilon
+///     * sampling_rate: Optional[float] - probability of keeping each subject, required for 'poisson'
 ///
 /// Returns:
 ///     New library's optimized representation of the data - Dataset
@@ -52,6 +64,7 @@ impl Dataset {
     #[inline]
     #[new]
     /// See the `Dataset` class documentation.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         raw_data: DatasetRawData,
         subject_id: Option<String>,
@@ -59,22 +72,51 @@ impl Dataset {
         multi_value_columns: Option<HashMap<String, String>>,
         sensitive_zeros: Option<Vec<String>>,
         record_limit: Option<usize>,
+        max_records_per_subject: Option<usize>,
+        record_sampling: Option<String>,
+        sampling_rate: Option<f64>,
     ) -> PyResult<Self> {
+        let record_sampling = Self::parse_record_sampling(record_sampling, sampling_rate)?;
+        let use_columns = use_columns.unwrap_or_default();
+        let multi_value_columns = multi_value_columns.unwrap_or_default();
+        let sensitive_zeros = sensitive_zeros.unwrap_or_default();
+        let record_limit = record_limit.unwrap_or_default();
+
         Ok(Self {
-            data_block: DatasetDataBlockCreator::create(
-                Ok(raw_data),
-                subject_id,
-                &use_columns.unwrap_or_default(),
-                &multi_value_columns.unwrap_or_default(),
-                &sensitive_zeros.unwrap_or_default(),
-                record_limit.unwrap_or_default(),
-            )?,
+            data_block: match (subject_id, max_records_per_subject) {
+                (Some(subject_id), Some(max_records_per_subject)) => {
+                    DatasetDataBlockCreator::create_with_max_records_per_subject(
+                        Ok(raw_data),
+                        subject_id,
+                        max_records_per_subject,
+                        &use_columns,
+                        &multi_value_columns,
+                        &sensitive_zeros,
+                        record_limit,
+                        record_sampling,
+                    )?
+                }
+                (None, Some(_)) => {
+                    return Err(PyValueError::new_err(
+                        "subject_id must be set to use max_records_per_subject",
+                    ))
+                }
+                (subject_id, None) => DatasetDataBlockCreator::create(
+                    Ok(raw_data),
+                    subject_id,
+                    &use_columns,
+                    &multi_value_columns,
+                    &sensitive_zeros,
+                    record_limit,
+                    record_sampling,
+                )?,
+            },
         })
     }
 
     #[staticmethod]
     #[pyo3(
-        text_signature = "(df, subject_id=None, use_columns=None, multi_value_columns=None, sensitive_zeros=None, record_limit=None)"
+        text_signature = "(df, subject_id=None, use_columns=None, multi_value_columns=None, sensitive_zeros=None, record_limit=None, max_records_per_subject=None, record_sampling=None, sampling_rate=None)"
     )]
     /// Creates a new Dataset from a pandas data frame.
     ///
@@ -100,9 +142,16 @@ impl Dataset {
     ///           any columns where zero values are of interest (and thus sensitive) should be listed as `sensitive_zeros`,
     ///           so they will be treated the same way as positive values
     ///     * record_limit: Optional[int] - number of records to use (starting from the first one)
+    ///     * max_records_per_subject: Optional[int] - keeps one record per row instead of joining the
+    ///                                                records with the same subject_id
+    ///         - at most this many records are randomly sampled for each subject (user-level DP)
+    ///         - requires subject_id to be set
+    ///     * record_sampling: Optional[str] - how the records are selected ('first', 'uniform' or 'poisson')
+    ///     * sampling_rate: Optional[float] - probability of keeping each subject, required for 'poisson'
     ///
     /// Returns:
     ///     New library's optimized representation of the data - Dataset
+    #[allow(clippy::too_many_arguments)]
     pub fn from_data_frame(
         df: PyObject,
         py: Python,
@@ -111,6 +160,9 @@ impl Dataset {
         multi_value_columns: Option<HashMap<String, String>>,
         sensitive_zeros: Option<Vec<String>>,
         record_limit: Option<usize>,
+        max_records_per_subject: Option<usize>,
+        record_sampling: Option<String>,
+        sampling_rate: Option<f64>,
     ) -> PyResult<Self> {
         Self::new(
             Self::data_frame_to_raw_data(df, py)?,
@@ -119,6 +171,9 @@ impl Dataset {
             multi_value_columns,
             sensitive_zeros,
             record_limit,
+            max_records_per_subject,
+            record_sampling,
+            sampling_rate,
         )
     }
 
@@ -258,6 +313,29 @@ impl Dataset {
     }
 }
 
+impl Dataset {
+    #[inline]
+    fn parse_record_sampling(
+        record_sampling: Option<String>,
+        sampling_rate: Option<f64>,
+    ) -> PyResult<RecordSampling> {
+        match (
+            record_sampling.map(|rs| rs.to_lowercase()).as_deref(),
+            sampling_rate,
+        ) {
+            (None | Some("first"), _) => Ok(RecordSampling::First),
+            (Some("uniform"), _) => Ok(RecordSampling::Uniform),
+            (Some("poisson"), Some(sampling_rate)) => Ok(RecordSampling::Poisson(sampling_rate)),
+            (Some("poisson"), None) => Err(PyValueError::new_err(
+                "sampling_rate must be set to use poisson record_sampling",
+            )),
+            _ => Err(PyValueError::new_err(
+                "record_sampling must be 'first', 'uniform' or 'poisson'",
+            )),
+        }
+    }
+}
+
 pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<Dataset>()?;
     Ok(())
@@ -2,6 +2,7 @@ use pyo3::prelude::*;
 
 mod aggregate_seeded;
 mod dataset;
+mod marginal;
 mod utils;
 
 #[pymodule]
@@ -14,6 +15,7 @@ mod utils;
 fn pacsynth(py: Python, m: &PyModule) -> PyResult<()> {
     aggregate_seeded::register(py, m)?;
     dataset::register(py, m)?;
+    marginal::register(py, m)?;
     utils::register(py, m)?;
     Ok(())
 }
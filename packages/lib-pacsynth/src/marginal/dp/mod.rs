@@ -0,0 +1,8 @@
+use pyo3::prelude::*;
+
+mod synthesizer;
+
+pub(crate) fn register(py: Python<'_>, parent_module: &PyModule) -> PyResult<()> {
+    synthesizer::register(py, parent_module)?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use sds_core::{
+    dp::DpMarginalParameters,
+    processing::generator::{DpMarginalModel, Generator},
+};
+
+use crate::{
+    dataset::{Dataset, DatasetRawData},
+    utils::create_progress_reporter,
+};
+
+#[pyclass]
+#[pyo3(
+    text_signature = "(epsilon=None, delta=None, single_attributes_budget_proportion=None, selection_budget_proportion=None, empty_value=None)"
+)]
+/// Differential Privacy (DP) Marginal Synthesizer.
+///
+/// DP Marginal synthesizer is a differentially private synthesizer that only measures a small
+/// set of low-order marginals, instead of all the attribute combinations up to a reporting length.
+/// It measures every single column count, privately selects a tree of informative column pairs,
+/// measures them and fits a consistent model (`.fit`), which is later used to sample synthetic
+/// records (`.sample`). This is better suited for wide datasets, where computing all the
+/// longer combinations is infeasible.
+///
+/// Each record is assumed to represent a single individual, use the `subject_id` on the
+/// `Dataset` to join records belonging to the same individual (or `max_records_per_subject`
+/// to bound how many records each individual contributes with).
+///
+/// Arguments:
+///     * epsilon: Optional[float] - overall privacy budget used to measure the single column counts,
+///                                  select the column pairs and measure them
+///         - if not provided, 4.0 will be used
+///     * delta: Optional[float] - delta used to convert the privacy budget
+///         - if not provided, will be set in runtime to `1 / (ln(number_of_records) * number_of_records)`
+///     * single_attributes_budget_proportion: Optional[float] - proportion of the privacy budget used
+///                                                              to measure the single column counts
+///         - if not provided, 1/3 will be used
+///     * selection_budget_proportion: Optional[float] - proportion of the privacy budget used to select
+///                                                      the column pairs (the remaining is used to measure them)
+///         - if not provided, 1/3 will be used
+///     * empty_value: Optional[str] - empty values on the synthetic data will be represented by this
+///         - if not provided, '' will be used
+///
+/// Returns:
+///     New DpMarginalSynthesizer
+pub struct DpMarginalSynthesizer {
+    _parameters: DpMarginalParameters,
+    _empty_value: String,
+    _model: Option<DpMarginalModel>,
+}
+
+#[pymethods]
+impl DpMarginalSynthesizer {
+    #[inline]
+    #[new]
+    pub fn new(
+        epsilon: Option<f64>,
+        delta: Option<f64>,
+        single_attributes_budget_proportion: Option<f64>,
+        selection_budget_proportion: Option<f64>,
+        empty_value: Option<String>,
+    ) -> PyResult<Self> {
+        let parameters = DpMarginalParameters::new(
+            epsilon.unwrap_or(4.0),
+            delta,
+            single_attributes_budget_proportion,
+            selection_budget_proportion,
+        );
+
+        parameters
+            .validate()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        Ok(Self {
+            _parameters: parameters,
+            _empty_value: empty_value.unwrap_or_default(),
+            _model: None,
+        })
+    }
+
+    #[pyo3(text_signature = "(self, dataset)")]
+    /// Computes the differentially private marginals and fits the model used
+    /// to synthesize data.
+    ///
+    /// Arguments:
+    ///     * dataset: Dataset - dataset to fit the model for
+    pub fn fit(&mut self, dataset: &Dataset) -> PyResult<()> {
+        self._model = Some(Generator::default().fit_dp_marginal(
+            &dataset.data_block,
+            self._parameters.clone(),
+            &mut create_progress_reporter(),
+        )?);
+
+        Ok(())
+    }
+
+    #[pyo3(text_signature = "(self, target_number_of_records=None, join_multi_value_columns=None)")]
+    /// Sample records from the fitted model.
+    ///
+    /// Arguments:
+    ///     * target_number_of_records: Optional[int] - desired number of records to be synthesized
+    ///         - if not set, the differentially private number of records will be used
+    ///     * join_multi_value_columns: Optional[bool] - whether previous spread multi value columns in the input
+    ///                                                  dataset should be joined back together in the resulting raw data
+    ///
+    /// Returns:
+    ///     The result raw data form the synthesis,
+    ///     where the first entry contains the headers - list[list[str]]
+    pub fn sample(
+        &self,
+        target_number_of_records: Option<usize>,
+        join_multi_value_columns: Option<bool>,
+    ) -> PyResult<DatasetRawData> {
+        let model = self._model.as_ref().ok_or_else(|| {
+            PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
+        })?;
+        let generated_data = Generator::default().generate_dp_marginal(
+            model,
+            &self._empty_value,
+            target_number_of_records,
+            &mut create_progress_reporter(),
+        )?;
+
+        Ok(generated_data.synthetic_data_to_vec(
+            &self._empty_value,
+            join_multi_value_columns.unwrap_or(false),
+        ))
+    }
+
+    #[pyo3(text_signature = "(self)")]
+    /// Gets the column pairs selected and measured by the `.fit` method.
+    ///
+    /// Returns:
+    ///     List of column name pairs - list[tuple[str, str]]
+    pub fn get_selected_pairs(&self) -> PyResult<Vec<(String, String)>> {
+        self._model
+            .as_ref()
+            .map(|model| {
+                model
+                    .selected_pairs()
+                    .iter()
+                    .map(|(a, b)| {
+                        (
+                            (*model.headers()[*a]).clone(),
+                            (*model.headers()[*b]).clone(),
+                        )
+                    })
+                    .collect()
+            })
+            .ok_or_else(|| {
+                PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
+            })
+    }
+
+    #[pyo3(text_signature = "(self)")]
+    /// Gets the differentially private number of records estimated by the `.fit` method.
+    ///
+    /// Returns:
+    ///     Protected number of records - int
+    pub fn get_dp_number_of_records(&self) -> PyResult<usize> {
+        self._model
+            .as_ref()
+            .map(|model| model.estimated_number_of_records())
+            .ok_or_else(|| {
+                PyRuntimeError::new_err("make sure 'fit' method has been successfully called first")
+            })
+    }
+}
+
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<DpMarginalSynthesizer>()?;
+    Ok(())
+}
@@ -2,12 +2,14 @@ use csv::ReaderBuilder;
 use log::{log_enabled, Level::Debug};
 use pyo3::prelude::*;
 use sds_core::{
-    data_block::{CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreator},
+    data_block::{
+        CsvDataBlockCreator, CsvDataBlockCreatorError, DataBlock, DataBlockCreator, RecordSampling,
+    },
     dp::DpParameters,
     dp::{InputValueByLen, NoisyCountThreshold},
     processing::{
         aggregator::{AggregatedData, Aggregator},
-        generator::{GeneratedData, Generator, OversamplingParameters},
+        generator::{GeneratedData, Generator, OversamplingParameters, TargetDistribution},
     },
     utils::reporting::{LoggerProgressReporter, StoppableResult},
 };
@@ -63,6 +65,7 @@ impl SDSProcessor {
             &multi_value_columns,
             &sensitive_zeros,
             record_limit,
+            RecordSampling::First,
         )
         .map(|data_block| SDSProcessor { data_block })
     }
@@ -135,6 +138,7 @@ impl SDSProcessor {
         resolution: usize,
         cache_max_size: usize,
         empty_value: &str,
+        target_distribution: Option<TargetDistribution>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
@@ -148,6 +152,7 @@ impl SDSProcessor {
             resolution,
             cache_max_size,
             empty_value,
+            target_distribution,
             &mut progress_reporter,
         )
     }
@@ -176,6 +181,7 @@ impl SDSProcessor {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_aggregate_seeded(
         &self,
         empty_value: &str,
@@ -184,6 +190,7 @@ impl SDSProcessor {
         weight_selection_percentile: Option<usize>,
         aggregate_counts_scale_factor: Option<f64>,
         target_number_of_records: Option<usize>,
+        target_distribution: Option<TargetDistribution>,
     ) -> StoppableResult<GeneratedData> {
         let mut progress_reporter = if log_enabled!(Debug) {
             Some(LoggerProgressReporter::new(Debug))
@@ -199,6 +206,7 @@ impl SDSProcessor {
             weight_selection_percentile,
             aggregate_counts_scale_factor,
             target_number_of_records,
+            target_distribution,
             &mut progress_reporter,
         )
     }
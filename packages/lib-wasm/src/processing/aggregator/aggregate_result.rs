@@ -5,7 +5,7 @@ use wasm_bindgen::{prelude::*, JsCast};
 
 use crate::{
     processing::aggregator::WasmAggregateStatistics,
-    utils::js::{JsAggregateResult, JsResult},
+    utils::js::{to_js_value, JsAggregateResult, JsRecordsRisk, JsResult},
 };
 
 #[wasm_bindgen]
@@ -56,6 +56,22 @@ impl WasmAggregateResult {
         }
     }
 
+    #[wasm_bindgen(js_name = "recordsRisk")]
+    pub fn records_risk(
+        &self,
+        resolution: usize,
+        number_of_top_combinations: usize,
+        combination_delimiter: &str,
+    ) -> JsResult<JsRecordsRisk> {
+        Ok(to_js_value(&self.aggregated_data.calc_records_risk(
+            resolution,
+            number_of_top_combinations,
+            combination_delimiter,
+        ))
+        .map_err(|err| JsValue::from(err.to_string()))?
+        .unchecked_into::<JsRecordsRisk>())
+    }
+
     #[wasm_bindgen(getter)]
     #[wasm_bindgen(js_name = "reportingLength")]
     pub fn reporting_length(&self) -> usize {
@@ -106,6 +122,14 @@ impl WasmAggregateResult {
                 .into(),
         )?;
 
+        if let Some(dp_budget_allocation) = &self.aggregated_data.dp_budget_allocation {
+            set(
+                &result,
+                &"dpBudgetAllocation".into(),
+                &to_js_value(dp_budget_allocation).map_err(|err| JsValue::from(err.to_string()))?,
+            )?;
+        }
+
         Ok(JsValue::from(result).unchecked_into::<JsAggregateResult>())
     }
 }
@@ -1,13 +1,20 @@
 use js_sys::{Object, Reflect::set};
-use sds_core::utils::time::ElapsedDurationLogger;
+use sds_core::{
+    data_block::DataBlock,
+    processing::evaluator::{Evaluator, UtilityBreakdown},
+    utils::time::ElapsedDurationLogger,
+};
 use wasm_bindgen::{prelude::*, JsCast};
 
 use crate::{
     processing::aggregator::WasmAggregateResult,
-    utils::js::{JsEvaluateResult, JsResult},
+    utils::js::{to_js_value, JsEvaluateResult, JsResult, JsUtilityBreakdown},
 };
 
-use super::microdata_data_stats::WasmMicrodataStatistics;
+use super::{
+    marginal_distances_stats::WasmMarginalDistancesStatistics,
+    microdata_data_stats::WasmMicrodataStatistics,
+};
 
 #[wasm_bindgen]
 #[derive(Default)]
@@ -16,9 +23,26 @@ pub struct WasmEvaluateResult {
     sensitive_data_stats: WasmMicrodataStatistics,
     synthetic_data_stats: WasmMicrodataStatistics,
     synthetic_vs_aggregate_data_stats: WasmMicrodataStatistics,
+    synthetic_marginal_distances: WasmMarginalDistancesStatistics,
+    synthetic_utility_breakdown: UtilityBreakdown,
     reporting_length: usize,
 }
 
+impl WasmEvaluateResult {
+    /// Adds the pairwise association comparison between
+    /// the sensitive and synthetic data to the synthetic data stats
+    #[inline]
+    pub(crate) fn with_pairwise_association(
+        mut self,
+        sensitive_data_block: &DataBlock,
+        synthetic_data_block: &DataBlock,
+    ) -> WasmEvaluateResult {
+        self.synthetic_data_stats
+            .set_pairwise_association(sensitive_data_block, synthetic_data_block);
+        self
+    }
+}
+
 #[wasm_bindgen]
 impl WasmEvaluateResult {
     #[wasm_bindgen(constructor)]
@@ -50,6 +74,15 @@ impl WasmEvaluateResult {
                 synthetic_aggregate_result,
                 resolution,
             )?,
+            synthetic_marginal_distances: WasmMarginalDistancesStatistics::from_aggregate_results(
+                sensitive_aggregate_result,
+                synthetic_aggregate_result,
+            )?,
+            synthetic_utility_breakdown: Evaluator::default().calc_utility_breakdown(
+                sensitive_aggregate_result,
+                synthetic_aggregate_result,
+                resolution,
+            ),
             reporting_length,
         })
     }
@@ -85,6 +118,19 @@ impl WasmEvaluateResult {
             &"syntheticVsAggregateDataStats".into(),
             &self.synthetic_vs_aggregate_data_stats.to_js()?.into(),
         )?;
+        set(
+            &result,
+            &"syntheticMarginalDistances".into(),
+            &self.synthetic_marginal_distances.to_js()?.into(),
+        )?;
+        set(
+            &result,
+            &"syntheticUtilityBreakdown".into(),
+            &to_js_value(&self.synthetic_utility_breakdown)
+                .map_err(|err| JsValue::from(err.to_string()))?
+                .unchecked_into::<JsUtilityBreakdown>()
+                .into(),
+        )?;
 
         Ok(JsValue::from(result).unchecked_into::<JsEvaluateResult>())
     }
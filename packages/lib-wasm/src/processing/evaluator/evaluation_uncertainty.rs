@@ -0,0 +1,12 @@
+use sds_core::processing::evaluator::BootstrapParameters;
+use wasm_bindgen::JsValue;
+
+use crate::utils::js::{from_js_value, JsBootstrapParameters};
+
+impl TryFrom<JsBootstrapParameters> for BootstrapParameters {
+    type Error = JsValue;
+
+    fn try_from(js_params: JsBootstrapParameters) -> Result<Self, Self::Error> {
+        from_js_value(&js_params).map_err(|err| JsValue::from(err.to_string()))
+    }
+}
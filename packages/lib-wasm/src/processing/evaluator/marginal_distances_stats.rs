@@ -0,0 +1,56 @@
+use crate::{
+    processing::aggregator::WasmAggregateResult,
+    utils::js::{to_js_value, JsMarginalDistancesStatistics, JsResult},
+};
+use sds_core::processing::{
+    aggregator::AggregatedMetricByLenMap,
+    evaluator::{Evaluator, MarginalDistance},
+};
+use serde::Serialize;
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+
+/// Significance level used to report the marginals that differ
+pub const MARGINAL_DISTANCES_SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+#[wasm_bindgen]
+pub struct WasmMarginalDistancesStatistics {
+    marginals: Vec<MarginalDistance>,
+    mean_total_variation_distance_by_len: AggregatedMetricByLenMap,
+    mean_jensen_shannon_divergence_by_len: AggregatedMetricByLenMap,
+    mean_chi_square_by_len: AggregatedMetricByLenMap,
+    percentage_of_different_marginals_by_len: AggregatedMetricByLenMap,
+}
+
+#[wasm_bindgen]
+impl WasmMarginalDistancesStatistics {
+    #[wasm_bindgen(constructor)]
+    pub fn from_aggregate_results(
+        original_aggregate_result: &WasmAggregateResult,
+        aggregate_result: &WasmAggregateResult,
+    ) -> JsResult<WasmMarginalDistancesStatistics> {
+        let marginal_distances = Evaluator::default()
+            .calc_marginal_distances(original_aggregate_result, aggregate_result);
+
+        Ok(WasmMarginalDistancesStatistics {
+            mean_total_variation_distance_by_len: marginal_distances
+                .calc_mean_total_variation_distance_by_len(),
+            mean_jensen_shannon_divergence_by_len: marginal_distances
+                .calc_mean_jensen_shannon_divergence_by_len(),
+            mean_chi_square_by_len: marginal_distances.calc_mean_chi_square_by_len(),
+            percentage_of_different_marginals_by_len: marginal_distances
+                .calc_percentage_of_different_marginals_by_len(
+                    MARGINAL_DISTANCES_SIGNIFICANCE_LEVEL,
+                ),
+            marginals: marginal_distances.marginals,
+        })
+    }
+
+    #[wasm_bindgen(js_name = "toJs")]
+    pub fn to_js(&self) -> JsResult<JsMarginalDistancesStatistics> {
+        Ok(to_js_value(self)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsMarginalDistancesStatistics>())
+    }
+}
@@ -0,0 +1,12 @@
+use sds_core::processing::evaluator::MembershipInferenceParameters;
+use wasm_bindgen::JsValue;
+
+use crate::utils::js::{from_js_value, JsMembershipInferenceParameters};
+
+impl TryFrom<JsMembershipInferenceParameters> for MembershipInferenceParameters {
+    type Error = JsValue;
+
+    fn try_from(js_params: JsMembershipInferenceParameters) -> Result<Self, Self::Error> {
+        from_js_value(&js_params).map_err(|err| JsValue::from(err.to_string()))
+    }
+}
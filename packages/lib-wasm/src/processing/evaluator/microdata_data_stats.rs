@@ -2,9 +2,12 @@ use crate::{
     processing::aggregator::WasmAggregateResult,
     utils::js::{to_js_value, JsMicrodataStatistics, JsResult},
 };
-use sds_core::processing::{
-    aggregator::{AggregatedCountByLenMap, AggregatedMetricByLenMap},
-    evaluator::Evaluator,
+use sds_core::{
+    data_block::DataBlock,
+    processing::{
+        aggregator::{AggregatedCountByLenMap, AggregatedMetricByLenMap},
+        evaluator::{Evaluator, PairwiseAssociation},
+    },
 };
 use serde::Serialize;
 use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
@@ -33,6 +36,20 @@ pub struct WasmMicrodataStatistics {
     percentage_of_records_with_rare_combinations: f64,
     percentage_of_unique_combinations: f64,
     percentage_of_rare_combinations: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pairwise_association: Option<PairwiseAssociation>,
+}
+
+impl WasmMicrodataStatistics {
+    #[inline]
+    pub(crate) fn set_pairwise_association(
+        &mut self,
+        original_data_block: &DataBlock,
+        data_block: &DataBlock,
+    ) {
+        self.pairwise_association =
+            Some(Evaluator::default().calc_pairwise_association(original_data_block, data_block));
+    }
 }
 
 #[wasm_bindgen]
@@ -106,6 +123,7 @@ impl WasmMicrodataStatistics {
                 .calc_percentage_of_unique_combinations(),
             percentage_of_rare_combinations: aggregate_result
                 .calc_percentage_of_rare_combinations(resolution),
+            pairwise_association: None,
         })
     }
 
@@ -1,5 +1,13 @@
 mod evaluate_result;
+mod evaluation_uncertainty;
+mod marginal_distances_stats;
+mod membership_inference;
 mod microdata_data_stats;
+mod ml_utility;
 
 pub use evaluate_result::*;
+pub use evaluation_uncertainty::*;
+pub use marginal_distances_stats::*;
+pub use membership_inference::*;
 pub use microdata_data_stats::*;
+pub use ml_utility::*;
@@ -36,6 +36,33 @@ impl WasmGenerateResult {
         self.resolution
     }
 
+    #[wasm_bindgen(getter)]
+    #[wasm_bindgen(js_name = "numberOfRecords")]
+    pub fn number_of_records(&self) -> usize {
+        self.generated_data.number_of_synthetic_records()
+    }
+
+    #[wasm_bindgen(js_name = "syntheticDataPageToJs")]
+    pub fn synthetic_data_page_to_js(
+        &self,
+        offset: usize,
+        limit: usize,
+        delimiter: char,
+        join_multi_value_columns: bool,
+        long_form: bool,
+    ) -> JsResult<String> {
+        self.generated_data
+            .synthetic_data_page_to_string(
+                offset,
+                limit,
+                delimiter,
+                "",
+                join_multi_value_columns,
+                long_form,
+            )
+            .map_err(|err| JsValue::from(err.to_string()))
+    }
+
     #[wasm_bindgen(js_name = "syntheticDataToJs")]
     pub fn synthetic_data_to_js(
         &self,
@@ -2,10 +2,17 @@ use super::errors::{
     INVALID_REPORTABLE_REPORTING_LENGTH_ERROR, MISSING_EVALUATE_RESULT_ERROR,
     MISSING_GENERATE_RESULT_ERROR, MISSING_NAVIGATE_RESULT_ERROR,
     MISSING_REPORTABLE_AGGREGATE_RESULT_ERROR, MISSING_SENSITIVE_AGGREGATE_RESULT_ERROR,
-    MISSING_SENSITIVE_DATA_ERROR, MISSING_SYNTHETIC_AGGREGATE_RESULT_ERROR,
-    MISSING_SYNTHETIC_PROCESSOR_ERROR,
+    MISSING_SENSITIVE_DATA_ERROR, MISSING_STORED_GENERATE_RESULTS_ERROR,
+    MISSING_SYNTHETIC_AGGREGATE_RESULT_ERROR, MISSING_SYNTHETIC_PROCESSOR_ERROR,
 };
 use js_sys::Function;
+use sds_core::{
+    data_block::RecordSampling,
+    processing::evaluator::{
+        BootstrapParameters, Evaluator, MembershipInferenceAttack, MembershipInferenceParameters,
+        MlUtilityEvaluator, MlUtilityParameters,
+    },
+};
 use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
 
 use crate::{
@@ -20,11 +27,13 @@ use crate::{
         },
     },
     utils::js::{
-        JsAggregateResult, JsAggregateStatistics, JsAttributesIntersectionByColumn,
-        JsBaseSynthesisParameters, JsCsvDataParameters, JsDpParameters, JsEvaluateResult,
-        JsGenerateResult, JsHeaderNames, JsNavigateResult, JsNoisyCountThreshold,
-        JsOversamplingParameters, JsProgressReporter, JsReportProgressCallback, JsResult,
-        JsSelectedAttributesByColumn,
+        to_js_value, JsAggregateResult, JsAggregateStatistics, JsAttributesIntersectionByColumn,
+        JsBaseSynthesisParameters, JsBootstrapParameters, JsCsvDataParameters, JsDpParameters,
+        JsEvaluateResult, JsEvaluationUncertainty, JsGenerateResult, JsHeaderNames,
+        JsMembershipInferenceParameters, JsMembershipInferenceResult, JsMlUtilityParameters,
+        JsMlUtilityResult, JsNavigateResult, JsNoisyCountThreshold, JsOversamplingParameters,
+        JsProgressReporter, JsRecordsRisk, JsReportProgressCallback, JsResult,
+        JsSelectedAttributesByColumn, JsSyntheticDataComparison,
     },
 };
 
@@ -41,6 +50,7 @@ pub struct WasmSdsContext {
     pre_computed_aggregates: bool,
     evaluate_result: Option<WasmEvaluateResult>,
     navigate_result: Option<WasmNavigateResult>,
+    stored_synthetic_processors: Vec<(String, WasmSdsProcessor)>,
 }
 
 #[wasm_bindgen]
@@ -57,6 +67,7 @@ impl WasmSdsContext {
         self.sensitive_aggregate_result = None;
         self.generate_result = None;
         self.pre_computed_aggregates = false;
+        self.stored_synthetic_processors.clear();
         self.clear_evaluate();
     }
 
@@ -94,6 +105,28 @@ impl WasmSdsContext {
         .to_js()
     }
 
+    #[wasm_bindgen(js_name = "sensitiveRecordsRisk")]
+    pub fn sensitive_records_risk(
+        &mut self,
+        reporting_length: usize,
+        resolution: usize,
+        number_of_top_combinations: usize,
+        combination_delimiter: &str,
+        progress_callback: JsReportProgressCallback,
+    ) -> JsResult<JsRecordsRisk> {
+        let js_callback: Function = progress_callback.dyn_into()?;
+
+        self.get_or_create_sensitive_aggregate_result(
+            reporting_length,
+            &mut Some(JsProgressReporter::new(&js_callback, &|p| p)),
+        )?
+        .records_risk(
+            resolution,
+            number_of_top_combinations,
+            combination_delimiter,
+        )
+    }
+
     #[wasm_bindgen(js_name = "generateUnseeded")]
     pub fn generate_unseeded(
         &mut self,
@@ -233,7 +266,6 @@ impl WasmSdsContext {
     ) -> JsResult<()> {
         let js_callback: Function = progress_callback.dyn_into()?;
         let resolution = self.get_generate_result()?.resolution();
-        let mut params = (*self.get_sensitive_data_params()?).clone();
 
         if self.pre_computed_aggregates {
             self.check_reportable_aggregate_result(reporting_length)?;
@@ -247,31 +279,193 @@ impl WasmSdsContext {
             );
         }
 
-        // always process all the synthetic data and all columns
-        params.record_limit = 0;
-        params.use_columns = HeaderNames::default();
-
-        self.synthetic_processor = Some(WasmSdsProcessor::new(
-            &self
-                .get_generate_result()?
-                .synthetic_data_to_js(params.delimiter, false, false)?,
-            &params,
-        )?);
+        self.synthetic_processor = Some(self.create_synthetic_processor()?);
         self.synthetic_aggregate_result = Some(self.get_synthetic_processor()?._aggregate(
             reporting_length,
             &mut Some(JsProgressReporter::new(&js_callback, &|p| 50.0 + 0.5 * p)),
         )?);
-        self.evaluate_result = Some(WasmEvaluateResult::from_aggregate_results(
-            self.get_sensitive_aggregate_result()?,
-            self.get_reportable_aggregate_result()?,
-            self.get_synthetic_aggregate_result()?,
-            resolution,
-            reporting_length,
-        )?);
+        self.evaluate_result = Some(
+            WasmEvaluateResult::from_aggregate_results(
+                self.get_sensitive_aggregate_result()?,
+                self.get_reportable_aggregate_result()?,
+                self.get_synthetic_aggregate_result()?,
+                resolution,
+                reporting_length,
+            )?
+            .with_pairwise_association(
+                &self.get_sensitive_processor()?.data_block,
+                &self.get_synthetic_processor()?.data_block,
+            ),
+        );
         self.clear_navigate();
         Ok(())
     }
 
+    #[wasm_bindgen(js_name = "attackMembership")]
+    pub fn attack_membership(
+        &self,
+        holdout_csv_data: &str,
+        parameters: JsMembershipInferenceParameters,
+    ) -> JsResult<JsMembershipInferenceResult> {
+        let mut params = (*self.get_sensitive_data_params()?).clone();
+
+        // the holdout data is not sampled, all its records are non-members
+        params.record_limit = 0;
+        params.record_sampling = RecordSampling::First;
+
+        let holdout_processor = WasmSdsProcessor::new(holdout_csv_data, &params)?;
+        let result =
+            MembershipInferenceAttack::new(MembershipInferenceParameters::try_from(parameters)?)
+                .run(
+                    &self.get_sensitive_processor()?.data_block,
+                    &holdout_processor.data_block,
+                    &self.get_synthetic_processor()?.data_block,
+                )
+                .map_err(|err| JsValue::from(err.to_string()))?;
+
+        Ok(to_js_value(&result)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsMembershipInferenceResult>())
+    }
+
+    #[wasm_bindgen(js_name = "evaluateMlUtility")]
+    pub fn evaluate_ml_utility(
+        &self,
+        test_csv_data: &str,
+        parameters: JsMlUtilityParameters,
+    ) -> JsResult<JsMlUtilityResult> {
+        let mut params = (*self.get_sensitive_data_params()?).clone();
+
+        // all the held-out records are used for testing
+        params.record_limit = 0;
+        params.record_sampling = RecordSampling::First;
+
+        let test_processor = WasmSdsProcessor::new(test_csv_data, &params)?;
+        let result = MlUtilityEvaluator::new(MlUtilityParameters::try_from(parameters)?)
+            .run(
+                &self.get_sensitive_processor()?.data_block,
+                &test_processor.data_block,
+                &self.get_synthetic_processor()?.data_block,
+            )
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        Ok(to_js_value(&result)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsMlUtilityResult>())
+    }
+
+    #[wasm_bindgen(js_name = "storeGenerateResult")]
+    pub fn store_generate_result(&mut self, name: &str) -> JsResult<()> {
+        let synthetic_processor = self.create_synthetic_processor()?;
+
+        match self
+            .stored_synthetic_processors
+            .iter_mut()
+            .find(|(stored_name, _)| stored_name == name)
+        {
+            Some((_, stored_processor)) => *stored_processor = synthetic_processor,
+            None => self
+                .stored_synthetic_processors
+                .push((name.to_owned(), synthetic_processor)),
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "removeStoredGenerateResult")]
+    pub fn remove_stored_generate_result(&mut self, name: &str) {
+        self.stored_synthetic_processors
+            .retain(|(stored_name, _)| stored_name != name);
+    }
+
+    #[wasm_bindgen(js_name = "storedGenerateResultNames")]
+    pub fn stored_generate_result_names(&self) -> JsResult<JsHeaderNames> {
+        let names: HeaderNames = self
+            .stored_synthetic_processors
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        Ok(to_js_value(&names)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsHeaderNames>())
+    }
+
+    #[wasm_bindgen(js_name = "compareStoredGenerateResults")]
+    pub fn compare_stored_generate_results(
+        &mut self,
+        reporting_length: usize,
+        resolution: usize,
+        progress_callback: JsReportProgressCallback,
+    ) -> JsResult<JsSyntheticDataComparison> {
+        let js_callback: Function = progress_callback.dyn_into()?;
+        let synthetic_aggregate_results =
+            self.aggregate_stored_generate_results(reporting_length, &js_callback)?;
+        let result = Evaluator::default().compare_synthetic_data(
+            self.get_sensitive_aggregate_result()?,
+            &synthetic_aggregate_results
+                .iter()
+                .map(|(name, aggregate_result)| (name.as_str(), &**aggregate_result))
+                .collect::<Vec<_>>(),
+            resolution,
+        );
+
+        Ok(to_js_value(&result)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsSyntheticDataComparison>())
+    }
+
+    #[wasm_bindgen(js_name = "evaluateUncertaintyWithBootstrap")]
+    pub fn evaluate_uncertainty_with_bootstrap(
+        &self,
+        reporting_length: usize,
+        parameters: JsBootstrapParameters,
+        progress_callback: JsReportProgressCallback,
+    ) -> JsResult<JsEvaluationUncertainty> {
+        let js_callback: Function = progress_callback.dyn_into()?;
+        let result = Evaluator::default()
+            .calc_uncertainty_with_bootstrap(
+                &self.get_sensitive_processor()?.data_block,
+                &self.get_synthetic_processor()?.data_block,
+                reporting_length,
+                self.get_generate_result()?.resolution(),
+                &BootstrapParameters::try_from(parameters)?,
+                &mut Some(JsProgressReporter::new(&js_callback, &|p| p)),
+            )
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        Ok(to_js_value(&result)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsEvaluationUncertainty>())
+    }
+
+    #[wasm_bindgen(js_name = "evaluateStoredGenerateResultsUncertainty")]
+    pub fn evaluate_stored_generate_results_uncertainty(
+        &mut self,
+        reporting_length: usize,
+        resolution: usize,
+        confidence_level: f64,
+        progress_callback: JsReportProgressCallback,
+    ) -> JsResult<JsEvaluationUncertainty> {
+        let js_callback: Function = progress_callback.dyn_into()?;
+        let synthetic_aggregate_results =
+            self.aggregate_stored_generate_results(reporting_length, &js_callback)?;
+        let result = Evaluator::default()
+            .calc_uncertainty_from_replicates(
+                self.get_sensitive_aggregate_result()?,
+                &synthetic_aggregate_results
+                    .iter()
+                    .map(|(_, aggregate_result)| &**aggregate_result)
+                    .collect::<Vec<_>>(),
+                resolution,
+                confidence_level,
+            )
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        Ok(to_js_value(&result)
+            .map_err(|err| JsValue::from(err.to_string()))?
+            .unchecked_into::<JsEvaluationUncertainty>())
+    }
+
     #[wasm_bindgen(js_name = "navigate")]
     pub fn navigate(&mut self) -> JsResult<()> {
         self.navigate_result = Some(WasmNavigateResult::from_synthetic_processor(
@@ -373,6 +567,62 @@ impl WasmSdsContext {
         self.clear_navigate();
     }
 
+    /// Aggregates the sensitive data and every stored synthetic data,
+    /// returning the name and aggregate result of the latter
+    fn aggregate_stored_generate_results(
+        &mut self,
+        reporting_length: usize,
+        js_callback: &Function,
+    ) -> JsResult<Vec<(String, WasmAggregateResult)>> {
+        if self.stored_synthetic_processors.is_empty() {
+            return Err(JsValue::from_str(MISSING_STORED_GENERATE_RESULTS_ERROR));
+        }
+
+        let progress_proportion = 100.0 / ((self.stored_synthetic_processors.len() + 1) as f64);
+
+        self.get_or_create_sensitive_aggregate_result(
+            reporting_length,
+            &mut Some(JsProgressReporter::new(js_callback, &|p| {
+                progress_proportion * p / 100.0
+            })),
+        )?;
+
+        let mut synthetic_aggregate_results = Vec::new();
+
+        for (i, (name, processor)) in self.stored_synthetic_processors.iter().enumerate() {
+            let progress_offset = progress_proportion * ((i + 1) as f64);
+
+            synthetic_aggregate_results.push((
+                name.clone(),
+                processor._aggregate(
+                    reporting_length,
+                    &mut Some(JsProgressReporter::new(js_callback, &|p| {
+                        progress_offset + progress_proportion * p / 100.0
+                    })),
+                )?,
+            ));
+        }
+        Ok(synthetic_aggregate_results)
+    }
+
+    #[inline]
+    fn create_synthetic_processor(&self) -> JsResult<WasmSdsProcessor> {
+        let mut params = (*self.get_sensitive_data_params()?).clone();
+
+        // always process all the synthetic data and all columns
+        params.record_limit = 0;
+        params.max_records_per_subject = None;
+        params.record_sampling = RecordSampling::First;
+        params.use_columns = HeaderNames::default();
+
+        WasmSdsProcessor::new(
+            &self
+                .get_generate_result()?
+                .synthetic_data_to_js(params.delimiter, false, false)?,
+            &params,
+        )
+    }
+
     #[inline]
     fn clear_navigate(&mut self) {
         self.navigate_result = None;
@@ -14,5 +14,7 @@ pub const MISSING_EVALUATE_RESULT_ERROR: &str = "missing evaluate result";
 
 pub const MISSING_NAVIGATE_RESULT_ERROR: &str = "missing navigate result";
 
+pub const MISSING_STORED_GENERATE_RESULTS_ERROR: &str = "missing stored synthesized data";
+
 pub const INVALID_REPORTABLE_REPORTING_LENGTH_ERROR: &str =
     "reportable aggregates computed with reporting length of {0}, trying to evaluate with {1}";
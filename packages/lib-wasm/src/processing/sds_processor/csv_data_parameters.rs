@@ -1,4 +1,5 @@
 use super::{header_names::HeaderNames, MultiValueColumns};
+use sds_core::data_block::RecordSampling;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
@@ -10,10 +11,13 @@ use crate::utils::js::{from_js_value, JsCsvDataParameters};
 pub struct WasmCsvDataParameters {
     pub(crate) delimiter: char,
     pub(crate) subject_id: Option<String>,
+    pub(crate) max_records_per_subject: Option<usize>,
     pub(crate) use_columns: HeaderNames,
     pub(crate) multi_value_columns: MultiValueColumns,
     pub(crate) sensitive_zeros: HeaderNames,
     pub(crate) record_limit: usize,
+    #[serde(default)]
+    pub(crate) record_sampling: RecordSampling,
 }
 
 impl TryFrom<JsCsvDataParameters> for WasmCsvDataParameters {
@@ -7,6 +7,14 @@ impl TryFrom<JsDpParameters> for DpParameters {
     type Error = JsValue;
 
     fn try_from(js_dp_params: JsDpParameters) -> Result<Self, Self::Error> {
-        from_js_value(&js_dp_params).map_err(|err| JsValue::from(err.to_string()))
+        let dp_parameters: DpParameters =
+            from_js_value(&js_dp_params).map_err(|err| JsValue::from(err.to_string()))?;
+
+        if let Some(workload) = &dp_parameters.workload {
+            workload
+                .validate_weights()
+                .map_err(|err| JsValue::from(err.to_string()))?;
+        }
+        Ok(dp_parameters)
     }
 }
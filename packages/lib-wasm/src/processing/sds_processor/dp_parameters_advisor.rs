@@ -0,0 +1,25 @@
+use sds_core::dp::{DpAdvisorInputs, DpParametersAdvisor};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::utils::js::{
+    from_js_value, to_js_value, JsDpAdvisorInputs, JsDpRecommendation, JsResult,
+};
+
+impl TryFrom<JsDpAdvisorInputs> for DpAdvisorInputs {
+    type Error = JsValue;
+
+    fn try_from(js_inputs: JsDpAdvisorInputs) -> Result<Self, Self::Error> {
+        from_js_value(&js_inputs).map_err(|err| JsValue::from(err.to_string()))
+    }
+}
+
+#[wasm_bindgen(js_name = "recommendDpParameters")]
+pub fn recommend_dp_parameters(inputs: JsDpAdvisorInputs) -> JsResult<JsDpRecommendation> {
+    let recommendation = DpParametersAdvisor::new(DpAdvisorInputs::try_from(inputs)?)
+        .recommend()
+        .map_err(|err| JsValue::from(err.to_string()))?;
+
+    Ok(to_js_value(&recommendation)
+        .map_err(|err| JsValue::from(err.to_string()))?
+        .unchecked_into::<JsDpRecommendation>())
+}
@@ -1,6 +1,7 @@
 mod base_synthesis_parameters;
 mod csv_data_parameters;
 mod dp_parameters;
+mod dp_parameters_advisor;
 mod header_names;
 mod multi_value_columns;
 mod noisy_count_threshold;
@@ -10,6 +11,7 @@ mod processor;
 pub use base_synthesis_parameters::*;
 pub use csv_data_parameters::*;
 pub use dp_parameters::*;
+pub use dp_parameters_advisor::*;
 pub use header_names::*;
 pub use multi_value_columns::*;
 pub use noisy_count_threshold::*;
@@ -47,16 +47,42 @@ impl WasmSdsProcessor {
         csv_data_params: &WasmCsvDataParameters,
     ) -> JsResult<WasmSdsProcessor> {
         let _duration_logger = ElapsedDurationLogger::new("sds processor creation");
-        let data_block = CsvDataBlockCreator::create(
-            Ok(ReaderBuilder::new()
-                .delimiter(csv_data_params.delimiter as u8)
-                .from_reader(Cursor::new(csv_data))),
-            csv_data_params.subject_id.clone(),
-            &csv_data_params.use_columns,
-            &csv_data_params.multi_value_columns,
-            &csv_data_params.sensitive_zeros,
-            csv_data_params.record_limit,
-        )
+        if csv_data_params.subject_id.is_none() && csv_data_params.max_records_per_subject.is_some()
+        {
+            return Err(JsValue::from(
+                "subjectId must be set to use maxRecordsPerSubject",
+            ));
+        }
+
+        let reader = Ok(ReaderBuilder::new()
+            .delimiter(csv_data_params.delimiter as u8)
+            .from_reader(Cursor::new(csv_data)));
+        let data_block = match (
+            &csv_data_params.subject_id,
+            csv_data_params.max_records_per_subject,
+        ) {
+            (Some(subject_id), Some(max_records_per_subject)) => {
+                CsvDataBlockCreator::create_with_max_records_per_subject(
+                    reader,
+                    subject_id.clone(),
+                    max_records_per_subject,
+                    &csv_data_params.use_columns,
+                    &csv_data_params.multi_value_columns,
+                    &csv_data_params.sensitive_zeros,
+                    csv_data_params.record_limit,
+                    csv_data_params.record_sampling,
+                )
+            }
+            (subject_id, _) => CsvDataBlockCreator::create(
+                reader,
+                subject_id.clone(),
+                &csv_data_params.use_columns,
+                &csv_data_params.multi_value_columns,
+                &csv_data_params.sensitive_zeros,
+                csv_data_params.record_limit,
+                csv_data_params.record_sampling,
+            ),
+        }
         .map_err(|err| JsValue::from(err.to_string()))?;
 
         Ok(WasmSdsProcessor { data_block })
@@ -205,12 +231,22 @@ impl WasmSdsProcessor {
         progress_reporter: &mut Option<JsProgressReporter>,
     ) -> JsResult<WasmAggregateResult> {
         let aggregator = Aggregator::new(self.data_block.clone());
+        let dp_parameters = DpParameters::try_from(dp_parameters)?;
+
+        if let Some(workload) = &dp_parameters.workload {
+            workload
+                .validate(
+                    &self.data_block.headers,
+                    &self.data_block.multi_value_column_metadata_map,
+                )
+                .map_err(|err| JsValue::from(err.to_string()))?;
+        }
 
         Ok(WasmAggregateResult::new(Arc::new(
             aggregator
                 .aggregate_with_dp(
                     reporting_length,
-                    &DpParameters::try_from(dp_parameters)?,
+                    &dp_parameters,
                     NoisyCountThreshold::try_from(threshold)?,
                     progress_reporter,
                 )
@@ -235,6 +271,8 @@ impl WasmSdsProcessor {
                     resolution,
                     cache_max_size,
                     &empty_value,
+                    // for now, the UI does not allow setting this
+                    None,
                     progress_reporter,
                 )
                 .map_err(|err| JsValue::from(err.to_string()))?,
@@ -323,6 +361,8 @@ impl WasmSdsProcessor {
                     None,
                     // for now, the UI does not allow setting this
                     None,
+                    // for now, the UI does not allow setting this
+                    None,
                     progress_reporter,
                 )
                 .map_err(|err| JsValue::from(err.to_string()))?,
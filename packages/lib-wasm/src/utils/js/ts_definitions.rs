@@ -10,13 +10,26 @@ export interface IMultiValueColumns {
   [headerName: string]: string
 }
 
+export enum RecordSamplingType {
+  First = 'First',
+  Uniform = 'Uniform',
+  Poisson = 'Poisson'
+}
+
+export interface IRecordSampling {
+  type: RecordSamplingType
+  rate?: number
+}
+
 export interface ICsvDataParameters {
   delimiter: string
   subjectId?: string
+  maxRecordsPerSubject?: number
   useColumns: HeaderNames
   multiValueColumns: IMultiValueColumns
   sensitiveZeros: HeaderNames
   recordLimit: number
+  recordSampling?: IRecordSampling
 }
 
 export interface IAggregatedMetricByString {
@@ -30,9 +43,36 @@ export interface IAggregateStatistics {
   numberOfRecords: number
 }
 
+export interface IRecordRiskCombination {
+  combination: string
+  combinationLength: number
+  count: number
+  contribution: number
+}
+
+export interface IRecordRisk {
+  recordIndex: number
+  riskScore: number
+  smallestCombinationCount: number
+  numberOfUniqueCombinations: number
+  numberOfRareCombinations: number
+  riskyColumns: string[]
+  topCombinations: IRecordRiskCombination[]
+}
+
+export interface IRecordsRisk {
+  resolution: number
+  numberOfRecords: number
+  numberOfRiskyRecords: number
+  percentageOfRiskyRecords: number
+  numberOfRiskyRecordsByColumn: IAggregatedMetricByString
+  records: IRecordRisk[]
+}
+
 export enum NoisyCountThresholdType {
   Fixed = 'Fixed',
-  Adaptive = 'Adaptive'
+  Adaptive = 'Adaptive',
+  TopK = 'TopK'
 }
 
 export interface IInputNumberByLength {
@@ -51,6 +91,64 @@ export interface IDpParameters {
   delta?: number
   sigmaProportions?: number[]
   numberOfRecordsEpsilonProportion?: number
+  workload?: IDpWorkload
+  columnHierarchies?: IDpColumnHierarchy[]
+}
+
+export interface IDpWorkloadQuery {
+  columns: string[]
+  weight: number
+}
+
+export interface IDpWorkload {
+  queries: IDpWorkloadQuery[]
+  uncoveredWeight?: number
+}
+
+export interface IDpColumnHierarchy {
+  columns: string[]
+}
+
+export interface IDpBudgetShare {
+  combinationLength: number
+  weight: number
+  percentileEpsilon: number
+  sigma: number
+  allowedSensitivity: number
+  rho: number
+  budgetProportion: number
+  numberOfCombinations: number
+}
+
+export interface IDpBudgetAllocation {
+  epsilon: number
+  delta: number
+  numberOfRecordsEpsilon: number
+  marginalsEpsilon: number
+  marginalsRho: number
+  shares: IDpBudgetShare[]
+  samplingRate?: number
+  amplifiedEpsilon: number
+  amplifiedDelta: number
+}
+
+export interface IDpAdvisorInputs {
+  numberOfRecords: number
+  attributeCardinalities: number[]
+  reportingLength: number
+  targetRelativeErrors: number[]
+  delta?: number
+  maxEpsilon?: number
+}
+
+export interface IDpRecommendation {
+  dpParameters: IDpParameters
+  threshold: INoisyCountThreshold
+  expectedMeanCounts: number[]
+  sensitivities: number[]
+  sigmas: number[]
+  expectedRelativeErrors: number[]
+  countThresholds: number[]
 }
 
 export interface IOversamplingParameters {
@@ -77,6 +175,19 @@ export interface IMetricByKey {
 export interface IAggregateResult {
   reportingLength: number
   aggregatesData?: string
+  dpBudgetAllocation?: IDpBudgetAllocation
+}
+
+export interface IPairwiseAssociation {
+  columns: string[]
+  sensitiveCramersV: number[][]
+  syntheticCramersV: number[][]
+  cramersVDifference: number[][]
+  sensitiveNormalizedMutualInformation: number[][]
+  syntheticNormalizedMutualInformation: number[][]
+  normalizedMutualInformationDifference: number[][]
+  cramersVMeanAbsDifference: number
+  normalizedMutualInformationMeanAbsDifference: number
 }
 
 export interface IMicrodataStatistics {
@@ -100,6 +211,44 @@ export interface IMicrodataStatistics {
   percentageOfRecordsWithRareCombinations: number
   percentageOfUniqueCombinations: number
   percentageOfRareCombinations: number
+  pairwiseAssociation?: IPairwiseAssociation
+}
+
+export interface IMarginalDistance {
+  columns: string[]
+  combinationLength: number
+  numberOfCombinations: number
+  totalVariationDistance: number
+  jensenShannonDivergence: number
+  chiSquare: number
+  chiSquareDegreesOfFreedom: number
+  chiSquarePValue?: number
+}
+
+export interface IMarginalDistancesStatistics {
+  marginals: IMarginalDistance[]
+  meanTotalVariationDistanceByLen: IMetricByKey
+  meanJensenShannonDivergenceByLen: IMetricByKey
+  meanChiSquareByLen: IMetricByKey
+  percentageOfDifferentMarginalsByLen: IMetricByKey
+}
+
+export interface IUtilityBreakdownEntry {
+  column: string
+  value?: string
+  numberOfSensitiveCombinations: number
+  numberOfSyntheticCombinations: number
+  meanPreservation: number
+  meanProportionalError: number
+  percentageOfSuppressedCombinations: number
+  percentageOfFabricatedCombinations: number
+  combinationsCountMeanAbsError: number
+}
+
+export interface IUtilityBreakdown {
+  resolution: number
+  columns: IUtilityBreakdownEntry[]
+  attributes: IUtilityBreakdownEntry[]
 }
 
 export interface IEvaluateResult {
@@ -108,6 +257,141 @@ export interface IEvaluateResult {
   sensitiveDataStats: IMicrodataStatistics
   syntheticDataStats: IMicrodataStatistics
   syntheticVsAggregateDataStats: IMicrodataStatistics
+  syntheticMarginalDistances: IMarginalDistancesStatistics
+  syntheticUtilityBreakdown: IUtilityBreakdown
+}
+
+export enum MembershipAttackScore {
+  Distance = 'Distance',
+  Density = 'Density'
+}
+
+export interface IMembershipInferenceParameters {
+  score: MembershipAttackScore
+  densityRadius?: number
+  confidenceLevel?: number
+}
+
+export interface IConfidenceInterval {
+  estimate: number
+  lowerBound: number
+  upperBound: number
+  confidenceLevel: number
+}
+
+export interface IMembershipInferenceResult {
+  score: MembershipAttackScore
+  numberOfTrainingRecords: number
+  numberOfHoldoutRecords: number
+  numberOfColumns: number
+  auc: IConfidenceInterval
+  advantage: IConfidenceInterval
+  advantageThreshold?: number
+}
+
+export enum MlUtilityClassifier {
+  NaiveBayes = 'NaiveBayes',
+  LogisticRegression = 'LogisticRegression',
+  DecisionTree = 'DecisionTree'
+}
+
+export interface IMlUtilityParameters {
+  target: string
+  features?: string[]
+  classifiers?: MlUtilityClassifier[]
+  maxTreeDepth?: number
+  logisticRegressionIterations?: number
+}
+
+export interface IMlUtilityMetrics {
+  accuracy: number
+  macroF1: number
+  auc?: number
+}
+
+export interface IMlUtilityClassifierResult {
+  classifier: MlUtilityClassifier
+  trainedOnReal: IMlUtilityMetrics
+  trainedOnSynthetic: IMlUtilityMetrics
+  accuracyGap: number
+  macroF1Gap: number
+  aucGap?: number
+}
+
+export interface IMlUtilityResult {
+  target: string
+  features: string[]
+  numberOfTrainingRecords: number
+  numberOfSyntheticRecords: number
+  numberOfTestRecords: number
+  numberOfClasses: number
+  majorityClassAccuracy: number
+  classifiers: IMlUtilityClassifierResult[]
+}
+
+export enum ComparisonMetric {
+  RecordExpansionPercentage = 'recordExpansionPercentage',
+  LeakageCount = 'leakageCount',
+  PercentageOfSuppressedCombinations = 'percentageOfSuppressedCombinations',
+  PercentageOfFabricatedCombinations = 'percentageOfFabricatedCombinations',
+  CombinationsCountMeanAbsError = 'combinationsCountMeanAbsError',
+  MeanTotalVariationDistance = 'meanTotalVariationDistance',
+  MeanJensenShannonDivergence = 'meanJensenShannonDivergence'
+}
+
+export interface IComparisonMetricValues {
+  metric: ComparisonMetric
+  values: number[]
+  winners: string[]
+}
+
+export interface ISyntheticDataComparison {
+  names: string[]
+  resolution: number
+  metrics: IComparisonMetricValues[]
+  numberOfWins: number[]
+}
+
+export enum EvaluationUncertaintyMethod {
+  GenerationReplicates = 'GenerationReplicates',
+  RecordBootstrap = 'RecordBootstrap'
+}
+
+export interface IBootstrapParameters {
+  numberOfSamples: number
+  seed: number
+  confidenceLevel?: number
+}
+
+export enum EvaluationMetric {
+  RecordExpansionPercentage = 'recordExpansionPercentage',
+  LeakageCount = 'leakageCount',
+  PercentageOfSuppressedCombinations = 'percentageOfSuppressedCombinations',
+  PercentageOfFabricatedCombinations = 'percentageOfFabricatedCombinations',
+  CombinationsCountMeanAbsError = 'combinationsCountMeanAbsError',
+  MeanTotalVariationDistance = 'meanTotalVariationDistance',
+  MeanJensenShannonDivergence = 'meanJensenShannonDivergence',
+  LeakagePercentage = 'leakagePercentage',
+  MeanPreservation = 'meanPreservation',
+  MeanProportionalError = 'meanProportionalError'
+}
+
+export interface IMetricUncertainty {
+  metric: EvaluationMetric
+  combinationLength?: number
+  countBucket?: number
+  numberOfSamples: number
+  mean: number
+  standardDeviation: number
+  confidenceInterval: IConfidenceInterval
+}
+
+export interface IEvaluationUncertainty {
+  method: EvaluationUncertaintyMethod
+  numberOfReplicates: number
+  resolution: number
+  confidenceLevel: number
+  metrics: IMetricUncertainty[]
 }
 
 export interface INavigateResult {
@@ -151,6 +435,9 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "IAggregateStatistics")]
     pub type JsAggregateStatistics;
 
+    #[wasm_bindgen(typescript_type = "IRecordsRisk")]
+    pub type JsRecordsRisk;
+
     #[wasm_bindgen(typescript_type = "NoisyCountThresholdType")]
     pub type JsNoisyCountThresholdType;
 
@@ -163,6 +450,12 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "IDpParameters")]
     pub type JsDpParameters;
 
+    #[wasm_bindgen(typescript_type = "IDpAdvisorInputs")]
+    pub type JsDpAdvisorInputs;
+
+    #[wasm_bindgen(typescript_type = "IDpRecommendation")]
+    pub type JsDpRecommendation;
+
     #[wasm_bindgen(typescript_type = "IOversamplingParameters")]
     pub type JsOversamplingParameters;
 
@@ -181,9 +474,36 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "IMicrodataStatistics")]
     pub type JsMicrodataStatistics;
 
+    #[wasm_bindgen(typescript_type = "IMarginalDistancesStatistics")]
+    pub type JsMarginalDistancesStatistics;
+
+    #[wasm_bindgen(typescript_type = "IUtilityBreakdown")]
+    pub type JsUtilityBreakdown;
+
     #[wasm_bindgen(typescript_type = "IEvaluateResult")]
     pub type JsEvaluateResult;
 
+    #[wasm_bindgen(typescript_type = "IMembershipInferenceParameters")]
+    pub type JsMembershipInferenceParameters;
+
+    #[wasm_bindgen(typescript_type = "IMembershipInferenceResult")]
+    pub type JsMembershipInferenceResult;
+
+    #[wasm_bindgen(typescript_type = "IMlUtilityParameters")]
+    pub type JsMlUtilityParameters;
+
+    #[wasm_bindgen(typescript_type = "IMlUtilityResult")]
+    pub type JsMlUtilityResult;
+
+    #[wasm_bindgen(typescript_type = "ISyntheticDataComparison")]
+    pub type JsSyntheticDataComparison;
+
+    #[wasm_bindgen(typescript_type = "IBootstrapParameters")]
+    pub type JsBootstrapParameters;
+
+    #[wasm_bindgen(typescript_type = "IEvaluationUncertainty")]
+    pub type JsEvaluationUncertainty;
+
     #[wasm_bindgen(typescript_type = "INavigateResult")]
     pub type JsNavigateResult;
 
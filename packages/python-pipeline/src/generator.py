@@ -37,6 +37,7 @@ def generate(config):
     weight_selection_percentile = config['weight_selection_percentile']
     aggregate_seeded_counts_scale_factor = config['aggregate_seeded_counts_scale_factor']
     aggregate_seeded_target_number_of_records = config['aggregate_seeded_target_number_of_records']
+    target_distribution = config['target_distribution']
 
     logging.info(f'Generate {sensitive_microdata_path}')
     start_time = time.time()
@@ -53,11 +54,18 @@ def generate(config):
         subject_id
     )
 
+    if target_distribution != None:
+        target_distribution = sds.TargetDistribution(
+            target_distribution['column'],
+            target_distribution['proportions']
+        )
+
     if synthesis_mode == 'unseeded':
         generated_data = sds_processor.generate_unseeded(
             resolution,
             cache_max_size,
             "",
+            target_distribution
         )
     elif synthesis_mode == 'row_seeded':
         generated_data = sds_processor.generate_row_seeded(
@@ -88,7 +96,8 @@ def generate(config):
             use_synthetic_counts,
             weight_selection_percentile,
             aggregate_seeded_counts_scale_factor,
-            aggregate_seeded_target_number_of_records
+            aggregate_seeded_target_number_of_records,
+            target_distribution
         )
     else:
         raise ValueError(f'invalid synthesis mode: {synthesis_mode}')
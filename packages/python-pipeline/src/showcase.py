@@ -108,6 +108,7 @@ def runForConfig(config):
         'aggregate_seeded_counts_scale_factor', None)
     config['aggregate_seeded_target_number_of_records'] = config.get(
         'aggregate_seeded_target_number_of_records', None)
+    config['target_distribution'] = config.get('target_distribution', None)
 
     # specified parameters affecting file I/O
     config['prefix'] = config.get('prefix', 'my')